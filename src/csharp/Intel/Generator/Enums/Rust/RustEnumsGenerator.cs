@@ -226,7 +226,7 @@ namespace Generator.Enums.Rust {
 			if (!(feature is null))
 				writer.WriteLine(feature);
 			writer.WriteLine(RustConstants.AttributeNoRustFmt);
-			writer.WriteLine($"static {arrayName}: [&str; {enumType.Values.Length}] = [");
+			writer.WriteLine($"pub(crate) static {arrayName}: [&str; {enumType.Values.Length}] = [");
 			using (writer.Indent()) {
 				for (int i = 0; i < enumType.Values.Length; i++)
 					writer.WriteLine($"\"{enumType.Values[i].Name(idConverter)}\",");
@@ -0,0 +1,59 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Exports the instruction database as JSON or CSV
+//!
+//! Usage: `cargo run --example export_instruction_database -- [json|csv] [output-file]`
+
+extern crate iced_x86;
+
+use iced_x86::InstructionDatabase;
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+use std::process;
+
+fn main() {
+	let args: Vec<String> = env::args().collect();
+	if args.len() < 2 || args.len() > 3 {
+		eprintln!("Usage: {} json|csv [output-file]", args[0]);
+		process::exit(1);
+	}
+	let data = match args[1].as_str() {
+		"json" => InstructionDatabase::to_json(),
+		"csv" => InstructionDatabase::to_csv(),
+		format => {
+			eprintln!("Unknown format: {}", format);
+			process::exit(1);
+		}
+	};
+	let result = if args.len() == 3 {
+		File::create(&args[2]).and_then(|mut file| file.write_all(data.as_bytes()))
+	} else {
+		io::stdout().write_all(data.as_bytes())
+	};
+	if let Err(error) = result {
+		eprintln!("Couldn't write the database: {}", error);
+		process::exit(1);
+	}
+}
@@ -33,7 +33,7 @@ pub enum RelocKind {
 	Offset64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_RELOC_KIND: [&str; 1] = [
+pub(crate) static GEN_DEBUG_RELOC_KIND: [&str; 1] = [
 	"Offset64",
 ];
 impl fmt::Debug for RelocKind {
@@ -24,10 +24,13 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 #[cfg(feature = "instr_info")]
 use super::info::enums::*;
 use super::mnemonics;
+use super::name_lookup;
 use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 use core::fmt;
-#[cfg(any(feature = "instr_info", feature = "encoder"))]
 use core::mem;
+use core::str::FromStr;
 
 // GENERATOR-BEGIN: Code
 // ⚠️This was generated by GENERATOR!🦹‍♂️
@@ -33632,7 +33635,7 @@ pub enum Code {
 	D3NOW_Pavgusb_mm_mmm64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CODE: [&str; 4203] = [
+pub(crate) static GEN_DEBUG_CODE: [&str; 4203] = [
 	"INVALID",
 	"DeclareByte",
 	"DeclareWord",
@@ -37853,6 +37856,35 @@ impl Default for Code {
 }
 // GENERATOR-END: Code
 
+impl Code {
+	/// Gets the name of this value. It's the same as the variant name, eg. `"Add_rm32_r32"` for `Code::Add_rm32_r32`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!("Add_rm32_r32", Code::Add_rm32_r32.as_str());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		GEN_DEBUG_CODE[self as usize]
+	}
+}
+
+impl FromStr for Code {
+	type Err = String;
+
+	/// Converts a name to a `Code`. The name must match the variant name exactly, eg. `"Add_rm32_r32"`.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match name_lookup::find(&GEN_DEBUG_CODE, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u16) }),
+			None => Err(name_lookup::invalid_name("Code", value)),
+		}
+	}
+}
+
 impl Code {
 	/// Gets the mnemonic
 	///
@@ -37993,6 +38025,33 @@ impl Code {
 		unsafe { (*super::info::info_table::TABLE.get_unchecked((self as usize) * 2) & InfoFlags1::SAVE_RESTORE) != 0 }
 	}
 
+	/// Gets the exceptions the instruction can raise, its SSE/AVX/EVEX exception class, alignment requirements and memory ordering info.
+	/// If the code has a `reg/mem` operand, the memory form is assumed, see [`InstructionInfo::exception_info()`] for a specific instruction.
+	///
+	/// [`InstructionInfo::exception_info()`]: struct.InstructionInfo.html#method.exception_info
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// let info = Code::EVEX_Vaddps_zmm_k1z_zmm_zmmm512b32_er.exception_info();
+	/// assert_eq!(ExceptionClass::E2, info.exception_class());
+	/// assert!(info.can_raise(ExceptionBits::XM));
+	///
+	/// let info = Code::Div_rm32.exception_info();
+	/// assert_eq!(ExceptionClass::None, info.exception_class());
+	/// assert!(info.can_raise(ExceptionBits::DE | ExceptionBits::GP | ExceptionBits::PF));
+	///
+	/// assert!(Code::Cpuid.exception_info().is_serializing());
+	/// assert!(Code::Mfence.exception_info().is_memory_fence());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn exception_info(self) -> ExceptionInfo {
+		ExceptionInfo::new(self)
+	}
+
 	/// Checks if it's a `Jcc SHORT` or `Jcc NEAR` instruction
 	#[cfg_attr(has_must_use, must_use)]
 	#[inline]
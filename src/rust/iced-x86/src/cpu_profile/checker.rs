@@ -0,0 +1,202 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Checks if instructions are supported by a CPU
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// // popcnt eax,ecx
+/// // vpaddd zmm0,zmm1,zmm2
+/// // ret
+/// let bytes = b"\xF3\x0F\xB8\xC1\x62\xF1\x75\x48\xFE\xC2\xC3";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instructions: Vec<_> = decoder.iter().collect();
+///
+/// let report = IsaChecker::with_profile(CpuProfile::Haswell).check(&instructions);
+/// assert!(!report.is_compliant());
+/// assert_eq!(1, report.violations().len());
+/// let violation = &report.violations()[0];
+/// assert_eq!(1, violation.index());
+/// assert_eq!(Code::EVEX_Vpaddd_zmm_k1z_zmm_zmmm512b32, violation.code());
+/// assert!(violation.missing_features().contains(CpuidFeature::AVX512F));
+/// assert_eq!(Some(CpuProfile::X86_64_V4), report.minimum_level());
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IsaChecker {
+	supported: CpuidFeatureSet,
+}
+
+impl IsaChecker {
+	/// Creates a checker
+	///
+	/// # Arguments
+	///
+	/// * `supported`: Features supported by the CPU
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new(supported: CpuidFeatureSet) -> Self {
+		Self { supported }
+	}
+
+	/// Creates a checker that uses the features of a predefined CPU profile
+	///
+	/// # Arguments
+	///
+	/// * `profile`: CPU profile
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn with_profile(profile: CpuProfile) -> Self {
+		Self::new(profile.features())
+	}
+
+	/// Gets the features supported by the CPU
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn supported_features(&self) -> &CpuidFeatureSet {
+		&self.supported
+	}
+
+	/// Checks if the CPU supports an instruction. `ENDBR32`/`ENDBR64` are always supported since they're NOPs on CPUs without CET.
+	///
+	/// # Arguments
+	///
+	/// * `instruction`: Instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_supported(&self, instruction: &Instruction) -> bool {
+		self.supported.contains_all(required_features(instruction))
+	}
+
+	/// Checks all instructions and returns a report with all unsupported instructions
+	///
+	/// # Arguments
+	///
+	/// * `instructions`: Instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn check(&self, instructions: &[Instruction]) -> IsaReport {
+		let mut required = CpuidFeatureSet::new();
+		let mut violations = Vec::new();
+		for (index, instruction) in instructions.iter().enumerate() {
+			let features = required_features(instruction);
+			required.add_all(features);
+			if !self.supported.contains_all(features) {
+				let mut missing = CpuidFeatureSet::new();
+				missing.add_all(features);
+				missing.difference_with(&self.supported);
+				violations.push(IsaViolation { index, ip: instruction.ip(), code: instruction.code(), missing });
+			}
+		}
+		IsaReport { required, violations }
+	}
+}
+
+/// An instruction that isn't supported by the CPU, see [`IsaReport::violations()`]
+///
+/// [`IsaReport::violations()`]: struct.IsaReport.html#method.violations
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IsaViolation {
+	index: usize,
+	ip: u64,
+	code: Code,
+	missing: CpuidFeatureSet,
+}
+
+impl IsaViolation {
+	/// Gets the index of the instruction in the checked slice
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// Gets the address of the instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn ip(&self) -> u64 {
+		self.ip
+	}
+
+	/// Gets the instruction code
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn code(&self) -> Code {
+		self.code
+	}
+
+	/// Gets the features used by the instruction that the CPU doesn't support
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn missing_features(&self) -> &CpuidFeatureSet {
+		&self.missing
+	}
+}
+
+/// The result of [`IsaChecker::check()`]
+///
+/// [`IsaChecker::check()`]: struct.IsaChecker.html#method.check
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct IsaReport {
+	required: CpuidFeatureSet,
+	violations: Vec<IsaViolation>,
+}
+
+impl IsaReport {
+	/// `true` if the CPU supports all instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_compliant(&self) -> bool {
+		self.violations.is_empty()
+	}
+
+	/// Gets all instructions that aren't supported by the CPU
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn violations(&self) -> &[IsaViolation] {
+		&self.violations
+	}
+
+	/// Gets all features used by the instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn required_features(&self) -> &CpuidFeatureSet {
+		&self.required
+	}
+
+	/// Gets the lowest x86-64 microarchitecture level that supports all instructions or `None` if no level supports them,
+	/// see [`CpuProfile::minimum_x86_64_level()`]
+	///
+	/// [`CpuProfile::minimum_x86_64_level()`]: enum.CpuProfile.html#method.minimum_x86_64_level
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn minimum_level(&self) -> Option<CpuProfile> {
+		CpuProfile::minimum_x86_64_level(&self.required)
+	}
+}
@@ -0,0 +1,472 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod checker;
+#[cfg(test)]
+mod tests;
+
+pub use self::checker::*;
+use super::iced_constants::IcedConstants;
+use super::*;
+use core::mem;
+
+const WORDS: usize = 3;
+
+// ENDBR32/ENDBR64 are in the hint NOP space and are NOPs if CET isn't supported or enabled
+#[inline]
+fn required_features(instruction: &Instruction) -> &'static [CpuidFeature] {
+	match instruction.code() {
+		Code::Endbr32 | Code::Endbr64 => &[],
+		_ => instruction.cpuid_features(),
+	}
+}
+
+/// A set of [`CpuidFeature`]s
+///
+/// [`CpuidFeature`]: enum.CpuidFeature.html
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CpuidFeatureSet {
+	bits: [u64; WORDS],
+}
+
+impl CpuidFeatureSet {
+	/// Creates an empty set
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a set with all features used by the instructions. `ENDBR32`/`ENDBR64` don't require any features
+	/// since they're NOPs on CPUs without CET.
+	///
+	/// # Arguments
+	///
+	/// * `instructions`: Instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn from_instructions(instructions: &[Instruction]) -> Self {
+		let mut set = Self::new();
+		for instruction in instructions {
+			set.add_all(required_features(instruction));
+		}
+		set
+	}
+
+	/// Checks if the set is empty
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.bits.iter().all(|&bits| bits == 0)
+	}
+
+	/// Gets the number of features in the set
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.bits.iter().map(|&bits| bits.count_ones() as usize).sum()
+	}
+
+	/// Adds a feature
+	///
+	/// # Arguments
+	///
+	/// * `feature`: Feature
+	#[inline]
+	pub fn add(&mut self, feature: CpuidFeature) {
+		let index = feature as usize;
+		self.bits[index / 64] |= 1 << (index % 64);
+	}
+
+	/// Adds all features
+	///
+	/// # Arguments
+	///
+	/// * `features`: Features
+	#[inline]
+	pub fn add_all(&mut self, features: &[CpuidFeature]) {
+		for &feature in features {
+			self.add(feature);
+		}
+	}
+
+	/// Removes a feature
+	///
+	/// # Arguments
+	///
+	/// * `feature`: Feature
+	#[inline]
+	pub fn remove(&mut self, feature: CpuidFeature) {
+		let index = feature as usize;
+		self.bits[index / 64] &= !(1 << (index % 64));
+	}
+
+	/// Checks if a feature is in the set
+	///
+	/// # Arguments
+	///
+	/// * `feature`: Feature
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn contains(&self, feature: CpuidFeature) -> bool {
+		let index = feature as usize;
+		(self.bits[index / 64] & (1 << (index % 64))) != 0
+	}
+
+	/// Checks if all features are in the set
+	///
+	/// # Arguments
+	///
+	/// * `features`: Features
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn contains_all(&self, features: &[CpuidFeature]) -> bool {
+		features.iter().all(|&feature| self.contains(feature))
+	}
+
+	/// Checks if `other` is a subset of this set
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_superset_of(&self, other: &CpuidFeatureSet) -> bool {
+		self.bits.iter().zip(other.bits.iter()).all(|(&a, &b)| (a & b) == b)
+	}
+
+	/// Adds all features in `other` to this set
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[inline]
+	pub fn union_with(&mut self, other: &CpuidFeatureSet) {
+		for (a, &b) in self.bits.iter_mut().zip(other.bits.iter()) {
+			*a |= b;
+		}
+	}
+
+	/// Removes all features in `other` from this set
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[inline]
+	pub fn difference_with(&mut self, other: &CpuidFeatureSet) {
+		for (a, &b) in self.bits.iter_mut().zip(other.bits.iter()) {
+			*a &= !b;
+		}
+	}
+
+	/// Gets an iterator over all features in the set, ordered by their enum value
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn iter<'a>(&'a self) -> CpuidFeatureSetIter<'a> {
+		CpuidFeatureSetIter { set: self, index: 0 }
+	}
+}
+
+impl<'a> IntoIterator for &'a CpuidFeatureSet {
+	type Item = CpuidFeature;
+	type IntoIter = CpuidFeatureSetIter<'a>;
+
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterates over all features in a [`CpuidFeatureSet`]
+///
+/// [`CpuidFeatureSet`]: struct.CpuidFeatureSet.html
+#[derive(Debug)]
+pub struct CpuidFeatureSetIter<'a> {
+	set: &'a CpuidFeatureSet,
+	index: usize,
+}
+
+impl<'a> Iterator for CpuidFeatureSetIter<'a> {
+	type Item = CpuidFeature;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		const_assert_eq!(IcedConstants::NUMBER_OF_CPUID_FEATURES, CpuidFeature::XSAVES as usize + 1);
+		const_assert!(IcedConstants::NUMBER_OF_CPUID_FEATURES <= WORDS * 64);
+		while self.index < IcedConstants::NUMBER_OF_CPUID_FEATURES {
+			let index = self.index;
+			self.index += 1;
+			if (self.set.bits[index / 64] & (1 << (index % 64))) != 0 {
+				return Some(unsafe { mem::transmute(index as u8) });
+			}
+		}
+		None
+	}
+}
+
+/// A CPU model or an x86-64 microarchitecture level (`x86-64-v1` - `x86-64-v4`) and the [`CpuidFeature`]s it supports
+///
+/// [`CpuidFeature`]: enum.CpuidFeature.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CpuProfile {
+	/// `x86-64-v1`: the baseline x86-64 ISA (`CMOV`, `CX8`, `FPU`, `FXSR`, `MMX`, `SSE`, `SSE2`, `SYSCALL`)
+	X86_64_V1,
+	/// `x86-64-v2`: `x86-64-v1` + `CMPXCHG16B`, `POPCNT`, `SSE3`, `SSSE3`, `SSE4.1`, `SSE4.2`
+	X86_64_V2,
+	/// `x86-64-v3`: `x86-64-v2` + `AVX`, `AVX2`, `BMI1`, `BMI2`, `F16C`, `FMA`, `LZCNT`, `MOVBE`, `XSAVE`
+	X86_64_V3,
+	/// `x86-64-v4`: `x86-64-v3` + `AVX512F`, `AVX512BW`, `AVX512CD`, `AVX512DQ`, `AVX512VL`
+	X86_64_V4,
+	/// Intel Atom (Silvermont)
+	Silvermont,
+	/// Intel Haswell
+	Haswell,
+	/// Intel Skylake-SP (Skylake with AVX-512)
+	SkylakeAvx512,
+	/// AMD Zen 3
+	Zen3,
+	/// AMD Zen 4
+	Zen4,
+}
+
+static BASE_FEATURES: [CpuidFeature; 24] = [
+	CpuidFeature::INTEL8086,
+	CpuidFeature::INTEL186,
+	CpuidFeature::INTEL286,
+	CpuidFeature::INTEL386,
+	CpuidFeature::INTEL486,
+	CpuidFeature::X64,
+	CpuidFeature::FPU,
+	CpuidFeature::FPU287,
+	CpuidFeature::FPU387,
+	CpuidFeature::CPUID,
+	CpuidFeature::TSC,
+	CpuidFeature::MSR,
+	CpuidFeature::RDPMC,
+	CpuidFeature::CX8,
+	CpuidFeature::CMOV,
+	CpuidFeature::CLFSH,
+	CpuidFeature::MMX,
+	CpuidFeature::SSE,
+	CpuidFeature::SSE2,
+	CpuidFeature::FXSR,
+	CpuidFeature::SYSCALL,
+	CpuidFeature::SEP,
+	CpuidFeature::PAUSE,
+	CpuidFeature::MULTIBYTENOP,
+];
+static V2_FEATURES: [CpuidFeature; 6] =
+	[CpuidFeature::CMPXCHG16B, CpuidFeature::POPCNT, CpuidFeature::SSE3, CpuidFeature::SSSE3, CpuidFeature::SSE4_1, CpuidFeature::SSE4_2];
+static V3_FEATURES: [CpuidFeature; 9] = [
+	CpuidFeature::AVX,
+	CpuidFeature::AVX2,
+	CpuidFeature::BMI1,
+	CpuidFeature::BMI2,
+	CpuidFeature::F16C,
+	CpuidFeature::FMA,
+	CpuidFeature::LZCNT,
+	CpuidFeature::MOVBE,
+	CpuidFeature::XSAVE,
+];
+static V4_FEATURES: [CpuidFeature; 5] =
+	[CpuidFeature::AVX512F, CpuidFeature::AVX512BW, CpuidFeature::AVX512CD, CpuidFeature::AVX512DQ, CpuidFeature::AVX512VL];
+static SILVERMONT_FEATURES: [CpuidFeature; 9] = [
+	CpuidFeature::AES,
+	CpuidFeature::PCLMULQDQ,
+	CpuidFeature::RDRAND,
+	CpuidFeature::MOVBE,
+	CpuidFeature::RDTSCP,
+	CpuidFeature::PREFETCHW,
+	CpuidFeature::MONITOR,
+	CpuidFeature::VMX,
+	CpuidFeature::SMX,
+];
+static HASWELL_FEATURES: [CpuidFeature; 13] = [
+	CpuidFeature::AES,
+	CpuidFeature::PCLMULQDQ,
+	CpuidFeature::RDRAND,
+	CpuidFeature::RDTSCP,
+	CpuidFeature::MONITOR,
+	CpuidFeature::FSGSBASE,
+	CpuidFeature::INVPCID,
+	CpuidFeature::XSAVEOPT,
+	CpuidFeature::HLE,
+	CpuidFeature::RTM,
+	CpuidFeature::HLE_or_RTM,
+	CpuidFeature::VMX,
+	CpuidFeature::SMX,
+];
+static SKYLAKE_AVX512_FEATURES: [CpuidFeature; 10] = [
+	CpuidFeature::ADX,
+	CpuidFeature::RDSEED,
+	CpuidFeature::PREFETCHW,
+	CpuidFeature::CLFLUSHOPT,
+	CpuidFeature::CLWB,
+	CpuidFeature::SMAP,
+	CpuidFeature::MPX,
+	CpuidFeature::XSAVEC,
+	CpuidFeature::XSAVES,
+	CpuidFeature::PKU,
+];
+static ZEN3_FEATURES: [CpuidFeature; 31] = [
+	CpuidFeature::AES,
+	CpuidFeature::PCLMULQDQ,
+	CpuidFeature::VAES,
+	CpuidFeature::VPCLMULQDQ,
+	CpuidFeature::SHA,
+	CpuidFeature::RDRAND,
+	CpuidFeature::RDSEED,
+	CpuidFeature::ADX,
+	CpuidFeature::RDTSCP,
+	CpuidFeature::RDPID,
+	CpuidFeature::RDPRU,
+	CpuidFeature::MONITOR,
+	CpuidFeature::MONITORX,
+	CpuidFeature::FSGSBASE,
+	CpuidFeature::INVPCID,
+	CpuidFeature::PKU,
+	CpuidFeature::SMAP,
+	CpuidFeature::CLFLUSHOPT,
+	CpuidFeature::CLWB,
+	CpuidFeature::CLZERO,
+	CpuidFeature::WBNOINVD,
+	CpuidFeature::PREFETCHW,
+	CpuidFeature::SSE4A,
+	CpuidFeature::XSAVEOPT,
+	CpuidFeature::XSAVEC,
+	CpuidFeature::XSAVES,
+	CpuidFeature::CET_SS,
+	CpuidFeature::SVM,
+	CpuidFeature::SVML,
+	CpuidFeature::SKINIT,
+	CpuidFeature::SKINIT_or_SVML,
+];
+static ZEN4_FEATURES: [CpuidFeature; 8] = [
+	CpuidFeature::AVX512_IFMA,
+	CpuidFeature::AVX512_VBMI,
+	CpuidFeature::AVX512_VBMI2,
+	CpuidFeature::AVX512_VNNI,
+	CpuidFeature::AVX512_BITALG,
+	CpuidFeature::AVX512_VPOPCNTDQ,
+	CpuidFeature::AVX512_BF16,
+	CpuidFeature::GFNI,
+];
+
+impl CpuProfile {
+	/// Gets the name of the profile, eg. `x86-64-v3` or `Zen 3`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn name(self) -> &'static str {
+		match self {
+			CpuProfile::X86_64_V1 => "x86-64-v1",
+			CpuProfile::X86_64_V2 => "x86-64-v2",
+			CpuProfile::X86_64_V3 => "x86-64-v3",
+			CpuProfile::X86_64_V4 => "x86-64-v4",
+			CpuProfile::Silvermont => "Silvermont",
+			CpuProfile::Haswell => "Haswell",
+			CpuProfile::SkylakeAvx512 => "Skylake-AVX512",
+			CpuProfile::Zen3 => "Zen 3",
+			CpuProfile::Zen4 => "Zen 4",
+		}
+	}
+
+	/// Gets all features supported by the CPU
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// let features = CpuProfile::X86_64_V3.features();
+	/// assert!(features.contains(CpuidFeature::AVX2));
+	/// assert!(!features.contains(CpuidFeature::AVX512F));
+	/// assert!(CpuProfile::Zen4.features().is_superset_of(&CpuProfile::X86_64_V4.features()));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn features(self) -> CpuidFeatureSet {
+		let mut set = CpuidFeatureSet::new();
+		set.add_all(&BASE_FEATURES);
+		match self {
+			CpuProfile::X86_64_V1 => {}
+			CpuProfile::X86_64_V2 => set.add_all(&V2_FEATURES),
+			CpuProfile::X86_64_V3 => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+			}
+			CpuProfile::X86_64_V4 => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+				set.add_all(&V4_FEATURES);
+			}
+			CpuProfile::Silvermont => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&SILVERMONT_FEATURES);
+			}
+			CpuProfile::Haswell => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+				set.add_all(&HASWELL_FEATURES);
+			}
+			CpuProfile::SkylakeAvx512 => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+				set.add_all(&V4_FEATURES);
+				set.add_all(&HASWELL_FEATURES);
+				set.add_all(&SKYLAKE_AVX512_FEATURES);
+			}
+			CpuProfile::Zen3 => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+				set.add_all(&ZEN3_FEATURES);
+			}
+			CpuProfile::Zen4 => {
+				set.add_all(&V2_FEATURES);
+				set.add_all(&V3_FEATURES);
+				set.add_all(&V4_FEATURES);
+				set.add_all(&ZEN3_FEATURES);
+				set.add_all(&ZEN4_FEATURES);
+			}
+		}
+		set
+	}
+
+	/// Gets the lowest x86-64 microarchitecture level (`x86-64-v1` - `x86-64-v4`) that supports all features or
+	/// `None` if no level supports all features
+	///
+	/// # Arguments
+	///
+	/// * `features`: Required features, eg. from [`CpuidFeatureSet::from_instructions()`]
+	///
+	/// [`CpuidFeatureSet::from_instructions()`]: struct.CpuidFeatureSet.html#method.from_instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn minimum_x86_64_level(features: &CpuidFeatureSet) -> Option<CpuProfile> {
+		[CpuProfile::X86_64_V1, CpuProfile::X86_64_V2, CpuProfile::X86_64_V3, CpuProfile::X86_64_V4]
+			.iter()
+			.cloned()
+			.find(|level| level.features().is_superset_of(features))
+	}
+}
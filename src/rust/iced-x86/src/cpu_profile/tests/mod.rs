@@ -0,0 +1,195 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::decode_all;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+static PROFILES: [CpuProfile; 9] = [
+	CpuProfile::X86_64_V1,
+	CpuProfile::X86_64_V2,
+	CpuProfile::X86_64_V3,
+	CpuProfile::X86_64_V4,
+	CpuProfile::Silvermont,
+	CpuProfile::Haswell,
+	CpuProfile::SkylakeAvx512,
+	CpuProfile::Zen3,
+	CpuProfile::Zen4,
+];
+
+#[test]
+fn feature_set() {
+	let mut set = CpuidFeatureSet::new();
+	assert!(set.is_empty());
+	assert_eq!(0, set.len());
+	set.add(CpuidFeature::XSAVES);
+	set.add(CpuidFeature::INTEL8086);
+	set.add(CpuidFeature::AVX512F);
+	assert_eq!(3, set.len());
+	assert!(set.contains(CpuidFeature::XSAVES));
+	assert!(!set.contains(CpuidFeature::XSAVEOPT));
+	assert!(set.contains_all(&[CpuidFeature::INTEL8086, CpuidFeature::AVX512F]));
+	assert!(!set.contains_all(&[CpuidFeature::INTEL8086, CpuidFeature::AVX512VL]));
+	let features: Vec<CpuidFeature> = set.iter().collect();
+	assert_eq!(vec![CpuidFeature::INTEL8086, CpuidFeature::AVX512F, CpuidFeature::XSAVES], features);
+
+	let mut other = CpuidFeatureSet::new();
+	other.add(CpuidFeature::AVX512F);
+	assert!(set.is_superset_of(&other));
+	assert!(!other.is_superset_of(&set));
+	set.difference_with(&other);
+	assert!(!set.contains(CpuidFeature::AVX512F));
+	set.union_with(&other);
+	assert!(set.contains(CpuidFeature::AVX512F));
+	set.remove(CpuidFeature::XSAVES);
+	set.remove(CpuidFeature::INTEL8086);
+	assert_eq!(other, set);
+	assert_eq!(1, (&set).into_iter().count());
+}
+
+#[test]
+fn profiles_are_supersets() {
+	let v1 = CpuProfile::X86_64_V1.features();
+	let v2 = CpuProfile::X86_64_V2.features();
+	let v3 = CpuProfile::X86_64_V3.features();
+	let v4 = CpuProfile::X86_64_V4.features();
+	assert!(v2.is_superset_of(&v1) && v2 != v1);
+	assert!(v3.is_superset_of(&v2) && v3 != v2);
+	assert!(v4.is_superset_of(&v3) && v4 != v3);
+
+	assert!(CpuProfile::Silvermont.features().is_superset_of(&v2));
+	assert!(!CpuProfile::Silvermont.features().contains(CpuidFeature::AVX));
+	assert!(CpuProfile::Haswell.features().is_superset_of(&v3));
+	assert!(!CpuProfile::Haswell.features().contains(CpuidFeature::AVX512F));
+	assert!(CpuProfile::SkylakeAvx512.features().is_superset_of(&CpuProfile::Haswell.features()));
+	assert!(CpuProfile::SkylakeAvx512.features().is_superset_of(&v4));
+	assert!(CpuProfile::Zen3.features().is_superset_of(&v3));
+	assert!(!CpuProfile::Zen3.features().contains(CpuidFeature::AVX512F));
+	assert!(CpuProfile::Zen4.features().is_superset_of(&CpuProfile::Zen3.features()));
+	assert!(CpuProfile::Zen4.features().is_superset_of(&v4));
+
+	for &profile in PROFILES.iter() {
+		let features = profile.features();
+		assert!(!profile.name().is_empty());
+		for &feature in &[
+			CpuidFeature::INTEL8086_ONLY,
+			CpuidFeature::INTEL286_ONLY,
+			CpuidFeature::INTEL386_ONLY,
+			CpuidFeature::INTEL386_A0_ONLY,
+			CpuidFeature::INTEL486_A_ONLY,
+			CpuidFeature::INTEL386_486_ONLY,
+			CpuidFeature::FPU287XL_ONLY,
+			CpuidFeature::FPU387SL_ONLY,
+			CpuidFeature::IA64,
+			CpuidFeature::D3NOW,
+		] {
+			assert!(!features.contains(feature), "{:?} {:?}", profile, feature);
+		}
+	}
+}
+
+#[test]
+fn baseline_instructions_are_x86_64_v1() {
+	// mov eax,[rcx]
+	// push rbp
+	// nop dword ptr [rax]
+	// cmove eax,ecx
+	// cpuid
+	// rdtsc
+	// syscall
+	// pause
+	// fld qword ptr [rax]
+	// emms
+	// addps xmm0,xmm1
+	// paddq xmm0,xmm1
+	// cmpxchg8b [rax]
+	// lock xadd [rax],ecx
+	// endbr64
+	// endbr32
+	let instructions =
+		decode_all(64, "8B 01 55 0F1F 00 0F44 C1 0FA2 0F31 0F05 F3 90 DD 00 0F77 0F58 C1 66 0FD4 C1 0FC7 08 F0 0FC1 08 F3 0F1E FA F3 0F1E FB");
+	let report = IsaChecker::with_profile(CpuProfile::X86_64_V1).check(&instructions);
+	assert!(report.is_compliant(), "{:?}", report.violations());
+	assert_eq!(Some(CpuProfile::X86_64_V1), report.minimum_level());
+	for &profile in PROFILES.iter() {
+		assert!(IsaChecker::with_profile(profile).check(&instructions).is_compliant());
+	}
+}
+
+#[test]
+fn minimum_level() {
+	fn level(hex_bytes: &str) -> Option<CpuProfile> {
+		IsaChecker::default().check(&decode_all(64, hex_bytes)).minimum_level()
+	}
+	// popcnt eax,ecx
+	assert_eq!(Some(CpuProfile::X86_64_V2), level("F3 0FB8 C1"));
+	// vpaddd ymm0,ymm1,ymm2
+	assert_eq!(Some(CpuProfile::X86_64_V3), level("C5F5 FE C2"));
+	// vpaddd zmm0,zmm1,zmm2
+	assert_eq!(Some(CpuProfile::X86_64_V4), level("62F17548 FE C2"));
+	// vpdpbusd zmm0,zmm1,zmm2
+	assert_eq!(None, level("62F27548 50 C2"));
+	assert_eq!(None, CpuProfile::minimum_x86_64_level(&CpuProfile::Zen4.features()));
+	assert_eq!(Some(CpuProfile::X86_64_V1), CpuProfile::minimum_x86_64_level(&CpuidFeatureSet::new()));
+}
+
+#[test]
+fn checker_reports_all_violations() {
+	// vpdpbusd zmm0,zmm1,zmm2
+	// add eax,ecx
+	// vpaddd zmm0,zmm1,zmm2
+	let instructions = decode_all(64, "62F27548 50 C2 01 C8 62F17548 FE C2");
+	let checker = IsaChecker::with_profile(CpuProfile::Zen3);
+	assert!(!checker.is_supported(&instructions[0]));
+	assert!(checker.is_supported(&instructions[1]));
+	let report = checker.check(&instructions);
+	assert!(!report.is_compliant());
+	assert_eq!(2, report.violations().len());
+
+	let violation = &report.violations()[0];
+	assert_eq!(0, violation.index());
+	assert_eq!(instructions[0].ip(), violation.ip());
+	assert_eq!(instructions[0].code(), violation.code());
+	let missing: Vec<CpuidFeature> = violation.missing_features().iter().collect();
+	assert_eq!(vec![CpuidFeature::AVX512_VNNI], missing);
+
+	let violation = &report.violations()[1];
+	assert_eq!(2, violation.index());
+	let missing: Vec<CpuidFeature> = violation.missing_features().iter().collect();
+	assert_eq!(vec![CpuidFeature::AVX512F], missing);
+
+	assert!(report.required_features().contains(CpuidFeature::AVX512_VNNI));
+	assert_eq!(CpuidFeatureSet::from_instructions(&instructions), *report.required_features());
+	assert!(IsaChecker::with_profile(CpuProfile::Zen4).check(&instructions).is_compliant());
+	assert_eq!(&CpuProfile::Zen4.features(), IsaChecker::with_profile(CpuProfile::Zen4).supported_features());
+}
+
+#[test]
+fn zen3_doesnt_support_mcommit() {
+	// mcommit
+	let instructions = decode_all(64, "F3 0F01 FA");
+	assert_eq!(Code::Mcommit, instructions[0].code());
+	assert!(!IsaChecker::with_profile(CpuProfile::Zen3).is_supported(&instructions[0]));
+	assert!(!CpuProfile::Zen4.features().contains(CpuidFeature::MCOMMIT));
+}
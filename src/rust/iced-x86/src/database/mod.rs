@@ -0,0 +1,309 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(test)]
+mod tests;
+
+use super::iced_constants::IcedConstants;
+use super::info::enums::InfoFlags1;
+use super::info::{info_table, rflags_table};
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::{fmt, mem};
+
+enum Value {
+	Str(String),
+	Bool(bool),
+	Int(i64),
+	List(Vec<String>),
+}
+
+type Record = Vec<(&'static str, Value)>;
+
+#[inline]
+fn name<T: fmt::Debug>(value: T) -> Value {
+	Value::Str(format!("{:?}", value))
+}
+
+fn rflags_names(rflags: u32) -> Value {
+	static NAMES: [(u32, &str); 9] = [
+		(RflagsBits::OF, "of"),
+		(RflagsBits::SF, "sf"),
+		(RflagsBits::ZF, "zf"),
+		(RflagsBits::AF, "af"),
+		(RflagsBits::CF, "cf"),
+		(RflagsBits::PF, "pf"),
+		(RflagsBits::DF, "df"),
+		(RflagsBits::IF, "if"),
+		(RflagsBits::AC, "ac"),
+	];
+	Value::List(NAMES.iter().filter(|&&(bit, _)| (rflags & bit) != 0).map(|&(_, name)| String::from(name)).collect())
+}
+
+fn create_record(code: Code) -> Record {
+	let op_code = code.op_code();
+	let flags1 = info_table::TABLE[code as usize * 2];
+	let rflags_info = ((flags1 >> InfoFlags1::RFLAGS_INFO_SHIFT) & InfoFlags1::RFLAGS_INFO_MASK) as usize;
+
+	let mut prefixes = Vec::new();
+	for &(can_use, name) in &[
+		(op_code.can_use_lock_prefix(), "lock"),
+		(op_code.can_use_xacquire_prefix(), "xacquire"),
+		(op_code.can_use_xrelease_prefix(), "xrelease"),
+		(op_code.can_use_rep_prefix(), "rep"),
+		(op_code.can_use_repne_prefix(), "repne"),
+		(op_code.can_use_bnd_prefix(), "bnd"),
+		(op_code.can_use_hint_taken_prefix(), "hint_taken"),
+		(op_code.can_use_notrack_prefix(), "notrack"),
+	] {
+		if can_use {
+			prefixes.push(String::from(name));
+		}
+	}
+	let mut evex = Vec::new();
+	for &(can_use, name) in &[
+		(op_code.can_broadcast(), "broadcast"),
+		(op_code.can_use_rounding_control(), "rounding_control"),
+		(op_code.can_suppress_all_exceptions(), "sae"),
+		(op_code.can_use_op_mask_register(), "op_mask"),
+		(op_code.require_non_zero_op_mask_register(), "non_zero_op_mask"),
+		(op_code.can_use_zeroing_masking(), "zeroing_masking"),
+	] {
+		if can_use {
+			evex.push(String::from(name));
+		}
+	}
+
+	vec![
+		("code", Value::Str(String::from(code.as_str()))),
+		("mnemonic", Value::Str(String::from(code.mnemonic().as_str()))),
+		("is_instruction", Value::Bool(op_code.is_instruction())),
+		("op_code_string", Value::Str(String::from(op_code.op_code_string()))),
+		("instruction_string", Value::Str(String::from(op_code.instruction_string()))),
+		("encoding", name(op_code.encoding())),
+		("mode16", Value::Bool(op_code.mode16())),
+		("mode32", Value::Bool(op_code.mode32())),
+		("mode64", Value::Bool(op_code.mode64())),
+		("table", name(op_code.table())),
+		("mandatory_prefix", name(op_code.mandatory_prefix())),
+		("op_code", Value::Int(op_code.op_code() as i64)),
+		("group_index", Value::Int(op_code.group_index() as i64)),
+		("operand_size", Value::Int(op_code.operand_size() as i64)),
+		("address_size", Value::Int(op_code.address_size() as i64)),
+		("l", Value::Int(op_code.l() as i64)),
+		("w", Value::Int(op_code.w() as i64)),
+		("is_lig", Value::Bool(op_code.is_lig())),
+		("is_wig", Value::Bool(op_code.is_wig())),
+		("is_wig32", Value::Bool(op_code.is_wig32())),
+		("tuple_type", name(op_code.tuple_type())),
+		("operands", Value::List((0..op_code.op_count()).map(|i| format!("{:?}", op_code.op_kind(i))).collect())),
+		("prefixes", Value::List(prefixes)),
+		("evex_features", Value::List(evex)),
+		("cpuid_features", Value::List(code.cpuid_features().iter().map(|&f| String::from(f.as_str())).collect())),
+		("flow_control", name(code.flow_control())),
+		("condition_code", name(code.condition_code())),
+		("rflags_read", rflags_names(rflags_table::FLAGS_READ[rflags_info] as u32)),
+		("rflags_written", rflags_names(rflags_table::FLAGS_WRITTEN[rflags_info] as u32)),
+		("rflags_cleared", rflags_names(rflags_table::FLAGS_CLEARED[rflags_info] as u32)),
+		("rflags_set", rflags_names(rflags_table::FLAGS_SET[rflags_info] as u32)),
+		("rflags_undefined", rflags_names(rflags_table::FLAGS_UNDEFINED[rflags_info] as u32)),
+		("is_privileged", Value::Bool(code.is_privileged())),
+		("is_protected_mode", Value::Bool(code.is_protected_mode())),
+		("is_stack_instruction", Value::Bool(code.is_stack_instruction())),
+		("is_save_restore_instruction", Value::Bool(code.is_save_restore_instruction())),
+	]
+}
+
+#[inline]
+fn to_code(index: usize) -> Code {
+	unsafe { mem::transmute(index as u16) }
+}
+
+fn write_json_string(output: &mut Write, value: &str) -> fmt::Result {
+	output.write_char('"')?;
+	for c in value.chars() {
+		match c {
+			'"' => output.write_str("\\\"")?,
+			'\\' => output.write_str("\\\\")?,
+			'\n' => output.write_str("\\n")?,
+			'\r' => output.write_str("\\r")?,
+			'\t' => output.write_str("\\t")?,
+			_ if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32)?,
+			_ => output.write_char(c)?,
+		}
+	}
+	output.write_char('"')
+}
+
+fn write_csv_string(output: &mut Write, value: &str) -> fmt::Result {
+	if value.contains(&[',', '"', '\n', '\r'][..]) {
+		output.write_char('"')?;
+		for c in value.chars() {
+			if c == '"' {
+				output.write_char('"')?;
+			}
+			output.write_char(c)?;
+		}
+		output.write_char('"')
+	} else {
+		output.write_str(value)
+	}
+}
+
+/// Exports the instruction database, i.e., the [`OpCodeInfo`] and instruction info of every [`Code`] value, so it can
+/// be used by tools that don't link to this crate.
+///
+/// Each record contains the code, mnemonic, op code and instruction strings, encoding, supported modes, table,
+/// mandatory prefix, op code, operand kinds, allowed prefixes, `EVEX` features, CPUID features, flow control,
+/// condition code, the read/written/cleared/set/undefined `RFLAGS` and the privileged/protected mode/stack/save
+/// restore flags. The `RFLAGS` are the flags most instructions using the code modify, eg. a shift by 0 doesn't modify any flags.
+///
+/// [`OpCodeInfo`]: struct.OpCodeInfo.html
+/// [`Code`]: enum.Code.html
+#[allow(missing_copy_implementations)]
+#[allow(missing_debug_implementations)]
+pub struct InstructionDatabase;
+
+impl InstructionDatabase {
+	/// Writes the database as a JSON array with one object per [`Code`] value. Lists are JSON arrays of strings and
+	/// enum values are strings.
+	///
+	/// [`Code`]: enum.Code.html
+	///
+	/// # Errors
+	///
+	/// Fails if `output` fails
+	///
+	/// # Arguments
+	///
+	/// * `output`: Output
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn write_json(output: &mut Write) -> fmt::Result {
+		output.write_str("[\n")?;
+		for index in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+			if index != 0 {
+				output.write_str(",\n")?;
+			}
+			output.write_char('{')?;
+			for (i, &(name, ref value)) in create_record(to_code(index)).iter().enumerate() {
+				if i != 0 {
+					output.write_char(',')?;
+				}
+				write_json_string(output, name)?;
+				output.write_char(':')?;
+				match *value {
+					Value::Str(ref s) => write_json_string(output, s)?,
+					Value::Bool(b) => output.write_str(if b { "true" } else { "false" })?,
+					Value::Int(n) => write!(output, "{}", n)?,
+					Value::List(ref list) => {
+						output.write_char('[')?;
+						for (j, s) in list.iter().enumerate() {
+							if j != 0 {
+								output.write_char(',')?;
+							}
+							write_json_string(output, s)?;
+						}
+						output.write_char(']')?;
+					}
+				}
+			}
+			output.write_char('}')?;
+		}
+		output.write_str("\n]\n")
+	}
+
+	/// Writes the database as CSV (RFC 4180) with a header row and one row per [`Code`] value. List items are
+	/// separated by a space.
+	///
+	/// [`Code`]: enum.Code.html
+	///
+	/// # Errors
+	///
+	/// Fails if `output` fails
+	///
+	/// # Arguments
+	///
+	/// * `output`: Output
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn write_csv(output: &mut Write) -> fmt::Result {
+		for (i, &(name, _)) in create_record(Code::INVALID).iter().enumerate() {
+			if i != 0 {
+				output.write_char(',')?;
+			}
+			output.write_str(name)?;
+		}
+		output.write_str("\r\n")?;
+		for index in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+			for (i, &(_, ref value)) in create_record(to_code(index)).iter().enumerate() {
+				if i != 0 {
+					output.write_char(',')?;
+				}
+				match *value {
+					Value::Str(ref s) => write_csv_string(output, s)?,
+					Value::Bool(b) => output.write_str(if b { "true" } else { "false" })?,
+					Value::Int(n) => write!(output, "{}", n)?,
+					Value::List(ref list) => {
+						let mut items = String::new();
+						for (j, s) in list.iter().enumerate() {
+							if j != 0 {
+								items.push(' ');
+							}
+							items.push_str(s);
+						}
+						write_csv_string(output, &items)?;
+					}
+				}
+			}
+			output.write_str("\r\n")?;
+		}
+		Ok(())
+	}
+
+	/// Returns the database as JSON, see [`write_json()`]
+	///
+	/// [`write_json()`]: #method.write_json
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn to_json() -> String {
+		let mut output = String::new();
+		// Writing to a String can't fail
+		let _ = Self::write_json(&mut output);
+		output
+	}
+
+	/// Returns the database as CSV, see [`write_csv()`]
+	///
+	/// [`write_csv()`]: #method.write_csv
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn to_csv() -> String {
+		let mut output = String::new();
+		// Writing to a String can't fail
+		let _ = Self::write_csv(&mut output);
+		output
+	}
+}
@@ -0,0 +1,242 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::iced_constants::IcedConstants;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::str::Chars;
+
+// Only what write_json() writes is supported: objects, arrays, strings, bools and integers
+#[derive(Debug, PartialEq)]
+enum JsonValue {
+	Str(String),
+	Bool(bool),
+	Int(i64),
+	Array(Vec<JsonValue>),
+	Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+	fn get(&self, name: &str) -> &JsonValue {
+		match *self {
+			JsonValue::Object(ref fields) => &fields.iter().find(|&&(ref key, _)| key == name).unwrap().1,
+			_ => panic!(),
+		}
+	}
+
+	fn as_str(&self) -> &str {
+		match *self {
+			JsonValue::Str(ref s) => s,
+			_ => panic!(),
+		}
+	}
+}
+
+fn str_array(values: &[&str]) -> JsonValue {
+	JsonValue::Array(values.iter().map(|&s| JsonValue::Str(String::from(s))).collect())
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+	while let Some(&c) = chars.peek() {
+		if c != ' ' && c != '\n' && c != '\r' && c != '\t' {
+			break;
+		}
+		let _ = chars.next();
+	}
+}
+
+fn parse_json_string(chars: &mut Peekable<Chars>) -> String {
+	assert_eq!(Some('"'), chars.next());
+	let mut result = String::new();
+	loop {
+		match chars.next().unwrap() {
+			'"' => return result,
+			'\\' => match chars.next().unwrap() {
+				'"' => result.push('"'),
+				'\\' => result.push('\\'),
+				'n' => result.push('\n'),
+				'r' => result.push('\r'),
+				't' => result.push('\t'),
+				'u' => {
+					let hex: String = chars.by_ref().take(4).collect();
+					result.push(char::from(u8::from_str_radix(&hex, 16).unwrap()));
+				}
+				c => panic!("Invalid escape: {}", c),
+			},
+			c => {
+				assert!(c >= ' ');
+				result.push(c);
+			}
+		}
+	}
+}
+
+fn parse_json_value(chars: &mut Peekable<Chars>) -> JsonValue {
+	skip_whitespace(chars);
+	let value = match *chars.peek().unwrap() {
+		'"' => JsonValue::Str(parse_json_string(chars)),
+		'[' => {
+			let _ = chars.next();
+			let mut values = Vec::new();
+			skip_whitespace(chars);
+			if chars.peek() == Some(&']') {
+				let _ = chars.next();
+			} else {
+				loop {
+					values.push(parse_json_value(chars));
+					match chars.next() {
+						Some(',') => {}
+						Some(']') => break,
+						c => panic!("Unexpected char: {:?}", c),
+					}
+				}
+			}
+			JsonValue::Array(values)
+		}
+		'{' => {
+			let _ = chars.next();
+			let mut fields = Vec::new();
+			loop {
+				skip_whitespace(chars);
+				let name = parse_json_string(chars);
+				skip_whitespace(chars);
+				assert_eq!(Some(':'), chars.next());
+				fields.push((name, parse_json_value(chars)));
+				match chars.next() {
+					Some(',') => {}
+					Some('}') => break,
+					c => panic!("Unexpected char: {:?}", c),
+				}
+			}
+			JsonValue::Object(fields)
+		}
+		't' | 'f' => {
+			let mut word = String::new();
+			while let Some(&c) = chars.peek() {
+				if !c.is_alphabetic() {
+					break;
+				}
+				word.push(c);
+				let _ = chars.next();
+			}
+			JsonValue::Bool(match word.as_str() {
+				"true" => true,
+				"false" => false,
+				_ => panic!("Invalid value: {}", word),
+			})
+		}
+		_ => {
+			let mut number = String::new();
+			while let Some(&c) = chars.peek() {
+				if c != '-' && (c < '0' || c > '9') {
+					break;
+				}
+				number.push(c);
+				let _ = chars.next();
+			}
+			JsonValue::Int(number.parse().unwrap())
+		}
+	};
+	skip_whitespace(chars);
+	value
+}
+
+fn parse_json(json: &str) -> JsonValue {
+	let mut chars = json.chars().peekable();
+	let value = parse_json_value(&mut chars);
+	assert_eq!(None, chars.next());
+	value
+}
+
+#[test]
+fn json_has_one_record_per_code() {
+	let json = parse_json(&InstructionDatabase::to_json());
+	let records = match json {
+		JsonValue::Array(records) => records,
+		_ => panic!(),
+	};
+	assert_eq!(IcedConstants::NUMBER_OF_CODE_VALUES, records.len());
+	for (i, record) in records.iter().enumerate() {
+		let code: Code = unsafe { core::mem::transmute(i as u16) };
+		assert_eq!(code.as_str(), record.get("code").as_str());
+		assert_eq!(code.op_code().instruction_string(), record.get("instruction_string").as_str());
+	}
+
+	let record = &records[Code::Adc_rm32_r32 as usize];
+	assert_eq!("Adc", record.get("mnemonic").as_str());
+	assert_eq!("o32 11 /r", record.get("op_code_string").as_str());
+	assert_eq!("Legacy", record.get("encoding").as_str());
+	assert_eq!(&JsonValue::Bool(true), record.get("mode16"));
+	assert_eq!(&JsonValue::Int(0x11), record.get("op_code"));
+	assert_eq!(&str_array(&["r32_or_mem", "r32_reg"]), record.get("operands"));
+	assert_eq!(&str_array(&["lock", "xacquire", "xrelease"]), record.get("prefixes"));
+	assert_eq!(&str_array(&["INTEL386"]), record.get("cpuid_features"));
+	assert_eq!(&str_array(&["cf"]), record.get("rflags_read"));
+	assert_eq!(&str_array(&["of", "sf", "zf", "af", "cf", "pf"]), record.get("rflags_written"));
+	assert_eq!("Next", record.get("flow_control").as_str());
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if in_quotes && chars.peek() == Some(&'"') => {
+				field.push('"');
+				let _ = chars.next();
+			}
+			'"' => in_quotes = !in_quotes,
+			',' if !in_quotes => fields.push(core::mem::replace(&mut field, String::new())),
+			_ => field.push(c),
+		}
+	}
+	fields.push(field);
+	fields
+}
+
+#[test]
+fn csv_has_one_row_per_code() {
+	let csv = InstructionDatabase::to_csv();
+	let lines: Vec<&str> = csv.split("\r\n").filter(|line| !line.is_empty()).collect();
+	assert_eq!(IcedConstants::NUMBER_OF_CODE_VALUES + 1, lines.len());
+	let header = parse_csv_line(lines[0]);
+	assert_eq!("code", header[0]);
+	let instruction_string_index = header.iter().position(|s| s == "instruction_string").unwrap();
+	let operands_index = header.iter().position(|s| s == "operands").unwrap();
+	for (i, line) in lines[1..].iter().enumerate() {
+		let row = parse_csv_line(line);
+		assert_eq!(header.len(), row.len(), "{}", line);
+		let code: Code = unsafe { core::mem::transmute(i as u16) };
+		assert_eq!(code.as_str(), row[0]);
+		assert_eq!(code.op_code().instruction_string(), row[instruction_string_index]);
+	}
+	let row = parse_csv_line(lines[1 + Code::Adc_rm32_r32 as usize]);
+	assert_eq!("ADC r/m32, r32", row[instruction_string_index]);
+	assert_eq!("r32_or_mem r32_reg", row[operands_index]);
+}
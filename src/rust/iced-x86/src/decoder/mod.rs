@@ -67,7 +67,7 @@ pub(crate) enum OpSize {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_SIZE: [&str; 3] = [
+pub(crate) static GEN_DEBUG_OP_SIZE: [&str; 3] = [
 	"Size16",
 	"Size32",
 	"Size64",
@@ -281,6 +281,8 @@ pub struct Decoder<'a> {
 	default_inverted_address_size: OpSize,
 	is64_mode: bool,
 	bitness: u32,
+	#[cfg(feature = "instr_info")]
+	cpuid_features: Option<CpuidFeatureSet>,
 }
 
 impl<'a> Decoder<'a> {
@@ -427,6 +429,8 @@ impl<'a> Decoder<'a> {
 			default_inverted_address_size,
 			is64_mode,
 			bitness,
+			#[cfg(feature = "instr_info")]
+			cpuid_features: None,
 		}
 	}
 
@@ -458,6 +462,58 @@ impl<'a> Decoder<'a> {
 		self.bitness
 	}
 
+	/// Gets the CPUID features supported by the emulated CPU or `None` if all instructions are decoded,
+	/// see [`set_cpuid_features()`]
+	///
+	/// [`set_cpuid_features()`]: #method.set_cpuid_features
+	#[cfg(feature = "instr_info")]
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cpuid_features(&self) -> Option<&CpuidFeatureSet> {
+		self.cpuid_features.as_ref()
+	}
+
+	/// Only decodes instructions supported by a CPU with these CPUID features.
+	///
+	/// An instruction that needs a missing feature is decoded the same way the CPU executes it if there's such an
+	/// instruction, eg. `TZCNT` is decoded as `BSF`, `LZCNT` as `BSR`, `PAUSE` as `NOP`, `WBNOINVD` as `WBINVD` and
+	/// hint instructions such as `ENDBR64` as reserved `NOP`s. Else it's decoded as an invalid instruction
+	/// ([`Code::INVALID`]) with the same length. Use [`DecoderOptions::NO_LAHF_SAHF_64`] if `LAHF`/`SAHF` aren't
+	/// supported in 64-bit mode.
+	///
+	/// [`Code::INVALID`]: enum.Code.html#variant.INVALID
+	/// [`DecoderOptions::NO_LAHF_SAHF_64`]: struct.DecoderOptions.html#associatedconstant.NO_LAHF_SAHF_64
+	///
+	/// # Arguments
+	///
+	/// * `features`: Supported features, eg. [`CpuProfile::features()`], or `None` to decode all instructions
+	///
+	/// [`CpuProfile::features()`]: enum.CpuProfile.html#method.features
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // tzcnt eax,ecx
+	/// // vpaddd ymm0,ymm1,ymm2
+	/// let bytes = b"\xF3\x0F\xBC\xC1\xC5\xF5\xFE\xC2";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// decoder.set_cpuid_features(Some(CpuProfile::X86_64_V2.features()));
+	///
+	/// let instr = decoder.decode();
+	/// assert_eq!(Code::Bsf_r32_rm32, instr.code());
+	/// assert_eq!(4, instr.len());
+	/// let instr = decoder.decode();
+	/// assert_eq!(Code::INVALID, instr.code());
+	/// assert_eq!(4, instr.len());
+	/// ```
+	#[cfg(feature = "instr_info")]
+	#[inline]
+	pub fn set_cpuid_features(&mut self, features: Option<CpuidFeatureSet>) {
+		self.cpuid_features = features;
+	}
+
 	/// Gets the max value that can be passed to [`set_position()`]. This is the size of the data that gets
 	/// decoded to instructions and it's the length of the slice that was passed to the constructor.
 	///
@@ -960,6 +1016,53 @@ impl<'a> Decoder<'a> {
 		let ip = self.ip.wrapping_add(instr_len as u64);
 		self.ip = ip;
 		instruction.set_next_ip(ip);
+
+		#[cfg(feature = "instr_info")]
+		{
+			if self.cpuid_features.is_some() {
+				self.check_cpuid_features(instruction, data_ptr);
+			}
+		}
+	}
+
+	#[cfg(feature = "instr_info")]
+	fn check_cpuid_features(&mut self, instruction: &mut Instruction, data_ptr: *const u8) {
+		let features = match self.cpuid_features {
+			Some(features) => features,
+			None => return,
+		};
+		if features.contains_all(instruction.cpuid_features()) {
+			return;
+		}
+
+		// Decode it again as the instruction that the CPU executes instead
+		let fallback_options = match instruction.code() {
+			Code::Tzcnt_r16_rm16 | Code::Tzcnt_r32_rm32 | Code::Tzcnt_r64_rm64 => DecoderOptions::NO_MPFX_0FBC,
+			Code::Lzcnt_r16_rm16 | Code::Lzcnt_r32_rm32 | Code::Lzcnt_r64_rm64 => DecoderOptions::NO_MPFX_0FBD,
+			Code::Pause => DecoderOptions::NO_PAUSE,
+			Code::Wbnoinvd => DecoderOptions::NO_WBNOINVD,
+			_ => DecoderOptions::FORCE_RESERVED_NOP,
+		};
+		if (self.options & fallback_options) == 0 {
+			let options = self.options;
+			self.options |= fallback_options;
+			self.cpuid_features = None;
+			self.data_ptr = data_ptr;
+			self.ip = instruction.ip();
+			self.decode_out(instruction);
+			self.cpuid_features = Some(features);
+			self.options = options;
+			if features.contains_all(instruction.cpuid_features()) {
+				return;
+			}
+		}
+
+		let instr_len = instruction.len();
+		let next_ip = instruction.next_ip();
+		*instruction = Instruction::default();
+		super::instruction_internal::internal_set_code_size(instruction, self.default_code_size);
+		super::instruction_internal::internal_set_len(instruction, instr_len as u32);
+		instruction.set_next_ip(next_ip);
 	}
 
 	#[cfg_attr(has_must_use, must_use)]
@@ -33,7 +33,7 @@ pub(crate) enum SerializedDataKind {
 	ArrayReference,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_SERIALIZED_DATA_KIND: [&str; 2] = [
+pub(crate) static GEN_DEBUG_SERIALIZED_DATA_KIND: [&str; 2] = [
 	"HandlerReference",
 	"ArrayReference",
 ];
@@ -260,7 +260,7 @@ pub(crate) enum OpCodeHandlerKind {
 	Yv_Xv,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_CODE_HANDLER_KIND: [&str; 199] = [
+pub(crate) static GEN_DEBUG_OP_CODE_HANDLER_KIND: [&str; 199] = [
 	"Bitness",
 	"Bitness_DontReadModRM",
 	"Invalid",
@@ -560,7 +560,7 @@ pub(crate) enum EvexOpCodeHandlerKind {
 	WV,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_EVEX_OP_CODE_HANDLER_KIND: [&str; 75] = [
+pub(crate) static GEN_DEBUG_EVEX_OP_CODE_HANDLER_KIND: [&str; 75] = [
 	"Invalid",
 	"Invalid2",
 	"Dup",
@@ -725,7 +725,7 @@ pub(crate) enum VexOpCodeHandlerKind {
 	WVIb,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_VEX_OP_CODE_HANDLER_KIND: [&str; 64] = [
+pub(crate) static GEN_DEBUG_VEX_OP_CODE_HANDLER_KIND: [&str; 64] = [
 	"Invalid",
 	"Invalid2",
 	"Dup",
@@ -273,3 +273,101 @@ fn instruction_operator_eq_neq() {
 	assert_eq!(true, instr1a != instr2);
 	assert_eq!(false, instr1a != instr1b);
 }
+
+#[cfg(feature = "instr_info")]
+fn decode_with_features(hex_bytes: &str, features: Option<CpuidFeatureSet>, options: u32) -> Instruction {
+	let bytes = to_vec_u8(hex_bytes).unwrap();
+	let mut decoder = Decoder::new(64, &bytes, options);
+	decoder.set_ip(0x1000);
+	decoder.set_cpuid_features(features);
+	let instr = decoder.decode();
+	assert_eq!(bytes.len(), instr.len());
+	assert_eq!(0x1000 + bytes.len() as u64, decoder.ip());
+	instr
+}
+
+#[test]
+#[cfg(feature = "instr_info")]
+fn decode_with_cpuid_features_uses_older_instructions() {
+	let v1 = Some(CpuProfile::X86_64_V1.features());
+	let v3 = Some(CpuProfile::X86_64_V3.features());
+	// tzcnt eax,ecx
+	assert_eq!(Code::Bsf_r32_rm32, decode_with_features("F3 0FBC C1", v1, DecoderOptions::NONE).code());
+	assert_eq!(Code::Tzcnt_r32_rm32, decode_with_features("F3 0FBC C1", v3, DecoderOptions::NONE).code());
+	// lzcnt rax,[rcx]
+	assert_eq!(Code::Bsr_r64_rm64, decode_with_features("F3 48 0FBD 01", v1, DecoderOptions::NONE).code());
+	assert_eq!(Code::Lzcnt_r64_rm64, decode_with_features("F3 48 0FBD 01", v3, DecoderOptions::NONE).code());
+	// wbnoinvd
+	assert_eq!(Code::Wbinvd, decode_with_features("F3 0F09", v3, DecoderOptions::NONE).code());
+
+	// pause
+	let mut features = CpuProfile::X86_64_V1.features();
+	assert_eq!(Code::Pause, decode_with_features("F3 90", Some(features), DecoderOptions::NONE).code());
+	features.remove(CpuidFeature::PAUSE);
+	let instr = decode_with_features("F3 90", Some(features), DecoderOptions::NONE);
+	assert_eq!(decode_with_features("F3 90", None, DecoderOptions::NO_PAUSE).code(), instr.code());
+
+	// endbr64
+	let instr = decode_with_features("F3 0F1E FA", v3, DecoderOptions::NONE);
+	assert_ne!(Code::Endbr64, instr.code());
+	assert_eq!(decode_with_features("F3 0F1E FA", None, DecoderOptions::FORCE_RESERVED_NOP), instr);
+	let mut features = CpuProfile::X86_64_V3.features();
+	features.add(CpuidFeature::CET_IBT);
+	assert_eq!(Code::Endbr64, decode_with_features("F3 0F1E FA", Some(features), DecoderOptions::NONE).code());
+}
+
+#[test]
+#[cfg(feature = "instr_info")]
+fn decode_with_cpuid_features_invalid() {
+	// vpaddd ymm0,ymm1,ymm2
+	// add eax,ecx
+	let bytes = b"\xC5\xF5\xFE\xC2\x01\xC8";
+	let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	decoder.set_ip(0x1000);
+	assert_eq!(None, decoder.cpuid_features());
+	decoder.set_cpuid_features(Some(CpuProfile::X86_64_V2.features()));
+	assert_eq!(Some(&CpuProfile::X86_64_V2.features()), decoder.cpuid_features());
+
+	let instr = decoder.decode();
+	assert_eq!(Code::INVALID, instr.code());
+	assert_eq!(4, instr.len());
+	assert_eq!(0x1000, instr.ip());
+	assert_eq!(0x1004, instr.next_ip());
+	assert_eq!(CodeSize::Code64, instr.code_size());
+	assert!(!decoder.invalid_no_more_bytes());
+
+	let instr = decoder.decode();
+	assert_eq!(Code::Add_rm32_r32, instr.code());
+	assert_eq!(0x1004, instr.ip());
+	assert!(!decoder.can_decode());
+
+	decoder.set_cpuid_features(None);
+	decoder.set_position(0);
+	assert_eq!(Code::VEX_Vpaddd_ymm_ymm_ymmm256, decoder.decode().code());
+}
+
+#[test]
+#[cfg(feature = "instr_info")]
+fn decode_with_cpuid_features_only_returns_supported_instructions() {
+	for &profile in &[CpuProfile::X86_64_V1, CpuProfile::X86_64_V3, CpuProfile::Zen4] {
+		let features = profile.features();
+		for tc in decoder_tests(false, false) {
+			let bytes = to_vec_u8(tc.hex_bytes()).unwrap();
+			let mut decoder = Decoder::new(tc.bitness(), &bytes, tc.decoder_options());
+			let expected = decoder.decode();
+			decoder.set_position(0);
+			decoder.set_ip(expected.ip());
+			decoder.set_cpuid_features(Some(features));
+			let instr = decoder.decode();
+			assert_eq!(expected.len(), instr.len(), "{}", tc.hex_bytes());
+			assert_eq!(expected.next_ip(), instr.next_ip());
+			assert_eq!(expected.next_ip(), decoder.ip());
+			if instr.code() != Code::INVALID {
+				assert!(features.contains_all(instr.cpuid_features()), "{} {:?}", tc.hex_bytes(), instr.code());
+				if features.contains_all(expected.cpuid_features()) {
+					assert_eq!(expected, instr);
+				}
+			}
+		}
+	}
+}
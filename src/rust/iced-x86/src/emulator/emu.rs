@@ -0,0 +1,691 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::int_utils::{mask, mul_high, sign_extend};
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{cmp, fmt};
+
+static GPRS: [Register; 16] = [
+	Register::RAX,
+	Register::RCX,
+	Register::RDX,
+	Register::RBX,
+	Register::RSP,
+	Register::RBP,
+	Register::RSI,
+	Register::RDI,
+	Register::R8,
+	Register::R9,
+	Register::R10,
+	Register::R11,
+	Register::R12,
+	Register::R13,
+	Register::R14,
+	Register::R15,
+];
+static SEGMENTS: [Register; 6] = [Register::ES, Register::CS, Register::SS, Register::DS, Register::FS, Register::GS];
+
+/// Emulates 16-bit, 32-bit and 64-bit integer code. Each instruction is decoded, lifted to IR (see [`IrLifter`])
+/// and then executed.
+///
+/// The CPU state isn't modified if an instruction fails, except when [`EmulatorError::Interrupt`] or
+/// [`EmulatorError::Halt`] is returned. A string instruction with a `REP` prefix executes one iteration per step.
+///
+/// [`IrLifter`]: struct.IrLifter.html
+/// [`EmulatorError::Interrupt`]: enum.EmulatorError.html#variant.Interrupt
+/// [`EmulatorError::Halt`]: enum.EmulatorError.html#variant.Halt
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// // mov eax,5
+/// // add eax,ecx
+/// // hlt
+/// let code = b"\xB8\x05\x00\x00\x00\x01\xC8\xF4".to_vec();
+/// let mut state = EmulatorCpuState::new();
+/// state.set_rip(0x1000);
+/// state.set_gpr(Register::RCX, 3);
+/// let mut emulator = Emulator::new(64, state, EmulatorFlatMemory::new(0x1000, code));
+/// loop {
+///     match emulator.step() {
+///         Ok(_) => {}
+///         Err(EmulatorError::Halt) => break,
+///         Err(error) => panic!("{:?}", error),
+///     }
+/// }
+/// assert_eq!(8, emulator.state().gpr(Register::RAX));
+/// assert_eq!(0x1008, emulator.state().rip());
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct Emulator<S: EmulatorState, M: EmulatorMemory> {
+	bitness: u32,
+	decoder_options: u32,
+	state: S,
+	memory: M,
+	lifter: IrLifter,
+	temps: Vec<u64>,
+	// Registers and flags are only written to `state` after all statements have been executed
+	gprs: [u64; 16],
+	segments: [u16; 6],
+	rflags: u64,
+	dirty_gprs: u16,
+	dirty_segments: u8,
+}
+
+impl<S: EmulatorState, M: EmulatorMemory> Emulator<S, M> {
+	/// Creates an emulator
+	///
+	/// # Panics
+	///
+	/// Panics if `bitness` is not one of 16, 32, 64.
+	///
+	/// # Arguments
+	///
+	/// * `bitness`: 16, 32 or 64
+	/// * `state`: CPU state
+	/// * `memory`: Memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new(bitness: u32, state: S, memory: M) -> Self {
+		Self {
+			bitness,
+			decoder_options: DecoderOptions::NONE,
+			state,
+			memory,
+			lifter: IrLifter::new(bitness),
+			temps: Vec::new(),
+			gprs: [0; 16],
+			segments: [0; 6],
+			rflags: 0,
+			dirty_gprs: 0,
+			dirty_segments: 0,
+		}
+	}
+
+	/// Gets the bitness (16, 32 or 64)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn bitness(&self) -> u32 {
+		self.bitness
+	}
+
+	/// Gets the decoder options used by [`step()`], see [`DecoderOptions`]
+	///
+	/// [`step()`]: #method.step
+	/// [`DecoderOptions`]: struct.DecoderOptions.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn decoder_options(&self) -> u32 {
+		self.decoder_options
+	}
+
+	/// Sets the decoder options used by [`step()`], see [`DecoderOptions`]
+	///
+	/// [`step()`]: #method.step
+	/// [`DecoderOptions`]: struct.DecoderOptions.html
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_decoder_options(&mut self, value: u32) {
+		self.decoder_options = value;
+	}
+
+	/// Gets the CPU state
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn state(&self) -> &S {
+		&self.state
+	}
+
+	/// Gets the CPU state
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn state_mut(&mut self) -> &mut S {
+		&mut self.state
+	}
+
+	/// Gets the memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn memory(&self) -> &M {
+		&self.memory
+	}
+
+	/// Gets the memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn memory_mut(&mut self) -> &mut M {
+		&mut self.memory
+	}
+
+	/// Returns the CPU state and the memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn into_parts(self) -> (S, M) {
+		(self.state, self.memory)
+	}
+
+	/// Decodes and executes the instruction at `CS:RIP` and returns it
+	///
+	/// # Errors
+	///
+	/// Fails if the instruction couldn't be decoded or executed, see [`EmulatorError`]
+	///
+	/// [`EmulatorError`]: enum.EmulatorError.html
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn step(&mut self) -> Result<Instruction, EmulatorError> {
+		let rip = self.state.rip();
+		let address = self.linear_address(Register::CS, rip);
+		let mut bytes = [0u8; 15];
+		let mut length = 0;
+		while length < bytes.len() && self.memory.read(address.wrapping_add(length as u64), &mut bytes[length..length + 1]).is_ok() {
+			length += 1;
+		}
+		let mut decoder = Decoder::new(self.bitness, &bytes[..length], self.decoder_options);
+		decoder.set_ip(rip);
+		let instruction = decoder.decode();
+		if instruction.code() == Code::INVALID {
+			return Err(if decoder.invalid_no_more_bytes() && length < bytes.len() {
+				EmulatorError::MemoryFault(address.wrapping_add(length as u64))
+			} else {
+				EmulatorError::InvalidInstruction
+			});
+		}
+		self.execute(&instruction)?;
+		Ok(instruction)
+	}
+
+	/// Executes an instruction. `RIP` is set to the target address or to the instruction's [`next_ip()`].
+	///
+	/// [`next_ip()`]: struct.Instruction.html#method.next_ip
+	///
+	/// # Errors
+	///
+	/// Fails if the instruction couldn't be executed, see [`EmulatorError`]
+	///
+	/// [`EmulatorError`]: enum.EmulatorError.html
+	///
+	/// # Arguments
+	///
+	/// * `instruction`: Instruction to execute
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn execute(&mut self, instruction: &Instruction) -> Result<(), EmulatorError> {
+		let block = match self.lifter.lift(instruction) {
+			Ok(block) => block,
+			Err(error) => return Err(EmulatorError::Unsupported(error)),
+		};
+		for (i, gpr) in self.gprs.iter_mut().enumerate() {
+			*gpr = self.state.gpr(GPRS[i]);
+		}
+		for (i, segment) in self.segments.iter_mut().enumerate() {
+			*segment = self.state.segment(SEGMENTS[i]);
+		}
+		self.rflags = self.state.rflags();
+		self.dirty_gprs = 0;
+		self.dirty_segments = 0;
+		self.temps.clear();
+		self.temps.resize(block.temp_count() as usize, 0);
+
+		let (rip, event) = self.run(&block)?;
+
+		for (i, &gpr) in self.gprs.iter().enumerate() {
+			if (self.dirty_gprs & (1 << i)) != 0 {
+				self.state.set_gpr(GPRS[i], gpr);
+			}
+		}
+		for (i, &segment) in self.segments.iter().enumerate() {
+			if (self.dirty_segments & (1 << i)) != 0 {
+				self.state.set_segment(SEGMENTS[i], segment);
+			}
+		}
+		self.state.set_rflags(self.rflags);
+		self.state.set_rip(rip);
+		match event {
+			Some(event) => Err(event),
+			None => Ok(()),
+		}
+	}
+
+	fn linear_address(&self, segment: Register, address: u64) -> u64 {
+		if self.bitness == 64 {
+			match segment {
+				Register::FS | Register::GS => self.state.segment_base(segment).wrapping_add(address),
+				_ => address,
+			}
+		} else {
+			self.state.segment_base(segment).wrapping_add(address) & 0xFFFF_FFFF
+		}
+	}
+
+	#[inline]
+	fn value(&self, value: IrValue, size: u32) -> u64 {
+		(match value {
+			IrValue::Temp(temp) => self.temps[temp.index() as usize],
+			IrValue::Const(value) => value,
+		}) & mask(size)
+	}
+
+	#[inline]
+	fn set_temp(&mut self, temp: IrTemp, value: u64) {
+		self.temps[temp.index() as usize] = value;
+	}
+
+	// Returns the new RIP and an optional interrupt/halt event
+	fn run(&mut self, block: &IrBlock) -> Result<(u64, Option<EmulatorError>), EmulatorError> {
+		// Flags computed by the last `SetFlags` statement, used by `UndefinedFlags`
+		let mut computed_flags = RflagsResult::default();
+		for stmt in block.statements() {
+			match *stmt {
+				IrStmt::ReadReg { dst, register, size } => {
+					let value = self.read_reg(register, size)?;
+					self.set_temp(dst, value);
+				}
+				IrStmt::WriteReg { register, size, src } => {
+					let value = self.value(src, size);
+					self.write_reg(register, size, value)?;
+				}
+				IrStmt::ReadFlag { dst, flag } => {
+					let value = if (self.rflags & rflags_bit(flag)?) != 0 { 1 } else { 0 };
+					self.set_temp(dst, value);
+				}
+				IrStmt::WriteFlag { flag, src } => {
+					let bit = rflags_bit(flag)?;
+					if self.value(src, 1) != 0 {
+						self.rflags |= bit;
+					} else {
+						self.rflags &= !bit;
+					}
+				}
+				IrStmt::Load { dst, segment, address, size } => {
+					let address = self.value(address, 8);
+					let value = self.load(segment, address, size)?;
+					self.set_temp(dst, value);
+				}
+				IrStmt::Store { segment, address, size, src } => {
+					// All loads are executed before the first (and only) store so the memory is only updated if
+					// the instruction doesn't fault
+					let address = self.value(address, 8);
+					let value = self.value(src, size);
+					self.store(segment, address, size, value)?;
+				}
+				IrStmt::Unary { dst, op, src, size } => {
+					let value = self.value(src, size);
+					let value = unary(op, value, size)?;
+					self.set_temp(dst, value);
+				}
+				IrStmt::Binary { dst, op, lhs, rhs, size } => {
+					let lhs = self.value(lhs, size);
+					let rhs = self.value(rhs, size);
+					let value = binary(op, lhs, rhs, size)?;
+					self.set_temp(dst, value);
+				}
+				IrStmt::Compare { dst, op, lhs, rhs, size } => {
+					let lhs = self.value(lhs, size);
+					let rhs = self.value(rhs, size);
+					let (slhs, srhs) = (sign_extend(lhs, size) as i64, sign_extend(rhs, size) as i64);
+					let value = match op {
+						IrCompareOp::Eq => lhs == rhs,
+						IrCompareOp::Ne => lhs != rhs,
+						IrCompareOp::Ult => lhs < rhs,
+						IrCompareOp::Ule => lhs <= rhs,
+						IrCompareOp::Slt => slhs < srhs,
+						IrCompareOp::Sle => slhs <= srhs,
+					};
+					self.set_temp(dst, value as u64);
+				}
+				IrStmt::Select { dst, condition, if_true, if_false, size } => {
+					let value = if self.value(condition, 1) != 0 { self.value(if_true, size) } else { self.value(if_false, size) };
+					self.set_temp(dst, value);
+				}
+				IrStmt::Divide { quotient, remainder, high, low, divisor, size, signed } => {
+					let high = self.value(high, size);
+					let low = self.value(low, size);
+					let divisor = self.value(divisor, size);
+					let (q, r) =
+						if signed { idiv(high, low, divisor, size) } else { div(high, low, divisor, size) }.ok_or(EmulatorError::DivideError)?;
+					self.set_temp(quotient, q);
+					self.set_temp(remainder, r);
+				}
+				IrStmt::SetFlags { flags, kind, size, lhs, rhs, carry, result } => {
+					let lhs = self.value(lhs, size);
+					let rhs = self.value(rhs, size);
+					let carry = self.value(carry, 1);
+					let result = self.value(result, size);
+					let mnemonic = match kind {
+						IrFlagsKind::Add => Mnemonic::Adc,
+						IrFlagsKind::Sub => Mnemonic::Sbb,
+						IrFlagsKind::Logic => Mnemonic::And,
+						IrFlagsKind::Inc => Mnemonic::Inc,
+						IrFlagsKind::Dec => Mnemonic::Dec,
+						IrFlagsKind::Neg => Mnemonic::Neg,
+						IrFlagsKind::Shl => Mnemonic::Shl,
+						IrFlagsKind::Shr => Mnemonic::Shr,
+						IrFlagsKind::Sar => Mnemonic::Sar,
+						IrFlagsKind::Rol => Mnemonic::Rol,
+						IrFlagsKind::Ror => Mnemonic::Ror,
+						IrFlagsKind::Mul => Mnemonic::Mul,
+						IrFlagsKind::Imul => Mnemonic::Imul,
+						IrFlagsKind::BitScan => Mnemonic::Bsf,
+						IrFlagsKind::Count => Mnemonic::Tzcnt,
+						IrFlagsKind::BitTest => Mnemonic::Bt,
+						IrFlagsKind::FloatCompare => return Err(EmulatorError::Unsupported(format!("Unsupported flags: {:?}", kind))),
+					};
+					// The flags that `BSF` and `BT` don't change are read from the old flags
+					let mut rflags = RflagsBits::NONE;
+					for &flag in &[RflagsBits::OF, RflagsBits::SF, RflagsBits::ZF, RflagsBits::AF, RflagsBits::CF, RflagsBits::PF] {
+						if (self.rflags & rflags_bit(flag)?) != 0 {
+							rflags |= flag;
+						}
+					}
+					// `ADC`/`SBB` read CF from `carry`
+					match kind {
+						IrFlagsKind::Add | IrFlagsKind::Sub => {
+							rflags &= !RflagsBits::CF;
+							if carry != 0 {
+								rflags |= RflagsBits::CF;
+							}
+						}
+						_ => {}
+					}
+					let result = match RflagsResult::compute(mnemonic, size, lhs, rhs, result, rflags) {
+						Some(result) => result,
+						None => return Err(EmulatorError::Unsupported(format!("Unsupported flags: {:?}", kind))),
+					};
+					self.update_flags(flags & result.modified(), result.values())?;
+					computed_flags = result;
+				}
+				IrStmt::UndefinedFlags { flags } => self.update_flags(flags & computed_flags.modified(), computed_flags.values())?,
+				IrStmt::Branch { condition, target, .. } => {
+					let taken = match condition {
+						Some(condition) => self.value(condition, 1) != 0,
+						None => true,
+					};
+					if taken {
+						return Ok((self.value(target, 8), None));
+					}
+				}
+				IrStmt::Interrupt { vector, condition } => {
+					let taken = match condition {
+						Some(condition) => self.value(condition, 1) != 0,
+						None => true,
+					};
+					if taken {
+						return Ok((block.next_ip(), Some(EmulatorError::Interrupt(vector))));
+					}
+				}
+				IrStmt::Halt => return Ok((block.next_ip(), Some(EmulatorError::Halt))),
+			}
+		}
+		Ok((block.next_ip(), None))
+	}
+
+	fn update_flags(&mut self, flags: u32, values: u32) -> Result<(), EmulatorError> {
+		let mut flags = flags;
+		while flags != 0 {
+			let flag = flags & flags.wrapping_neg();
+			flags &= !flag;
+			let bit = rflags_bit(flag)?;
+			if (values & flag) != 0 {
+				self.rflags |= bit;
+			} else {
+				self.rflags &= !bit;
+			}
+		}
+		Ok(())
+	}
+
+	fn read_reg(&self, register: Register, size: u32) -> Result<u64, EmulatorError> {
+		if register.is_gpr() {
+			let value = self.gprs[register.full_register() as usize - Register::RAX as usize];
+			Ok((if is_high_byte_register(register) { value >> 8 } else { value }) & mask(size))
+		} else if register.is_segment_register() {
+			Ok(self.segments[register as usize - Register::ES as usize] as u64 & mask(size))
+		} else {
+			Err(unsupported_register(register))
+		}
+	}
+
+	fn write_reg(&mut self, register: Register, size: u32, value: u64) -> Result<(), EmulatorError> {
+		if register.is_gpr() {
+			let index = register.full_register() as usize - Register::RAX as usize;
+			let (shift, mask) = if is_high_byte_register(register) { (8, 0xFF00) } else { (0, mask(size)) };
+			self.gprs[index] = (self.gprs[index] & !mask) | ((value << shift) & mask);
+			self.dirty_gprs |= 1 << index;
+			Ok(())
+		} else if register.is_segment_register() {
+			let index = register as usize - Register::ES as usize;
+			self.segments[index] = value as u16;
+			self.dirty_segments |= 1 << index;
+			Ok(())
+		} else {
+			Err(unsupported_register(register))
+		}
+	}
+
+	fn load(&mut self, segment: Register, address: u64, size: u32) -> Result<u64, EmulatorError> {
+		if size > 8 {
+			return Err(EmulatorError::Unsupported(format!("Unsupported memory size: {}", size)));
+		}
+		let address = self.linear_address(segment, address);
+		let mut data = [0u8; 8];
+		self.memory.read(address, &mut data[..size as usize])?;
+		Ok(data.iter().rev().fold(0, |value, &b| (value << 8) | b as u64))
+	}
+
+	fn store(&mut self, segment: Register, address: u64, size: u32, value: u64) -> Result<(), EmulatorError> {
+		if size > 8 {
+			return Err(EmulatorError::Unsupported(format!("Unsupported memory size: {}", size)));
+		}
+		let address = self.linear_address(segment, address);
+		let mut data = [0u8; 8];
+		for (i, b) in data.iter_mut().enumerate() {
+			*b = (value >> (i * 8)) as u8;
+		}
+		self.memory.write(address, &data[..size as usize])
+	}
+}
+
+#[inline]
+fn is_high_byte_register(register: Register) -> bool {
+	match register {
+		Register::AH | Register::CH | Register::DH | Register::BH => true,
+		_ => false,
+	}
+}
+
+fn unsupported_register(register: Register) -> EmulatorError {
+	EmulatorError::Unsupported(format!("Unsupported register: {:?}", register))
+}
+
+fn rflags_bit(flag: u32) -> Result<u64, EmulatorError> {
+	Ok(match flag {
+		RflagsBits::CF => 1 << 0,
+		RflagsBits::PF => 1 << 2,
+		RflagsBits::AF => 1 << 4,
+		RflagsBits::ZF => 1 << 6,
+		RflagsBits::SF => 1 << 7,
+		RflagsBits::IF => 1 << 9,
+		RflagsBits::DF => 1 << 10,
+		RflagsBits::OF => 1 << 11,
+		RflagsBits::AC => 1 << 18,
+		_ => return Err(EmulatorError::Unsupported(format!("Unsupported flag: 0x{:X}", flag))),
+	})
+}
+
+fn unsupported_op<T: fmt::Debug>(op: T) -> EmulatorError {
+	EmulatorError::Unsupported(format!("Unsupported operation: {:?}", op))
+}
+
+fn unary(op: IrUnaryOp, value: u64, size: u32) -> Result<u64, EmulatorError> {
+	let bits = size * 8;
+	Ok(match op {
+		IrUnaryOp::Not => !value & mask(size),
+		IrUnaryOp::Neg => value.wrapping_neg() & mask(size),
+		IrUnaryOp::ByteSwap => value.swap_bytes() >> (64 - bits),
+		IrUnaryOp::PopCount => value.count_ones() as u64,
+		IrUnaryOp::TrailingZeros => {
+			if value == 0 {
+				bits as u64
+			} else {
+				value.trailing_zeros() as u64
+			}
+		}
+		IrUnaryOp::LeadingZeros => (value.leading_zeros() - (64 - bits)) as u64,
+		IrUnaryOp::ZeroExtend(_) => value,
+		IrUnaryOp::SignExtend(new_size) => sign_extend(value, size) & mask(new_size),
+		IrUnaryOp::Truncate(new_size) => value & mask(new_size),
+		IrUnaryOp::FloatSqrt | IrUnaryOp::FloatConvert(_) | IrUnaryOp::FloatToInt(_, _) | IrUnaryOp::IntToFloat(_) => return Err(unsupported_op(op)),
+	})
+}
+
+fn binary(op: IrBinaryOp, lhs: u64, rhs: u64, size: u32) -> Result<u64, EmulatorError> {
+	let bits = size * 8;
+	Ok(match op {
+		IrBinaryOp::Add => lhs.wrapping_add(rhs) & mask(size),
+		IrBinaryOp::Sub => lhs.wrapping_sub(rhs) & mask(size),
+		IrBinaryOp::Mul => lhs.wrapping_mul(rhs) & mask(size),
+		IrBinaryOp::UMulHigh => {
+			if size == 8 {
+				mul_high(lhs, rhs)
+			} else {
+				lhs.wrapping_mul(rhs) >> bits
+			}
+		}
+		IrBinaryOp::SMulHigh => {
+			let (slhs, srhs) = (sign_extend(lhs, size), sign_extend(rhs, size));
+			if size == 8 {
+				let mut high = mul_high(lhs, rhs);
+				if (slhs as i64) < 0 {
+					high = high.wrapping_sub(rhs);
+				}
+				if (srhs as i64) < 0 {
+					high = high.wrapping_sub(lhs);
+				}
+				high
+			} else {
+				(((slhs as i64).wrapping_mul(srhs as i64)) >> bits) as u64 & mask(size)
+			}
+		}
+		IrBinaryOp::And => lhs & rhs,
+		IrBinaryOp::Or => lhs | rhs,
+		IrBinaryOp::Xor => lhs ^ rhs,
+		IrBinaryOp::Shl => {
+			if rhs >= bits as u64 {
+				0
+			} else {
+				(lhs << rhs) & mask(size)
+			}
+		}
+		IrBinaryOp::Shr => {
+			if rhs >= bits as u64 {
+				0
+			} else {
+				lhs >> rhs
+			}
+		}
+		IrBinaryOp::Sar => ((sign_extend(lhs, size) as i64) >> cmp::min(rhs, 63)) as u64 & mask(size),
+		IrBinaryOp::Rol | IrBinaryOp::Ror => {
+			let count = (rhs % bits as u64) as u32;
+			let count = if op == IrBinaryOp::Rol { count } else { (bits - count) % bits };
+			if count == 0 {
+				lhs
+			} else {
+				((lhs << count) | (lhs >> (bits - count))) & mask(size)
+			}
+		}
+		IrBinaryOp::FloatAdd | IrBinaryOp::FloatSub | IrBinaryOp::FloatMul | IrBinaryOp::FloatDiv | IrBinaryOp::FloatMin | IrBinaryOp::FloatMax => {
+			return Err(unsupported_op(op))
+		}
+	})
+}
+
+// Divides the 128-bit value `high:low` by `divisor`, the quotient must fit in 64 bits
+fn div128(high: u64, low: u64, divisor: u64) -> Option<(u64, u64)> {
+	if divisor == 0 || high >= divisor {
+		return None;
+	}
+	let mut remainder = high;
+	let mut quotient = 0;
+	for i in (0..64).rev() {
+		let carry = (remainder >> 63) != 0;
+		remainder = (remainder << 1) | ((low >> i) & 1);
+		quotient <<= 1;
+		if carry || remainder >= divisor {
+			remainder = remainder.wrapping_sub(divisor);
+			quotient |= 1;
+		}
+	}
+	Some((quotient, remainder))
+}
+
+fn div(high: u64, low: u64, divisor: u64, size: u32) -> Option<(u64, u64)> {
+	if size == 8 {
+		div128(high, low, divisor)
+	} else {
+		let dividend = (high << (size * 8)) | low;
+		let quotient = dividend.checked_div(divisor)?;
+		if quotient > mask(size) {
+			return None;
+		}
+		Some((quotient, dividend % divisor))
+	}
+}
+
+fn idiv(high: u64, low: u64, divisor: u64, size: u32) -> Option<(u64, u64)> {
+	let sdivisor = sign_extend(divisor, size) as i64;
+	if size == 8 {
+		let negative_dividend = (high as i64) < 0;
+		let (abs_high, abs_low) = if negative_dividend { (!high + if low == 0 { 1 } else { 0 }, low.wrapping_neg()) } else { (high, low) };
+		let negative_divisor = sdivisor < 0;
+		let (quotient, remainder) = div128(abs_high, abs_low, sdivisor.wrapping_abs() as u64)?;
+		let quotient = if negative_dividend != negative_divisor {
+			if quotient > 1 << 63 {
+				return None;
+			}
+			quotient.wrapping_neg()
+		} else {
+			if quotient >= 1 << 63 {
+				return None;
+			}
+			quotient
+		};
+		let remainder = if negative_dividend { remainder.wrapping_neg() } else { remainder };
+		Some((quotient, remainder))
+	} else {
+		let dividend = sign_extend((high << (size * 8)) | low, size * 2) as i64;
+		let quotient = dividend.checked_div(sdivisor)?;
+		let min = -(1i64 << (size * 8 - 1));
+		if quotient < min || quotient > !min {
+			return None;
+		}
+		Some((quotient as u64 & mask(size), dividend.checked_rem(sdivisor)? as u64 & mask(size)))
+	}
+}
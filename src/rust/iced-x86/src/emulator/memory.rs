@@ -0,0 +1,104 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A flat memory that starts at a linear address
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let mut memory = EmulatorFlatMemory::new(0x1000, vec![0; 0x100]);
+/// assert!(memory.write(0x1010, b"\x12\x34").is_ok());
+/// assert_eq!(0x12, memory.data()[0x10]);
+/// assert_eq!(Err(EmulatorError::MemoryFault(0x10FF)), memory.write(0x10FF, b"\x12\x34"));
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct EmulatorFlatMemory {
+	base: u64,
+	data: Vec<u8>,
+}
+
+impl EmulatorFlatMemory {
+	/// Creates a new instance
+	///
+	/// # Arguments
+	///
+	/// * `base`: Linear address of the first byte in `data`
+	/// * `data`: Memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new(base: u64, data: Vec<u8>) -> Self {
+		Self { base, data }
+	}
+
+	/// Gets the linear address of the first byte
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn base(&self) -> u64 {
+		self.base
+	}
+
+	/// Gets the memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Gets the memory
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn data_mut(&mut self) -> &mut [u8] {
+		&mut self.data
+	}
+
+	fn offset(&self, address: u64, len: usize) -> Result<usize, EmulatorError> {
+		let offset = address.wrapping_sub(self.base);
+		if offset <= self.data.len() as u64 && len as u64 <= self.data.len() as u64 - offset {
+			Ok(offset as usize)
+		} else {
+			Err(EmulatorError::MemoryFault(address))
+		}
+	}
+}
+
+impl EmulatorMemory for EmulatorFlatMemory {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn read(&mut self, address: u64, data: &mut [u8]) -> Result<(), EmulatorError> {
+		let offset = self.offset(address, data.len())?;
+		data.copy_from_slice(&self.data[offset..offset + data.len()]);
+		Ok(())
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write(&mut self, address: u64, data: &[u8]) -> Result<(), EmulatorError> {
+		let offset = self.offset(address, data.len())?;
+		self.data[offset..offset + data.len()].copy_from_slice(data);
+		Ok(())
+	}
+}
@@ -0,0 +1,150 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod emu;
+mod memory;
+mod state;
+#[cfg(test)]
+mod tests;
+
+pub use self::emu::*;
+pub use self::memory::*;
+pub use self::state::*;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// An error (or event) returned by [`Emulator`]
+///
+/// [`Emulator`]: struct.Emulator.html
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum EmulatorError {
+	/// The bytes at `RIP` aren't a valid instruction (`#UD`)
+	InvalidInstruction,
+	/// The instruction or one of its operands isn't supported by the emulator
+	Unsupported(String),
+	/// The memory at this linear address couldn't be read or written
+	MemoryFault(u64),
+	/// Divide error (`#DE`), the divisor is 0 or the quotient is too big
+	DivideError,
+	/// `INT n`, `INT3` or `INT1` was executed, `RIP` points to the next instruction
+	Interrupt(u8),
+	/// `HLT` was executed, `RIP` points to the next instruction
+	Halt,
+}
+
+/// The CPU state used by [`Emulator`]
+///
+/// [`Emulator`]: struct.Emulator.html
+pub trait EmulatorState {
+	/// Gets a 64-bit general purpose register (`RAX`-`R15`)
+	///
+	/// # Arguments
+	///
+	/// * `register`: 64-bit GPR
+	fn gpr(&self, register: Register) -> u64;
+
+	/// Sets a 64-bit general purpose register (`RAX`-`R15`)
+	///
+	/// # Arguments
+	///
+	/// * `register`: 64-bit GPR
+	/// * `value`: New value
+	fn set_gpr(&mut self, register: Register, value: u64);
+
+	/// Gets the selector of a segment register (`ES`, `CS`, `SS`, `DS`, `FS`, `GS`)
+	///
+	/// # Arguments
+	///
+	/// * `register`: Segment register
+	fn segment(&self, register: Register) -> u16;
+
+	/// Sets the selector of a segment register (`ES`, `CS`, `SS`, `DS`, `FS`, `GS`). The emulator doesn't load
+	/// descriptors, the segment base isn't updated.
+	///
+	/// # Arguments
+	///
+	/// * `register`: Segment register
+	/// * `value`: New selector
+	fn set_segment(&mut self, register: Register, value: u16);
+
+	/// Gets the base of a segment (`ES`, `CS`, `SS`, `DS`, `FS`, `GS`). Only the `FS` and `GS` bases are used in 64-bit mode.
+	///
+	/// # Arguments
+	///
+	/// * `register`: Segment register
+	fn segment_base(&self, register: Register) -> u64;
+
+	/// Gets `RIP`
+	fn rip(&self) -> u64;
+
+	/// Sets `RIP`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	fn set_rip(&mut self, value: u64);
+
+	/// Gets `RFLAGS` (`CF` is bit 0, `PF` is bit 2, etc)
+	fn rflags(&self) -> u64;
+
+	/// Sets `RFLAGS` (`CF` is bit 0, `PF` is bit 2, etc)
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	fn set_rflags(&mut self, value: u64);
+}
+
+/// The memory used by [`Emulator`]. All addresses are linear addresses.
+///
+/// [`Emulator`]: struct.Emulator.html
+pub trait EmulatorMemory {
+	/// Reads memory
+	///
+	/// # Errors
+	///
+	/// Returns [`EmulatorError::MemoryFault`] if the memory can't be read
+	///
+	/// [`EmulatorError::MemoryFault`]: enum.EmulatorError.html#variant.MemoryFault
+	///
+	/// # Arguments
+	///
+	/// * `address`: Linear address
+	/// * `data`: Updated with the data
+	fn read(&mut self, address: u64, data: &mut [u8]) -> Result<(), EmulatorError>;
+
+	/// Writes memory. Nothing should be written if it fails.
+	///
+	/// # Errors
+	///
+	/// Returns [`EmulatorError::MemoryFault`] if the memory can't be written
+	///
+	/// [`EmulatorError::MemoryFault`]: enum.EmulatorError.html#variant.MemoryFault
+	///
+	/// # Arguments
+	///
+	/// * `address`: Linear address
+	/// * `data`: Data to write
+	fn write(&mut self, address: u64, data: &[u8]) -> Result<(), EmulatorError>;
+}
@@ -0,0 +1,141 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::*;
+
+/// A simple [`EmulatorState`] implementation
+///
+/// [`EmulatorState`]: trait.EmulatorState.html
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let mut state = EmulatorCpuState::new();
+/// state.set_gpr(Register::RCX, 0x1234);
+/// state.set_segment_base(Register::FS, 0x7000_0000);
+/// assert_eq!(0x1234, state.gpr(Register::RCX));
+/// assert_eq!(0x7000_0000, state.segment_base(Register::FS));
+/// // Bit 1 is always set
+/// assert_eq!(2, state.rflags());
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct EmulatorCpuState {
+	gprs: [u64; 16],
+	segments: [u16; 6],
+	segment_bases: [u64; 6],
+	rip: u64,
+	rflags: u64,
+}
+
+impl EmulatorCpuState {
+	/// Creates a new instance. All registers are 0 except bit 1 of `RFLAGS` which is always set.
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		Self { gprs: [0; 16], segments: [0; 6], segment_bases: [0; 6], rip: 0, rflags: 2 }
+	}
+
+	/// Sets the base of a segment (`ES`, `CS`, `SS`, `DS`, `FS`, `GS`)
+	///
+	/// # Panics
+	///
+	/// Panics if `register` isn't a segment register
+	///
+	/// # Arguments
+	///
+	/// * `register`: Segment register
+	/// * `base`: New base
+	#[inline]
+	pub fn set_segment_base(&mut self, register: Register, base: u64) {
+		self.segment_bases[segment_index(register)] = base;
+	}
+}
+
+impl Default for EmulatorCpuState {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		EmulatorCpuState::new()
+	}
+}
+
+#[inline]
+fn gpr_index(register: Register) -> usize {
+	assert!(register.is_gpr64());
+	register as usize - Register::RAX as usize
+}
+
+#[inline]
+fn segment_index(register: Register) -> usize {
+	assert!(register.is_segment_register());
+	register as usize - Register::ES as usize
+}
+
+impl EmulatorState for EmulatorCpuState {
+	#[inline]
+	fn gpr(&self, register: Register) -> u64 {
+		self.gprs[gpr_index(register)]
+	}
+
+	#[inline]
+	fn set_gpr(&mut self, register: Register, value: u64) {
+		self.gprs[gpr_index(register)] = value;
+	}
+
+	#[inline]
+	fn segment(&self, register: Register) -> u16 {
+		self.segments[segment_index(register)]
+	}
+
+	#[inline]
+	fn set_segment(&mut self, register: Register, value: u16) {
+		self.segments[segment_index(register)] = value;
+	}
+
+	#[inline]
+	fn segment_base(&self, register: Register) -> u64 {
+		self.segment_bases[segment_index(register)]
+	}
+
+	#[inline]
+	fn rip(&self) -> u64 {
+		self.rip
+	}
+
+	#[inline]
+	fn set_rip(&mut self, value: u64) {
+		self.rip = value;
+	}
+
+	#[inline]
+	fn rflags(&self) -> u64 {
+		self.rflags
+	}
+
+	#[inline]
+	fn set_rflags(&mut self, value: u64) {
+		self.rflags = value | 2;
+	}
+}
@@ -0,0 +1,275 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::*;
+
+const CODE_ADDRESS: u64 = 0x1000;
+const DATA_ADDRESS: u64 = 0x1800;
+const STACK_ADDRESS: u64 = 0x2000;
+
+fn create_emulator(bitness: u32, hex_bytes: &str) -> Emulator<EmulatorCpuState, EmulatorFlatMemory> {
+	let code = to_vec_u8(hex_bytes).unwrap();
+	let mut data = vec![0; (STACK_ADDRESS - CODE_ADDRESS) as usize];
+	data[..code.len()].copy_from_slice(&code);
+	let mut state = EmulatorCpuState::new();
+	state.set_rip(CODE_ADDRESS);
+	state.set_gpr(Register::RSP, STACK_ADDRESS);
+	Emulator::new(bitness, state, EmulatorFlatMemory::new(CODE_ADDRESS, data))
+}
+
+fn data_offset(address: u64) -> usize {
+	(address - CODE_ADDRESS) as usize
+}
+
+// Steps until an error is returned
+fn run(emulator: &mut Emulator<EmulatorCpuState, EmulatorFlatMemory>) -> EmulatorError {
+	for _ in 0..1000 {
+		if let Err(error) = emulator.step() {
+			return error;
+		}
+	}
+	panic!();
+}
+
+const CF: u64 = 1 << 0;
+const PF: u64 = 1 << 2;
+const AF: u64 = 1 << 4;
+const ZF: u64 = 1 << 6;
+const SF: u64 = 1 << 7;
+const OF: u64 = 1 << 11;
+
+#[test]
+fn add_updates_flags() {
+	// mov eax,0FFFFFFFFh
+	// add eax,1
+	// hlt
+	let mut emulator = create_emulator(64, "B8 FFFFFFFF 83 C0 01 F4");
+	emulator.state_mut().set_gpr(Register::RAX, 0x1234_5678_9ABC_DEF0);
+	assert_eq!(EmulatorError::Halt, run(&mut emulator));
+	assert_eq!(0, emulator.state().gpr(Register::RAX));
+	assert_eq!(2 | CF | PF | AF | ZF, emulator.state().rflags());
+	assert_eq!(CODE_ADDRESS + 9, emulator.state().rip());
+}
+
+#[test]
+fn partial_register_writes() {
+	// mov al,ah
+	let mut emulator = create_emulator(64, "88 E0 89 C0");
+	emulator.state_mut().set_gpr(Register::RAX, 0x1122_3344_5566_7788);
+	let instruction = emulator.step().unwrap();
+	assert_eq!(Code::Mov_rm8_r8, instruction.code());
+	assert_eq!(0x1122_3344_5566_7777, emulator.state().gpr(Register::RAX));
+	// mov eax,eax
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x5566_7777, emulator.state().gpr(Register::RAX));
+}
+
+#[test]
+fn rep_movsb() {
+	// rep movsb
+	let mut emulator = create_emulator(32, "F3 A4");
+	emulator.memory_mut().data_mut()[data_offset(DATA_ADDRESS)..data_offset(DATA_ADDRESS) + 4].copy_from_slice(b"abcd");
+	emulator.state_mut().set_gpr(Register::RSI, DATA_ADDRESS);
+	emulator.state_mut().set_gpr(Register::RDI, DATA_ADDRESS + 0x100);
+	emulator.state_mut().set_gpr(Register::RCX, 4);
+	for i in 0..4 {
+		let _ = emulator.step().unwrap();
+		let expected_rip = if i == 3 { CODE_ADDRESS + 2 } else { CODE_ADDRESS };
+		assert_eq!(expected_rip, emulator.state().rip());
+	}
+	assert_eq!(0, emulator.state().gpr(Register::RCX));
+	assert_eq!(DATA_ADDRESS + 4, emulator.state().gpr(Register::RSI));
+	assert_eq!(DATA_ADDRESS + 0x104, emulator.state().gpr(Register::RDI));
+	let offset = data_offset(DATA_ADDRESS + 0x100);
+	assert_eq!(b"abcd", &emulator.memory().data()[offset..offset + 4]);
+
+	// ECX is 0 so nothing is copied
+	emulator.state_mut().set_rip(CODE_ADDRESS);
+	let _ = emulator.step().unwrap();
+	assert_eq!(CODE_ADDRESS + 2, emulator.state().rip());
+	assert_eq!(DATA_ADDRESS + 4, emulator.state().gpr(Register::RSI));
+}
+
+#[test]
+fn call_ret() {
+	// call 100Ah
+	// hlt
+	// (4 bytes padding)
+	// mov rax,2Ah
+	// ret
+	let mut emulator = create_emulator(64, "E8 05000000 F4 90909090 48 C7 C0 2A000000 C3");
+	let _ = emulator.step().unwrap();
+	assert_eq!(CODE_ADDRESS + 0x0A, emulator.state().rip());
+	assert_eq!(STACK_ADDRESS - 8, emulator.state().gpr(Register::RSP));
+	let offset = data_offset(STACK_ADDRESS - 8);
+	assert_eq!(&[0x05, 0x10, 0, 0, 0, 0, 0, 0], &emulator.memory().data()[offset..offset + 8]);
+	assert_eq!(EmulatorError::Halt, run(&mut emulator));
+	assert_eq!(42, emulator.state().gpr(Register::RAX));
+	assert_eq!(STACK_ADDRESS, emulator.state().gpr(Register::RSP));
+	assert_eq!(CODE_ADDRESS + 6, emulator.state().rip());
+}
+
+#[test]
+fn loop_sum() {
+	// mov ecx,5
+	// xor eax,eax
+	// add eax,ecx
+	// loop 1007h
+	// hlt
+	let mut emulator = create_emulator(32, "B9 05000000 31 C0 01 C8 E2 FC F4");
+	assert_eq!(EmulatorError::Halt, run(&mut emulator));
+	assert_eq!(15, emulator.state().gpr(Register::RAX));
+	assert_eq!(0, emulator.state().gpr(Register::RCX));
+}
+
+#[test]
+fn segment_base_16() {
+	// mov ax,[bx]
+	let mut emulator = create_emulator(16, "8B 07");
+	emulator.state_mut().set_segment_base(Register::DS, 0x1000);
+	emulator.state_mut().set_gpr(Register::RBX, 0x0800);
+	emulator.memory_mut().data_mut()[data_offset(DATA_ADDRESS)] = 0x34;
+	emulator.memory_mut().data_mut()[data_offset(DATA_ADDRESS) + 1] = 0x12;
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x1234, emulator.state().gpr(Register::RAX));
+}
+
+#[test]
+fn mul_div() {
+	// mul rcx
+	let mut emulator = create_emulator(64, "48 F7 E1");
+	emulator.state_mut().set_gpr(Register::RAX, u64::max_value());
+	emulator.state_mut().set_gpr(Register::RCX, 2);
+	let _ = emulator.step().unwrap();
+	assert_eq!(0xFFFF_FFFF_FFFF_FFFE, emulator.state().gpr(Register::RAX));
+	assert_eq!(1, emulator.state().gpr(Register::RDX));
+	assert_eq!(CF | OF, emulator.state().rflags() & (CF | OF));
+
+	// idiv rcx
+	let mut emulator = create_emulator(64, "48 F7 F9");
+	emulator.state_mut().set_gpr(Register::RAX, (-7i64) as u64);
+	emulator.state_mut().set_gpr(Register::RDX, u64::max_value());
+	emulator.state_mut().set_gpr(Register::RCX, 2);
+	let _ = emulator.step().unwrap();
+	assert_eq!((-3i64) as u64, emulator.state().gpr(Register::RAX));
+	assert_eq!(u64::max_value(), emulator.state().gpr(Register::RDX));
+
+	// div cl
+	let mut emulator = create_emulator(32, "F6 F1");
+	emulator.state_mut().set_gpr(Register::RAX, 0x1234_0203);
+	emulator.state_mut().set_gpr(Register::RCX, 0x10);
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x1234_0320, emulator.state().gpr(Register::RAX));
+}
+
+#[test]
+fn divide_error_doesnt_modify_state() {
+	// div ecx
+	let mut emulator = create_emulator(32, "F7 F1");
+	emulator.state_mut().set_gpr(Register::RAX, 5);
+	let state = *emulator.state();
+	assert_eq!(Err(EmulatorError::DivideError), emulator.step());
+	assert_eq!(state, *emulator.state());
+
+	// div ecx (quotient too big)
+	emulator.state_mut().set_gpr(Register::RDX, 2);
+	emulator.state_mut().set_gpr(Register::RCX, 2);
+	assert_eq!(Err(EmulatorError::DivideError), emulator.step());
+}
+
+#[test]
+fn shift_flags() {
+	// shl al,1
+	let mut emulator = create_emulator(64, "D0 E0");
+	emulator.state_mut().set_gpr(Register::RAX, 0x81);
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x02, emulator.state().gpr(Register::RAX));
+	assert_eq!(CF | OF, emulator.state().rflags() & (CF | OF | ZF | SF));
+
+	// shl al,cl (cl = 0, the flags aren't modified)
+	let mut emulator = create_emulator(64, "D2 E0");
+	emulator.state_mut().set_gpr(Register::RAX, 0x81);
+	emulator.state_mut().set_rflags(CF | ZF);
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x81, emulator.state().gpr(Register::RAX));
+	assert_eq!(2 | CF | ZF, emulator.state().rflags());
+}
+
+#[test]
+fn bit_scan_flags() {
+	// bsf eax,ecx (ecx = 0, only ZF is modified)
+	let mut emulator = create_emulator(64, "0F BC C1");
+	emulator.state_mut().set_gpr(Register::RAX, 0x1234);
+	emulator.state_mut().set_rflags(2 | CF | SF);
+	let _ = emulator.step().unwrap();
+	assert_eq!(0x1234, emulator.state().gpr(Register::RAX));
+	assert_eq!(2 | CF | SF | ZF, emulator.state().rflags());
+
+	// bsf eax,ecx (ecx = 10h)
+	let mut emulator = create_emulator(64, "0F BC C1");
+	emulator.state_mut().set_gpr(Register::RCX, 0x10);
+	emulator.state_mut().set_rflags(2 | CF | ZF);
+	let _ = emulator.step().unwrap();
+	assert_eq!(4, emulator.state().gpr(Register::RAX));
+	assert_eq!(2, emulator.state().rflags());
+}
+
+#[test]
+fn faults() {
+	// mov rax,[1000000h]
+	let mut emulator = create_emulator(64, "48 8B 04 25 00000001");
+	assert_eq!(Err(EmulatorError::MemoryFault(0x0100_0000)), emulator.step());
+	assert_eq!(CODE_ADDRESS, emulator.state().rip());
+
+	// int3
+	let mut emulator = create_emulator(64, "CC");
+	assert_eq!(Err(EmulatorError::Interrupt(3)), emulator.step());
+	assert_eq!(CODE_ADDRESS + 1, emulator.state().rip());
+
+	// into
+	let mut emulator = create_emulator(32, "CE");
+	assert!(emulator.step().is_ok());
+	assert_eq!(CODE_ADDRESS + 1, emulator.state().rip());
+	let mut emulator = create_emulator(32, "CE");
+	emulator.state_mut().set_rflags(2 | OF);
+	assert_eq!(Err(EmulatorError::Interrupt(4)), emulator.step());
+	assert_eq!(CODE_ADDRESS + 1, emulator.state().rip());
+
+	// (invalid)
+	let mut emulator = create_emulator(64, "FF FF");
+	assert_eq!(Err(EmulatorError::InvalidInstruction), emulator.step());
+
+	// The last byte of memory is a prefix
+	let mut emulator = create_emulator(64, "");
+	emulator.memory_mut().data_mut()[data_offset(STACK_ADDRESS - 1)] = 0x48;
+	emulator.state_mut().set_rip(STACK_ADDRESS - 1);
+	assert_eq!(Err(EmulatorError::MemoryFault(STACK_ADDRESS)), emulator.step());
+
+	// addss xmm0,xmm1
+	let mut emulator = create_emulator(64, "F3 0F58 C1");
+	match emulator.step() {
+		Err(EmulatorError::Unsupported(_)) => {}
+		result => panic!("{:?}", result),
+	}
+}
@@ -38,7 +38,7 @@ pub(crate) enum DisplSize {
 	RipRelSize4_Target64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_DISPL_SIZE: [&str; 7] = [
+pub(crate) static GEN_DEBUG_DISPL_SIZE: [&str; 7] = [
 	"None",
 	"Size1",
 	"Size2",
@@ -94,7 +94,7 @@ pub(crate) enum ImmSize {
 	Size1OpCode,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_IMM_SIZE: [&str; 19] = [
+pub(crate) static GEN_DEBUG_IMM_SIZE: [&str; 19] = [
 	"None",
 	"Size1",
 	"Size2",
@@ -170,7 +170,7 @@ pub(crate) enum OperandSize {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OPERAND_SIZE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_OPERAND_SIZE: [&str; 4] = [
 	"None",
 	"Size16",
 	"Size32",
@@ -204,7 +204,7 @@ pub(crate) enum AddressSize {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_ADDRESS_SIZE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_ADDRESS_SIZE: [&str; 4] = [
 	"None",
 	"Size16",
 	"Size32",
@@ -238,7 +238,7 @@ pub(crate) enum LegacyOpCodeTable {
 	Table0F3A,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_LEGACY_OP_CODE_TABLE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_LEGACY_OP_CODE_TABLE: [&str; 4] = [
 	"Normal",
 	"Table0F",
 	"Table0F38",
@@ -271,7 +271,7 @@ pub(crate) enum VexOpCodeTable {
 	Table0F3A,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_VEX_OP_CODE_TABLE: [&str; 3] = [
+pub(crate) static GEN_DEBUG_VEX_OP_CODE_TABLE: [&str; 3] = [
 	"Table0F",
 	"Table0F38",
 	"Table0F3A",
@@ -303,7 +303,7 @@ pub(crate) enum XopOpCodeTable {
 	XOPA,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_XOP_OP_CODE_TABLE: [&str; 3] = [
+pub(crate) static GEN_DEBUG_XOP_OP_CODE_TABLE: [&str; 3] = [
 	"XOP8",
 	"XOP9",
 	"XOPA",
@@ -335,7 +335,7 @@ pub(crate) enum EvexOpCodeTable {
 	Table0F3A,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_EVEX_OP_CODE_TABLE: [&str; 3] = [
+pub(crate) static GEN_DEBUG_EVEX_OP_CODE_TABLE: [&str; 3] = [
 	"Table0F",
 	"Table0F38",
 	"Table0F3A",
@@ -367,7 +367,7 @@ pub(crate) enum Encodable {
 	Only64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_ENCODABLE: [&str; 3] = [
+pub(crate) static GEN_DEBUG_ENCODABLE: [&str; 3] = [
 	"Any",
 	"Only1632",
 	"Only64",
@@ -402,7 +402,7 @@ pub(crate) enum VexVectorLength {
 	LIG,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_VEX_VECTOR_LENGTH: [&str; 6] = [
+pub(crate) static GEN_DEBUG_VEX_VECTOR_LENGTH: [&str; 6] = [
 	"LZ",
 	"L0",
 	"L1",
@@ -438,7 +438,7 @@ pub(crate) enum XopVectorLength {
 	L1,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_XOP_VECTOR_LENGTH: [&str; 4] = [
+pub(crate) static GEN_DEBUG_XOP_VECTOR_LENGTH: [&str; 4] = [
 	"L128",
 	"L256",
 	"L0",
@@ -471,7 +471,7 @@ pub(crate) enum EvexVectorLength {
 	L512,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_EVEX_VECTOR_LENGTH: [&str; 3] = [
+pub(crate) static GEN_DEBUG_EVEX_VECTOR_LENGTH: [&str; 3] = [
 	"L128",
 	"L256",
 	"L512",
@@ -573,7 +573,7 @@ pub(crate) enum AllowedPrefixes {
 	Xrelease,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_ALLOWED_PREFIXES: [&str; 9] = [
+pub(crate) static GEN_DEBUG_ALLOWED_PREFIXES: [&str; 9] = [
 	"None",
 	"Bnd",
 	"BndNotrack",
@@ -612,7 +612,7 @@ pub(crate) enum WBit {
 	WIG32,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_WBIT: [&str; 4] = [
+pub(crate) static GEN_DEBUG_WBIT: [&str; 4] = [
 	"W0",
 	"W1",
 	"WIG",
@@ -879,7 +879,7 @@ pub(crate) enum LegacyOpKind {
 	r64_ro,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_LEGACY_OP_KIND: [&str; 121] = [
+pub(crate) static GEN_DEBUG_LEGACY_OP_KIND: [&str; 121] = [
 	"None",
 	"Aww",
 	"Adw",
@@ -1065,7 +1065,7 @@ pub(crate) enum VexOpKind {
 	WY,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_VEX_OP_KIND: [&str; 39] = [
+pub(crate) static GEN_DEBUG_VEX_OP_KIND: [&str; 39] = [
 	"None",
 	"Ed",
 	"Eq",
@@ -1149,7 +1149,7 @@ pub(crate) enum XopOpKind {
 	WY,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_XOP_OP_KIND: [&str; 19] = [
+pub(crate) static GEN_DEBUG_XOP_OP_KIND: [&str; 19] = [
 	"None",
 	"Ed",
 	"Eq",
@@ -1230,7 +1230,7 @@ pub(crate) enum EvexOpKind {
 	WZ,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_EVEX_OP_KIND: [&str; 36] = [
+pub(crate) static GEN_DEBUG_EVEX_OP_KIND: [&str; 36] = [
 	"None",
 	"Ed",
 	"Eq",
@@ -1298,7 +1298,7 @@ pub enum RepPrefixKind {
 	Repne,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_REP_PREFIX_KIND: [&str; 3] = [
+pub(crate) static GEN_DEBUG_REP_PREFIX_KIND: [&str; 3] = [
 	"None",
 	"Repe",
 	"Repne",
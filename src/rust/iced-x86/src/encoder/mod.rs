@@ -30,18 +30,28 @@ mod op_code;
 mod op_code_data;
 mod op_code_fmt;
 mod op_code_handler;
+mod op_code_query;
 pub(crate) mod op_code_tbl;
 mod op_kind_tables;
+mod operand;
 mod ops;
 mod ops_tables;
+#[cfg(feature = "instr_info")]
+mod replace_register;
 #[cfg(test)]
 pub(crate) mod tests;
+mod validate;
 
 pub use self::enums::*;
 use self::handlers_table::*;
 pub use self::mem_op::*;
 pub use self::op_code::*;
 use self::op_code_handler::OpCodeHandler;
+pub use self::op_code_query::*;
+pub use self::operand::*;
+#[cfg(feature = "instr_info")]
+pub use self::replace_register::*;
+pub use self::validate::*;
 use super::iced_constants::IcedConstants;
 use super::*;
 #[cfg(not(feature = "std"))]
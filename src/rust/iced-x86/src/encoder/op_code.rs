@@ -539,6 +539,24 @@ impl OpCodeInfo {
 		self.encoding
 	}
 
+	/// Gets the exceptions the instruction can raise, its SSE/AVX/EVEX exception class, alignment requirements and memory ordering info
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// let info = Code::EVEX_Vmovapd_ymm_k1z_ymmm256.op_code().exception_info();
+	/// assert_eq!(ExceptionClass::E1, info.exception_class());
+	/// assert_eq!(32, info.alignment());
+	/// ```
+	#[cfg(feature = "instr_info")]
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn exception_info(&self) -> ExceptionInfo {
+		self.code.exception_info()
+	}
+
 	/// `true` if it's an instruction, `false` if it's eg. [`Code::INVALID`], [`db`], [`dw`], [`dd`], [`dq`]
 	///
 	/// # Examples
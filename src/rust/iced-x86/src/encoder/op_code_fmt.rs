@@ -43,7 +43,7 @@ pub(crate) enum LKind {
 	LZ,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_LKIND: [&str; 4] = [
+pub(crate) static GEN_DEBUG_LKIND: [&str; 4] = [
 	"None",
 	"L128",
 	"L0",
@@ -0,0 +1,426 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::iced_constants::IcedConstants;
+use super::super::instruction_memory_sizes;
+use super::super::mnemonic::GEN_DEBUG_MNEMONIC;
+use super::super::mnemonics::TO_MNEMONIC;
+use super::op_code_tbl::OP_CODE_INFO_TBL;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+lazy_static! {
+	// All instruction codes (not db/dw/dd/dq or INVALID) indexed by mnemonic
+	static ref CODES_BY_MNEMONIC: Vec<Vec<Code>> = {
+		let mut result: Vec<Vec<Code>> = (0..GEN_DEBUG_MNEMONIC.len()).map(|_| Vec::new()).collect();
+		for (i, info) in OP_CODE_INFO_TBL.iter().enumerate() {
+			if info.is_instruction() {
+				result[TO_MNEMONIC[i] as usize].push(info.code());
+			}
+		}
+		result
+	};
+}
+
+/// An operand used by [`OpCodeInfo::find()`] to find all [`OpCodeInfo`]s that can encode an instruction
+///
+/// [`OpCodeInfo::find()`]: struct.OpCodeInfo.html#method.find
+/// [`OpCodeInfo`]: struct.OpCodeInfo.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OpCodeOperand {
+	/// A register operand. It matches all operand kinds that can encode the register, eg. `EAX` matches the
+	/// `r32_reg`, `r32_or_mem`, `r32_rm`, etc operand kinds and the fixed `eax` operand kind.
+	Register(Register),
+	/// A memory operand. The value is the size of the memory operand in bytes or 0 if any size is accepted.
+	Memory(u32),
+	/// A broadcast memory operand (`EVEX`). The value is the size of the broadcasted element in bytes or 0 if any size is accepted.
+	Broadcast(u32),
+	/// An immediate operand. The value is the number of bits needed to store the (sign extended) immediate value: 8, 16,
+	/// 32 or 64. It matches all immediate operand kinds that are at least this big. The implicit `1` of eg. `SHL r/m32, 1`
+	/// is matched by [`ImmediateOne`].
+	///
+	/// [`ImmediateOne`]: #variant.ImmediateOne
+	Immediate(u32),
+	/// The implicit immediate value `1` used by eg. `SHL r/m32, 1`
+	ImmediateOne,
+	/// A near branch target (`rel8`, `rel16` or `rel32`)
+	Branch,
+	/// A far branch target (`ptr16:16` or `ptr16:32`)
+	FarBranch,
+}
+
+impl Default for OpCodeOperand {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		OpCodeOperand::Memory(0)
+	}
+}
+
+pub(super) fn matches_register(kind: OpCodeOperandKind, register: Register) -> bool {
+	match kind {
+		OpCodeOperandKind::r8_or_mem | OpCodeOperandKind::r8_reg | OpCodeOperandKind::r8_opcode => register.is_gpr8(),
+		OpCodeOperandKind::r16_or_mem
+		| OpCodeOperandKind::r16_reg
+		| OpCodeOperandKind::r16_reg_mem
+		| OpCodeOperandKind::r16_rm
+		| OpCodeOperandKind::r16_opcode => register.is_gpr16(),
+		OpCodeOperandKind::r32_or_mem
+		| OpCodeOperandKind::r32_or_mem_mpx
+		| OpCodeOperandKind::r32_reg
+		| OpCodeOperandKind::r32_reg_mem
+		| OpCodeOperandKind::r32_rm
+		| OpCodeOperandKind::r32_opcode
+		| OpCodeOperandKind::r32_vvvv => register.is_gpr32(),
+		OpCodeOperandKind::r64_or_mem
+		| OpCodeOperandKind::r64_or_mem_mpx
+		| OpCodeOperandKind::r64_reg
+		| OpCodeOperandKind::r64_reg_mem
+		| OpCodeOperandKind::r64_rm
+		| OpCodeOperandKind::r64_opcode
+		| OpCodeOperandKind::r64_vvvv => register.is_gpr64(),
+		OpCodeOperandKind::mm_or_mem | OpCodeOperandKind::mm_reg | OpCodeOperandKind::mm_rm => register.is_mm(),
+		OpCodeOperandKind::xmm_or_mem
+		| OpCodeOperandKind::xmm_reg
+		| OpCodeOperandKind::xmm_rm
+		| OpCodeOperandKind::xmm_vvvv
+		| OpCodeOperandKind::xmm_is4
+		| OpCodeOperandKind::xmm_is5 => register.is_xmm(),
+		OpCodeOperandKind::xmmp3_vvvv => register.is_xmm() && ((register as u32 - Register::XMM0 as u32) & 3) == 0,
+		OpCodeOperandKind::ymm_or_mem
+		| OpCodeOperandKind::ymm_reg
+		| OpCodeOperandKind::ymm_rm
+		| OpCodeOperandKind::ymm_vvvv
+		| OpCodeOperandKind::ymm_is4
+		| OpCodeOperandKind::ymm_is5 => register.is_ymm(),
+		OpCodeOperandKind::zmm_or_mem | OpCodeOperandKind::zmm_reg | OpCodeOperandKind::zmm_rm | OpCodeOperandKind::zmm_vvvv => register.is_zmm(),
+		OpCodeOperandKind::zmmp3_vvvv => register.is_zmm() && ((register as u32 - Register::ZMM0 as u32) & 3) == 0,
+		OpCodeOperandKind::k_or_mem | OpCodeOperandKind::k_reg | OpCodeOperandKind::k_rm | OpCodeOperandKind::k_vvvv => register.is_k(),
+		OpCodeOperandKind::kp1_reg => register.is_k() && ((register as u32 - Register::K0 as u32) & 1) == 0,
+		OpCodeOperandKind::bnd_or_mem_mpx | OpCodeOperandKind::bnd_reg => register.is_bnd(),
+		OpCodeOperandKind::seg_reg => register.is_segment_register(),
+		OpCodeOperandKind::cr_reg => register.is_cr(),
+		OpCodeOperandKind::dr_reg => register.is_dr(),
+		OpCodeOperandKind::tr_reg => register.is_tr(),
+		OpCodeOperandKind::sti_opcode => register.is_st(),
+		OpCodeOperandKind::es => register == Register::ES,
+		OpCodeOperandKind::cs => register == Register::CS,
+		OpCodeOperandKind::ss => register == Register::SS,
+		OpCodeOperandKind::ds => register == Register::DS,
+		OpCodeOperandKind::fs => register == Register::FS,
+		OpCodeOperandKind::gs => register == Register::GS,
+		OpCodeOperandKind::al => register == Register::AL,
+		OpCodeOperandKind::cl => register == Register::CL,
+		OpCodeOperandKind::ax => register == Register::AX,
+		OpCodeOperandKind::dx => register == Register::DX,
+		OpCodeOperandKind::eax => register == Register::EAX,
+		OpCodeOperandKind::rax => register == Register::RAX,
+		OpCodeOperandKind::st0 => register == Register::ST0,
+		_ => false,
+	}
+}
+
+// Returns the register's index in its register class, eg. 9 for R9D and XMM9, or 0 if it's not a GPR, vector, CR or DR register.
+// The 8-bit GPRs are indexed AL, CL, DL, BL, AH, CH, DH, BH, SPL, BPL, SIL, DIL, R8L, ..., R15L.
+pub(super) fn register_index(register: Register) -> u32 {
+	let first = if register.is_gpr8() {
+		Register::AL
+	} else if register.is_gpr16() {
+		Register::AX
+	} else if register.is_gpr32() {
+		Register::EAX
+	} else if register.is_gpr64() {
+		Register::RAX
+	} else if register.is_xmm() {
+		Register::XMM0
+	} else if register.is_ymm() {
+		Register::YMM0
+	} else if register.is_zmm() {
+		Register::ZMM0
+	} else if register.is_cr() {
+		Register::CR0
+	} else if register.is_dr() {
+		Register::DR0
+	} else {
+		return 0;
+	};
+	register as u32 - first as u32
+}
+
+// Checks if an instruction with encoding `encoding` can encode `register` in `bitness`-bit mode. Registers that
+// need a REX prefix (or VEX/XOP/EVEX extension bits), eg. SPL, R8D and XMM8, can only be used in 64-bit mode and
+// vector registers 16-31 can only be encoded by EVEX. CR8 is an exception, it's encoded with a LOCK prefix in 16/32-bit mode.
+pub(super) fn can_encode_register(encoding: EncodingKind, bitness: u32, register: Register) -> bool {
+	if register == Register::CR8 {
+		return true;
+	}
+	let index = register_index(register);
+	if index >= 16 && register.is_vector_register() && encoding != EncodingKind::EVEX {
+		return false;
+	}
+	index < 8 || bitness == 64
+}
+
+pub(super) fn is_memory(kind: OpCodeOperandKind) -> bool {
+	match kind {
+		OpCodeOperandKind::mem_offs
+		| OpCodeOperandKind::mem
+		| OpCodeOperandKind::mem_mpx
+		| OpCodeOperandKind::mem_mib
+		| OpCodeOperandKind::mem_vsib32x
+		| OpCodeOperandKind::mem_vsib64x
+		| OpCodeOperandKind::mem_vsib32y
+		| OpCodeOperandKind::mem_vsib64y
+		| OpCodeOperandKind::mem_vsib32z
+		| OpCodeOperandKind::mem_vsib64z
+		| OpCodeOperandKind::r8_or_mem
+		| OpCodeOperandKind::r16_or_mem
+		| OpCodeOperandKind::r32_or_mem
+		| OpCodeOperandKind::r32_or_mem_mpx
+		| OpCodeOperandKind::r64_or_mem
+		| OpCodeOperandKind::r64_or_mem_mpx
+		| OpCodeOperandKind::mm_or_mem
+		| OpCodeOperandKind::xmm_or_mem
+		| OpCodeOperandKind::ymm_or_mem
+		| OpCodeOperandKind::zmm_or_mem
+		| OpCodeOperandKind::bnd_or_mem_mpx
+		| OpCodeOperandKind::k_or_mem
+		| OpCodeOperandKind::seg_rSI
+		| OpCodeOperandKind::es_rDI
+		| OpCodeOperandKind::seg_rDI
+		| OpCodeOperandKind::seg_rBX_al => true,
+		_ => false,
+	}
+}
+
+// Returns the size in bytes of the operand's immediate, displacement or branch target
+fn immediate_size(kind: OpCodeOperandKind) -> u32 {
+	match kind {
+		OpCodeOperandKind::imm2_m2z
+		| OpCodeOperandKind::imm8
+		| OpCodeOperandKind::imm8sex16
+		| OpCodeOperandKind::imm8sex32
+		| OpCodeOperandKind::imm8sex64
+		| OpCodeOperandKind::xmm_is4
+		| OpCodeOperandKind::xmm_is5
+		| OpCodeOperandKind::ymm_is4
+		| OpCodeOperandKind::ymm_is5
+		| OpCodeOperandKind::br16_1
+		| OpCodeOperandKind::br32_1
+		| OpCodeOperandKind::br64_1 => 1,
+		OpCodeOperandKind::imm16 | OpCodeOperandKind::br16_2 | OpCodeOperandKind::xbegin_2 | OpCodeOperandKind::brdisp_2 => 2,
+		OpCodeOperandKind::imm32
+		| OpCodeOperandKind::imm32sex64
+		| OpCodeOperandKind::br32_4
+		| OpCodeOperandKind::br64_4
+		| OpCodeOperandKind::xbegin_4
+		| OpCodeOperandKind::brdisp_4
+		| OpCodeOperandKind::farbr2_2 => 4,
+		OpCodeOperandKind::farbr4_2 => 6,
+		OpCodeOperandKind::imm64 => 8,
+		_ => 0,
+	}
+}
+
+fn matches(op_code: &OpCodeInfo, bitness: u32, kind: OpCodeOperandKind, operand: OpCodeOperand) -> bool {
+	match operand {
+		OpCodeOperand::Register(register) => matches_register(kind, register) && can_encode_register(op_code.encoding(), bitness, register),
+		OpCodeOperand::Memory(size) => {
+			is_memory(kind) && (size == 0 || instruction_memory_sizes::SIZES[op_code.code() as usize].size() == size as usize)
+		}
+		OpCodeOperand::Broadcast(size) => {
+			is_memory(kind)
+				&& op_code.can_broadcast()
+				&& (size == 0
+					|| instruction_memory_sizes::SIZES[op_code.code() as usize + IcedConstants::NUMBER_OF_CODE_VALUES].size() == size as usize)
+		}
+		OpCodeOperand::Immediate(bits) => match kind {
+			OpCodeOperandKind::imm2_m2z
+			| OpCodeOperandKind::imm8
+			| OpCodeOperandKind::imm8sex16
+			| OpCodeOperandKind::imm8sex32
+			| OpCodeOperandKind::imm8sex64
+			| OpCodeOperandKind::imm16
+			| OpCodeOperandKind::imm32
+			| OpCodeOperandKind::imm32sex64
+			| OpCodeOperandKind::imm64 => bits <= immediate_size(kind) * 8,
+			_ => false,
+		},
+		OpCodeOperand::ImmediateOne => kind == OpCodeOperandKind::imm8_const_1,
+		OpCodeOperand::Branch => match kind {
+			OpCodeOperandKind::br16_1
+			| OpCodeOperandKind::br32_1
+			| OpCodeOperandKind::br64_1
+			| OpCodeOperandKind::br16_2
+			| OpCodeOperandKind::br32_4
+			| OpCodeOperandKind::br64_4
+			| OpCodeOperandKind::xbegin_2
+			| OpCodeOperandKind::xbegin_4
+			| OpCodeOperandKind::brdisp_2
+			| OpCodeOperandKind::brdisp_4 => true,
+			_ => false,
+		},
+		OpCodeOperand::FarBranch => kind == OpCodeOperandKind::farbr2_2 || kind == OpCodeOperandKind::farbr4_2,
+	}
+}
+
+// Estimates the length of the shortest instruction using this op code. Displacements aren't included.
+fn estimated_length(op_code: &OpCodeInfo, bitness: u32) -> u32 {
+	let mut length = match op_code.encoding() {
+		EncodingKind::Legacy => {
+			let mut length = match op_code.table() {
+				OpCodeTableKind::Normal => 1,
+				OpCodeTableKind::T0F => 2,
+				_ => 3,
+			};
+			match op_code.mandatory_prefix() {
+				MandatoryPrefix::P66 | MandatoryPrefix::PF3 | MandatoryPrefix::PF2 => length += 1,
+				_ => {}
+			}
+			match (op_code.operand_size(), bitness) {
+				(16, 32) | (16, 64) | (32, 16) | (64, _) => length += 1,
+				_ => {}
+			}
+			match (op_code.address_size(), bitness) {
+				(16, 32) | (32, 16) | (32, 64) => length += 1,
+				_ => {}
+			}
+			if op_code.fwait() {
+				length += 1;
+			}
+			length
+		}
+		// VEX2 can only be used if the table is 0F and W isn't needed
+		EncodingKind::VEX => {
+			if op_code.table() == OpCodeTableKind::T0F && (op_code.w() == 0 || op_code.is_wig()) {
+				3
+			} else {
+				4
+			}
+		}
+		EncodingKind::EVEX => 5,
+		EncodingKind::XOP => 4,
+		EncodingKind::D3NOW => 3,
+	};
+	let mut has_modrm = op_code.encoding() == EncodingKind::D3NOW;
+	for i in 0..op_code.op_count() {
+		let kind = op_code.op_kind(i);
+		length += immediate_size(kind);
+		match kind {
+			OpCodeOperandKind::mem_offs => length += if op_code.address_size() != 0 { op_code.address_size() / 8 } else { bitness / 8 },
+			// Implicit memory operands (string instructions)
+			OpCodeOperandKind::seg_rSI | OpCodeOperandKind::es_rDI | OpCodeOperandKind::seg_rDI | OpCodeOperandKind::seg_rBX_al => {}
+			OpCodeOperandKind::r8_reg
+			| OpCodeOperandKind::r16_reg
+			| OpCodeOperandKind::r16_reg_mem
+			| OpCodeOperandKind::r16_rm
+			| OpCodeOperandKind::r32_reg
+			| OpCodeOperandKind::r32_reg_mem
+			| OpCodeOperandKind::r32_rm
+			| OpCodeOperandKind::r64_reg
+			| OpCodeOperandKind::r64_reg_mem
+			| OpCodeOperandKind::r64_rm
+			| OpCodeOperandKind::seg_reg
+			| OpCodeOperandKind::k_reg
+			| OpCodeOperandKind::kp1_reg
+			| OpCodeOperandKind::k_rm
+			| OpCodeOperandKind::mm_reg
+			| OpCodeOperandKind::mm_rm
+			| OpCodeOperandKind::xmm_reg
+			| OpCodeOperandKind::xmm_rm
+			| OpCodeOperandKind::ymm_reg
+			| OpCodeOperandKind::ymm_rm
+			| OpCodeOperandKind::zmm_reg
+			| OpCodeOperandKind::zmm_rm
+			| OpCodeOperandKind::cr_reg
+			| OpCodeOperandKind::dr_reg
+			| OpCodeOperandKind::tr_reg
+			| OpCodeOperandKind::bnd_reg => has_modrm = true,
+			_ => {
+				if is_memory(kind) {
+					has_modrm = true;
+				}
+			}
+		}
+	}
+	if has_modrm || op_code.is_group() {
+		length += 1;
+	}
+	length
+}
+
+impl OpCodeInfo {
+	/// Finds all op codes with mnemonic `mnemonic` that can be used in `bitness`-bit mode and that have operands
+	/// matching `operands`. The result is sorted by the estimated instruction length, shortest first. Codes with the
+	/// same estimated length are sorted by their [`Code`] value.
+	///
+	/// [`Code`]: enum.Code.html
+	///
+	/// # Panics
+	///
+	/// Panics if `bitness` is not one of 16, 32, 64.
+	///
+	/// # Arguments
+	///
+	/// * `mnemonic`: Mnemonic
+	/// * `bitness`: 16, 32 or 64
+	/// * `operands`: All explicit operands, see [`OpCodeOperand`]
+	/// * `op_mask`: `true` if an opmask register (`k1`-`k7`) is used. If `false`, op codes that require an opmask
+	/// register aren't returned.
+	///
+	/// [`OpCodeOperand`]: enum.OpCodeOperand.html
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // add eax,5
+	/// let op_codes = OpCodeInfo::find(Mnemonic::Add, 64, &[OpCodeOperand::Register(Register::EAX), OpCodeOperand::Immediate(8)], false);
+	/// assert_eq!(Code::Add_rm32_imm8, op_codes[0].code());
+	/// assert!(op_codes.iter().any(|op_code| op_code.code() == Code::Add_EAX_imm32));
+	/// assert!(op_codes.iter().any(|op_code| op_code.code() == Code::Add_rm32_imm32));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn find(mnemonic: Mnemonic, bitness: u32, operands: &[OpCodeOperand], op_mask: bool) -> Vec<&'static OpCodeInfo> {
+		match bitness {
+			16 | 32 | 64 => {}
+			_ => panic!(),
+		}
+		let mut result: Vec<(u32, &'static OpCodeInfo)> = CODES_BY_MNEMONIC[mnemonic as usize]
+			.iter()
+			.map(|&code| &OP_CODE_INFO_TBL[code as usize])
+			.filter(|op_code| {
+				op_code.is_available_in_mode(bitness)
+					&& op_code.op_count() as usize == operands.len()
+					&& (if op_mask { op_code.can_use_op_mask_register() } else { !op_code.require_non_zero_op_mask_register() })
+					&& operands.iter().enumerate().all(|(i, &operand)| matches(op_code, bitness, op_code.op_kind(i as u32), operand))
+			})
+			.map(|op_code| (estimated_length(op_code, bitness), op_code))
+			.collect();
+		result.sort_by_key(|&(length, _)| length);
+		result.into_iter().map(|(_, op_code)| op_code).collect()
+	}
+}
@@ -0,0 +1,310 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::handlers_table::HANDLERS_TABLE;
+use super::op_code_query::{is_memory, matches_register};
+use core::{fmt, i32, i64, u16, u32, u8};
+#[cfg(feature = "std")]
+use std::error;
+
+/// An operand passed to [`Instruction::try_new()`]
+///
+/// [`Instruction::try_new()`]: struct.Instruction.html#method.try_new
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Operand {
+	/// A register
+	Register(Register),
+	/// A memory operand. It's also used by the implicit memory operands of the string instructions, eg. `[rsi]` of `LODSB`.
+	Memory(MemoryOperand),
+	/// An immediate value. Negative values are passed as sign extended `u64` values, eg. `-1i64 as u64`.
+	Immediate(u64),
+	/// A near branch target
+	Branch(u64),
+	/// A far branch target (`selector`, `offset`)
+	FarBranch(u16, u32),
+}
+
+/// The error returned by [`Instruction::try_new()`]
+///
+/// [`Instruction::try_new()`]: struct.Instruction.html#method.try_new
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CreateInstructionError {
+	/// The code isn't an instruction, eg. [`Code::INVALID`] or [`Code::DeclareByte`]
+	///
+	/// [`Code::INVALID`]: enum.Code.html#variant.INVALID
+	/// [`Code::DeclareByte`]: enum.Code.html#variant.DeclareByte
+	InvalidCode,
+	/// Wrong number of operands
+	WrongOperandCount {
+		/// Number of operands the code has
+		expected: u32,
+		/// Number of operands that were passed in
+		actual: u32,
+	},
+	/// The operand can't be used by this code, eg. a memory operand was used but the code only supports registers
+	InvalidOperand {
+		/// Operand index
+		operand: u32,
+	},
+	/// The immediate or branch target doesn't fit in the operand
+	ValueOutOfRange {
+		/// Operand index
+		operand: u32,
+	},
+}
+
+impl fmt::Display for CreateInstructionError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			CreateInstructionError::InvalidCode => write!(f, "The code isn't an instruction"),
+			CreateInstructionError::WrongOperandCount { expected, actual } => write!(f, "Expected {} operands, got {}", expected, actual),
+			CreateInstructionError::InvalidOperand { operand } => write!(f, "Operand {}: Invalid operand", operand),
+			CreateInstructionError::ValueOutOfRange { operand } => write!(f, "Operand {}: Value is out of range", operand),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl error::Error for CreateInstructionError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn description(&self) -> &str {
+		match *self {
+			CreateInstructionError::InvalidCode => "The code isn't an instruction",
+			CreateInstructionError::WrongOperandCount { .. } => "Wrong number of operands",
+			CreateInstructionError::InvalidOperand { .. } => "Invalid operand",
+			CreateInstructionError::ValueOutOfRange { .. } => "Value is out of range",
+		}
+	}
+}
+
+// Checks if the immediate can be stored in an operand with the given immediate kind. Both the zero extended and the
+// sign extended (`u64`) values of negative numbers are accepted.
+fn immediate_fits(op_kind: OpKind, value: u64) -> bool {
+	let signed = value as i64;
+	match op_kind {
+		OpKind::Immediate8 | OpKind::Immediate8_2nd => value <= u8::MAX as u64 || (-0x80 <= signed && signed < 0),
+		OpKind::Immediate8to16 => value <= 0x7F || (0xFF80 <= value && value <= 0xFFFF) || (-0x80 <= signed && signed < 0),
+		OpKind::Immediate8to32 => value <= 0x7F || (0xFFFF_FF80 <= value && value <= 0xFFFF_FFFF) || (-0x80 <= signed && signed < 0),
+		OpKind::Immediate8to64 => -0x80 <= signed && signed <= 0x7F,
+		OpKind::Immediate16 => value <= u16::MAX as u64 || (-0x8000 <= signed && signed < 0),
+		OpKind::Immediate32 => value <= u32::MAX as u64 || (i32::MIN as i64 <= signed && signed < 0),
+		OpKind::Immediate32to64 => i32::MIN as i64 <= signed && signed <= i32::MAX as i64,
+		OpKind::Immediate64 => true,
+		_ => false,
+	}
+}
+
+fn set_immediate(instruction: &mut Instruction, operand: u32, op_kind: OpKind, value: u64) {
+	instruction.set_op_kind(operand, op_kind);
+	match op_kind {
+		OpKind::Immediate8 | OpKind::Immediate8to16 | OpKind::Immediate8to32 | OpKind::Immediate8to64 => instruction.set_immediate8(value as u8),
+		OpKind::Immediate8_2nd => instruction.set_immediate8_2nd(value as u8),
+		OpKind::Immediate16 => instruction.set_immediate16(value as u16),
+		OpKind::Immediate32 | OpKind::Immediate32to64 => instruction.set_immediate32(value as u32),
+		_ => instruction.set_immediate64(value),
+	}
+}
+
+fn set_memory(instruction: &mut Instruction, memory: &MemoryOperand) {
+	instruction.set_memory_base(memory.base);
+	instruction.set_memory_index(memory.index);
+	instruction.set_memory_index_scale(memory.scale);
+	instruction.set_memory_displ_size(memory.displ_size);
+	instruction.set_memory_displacement(memory.displacement as u32);
+	instruction.set_is_broadcast(memory.is_broadcast);
+	instruction.set_segment_prefix(memory.segment_prefix);
+}
+
+// Gets the op kind of a string instruction's implicit memory operand or `None` if the base register can't be used
+fn string_memory_op_kind(kind: OpCodeOperandKind, memory: &MemoryOperand) -> Option<OpKind> {
+	if memory.index != Register::None || memory.displacement != 0 || memory.is_broadcast {
+		return None;
+	}
+	let op_kinds = match kind {
+		OpCodeOperandKind::seg_rSI => {
+			[(Register::SI, OpKind::MemorySegSI), (Register::ESI, OpKind::MemorySegESI), (Register::RSI, OpKind::MemorySegRSI)]
+		}
+		OpCodeOperandKind::seg_rDI => {
+			[(Register::DI, OpKind::MemorySegDI), (Register::EDI, OpKind::MemorySegEDI), (Register::RDI, OpKind::MemorySegRDI)]
+		}
+		OpCodeOperandKind::es_rDI => {
+			// The segment is always ES
+			if memory.segment_prefix != Register::None && memory.segment_prefix != Register::ES {
+				return None;
+			}
+			[(Register::DI, OpKind::MemoryESDI), (Register::EDI, OpKind::MemoryESEDI), (Register::RDI, OpKind::MemoryESRDI)]
+		}
+		_ => return None,
+	};
+	op_kinds.iter().find(|&&(base, _)| base == memory.base).map(|&(_, op_kind)| op_kind)
+}
+
+pub(super) fn is_valid_index(kind: OpCodeOperandKind, index: Register) -> bool {
+	match kind {
+		OpCodeOperandKind::mem_vsib32x | OpCodeOperandKind::mem_vsib64x => index.is_xmm(),
+		OpCodeOperandKind::mem_vsib32y | OpCodeOperandKind::mem_vsib64y => index.is_ymm(),
+		OpCodeOperandKind::mem_vsib32z | OpCodeOperandKind::mem_vsib64z => index.is_zmm(),
+		_ => !index.is_vector_register(),
+	}
+}
+
+impl Instruction {
+	/// Creates an instruction. All operands are verified, see [`CreateInstructionError`] for the possible errors.
+	/// The `with_*()` methods can be used if you know the operands at compile time.
+	///
+	/// Use [`with_xbegin()`] to create `XBEGIN` instructions and `with_movsb()`, `with_stosd()`, etc to add
+	/// `REP` prefixes to string instructions.
+	///
+	/// [`CreateInstructionError`]: enum.CreateInstructionError.html
+	/// [`with_xbegin()`]: #method.with_xbegin
+	///
+	/// # Errors
+	///
+	/// Fails if `code` isn't an instruction, if the number of operands is wrong, or if an operand can't be used by `code`.
+	///
+	/// # Arguments
+	///
+	/// * `code`: Code value
+	/// * `operands`: All operands
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// let instruction = Instruction::try_new(Code::Add_rm32_imm8, &[Operand::Register(Register::ECX), Operand::Immediate(-5i64 as u64)]).unwrap();
+	/// assert_eq!(Register::ECX, instruction.op0_register());
+	/// assert_eq!(OpKind::Immediate8to32, instruction.op1_kind());
+	/// assert_eq!(-5, instruction.immediate8to32());
+	///
+	/// assert_eq!(
+	///     Err(CreateInstructionError::ValueOutOfRange { operand: 1 }),
+	///     Instruction::try_new(Code::Add_rm32_imm8, &[Operand::Register(Register::ECX), Operand::Immediate(0x80)])
+	/// );
+	/// assert_eq!(
+	///     Err(CreateInstructionError::InvalidOperand { operand: 0 }),
+	///     Instruction::try_new(Code::Add_rm32_imm8, &[Operand::Register(Register::CX), Operand::Immediate(1)])
+	/// );
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn try_new(code: Code, operands: &[Operand]) -> Result<Instruction, CreateInstructionError> {
+		let op_code = code.op_code();
+		if !op_code.is_instruction() {
+			return Err(CreateInstructionError::InvalidCode);
+		}
+		if operands.len() != op_code.op_count() as usize {
+			return Err(CreateInstructionError::WrongOperandCount { expected: op_code.op_count(), actual: operands.len() as u32 });
+		}
+		let handler_operands = &HANDLERS_TABLE[code as usize].operands;
+
+		let mut instruction = Instruction::default();
+		instruction.set_code(code);
+		for (i, operand) in operands.iter().enumerate() {
+			let index = i as u32;
+			let kind = op_code.op_kind(index);
+			let invalid_operand = CreateInstructionError::InvalidOperand { operand: index };
+			match *operand {
+				Operand::Register(register) => {
+					if !matches_register(kind, register) {
+						return Err(invalid_operand);
+					}
+					instruction.set_op_kind(index, OpKind::Register);
+					instruction.set_op_register(index, register);
+				}
+
+				Operand::Memory(ref memory) => match kind {
+					OpCodeOperandKind::seg_rSI | OpCodeOperandKind::seg_rDI | OpCodeOperandKind::es_rDI => {
+						match string_memory_op_kind(kind, memory) {
+							Some(op_kind) => {
+								instruction.set_op_kind(index, op_kind);
+								if kind != OpCodeOperandKind::es_rDI {
+									instruction.set_segment_prefix(memory.segment_prefix);
+								}
+							}
+							None => return Err(invalid_operand),
+						}
+					}
+					_ => {
+						if !is_memory(kind) || !is_valid_index(kind, memory.index) || (memory.is_broadcast && !op_code.can_broadcast()) {
+							return Err(invalid_operand);
+						}
+						instruction.set_op_kind(index, OpKind::Memory);
+						set_memory(&mut instruction, memory);
+					}
+				},
+
+				Operand::Immediate(value) => {
+					let op_kind = match handler_operands.get(i).and_then(|op| op.immediate_op_kind()) {
+						Some(op_kind) => op_kind,
+						None => return Err(invalid_operand),
+					};
+					let fits = match kind {
+						OpCodeOperandKind::imm8_const_1 => value == 1,
+						OpCodeOperandKind::imm2_m2z => value <= 3,
+						_ => immediate_fits(op_kind, value),
+					};
+					if !fits {
+						return Err(CreateInstructionError::ValueOutOfRange { operand: index });
+					}
+					set_immediate(&mut instruction, index, op_kind, value);
+				}
+
+				Operand::Branch(target) => {
+					let op_kind = match kind {
+						// with_xbegin() must be used since the op kind depends on the bitness
+						OpCodeOperandKind::xbegin_2 | OpCodeOperandKind::xbegin_4 => return Err(invalid_operand),
+						_ => match handler_operands.get(i).and_then(|op| op.near_branch_op_kind()) {
+							Some(op_kind) => op_kind,
+							None => return Err(invalid_operand),
+						},
+					};
+					instruction.set_op_kind(index, op_kind);
+					match op_kind {
+						OpKind::NearBranch16 if target <= u16::MAX as u64 => instruction.set_near_branch16(target as u16),
+						OpKind::NearBranch32 if target <= u32::MAX as u64 => instruction.set_near_branch32(target as u32),
+						OpKind::NearBranch64 => instruction.set_near_branch64(target),
+						_ => return Err(CreateInstructionError::ValueOutOfRange { operand: index }),
+					}
+				}
+
+				Operand::FarBranch(selector, offset) => {
+					let op_kind = match handler_operands.get(i).and_then(|op| op.far_branch_op_kind()) {
+						Some(op_kind) => op_kind,
+						None => return Err(invalid_operand),
+					};
+					instruction.set_op_kind(index, op_kind);
+					match op_kind {
+						OpKind::FarBranch16 if offset <= u16::MAX as u32 => instruction.set_far_branch16(offset as u16),
+						OpKind::FarBranch32 => instruction.set_far_branch32(offset),
+						_ => return Err(CreateInstructionError::ValueOutOfRange { operand: index }),
+					}
+					instruction.set_far_branch_selector(selector);
+				}
+			}
+		}
+		debug_assert_eq!(operands.len() as u32, instruction.op_count());
+		Ok(instruction)
+	}
+}
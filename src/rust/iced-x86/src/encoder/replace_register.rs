@@ -0,0 +1,251 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::op_code_query::{can_encode_register, matches_register, register_index};
+use super::validate::{is_high_byte_register, is_valid_memory16, needs_rex_prefix};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+
+/// The error returned by [`Instruction::replace_register()`]
+///
+/// [`Instruction::replace_register()`]: struct.Instruction.html#method.replace_register
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ReplaceRegisterError {
+	/// The registers aren't the same kind of register, eg. `RAX` and `XMM0`
+	IncompatibleRegisters,
+	/// The register is used implicitly by the instruction, eg. `RCX` in `REP MOVSB`
+	ImplicitRegister(Register),
+	/// The register can't be replaced. It's a fixed operand (eg. `AL` in `ADD AL,imm8`), there's no sub register
+	/// with the same size (eg. `AH` -> `SIL`), the new register can't be used by the operand (eg. `RSP` as an index register,
+	/// `XMM17` in a `VEX` instruction or `[AX+SI]`), or the new register needs a `REX` prefix and the instruction uses `AH`-`BH`.
+	InvalidRegister(Register),
+}
+
+impl fmt::Display for ReplaceRegisterError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ReplaceRegisterError::IncompatibleRegisters => write!(f, "The registers aren't the same kind of register"),
+			ReplaceRegisterError::ImplicitRegister(register) => write!(f, "Register {:?} is used implicitly", register),
+			ReplaceRegisterError::InvalidRegister(register) => write!(f, "Register {:?} can't be replaced", register),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ReplaceRegisterError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn description(&self) -> &str {
+		match *self {
+			ReplaceRegisterError::IncompatibleRegisters => "The registers aren't the same kind of register",
+			ReplaceRegisterError::ImplicitRegister(_) => "The register is used implicitly",
+			ReplaceRegisterError::InvalidRegister(_) => "The register can't be replaced",
+		}
+	}
+}
+
+// Gets the new register if `register` is part of `from` (a full register), eg. `AH` -> `BH` if `from` = `RAX` and `to` = `RBX`
+fn replace(register: Register, from: Register, to: Register) -> Result<Option<Register>, ReplaceRegisterError> {
+	if register == Register::None || register.full_register() != from {
+		return Ok(None);
+	}
+	let is_high_byte = Register::AH <= register && register <= Register::BH;
+	match to.with_size(register.size(), is_high_byte) {
+		Some(new_register) => Ok(Some(new_register)),
+		None => Err(ReplaceRegisterError::InvalidRegister(register)),
+	}
+}
+
+// Returns the bitness of the instruction's mode or 64 (which accepts the most registers) if it's not known
+fn bitness(instruction: &Instruction) -> u32 {
+	match instruction.code_size() {
+		CodeSize::Code16 => 16,
+		CodeSize::Code32 => 32,
+		_ => 64,
+	}
+}
+
+fn has_high_byte_register(instruction: &Instruction) -> bool {
+	(0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Register && is_high_byte_register(instruction.op_register(i)))
+}
+
+impl Instruction {
+	/// Replaces all uses of `from` with `to` in the operands, the memory operand's base and index registers, the op mask
+	/// register and the segment prefix. All registers that are part of the same full register are replaced, eg. if `from`
+	/// is `RAX` and `to` is `RBX`, then `EAX` is replaced with `EBX` and `AL` is replaced with `BL`. XMM, YMM and ZMM
+	/// registers are also replaced, eg. `XMM1` -> `XMM2` also replaces `ZMM1` with `ZMM2`.
+	///
+	/// Returns the number of replaced registers. The instruction isn't modified if an error is returned.
+	///
+	/// The registers must be valid in the instruction's mode (see [`code_size()`]). 64-bit mode is used if it's not known.
+	/// It's not checked whether `to` is already used by the instruction.
+	///
+	/// # Errors
+	///
+	/// Fails if the registers aren't the same kind of register, if `from` is used implicitly by the instruction (see
+	/// [`InstructionInfo::used_registers()`]) or if a register can't be replaced. See [`ReplaceRegisterError`].
+	///
+	/// [`InstructionInfo::used_registers()`]: struct.InstructionInfo.html#method.used_registers
+	/// [`ReplaceRegisterError`]: enum.ReplaceRegisterError.html
+	/// [`code_size()`]: #method.code_size
+	///
+	/// # Arguments
+	///
+	/// * `from`: The register to replace, eg. `RAX` or `AL`
+	/// * `to`: The new register, eg. `RBX` or `BL`
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // add [rax+rcx*4],eax
+	/// let memory = MemoryOperand::with_base_index_scale(Register::RAX, Register::RCX, 4);
+	/// let mut instruction = Instruction::with_mem_reg(Code::Add_rm32_r32, &memory, Register::EAX);
+	/// assert_eq!(Ok(2), instruction.replace_register(Register::RAX, Register::RDX));
+	/// assert_eq!(Register::RDX, instruction.memory_base());
+	/// assert_eq!(Register::EDX, instruction.op1_register());
+	///
+	/// // RCX is used implicitly by REP MOVSB
+	/// let mut instruction = Instruction::with_rep_movsb(64);
+	/// assert_eq!(Err(ReplaceRegisterError::ImplicitRegister(Register::RCX)), instruction.replace_register(Register::RCX, Register::RDX));
+	///
+	/// // AL is a fixed operand
+	/// let mut instruction = Instruction::with_reg_u32(Code::Add_AL_imm8, Register::AL, 1);
+	/// assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::AL)), instruction.replace_register(Register::RAX, Register::RBX));
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn replace_register(&mut self, from: Register, to: Register) -> Result<u32, ReplaceRegisterError> {
+		let from = from.full_register();
+		let to = to.full_register();
+		if from == Register::None || to == Register::None || from.base() != to.base() {
+			return Err(ReplaceRegisterError::IncompatibleRegisters);
+		}
+		if from == to {
+			return Ok(0);
+		}
+
+		let mut instruction = *self;
+		let mut count = 0;
+		let op_code = self.code().op_code();
+		let bitness = bitness(self);
+		let can_encode = |register: Register| can_encode_register(op_code.encoding(), bitness, register);
+		// The first replaced register that now needs a REX prefix
+		let mut rex_register = None;
+		for i in 0..self.op_count() {
+			match self.op_kind(i) {
+				OpKind::Register => {
+					let register = self.op_register(i);
+					if let Some(new_register) = replace(register, from, to)? {
+						// Don't verify invalid instructions, eg. decoded instructions with an unaligned xmm+3 register
+						let kind = op_code.op_kind(i);
+						let is_valid = |register: Register| matches_register(kind, register) && can_encode(register);
+						if is_valid(register) && !is_valid(new_register) {
+							return Err(ReplaceRegisterError::InvalidRegister(register));
+						}
+						if rex_register.is_none() && register_index(register) < 8 && register_index(new_register) >= 8 {
+							rex_register = Some(register);
+						}
+						instruction.set_op_register(i, new_register);
+						count += 1;
+					}
+				}
+				OpKind::Memory => {
+					let base = self.memory_base();
+					let index = self.memory_index();
+					if op_code.op_kind(i) == OpCodeOperandKind::seg_rBX_al {
+						if base.full_register() == from || index.full_register() == from {
+							return Err(ReplaceRegisterError::ImplicitRegister(if base.full_register() == from { base } else { index }));
+						}
+						continue;
+					}
+					if let Some(new_base) = replace(base, from, to)? {
+						if can_encode(base) && !can_encode(new_base) {
+							return Err(ReplaceRegisterError::InvalidRegister(base));
+						}
+						if rex_register.is_none() && register_index(base) < 8 && register_index(new_base) >= 8 {
+							rex_register = Some(base);
+						}
+						instruction.set_memory_base(new_base);
+						count += 1;
+					}
+					if let Some(new_index) = replace(index, from, to)? {
+						if new_index == Register::SP
+							|| new_index == Register::ESP
+							|| new_index == Register::RSP
+							|| (can_encode(index) && !can_encode(new_index))
+						{
+							return Err(ReplaceRegisterError::InvalidRegister(index));
+						}
+						if rex_register.is_none() && register_index(index) < 8 && register_index(new_index) >= 8 {
+							rex_register = Some(index);
+						}
+						instruction.set_memory_index(new_index);
+						count += 1;
+					}
+					// Only some 16-bit base and index register pairs can be encoded
+					let scale = self.memory_index_scale();
+					if (base.is_gpr16() || index.is_gpr16())
+						&& is_valid_memory16(base, index, scale)
+						&& !is_valid_memory16(instruction.memory_base(), instruction.memory_index(), scale)
+					{
+						return Err(ReplaceRegisterError::InvalidRegister(if instruction.memory_base() != base { base } else { index }));
+					}
+				}
+				_ => {}
+			}
+		}
+		let op_mask = self.op_mask();
+		if let Some(new_op_mask) = replace(op_mask, from, to)? {
+			if new_op_mask == Register::K0 {
+				return Err(ReplaceRegisterError::InvalidRegister(op_mask));
+			}
+			instruction.set_op_mask(new_op_mask);
+			count += 1;
+		}
+		if let Some(new_segment_prefix) = replace(self.segment_prefix(), from, to)? {
+			instruction.set_segment_prefix(new_segment_prefix);
+			count += 1;
+		}
+		// AH, CH, DH and BH can't be encoded if the instruction has a REX prefix
+		if let Some(rex_register) = rex_register {
+			if has_high_byte_register(&instruction) && needs_rex_prefix(&instruction, op_code) && !needs_rex_prefix(self, op_code) {
+				return Err(ReplaceRegisterError::InvalidRegister(rex_register));
+			}
+		}
+
+		// All explicit uses have been replaced so any remaining uses of `from` are implicit
+		let mut factory = InstructionInfoFactory::new();
+		let info = factory.info_options(&instruction, InstructionInfoOptions::NO_MEMORY_USAGE);
+		for used_register in info.used_registers() {
+			if used_register.register().full_register() == from {
+				return Err(ReplaceRegisterError::ImplicitRegister(used_register.register()));
+			}
+		}
+
+		*self = instruction;
+		Ok(count)
+	}
+}
@@ -0,0 +1,149 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::*;
+
+#[test]
+fn try_new_matches_with_methods() {
+	let memory = MemoryOperand::with_base_index(Register::RAX, Register::RCX);
+	let tests = [
+		(
+			Instruction::try_new(Code::Add_rm32_imm8, &[Operand::Register(Register::ECX), Operand::Immediate(-5i64 as u64)]),
+			Instruction::with_reg_i32(Code::Add_rm32_imm8, Register::ECX, -5),
+		),
+		(
+			Instruction::try_new(Code::Mov_r64_rm64, &[Operand::Register(Register::RDX), Operand::Memory(memory)]),
+			Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RDX, &memory),
+		),
+		(
+			Instruction::try_new(Code::Jmp_rel32_64, &[Operand::Branch(0x1234_5678_9ABC)]),
+			Instruction::with_branch(Code::Jmp_rel32_64, 0x1234_5678_9ABC),
+		),
+		(
+			Instruction::try_new(Code::Jmp_ptr1632, &[Operand::FarBranch(0x1234, 0x5678_9ABC)]),
+			Instruction::with_far_branch(Code::Jmp_ptr1632, 0x1234, 0x5678_9ABC),
+		),
+		(
+			Instruction::try_new(
+				Code::Lodsb_AL_m8,
+				&[Operand::Register(Register::AL), Operand::Memory(MemoryOperand::with_base_displ_bcst_seg(Register::ESI, 0, false, Register::FS))],
+			),
+			Instruction::with_lodsb(32, Register::FS, RepPrefixKind::None),
+		),
+		(
+			Instruction::try_new(Code::Stosb_m8_AL, &[Operand::Memory(MemoryOperand::with_base(Register::RDI)), Operand::Register(Register::AL)]),
+			Instruction::with_stosb(64, RepPrefixKind::None),
+		),
+		(
+			Instruction::try_new(Code::Enterq_imm16_imm8, &[Operand::Immediate(0x1234), Operand::Immediate(0xA5)]),
+			Instruction::with_u32_u32(Code::Enterq_imm16_imm8, 0x1234, 0xA5),
+		),
+	];
+	for &(ref actual, ref expected) in tests.iter() {
+		let actual = actual.unwrap();
+		assert!(actual.eq_all_bits(expected), "{:?} != {:?}", actual, expected);
+	}
+}
+
+#[test]
+fn try_new_checks_code_and_operand_count() {
+	assert_eq!(Err(CreateInstructionError::InvalidCode), Instruction::try_new(Code::INVALID, &[]));
+	assert_eq!(Err(CreateInstructionError::InvalidCode), Instruction::try_new(Code::DeclareByte, &[]));
+	assert_eq!(
+		Err(CreateInstructionError::WrongOperandCount { expected: 2, actual: 1 }),
+		Instruction::try_new(Code::Add_rm32_r32, &[Operand::Register(Register::EAX)])
+	);
+	assert!(Instruction::try_new(Code::Nopd, &[]).is_ok());
+}
+
+#[test]
+fn try_new_checks_operand_kinds() {
+	let reg = |register| Operand::Register(register);
+	let mem = Operand::Memory(MemoryOperand::with_base(Register::RAX));
+	assert_eq!(
+		Err(CreateInstructionError::InvalidOperand { operand: 0 }),
+		Instruction::try_new(Code::Add_rm32_r32, &[reg(Register::AX), reg(Register::EAX)])
+	);
+	assert_eq!(Err(CreateInstructionError::InvalidOperand { operand: 0 }), Instruction::try_new(Code::Add_r32_rm32, &[mem, reg(Register::EAX)]));
+	assert_eq!(Err(CreateInstructionError::InvalidOperand { operand: 1 }), Instruction::try_new(Code::Add_rm32_r32, &[mem, Operand::Immediate(1)]));
+	assert_eq!(Err(CreateInstructionError::InvalidOperand { operand: 0 }), Instruction::try_new(Code::Jmp_rel32_64, &[Operand::FarBranch(1, 2)]));
+	assert_eq!(Err(CreateInstructionError::InvalidOperand { operand: 0 }), Instruction::try_new(Code::Xbegin_rel32, &[Operand::Branch(0)]));
+	assert_eq!(
+		Err(CreateInstructionError::InvalidOperand { operand: 1 }),
+		Instruction::try_new(Code::Lodsb_AL_m8, &[reg(Register::AL), Operand::Memory(MemoryOperand::with_base(Register::RDI))])
+	);
+
+	let vsib32x = MemoryOperand::with_base_index(Register::RAX, Register::XMM1);
+	let vsib32y = MemoryOperand::with_base_index(Register::RAX, Register::YMM1);
+	assert!(Instruction::try_new(Code::EVEX_Vpgatherdd_xmm_k1_vm32x, &[reg(Register::XMM0), Operand::Memory(vsib32x)]).is_ok());
+	assert_eq!(
+		Err(CreateInstructionError::InvalidOperand { operand: 1 }),
+		Instruction::try_new(Code::EVEX_Vpgatherdd_xmm_k1_vm32x, &[reg(Register::XMM0), Operand::Memory(vsib32y)])
+	);
+	assert_eq!(
+		Err(CreateInstructionError::InvalidOperand { operand: 1 }),
+		Instruction::try_new(Code::Mov_r64_rm64, &[reg(Register::RAX), Operand::Memory(vsib32x)])
+	);
+
+	let bcst = MemoryOperand::with_base_displ_bcst_seg(Register::RAX, 0, true, Register::None);
+	let instruction =
+		Instruction::try_new(Code::EVEX_Vpaddd_xmm_k1z_xmm_xmmm128b32, &[reg(Register::XMM0), reg(Register::XMM1), Operand::Memory(bcst)]).unwrap();
+	assert!(instruction.is_broadcast());
+	assert_eq!(
+		Err(CreateInstructionError::InvalidOperand { operand: 1 }),
+		Instruction::try_new(Code::Mov_r64_rm64, &[reg(Register::RAX), Operand::Memory(bcst)])
+	);
+}
+
+#[test]
+fn try_new_checks_values() {
+	let eax = Operand::Register(Register::EAX);
+	let rax = Operand::Register(Register::RAX);
+	let out_of_range = Err(CreateInstructionError::ValueOutOfRange { operand: 1 });
+
+	assert!(Instruction::try_new(Code::Add_rm32_imm8, &[eax, Operand::Immediate(0x7F)]).is_ok());
+	assert!(Instruction::try_new(Code::Add_rm32_imm8, &[eax, Operand::Immediate(0xFFFF_FF80)]).is_ok());
+	assert_eq!(out_of_range, Instruction::try_new(Code::Add_rm32_imm8, &[eax, Operand::Immediate(0x80)]));
+	assert_eq!(out_of_range, Instruction::try_new(Code::Add_rm32_imm8, &[eax, Operand::Immediate(-129i64 as u64)]));
+
+	assert!(Instruction::try_new(Code::Add_rm32_imm32, &[eax, Operand::Immediate(0xFFFF_FFFF)]).is_ok());
+	assert_eq!(out_of_range, Instruction::try_new(Code::Add_rm32_imm32, &[eax, Operand::Immediate(0x1_0000_0000)]));
+
+	assert!(Instruction::try_new(Code::Add_rm64_imm32, &[rax, Operand::Immediate(-1i64 as u64)]).is_ok());
+	assert_eq!(out_of_range, Instruction::try_new(Code::Add_rm64_imm32, &[rax, Operand::Immediate(0xFFFF_FFFF)]));
+
+	let instruction = Instruction::try_new(Code::Mov_r64_imm64, &[rax, Operand::Immediate(0x1234_5678_9ABC_DEF0)]).unwrap();
+	assert_eq!(0x1234_5678_9ABC_DEF0, instruction.immediate64());
+
+	assert!(Instruction::try_new(Code::Rol_rm32_1, &[eax, Operand::Immediate(1)]).is_ok());
+	assert_eq!(out_of_range, Instruction::try_new(Code::Rol_rm32_1, &[eax, Operand::Immediate(2)]));
+
+	assert_eq!(
+		Err(CreateInstructionError::ValueOutOfRange { operand: 0 }),
+		Instruction::try_new(Code::Jmp_rel32_32, &[Operand::Branch(0x1_0000_0000)])
+	);
+	assert_eq!(
+		Err(CreateInstructionError::ValueOutOfRange { operand: 0 }),
+		Instruction::try_new(Code::Jmp_ptr1616, &[Operand::FarBranch(0x1234, 0x1_0000)])
+	);
+}
@@ -22,9 +22,14 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
 mod create;
+mod create_operands;
 pub(crate) mod non_decoded_tests;
+mod op_code_query;
 mod op_code_test_case;
 mod op_code_test_case_parser;
+#[cfg(feature = "instr_info")]
+mod replace_register;
+mod validate;
 
 use self::op_code_test_case::*;
 use self::op_code_test_case_parser::OpCodeInfoTestParser;
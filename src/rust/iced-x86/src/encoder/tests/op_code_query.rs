@@ -0,0 +1,113 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn find(mnemonic: Mnemonic, bitness: u32, operands: &[OpCodeOperand], op_mask: bool) -> Vec<Code> {
+	OpCodeInfo::find(mnemonic, bitness, operands, op_mask).iter().map(|op_code| op_code.code()).collect()
+}
+
+#[test]
+fn find_prefers_shortest_encoding() {
+	let reg_imm8 = [OpCodeOperand::Register(Register::EAX), OpCodeOperand::Immediate(8)];
+	assert_eq!(vec![Code::Add_rm32_imm8, Code::Add_EAX_imm32, Code::Add_rm32_imm32], find(Mnemonic::Add, 64, &reg_imm8, false));
+	let reg_imm32 = [OpCodeOperand::Register(Register::EAX), OpCodeOperand::Immediate(32)];
+	assert_eq!(vec![Code::Add_EAX_imm32, Code::Add_rm32_imm32], find(Mnemonic::Add, 64, &reg_imm32, false));
+	let mem_imm32 = [OpCodeOperand::Memory(4), OpCodeOperand::Immediate(32)];
+	assert_eq!(vec![Code::Add_rm32_imm32], find(Mnemonic::Add, 64, &mem_imm32, false));
+
+	let mov = [OpCodeOperand::Register(Register::ECX), OpCodeOperand::Immediate(32)];
+	assert_eq!(vec![Code::Mov_r32_imm32, Code::Mov_rm32_imm32], find(Mnemonic::Mov, 32, &mov, false));
+	let mov64 = [OpCodeOperand::Register(Register::RCX), OpCodeOperand::Immediate(64)];
+	assert_eq!(vec![Code::Mov_r64_imm64], find(Mnemonic::Mov, 64, &mov64, false));
+
+	let shl = [OpCodeOperand::Register(Register::EDX), OpCodeOperand::ImmediateOne];
+	assert_eq!(vec![Code::Shl_rm32_1], find(Mnemonic::Shl, 32, &shl, false));
+	let shl = [OpCodeOperand::Register(Register::EDX), OpCodeOperand::Immediate(8)];
+	assert_eq!(vec![Code::Shl_rm32_imm8], find(Mnemonic::Shl, 32, &shl, false));
+}
+
+#[test]
+fn find_checks_bitness() {
+	let operands = [OpCodeOperand::Branch];
+	// The 16-bit forms need an operand size prefix
+	assert_eq!(vec![Code::Jmp_rel8_64, Code::Jmp_rel8_16, Code::Jmp_rel16, Code::Jmp_rel32_64], find(Mnemonic::Jmp, 64, &operands, false));
+	assert_eq!(vec![Code::Jmp_rel8_32, Code::Jmp_rel8_16, Code::Jmp_rel16, Code::Jmp_rel32_32], find(Mnemonic::Jmp, 32, &operands, false));
+	assert_eq!(vec![Code::Jmp_ptr1616, Code::Jmp_ptr1632], find(Mnemonic::Jmp, 32, &[OpCodeOperand::FarBranch], false));
+	assert!(find(Mnemonic::Jmp, 64, &[OpCodeOperand::FarBranch], false).is_empty());
+	assert!(find(Mnemonic::Add, 32, &[OpCodeOperand::Register(Register::RAX), OpCodeOperand::Register(Register::RCX)], false).is_empty());
+}
+
+#[test]
+fn find_checks_extended_registers() {
+	// REX registers can only be used in 64-bit mode
+	let r8d = [OpCodeOperand::Register(Register::R8D), OpCodeOperand::Register(Register::EAX)];
+	assert_eq!(vec![Code::Add_rm32_r32, Code::Add_r32_rm32], find(Mnemonic::Add, 64, &r8d, false));
+	assert!(find(Mnemonic::Add, 32, &r8d, false).is_empty());
+	let sil = [OpCodeOperand::Register(Register::SIL), OpCodeOperand::Immediate(8)];
+	assert!(!find(Mnemonic::Mov, 64, &sil, false).is_empty());
+	assert!(find(Mnemonic::Mov, 32, &sil, false).is_empty());
+	let xmm8 = [OpCodeOperand::Register(Register::XMM8), OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2)];
+	assert!(find(Mnemonic::Vaddps, 32, &xmm8, false).is_empty());
+	// CR8 is encoded with a LOCK prefix in 32-bit mode
+	let cr8 = [OpCodeOperand::Register(Register::EAX), OpCodeOperand::Register(Register::CR8)];
+	assert_eq!(vec![Code::Mov_r32_cr], find(Mnemonic::Mov, 32, &cr8, false));
+
+	// XMM16-XMM31 can only be encoded by EVEX
+	let xmm16 = [OpCodeOperand::Register(Register::XMM16), OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2)];
+	assert_eq!(vec![Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32], find(Mnemonic::Vaddps, 64, &xmm16, false));
+	let ymm31 = [OpCodeOperand::Register(Register::YMM1), OpCodeOperand::Register(Register::YMM2), OpCodeOperand::Register(Register::YMM31)];
+	assert_eq!(vec![Code::EVEX_Vaddps_ymm_k1z_ymm_ymmm256b32], find(Mnemonic::Vaddps, 64, &ymm31, false));
+	assert!(find(Mnemonic::Addps, 64, &[OpCodeOperand::Register(Register::XMM16), OpCodeOperand::Register(Register::XMM1)], false).is_empty());
+	assert!(find(Mnemonic::Vaddps, 32, &xmm16, false).is_empty());
+}
+
+#[test]
+fn find_vector_instructions() {
+	let xmm = [OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2), OpCodeOperand::Memory(16)];
+	assert_eq!(vec![Code::VEX_Vaddps_xmm_xmm_xmmm128, Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32], find(Mnemonic::Vaddps, 64, &xmm, false));
+	assert_eq!(vec![Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32], find(Mnemonic::Vaddps, 64, &xmm, true));
+	let bcst = [OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2), OpCodeOperand::Broadcast(4)];
+	assert_eq!(vec![Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32], find(Mnemonic::Vaddps, 64, &bcst, false));
+	let bcst = [OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2), OpCodeOperand::Broadcast(8)];
+	assert!(find(Mnemonic::Vaddps, 64, &bcst, false).is_empty());
+	let xmm = [OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Register(Register::XMM2)];
+	assert_eq!(vec![Code::Addps_xmm_xmmm128], find(Mnemonic::Addps, 16, &xmm, false));
+
+	// The EVEX form requires an opmask register
+	let gather = [OpCodeOperand::Register(Register::XMM1), OpCodeOperand::Memory(0)];
+	assert!(find(Mnemonic::Vpgatherdd, 64, &gather, false).is_empty());
+	assert_eq!(vec![Code::EVEX_Vpgatherdd_xmm_k1_vm32x], find(Mnemonic::Vpgatherdd, 64, &gather, true));
+}
+
+#[test]
+fn find_returns_valid_op_codes() {
+	for op_code in OpCodeInfo::find(Mnemonic::Mov, 64, &[OpCodeOperand::Register(Register::RAX), OpCodeOperand::Memory(0)], false) {
+		assert_eq!(Mnemonic::Mov, op_code.code().mnemonic());
+		assert!(op_code.mode64());
+		assert_eq!(2, op_code.op_count());
+	}
+	assert!(find(Mnemonic::INVALID, 64, &[], false).is_empty());
+}
@@ -0,0 +1,129 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::*;
+
+#[test]
+fn replace_register_replaces_all_sizes() {
+	// mov ah,[rax+rax*2+10h]
+	let memory = MemoryOperand::with_base_index_scale_displ_size(Register::RAX, Register::RAX, 2, 0x10, 1);
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r8_rm8, Register::AH, &memory);
+	assert_eq!(Ok(3), instruction.replace_register(Register::EAX, Register::BL));
+	assert_eq!(Register::BH, instruction.op0_register());
+	assert_eq!(Register::RBX, instruction.memory_base());
+	assert_eq!(Register::RBX, instruction.memory_index());
+
+	let mut encoder = Encoder::new(64);
+	assert_eq!(Ok(4), encoder.encode(&instruction, 0));
+	assert_eq!(vec![0x8A, 0x7C, 0x5B, 0x10], encoder.take_buffer());
+
+	let copy = instruction;
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::BH)), instruction.replace_register(Register::RBX, Register::RSI));
+	assert!(copy.eq_all_bits(&instruction));
+
+	assert_eq!(Ok(0), instruction.replace_register(Register::RCX, Register::RDX));
+	assert_eq!(Ok(0), instruction.replace_register(Register::RBX, Register::BX));
+	assert_eq!(Err(ReplaceRegisterError::IncompatibleRegisters), instruction.replace_register(Register::RBX, Register::XMM0));
+	assert_eq!(Err(ReplaceRegisterError::IncompatibleRegisters), instruction.replace_register(Register::None, Register::None));
+	assert!(copy.eq_all_bits(&instruction));
+}
+
+#[test]
+fn replace_register_replaces_vector_and_op_mask_registers() {
+	let mut instruction = Instruction::with_reg_reg_reg(Code::EVEX_Vaddps_zmm_k1z_zmm_zmmm512b32_er, Register::ZMM1, Register::ZMM2, Register::ZMM1);
+	instruction.set_op_mask(Register::K1);
+	assert_eq!(Ok(2), instruction.replace_register(Register::XMM1, Register::YMM30));
+	assert_eq!(Register::ZMM30, instruction.op0_register());
+	assert_eq!(Register::ZMM2, instruction.op1_register());
+	assert_eq!(Register::ZMM30, instruction.op2_register());
+	assert_eq!(Ok(1), instruction.replace_register(Register::K1, Register::K5));
+	assert_eq!(Register::K5, instruction.op_mask());
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::K5)), instruction.replace_register(Register::K5, Register::K0));
+
+	// vpgatherdd zmm0{k1},[rax+zmm1]
+	let memory = MemoryOperand::with_base_index(Register::RAX, Register::ZMM1);
+	let mut instruction = Instruction::with_reg_mem(Code::EVEX_Vpgatherdd_zmm_k1_vm32z, Register::ZMM0, &memory);
+	instruction.set_op_mask(Register::K1);
+	assert_eq!(Ok(1), instruction.replace_register(Register::ZMM1, Register::ZMM3));
+	assert_eq!(Register::ZMM3, instruction.memory_index());
+	assert_eq!(Ok(()), instruction.validate(64));
+}
+
+#[test]
+fn replace_register_checks_implicit_and_fixed_registers() {
+	let mut instruction = Instruction::with_reg(Code::Mul_rm64, Register::RCX);
+	assert_eq!(Err(ReplaceRegisterError::ImplicitRegister(Register::RDX)), instruction.replace_register(Register::RDX, Register::RBX));
+	assert_eq!(Err(ReplaceRegisterError::ImplicitRegister(Register::RAX)), instruction.replace_register(Register::RAX, Register::RBX));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RCX, Register::RBX));
+	assert_eq!(Register::RBX, instruction.op0_register());
+
+	let mut instruction = Instruction::with_rep_stosq(64);
+	assert_eq!(Err(ReplaceRegisterError::ImplicitRegister(Register::RDI)), instruction.replace_register(Register::RDI, Register::RBX));
+
+	let mut instruction = Instruction::with_mem(Code::Xlat_m8, &MemoryOperand::with_base_index(Register::RBX, Register::AL));
+	assert_eq!(Err(ReplaceRegisterError::ImplicitRegister(Register::RBX)), instruction.replace_register(Register::RBX, Register::RDX));
+
+	let mut instruction = Instruction::with_reg_reg(Code::Shl_rm64_CL, Register::RAX, Register::CL);
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::CL)), instruction.replace_register(Register::RCX, Register::RDX));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RAX, Register::RDX));
+
+	let mut instruction = Instruction::with_reg_mem(Code::Lea_r64_m, Register::RAX, &MemoryOperand::with_base_index(Register::RCX, Register::RDX));
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::RDX)), instruction.replace_register(Register::RDX, Register::RSP));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RCX, Register::RSP));
+}
+
+#[test]
+fn replace_register_checks_encodable_registers() {
+	// REX prefixes can't be used with AH, CH, DH and BH
+	let mut instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::AH, Register::CL);
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::CL)), instruction.replace_register(Register::RCX, Register::RSI));
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::CL)), instruction.replace_register(Register::RCX, Register::R8));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RCX, Register::RDX));
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r8_rm8, Register::AH, &MemoryOperand::with_base(Register::RCX));
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::RCX)), instruction.replace_register(Register::RCX, Register::R9));
+	let mut instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::AL, Register::CL);
+	assert_eq!(Ok(1), instruction.replace_register(Register::RCX, Register::RSI));
+
+	// Invalid 16-bit base and index register pairs
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r16_rm16, Register::AX, &MemoryOperand::with_base_index(Register::BX, Register::SI));
+	instruction.set_code_size(CodeSize::Code16);
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::BX)), instruction.replace_register(Register::RBX, Register::RAX));
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::SI)), instruction.replace_register(Register::RSI, Register::RBP));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RBX, Register::RBP));
+	assert_eq!(Ok(1), instruction.replace_register(Register::RSI, Register::RDI));
+	assert_eq!(Ok(()), instruction.validate(16));
+
+	// XMM16-XMM31 can only be used by EVEX instructions
+	let mut instruction = Instruction::with_reg_reg_reg(Code::VEX_Vaddps_xmm_xmm_xmmm128, Register::XMM0, Register::XMM1, Register::XMM2);
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::XMM1)), instruction.replace_register(Register::XMM1, Register::XMM17));
+	assert_eq!(Ok(1), instruction.replace_register(Register::XMM1, Register::XMM15));
+	let mut instruction = Instruction::with_reg_reg_reg(Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32, Register::XMM0, Register::XMM1, Register::XMM2);
+	assert_eq!(Ok(1), instruction.replace_register(Register::XMM1, Register::XMM17));
+
+	// REX registers can only be used in 64-bit mode
+	let mut instruction = Instruction::with_reg_reg(Code::Add_rm32_r32, Register::EAX, Register::ECX);
+	instruction.set_code_size(CodeSize::Code32);
+	assert_eq!(Err(ReplaceRegisterError::InvalidRegister(Register::ECX)), instruction.replace_register(Register::RCX, Register::R8));
+	instruction.set_code_size(CodeSize::Code64);
+	assert_eq!(Ok(1), instruction.replace_register(Register::RCX, Register::R8));
+}
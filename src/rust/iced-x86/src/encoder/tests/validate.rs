@@ -0,0 +1,198 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::decoder::tests::test_utils::*;
+use super::super::super::test_utils::create_decoder;
+use super::super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn is_prefix_error(error: ValidationError) -> bool {
+	match error {
+		ValidationError::LockPrefixNotSupported
+		| ValidationError::RepPrefixNotSupported
+		| ValidationError::RepnePrefixNotSupported
+		| ValidationError::XacquirePrefixNotSupported
+		| ValidationError::XreleasePrefixNotSupported
+		| ValidationError::BndPrefixNotSupported
+		| ValidationError::NotrackPrefixNotSupported => true,
+		_ => false,
+	}
+}
+
+#[test]
+fn validate_decoded_instructions() {
+	for info in encoder_tests(true, false).iter() {
+		let bytes = to_vec_u8(info.hex_bytes()).unwrap();
+		let instruction = create_decoder(info.bitness(), bytes.as_slice(), info.decoder_options()).0.decode();
+		// Some tests use useless prefixes
+		let errors: Vec<ValidationError> = match instruction.validate(info.bitness()) {
+			Ok(()) => Vec::new(),
+			Err(errors) => errors.into_iter().filter(|&error| !is_prefix_error(error)).collect(),
+		};
+		assert!(errors.is_empty(), "{} {:?}: {:?}", info.hex_bytes(), info.code(), errors);
+	}
+}
+
+#[test]
+fn validate_operands() {
+	let instruction = Instruction::with_reg_reg(Code::Add_rm32_r32, Register::ECX, Register::EDX);
+	assert_eq!(Ok(()), instruction.validate(32));
+
+	let mut instruction = instruction;
+	instruction.set_op0_register(Register::RCX);
+	instruction.set_op1_kind(OpKind::Immediate8);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 0 }, ValidationError::InvalidOpKind { operand: 1 }]), instruction.validate(64));
+
+	let instruction = Instruction::with_reg(Code::Push_r64, Register::RAX);
+	assert_eq!(Ok(()), instruction.validate(64));
+	assert_eq!(Err(vec![ValidationError::InvalidMode]), instruction.validate(32));
+
+	let mut instruction = Instruction::with_reg_u32(Code::Rol_rm32_1, Register::EAX, 1);
+	assert_eq!(Ok(()), instruction.validate(64));
+	instruction.set_immediate8(2);
+	assert_eq!(Err(vec![ValidationError::InvalidImmediate { operand: 1 }]), instruction.validate(64));
+
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base_index(Register::RAX, Register::RCX));
+	assert_eq!(Ok(()), instruction.validate(64));
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(32));
+	instruction.set_memory_index(Register::ECX);
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(64));
+	instruction.set_memory_index(Register::XMM1);
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(64));
+
+	assert_eq!(Err(vec![ValidationError::InvalidCode]), Instruction::default().validate(64));
+	let mut instruction = Instruction::with_declare_qword_1(1);
+	assert_eq!(Ok(()), instruction.validate(16));
+	instruction.set_declare_data_len(3);
+	assert_eq!(Err(vec![ValidationError::InvalidDeclareDataLength]), instruction.validate(16));
+}
+
+#[test]
+fn validate_registers() {
+	// REX registers can only be used in 64-bit mode
+	let instruction = Instruction::with_reg_reg(Code::Add_rm32_r32, Register::R8D, Register::EAX);
+	assert_eq!(Ok(()), instruction.validate(64));
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 0 }]), instruction.validate(32));
+	let instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::AL, Register::SIL);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 1 }]), instruction.validate(32));
+
+	// XMM16-XMM31 can only be used by EVEX instructions
+	let instruction = Instruction::with_reg_reg_reg(Code::VEX_Vaddps_xmm_xmm_xmmm128, Register::XMM16, Register::XMM1, Register::XMM2);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 0 }]), instruction.validate(64));
+	let instruction = Instruction::with_reg_reg_reg(Code::EVEX_Vaddps_xmm_k1z_xmm_xmmm128b32, Register::XMM16, Register::XMM1, Register::XMM2);
+	assert_eq!(Ok(()), instruction.validate(64));
+
+	// AH, CH, DH and BH can't be used with a REX prefix
+	let instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::AH, Register::CL);
+	assert_eq!(Ok(()), instruction.validate(64));
+	let instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::AH, Register::SIL);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 0 }]), instruction.validate(64));
+	let instruction = Instruction::with_reg_reg(Code::Mov_rm8_r8, Register::R8L, Register::BH);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 1 }]), instruction.validate(64));
+	let instruction = Instruction::with_reg_reg(Code::Movzx_r64_rm8, Register::RAX, Register::AH);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 1 }]), instruction.validate(64));
+	let instruction = Instruction::with_mem_reg(Code::Mov_rm8_r8, &MemoryOperand::with_base(Register::R9), Register::CH);
+	assert_eq!(Err(vec![ValidationError::InvalidRegister { operand: 1 }]), instruction.validate(64));
+}
+
+#[test]
+fn validate_memory_operands() {
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r16_rm16, Register::AX, &MemoryOperand::with_base_index(Register::BX, Register::SI));
+	assert_eq!(Ok(()), instruction.validate(16));
+	assert_eq!(Ok(()), instruction.validate(32));
+	instruction.set_memory_base(Register::BP);
+	instruction.set_memory_index(Register::DI);
+	assert_eq!(Ok(()), instruction.validate(16));
+	// Invalid 16-bit base and index pairs
+	for &(base, index) in
+		[(Register::AX, Register::SI), (Register::BX, Register::BP), (Register::SI, Register::DI), (Register::SP, Register::None)].iter()
+	{
+		instruction.set_memory_base(base);
+		instruction.set_memory_index(index);
+		assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(16));
+	}
+	instruction.set_memory_base(Register::BX);
+	instruction.set_memory_index(Register::SI);
+	instruction.set_memory_index_scale(2);
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(16));
+
+	// ESP/RSP can't be an index register
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base_index(Register::EAX, Register::ESP));
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(32));
+	instruction.set_memory_base(Register::ESP);
+	instruction.set_memory_index(Register::EAX);
+	assert_eq!(Ok(()), instruction.validate(32));
+	let instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base_index(Register::RAX, Register::RSP));
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(64));
+
+	// REX registers can only be used in 64-bit mode
+	let instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base(Register::R8D));
+	assert_eq!(Ok(()), instruction.validate(64));
+	assert_eq!(Err(vec![ValidationError::InvalidMemoryOperand { operand: 1 }]), instruction.validate(32));
+}
+
+#[test]
+fn validate_evex_and_prefixes() {
+	let mut instruction = Instruction::with_reg_reg_reg(Code::EVEX_Vpaddd_zmm_k1z_zmm_zmmm512b32, Register::ZMM0, Register::ZMM1, Register::ZMM2);
+	instruction.set_op_mask(Register::K1);
+	instruction.set_zeroing_masking(true);
+	assert_eq!(Ok(()), instruction.validate(64));
+	instruction.set_rounding_control(RoundingControl::RoundDown);
+	instruction.set_suppress_all_exceptions(true);
+	assert_eq!(Err(vec![ValidationError::RoundingControlNotSupported, ValidationError::SuppressAllExceptionsNotSupported]), instruction.validate(64));
+
+	let mut instruction = Instruction::with_reg_reg(Code::EVEX_Vmovdqa32_zmm_k1z_zmmm512, Register::ZMM0, Register::ZMM1);
+	instruction.set_is_broadcast(true);
+	assert_eq!(Err(vec![ValidationError::BroadcastNotSupported]), instruction.validate(64));
+
+	let instruction =
+		Instruction::with_reg_mem(Code::EVEX_Vpgatherdd_zmm_k1_vm32z, Register::ZMM0, &MemoryOperand::with_base_index(Register::RAX, Register::ZMM1));
+	assert_eq!(Err(vec![ValidationError::OpMaskRequired]), instruction.validate(64));
+
+	let mut instruction = Instruction::with_reg_reg(Code::Mov_r32_rm32, Register::ECX, Register::EDX);
+	instruction.set_has_lock_prefix(true);
+	instruction.set_has_xacquire_prefix(true);
+	instruction.set_has_repne_prefix(true);
+	assert_eq!(
+		Err(vec![ValidationError::LockPrefixNotSupported, ValidationError::XacquirePrefixNotSupported, ValidationError::RepnePrefixNotSupported]),
+		instruction.validate(64)
+	);
+
+	let mut instruction = Instruction::with_mem_reg(Code::Add_rm32_r32, &MemoryOperand::with_base(Register::RAX), Register::EDX);
+	instruction.set_has_lock_prefix(true);
+	instruction.set_has_xacquire_prefix(true);
+	assert_eq!(Ok(()), instruction.validate(64));
+
+	let mut instruction = Instruction::with_reg(Code::Jmp_rm64, Register::RAX);
+	instruction.set_segment_prefix(Register::DS);
+	instruction.set_has_repne_prefix(true);
+	assert_eq!(Ok(()), instruction.validate(64));
+	let mut instruction = Instruction::with_reg(Code::Inc_rm64, Register::RAX);
+	instruction.set_segment_prefix(Register::DS);
+	assert_eq!(Err(vec![ValidationError::NotrackPrefixNotSupported]), instruction.validate(64));
+	let mut instruction = Instruction::with_branch(Code::Loop_rel8_64_RCX, 0x1234);
+	instruction.set_has_repne_prefix(true);
+	assert_eq!(Err(vec![ValidationError::BndPrefixNotSupported]), instruction.validate(64));
+}
@@ -0,0 +1,508 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::handlers_table::HANDLERS_TABLE;
+use super::op_code_query::{can_encode_register, is_memory, matches_register, register_index};
+use super::operand::is_valid_index;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error;
+
+/// An error returned by [`Instruction::validate()`]
+///
+/// [`Instruction::validate()`]: struct.Instruction.html#method.validate
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ValidationError {
+	/// The code is [`Code::INVALID`]
+	///
+	/// [`Code::INVALID`]: enum.Code.html#variant.INVALID
+	InvalidCode,
+	/// The instruction can't be encoded in this mode, eg. `PUSH r64` in 32-bit mode
+	InvalidMode,
+	/// The number of bytes in a `db`/`dw`/`dd`/`dq` directive is invalid
+	InvalidDeclareDataLength,
+	/// The operand's op kind can't be used by this code, eg. it's a memory operand but the code only supports registers
+	InvalidOpKind {
+		/// Operand index
+		operand: u32,
+	},
+	/// The operand's register can't be used by this code, eg. `AX` is used but the code only supports 32-bit registers, `R8D`
+	/// is used in 32-bit mode, `XMM16` is used by a `VEX` instruction or `AH` is used by an instruction that needs a `REX` prefix
+	InvalidRegister {
+		/// Operand index
+		operand: u32,
+	},
+	/// The memory operand's base or index register is invalid in this mode, they don't have the same size, they're an invalid
+	/// 16-bit base and index register pair or the index register is `ESP`/`RSP`
+	InvalidMemoryOperand {
+		/// Operand index
+		operand: u32,
+	},
+	/// The immediate value can't be used by this code, eg. `SHL r/m32, 1` with an immediate value other than 1
+	InvalidImmediate {
+		/// Operand index
+		operand: u32,
+	},
+	/// An op mask register is used but the code doesn't support it
+	OpMaskNotSupported,
+	/// The code requires a non-zero op mask register (`k1`-`k7`)
+	OpMaskRequired,
+	/// Zeroing-masking is used but the code doesn't support it
+	ZeroingMaskingNotSupported,
+	/// Broadcasting is used but the code doesn't support it
+	BroadcastNotSupported,
+	/// Static rounding control is used but the code doesn't support it
+	RoundingControlNotSupported,
+	/// Suppress all exceptions (`{sae}`) is used but the code doesn't support it
+	SuppressAllExceptionsNotSupported,
+	/// The `LOCK` prefix is used but the code doesn't support it
+	LockPrefixNotSupported,
+	/// The `REP`/`REPE` prefix is used but the code doesn't support it
+	RepPrefixNotSupported,
+	/// The `REPNE` prefix is used but the code doesn't support it
+	RepnePrefixNotSupported,
+	/// The `XACQUIRE` prefix is used but the code doesn't support it
+	XacquirePrefixNotSupported,
+	/// The `XRELEASE` prefix is used but the code doesn't support it
+	XreleasePrefixNotSupported,
+	/// The `BND` prefix (`REPNE` prefix on a branch) is used but the code doesn't support it
+	BndPrefixNotSupported,
+	/// The `NOTRACK` prefix (`DS` segment prefix without a memory operand) is used but the code supports neither `NOTRACK` nor branch hints
+	NotrackPrefixNotSupported,
+}
+
+impl fmt::Display for ValidationError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ValidationError::InvalidOpKind { operand } => write!(f, "Operand {}: Invalid op kind", operand),
+			ValidationError::InvalidRegister { operand } => write!(f, "Operand {}: Invalid register", operand),
+			ValidationError::InvalidMemoryOperand { operand } => write!(f, "Operand {}: Invalid memory operand", operand),
+			ValidationError::InvalidImmediate { operand } => write!(f, "Operand {}: Invalid immediate", operand),
+			_ => write!(f, "{}", description(*self)),
+		}
+	}
+}
+
+fn description(error: ValidationError) -> &'static str {
+	match error {
+		ValidationError::InvalidCode => "The code is INVALID",
+		ValidationError::InvalidMode => "The instruction can't be encoded in this mode",
+		ValidationError::InvalidDeclareDataLength => "Invalid db/dw/dd/dq data length",
+		ValidationError::InvalidOpKind { .. } => "Invalid op kind",
+		ValidationError::InvalidRegister { .. } => "Invalid register",
+		ValidationError::InvalidMemoryOperand { .. } => "Invalid memory operand",
+		ValidationError::InvalidImmediate { .. } => "Invalid immediate",
+		ValidationError::OpMaskNotSupported => "Op mask registers aren't supported",
+		ValidationError::OpMaskRequired => "An op mask register is required",
+		ValidationError::ZeroingMaskingNotSupported => "Zeroing-masking isn't supported",
+		ValidationError::BroadcastNotSupported => "Broadcasting isn't supported",
+		ValidationError::RoundingControlNotSupported => "Rounding control isn't supported",
+		ValidationError::SuppressAllExceptionsNotSupported => "{sae} isn't supported",
+		ValidationError::LockPrefixNotSupported => "The LOCK prefix isn't supported",
+		ValidationError::RepPrefixNotSupported => "The REP prefix isn't supported",
+		ValidationError::RepnePrefixNotSupported => "The REPNE prefix isn't supported",
+		ValidationError::XacquirePrefixNotSupported => "The XACQUIRE prefix isn't supported",
+		ValidationError::XreleasePrefixNotSupported => "The XRELEASE prefix isn't supported",
+		ValidationError::BndPrefixNotSupported => "The BND prefix isn't supported",
+		ValidationError::NotrackPrefixNotSupported => "The NOTRACK prefix isn't supported",
+	}
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ValidationError {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn description(&self) -> &str {
+		description(*self)
+	}
+}
+
+// Returns the address size (16, 32, 64) of a string instruction's implicit memory operand or 0 if the op kind can't be used
+fn string_address_size(kind: OpCodeOperandKind, op_kind: OpKind) -> u32 {
+	match (kind, op_kind) {
+		(OpCodeOperandKind::seg_rSI, OpKind::MemorySegSI)
+		| (OpCodeOperandKind::seg_rDI, OpKind::MemorySegDI)
+		| (OpCodeOperandKind::es_rDI, OpKind::MemoryESDI) => 16,
+		(OpCodeOperandKind::seg_rSI, OpKind::MemorySegESI)
+		| (OpCodeOperandKind::seg_rDI, OpKind::MemorySegEDI)
+		| (OpCodeOperandKind::es_rDI, OpKind::MemoryESEDI) => 32,
+		(OpCodeOperandKind::seg_rSI, OpKind::MemorySegRSI)
+		| (OpCodeOperandKind::seg_rDI, OpKind::MemorySegRDI)
+		| (OpCodeOperandKind::es_rDI, OpKind::MemoryESRDI) => 64,
+		_ => 0,
+	}
+}
+
+fn is_valid_address_size(bitness: u32, address_size: u32) -> bool {
+	match address_size {
+		16 => bitness != 64,
+		32 => true,
+		64 => bitness == 64,
+		_ => false,
+	}
+}
+
+// Returns the size of an address register (16, 32, 64) or 0 if it's not an address register
+fn address_register_size(register: Register) -> u32 {
+	if register.is_gpr16() {
+		16
+	} else if register.is_gpr32() || register == Register::EIP {
+		32
+	} else if register.is_gpr64() || register == Register::RIP {
+		64
+	} else {
+		0
+	}
+}
+
+// The valid 16-bit memory operands are [bx+si], [bx+di], [bp+si], [bp+di], [si], [di], [bp], [bx] and [disp16]
+pub(super) fn is_valid_memory16(base: Register, index: Register, scale: u32) -> bool {
+	scale == 1
+		&& match (base, index) {
+			(Register::BX, Register::SI)
+			| (Register::BX, Register::DI)
+			| (Register::BP, Register::SI)
+			| (Register::BP, Register::DI)
+			| (Register::SI, Register::None)
+			| (Register::DI, Register::None)
+			| (Register::BP, Register::None)
+			| (Register::BX, Register::None)
+			| (Register::None, Register::None) => true,
+			_ => false,
+		}
+}
+
+fn is_valid_memory(instruction: &Instruction, bitness: u32, op_code: &OpCodeInfo, kind: OpCodeOperandKind) -> bool {
+	let base = instruction.memory_base();
+	let index = instruction.memory_index();
+	if !can_encode_register(op_code.encoding(), bitness, base) || !can_encode_register(op_code.encoding(), bitness, index) {
+		return false;
+	}
+	if kind == OpCodeOperandKind::seg_rBX_al {
+		return index == Register::AL
+			&& (base == Register::BX || base == Register::EBX || base == Register::RBX)
+			&& is_valid_address_size(bitness, address_register_size(base));
+	}
+	if !is_valid_index(kind, index) {
+		return false;
+	}
+	let base_size = address_register_size(base);
+	if base != Register::None && (base_size == 0 || !is_valid_address_size(bitness, base_size)) {
+		return false;
+	}
+	if base_size == 16 || address_register_size(index) == 16 {
+		return is_valid_address_size(bitness, 16) && is_valid_memory16(base, index, instruction.memory_index_scale());
+	}
+	if index == Register::None || index.is_vector_register() {
+		return true;
+	}
+	let index_size = address_register_size(index);
+	if index_size == 0
+		|| index == Register::EIP
+		|| index == Register::RIP
+		|| index == Register::ESP
+		|| index == Register::RSP
+		|| !is_valid_address_size(bitness, index_size)
+	{
+		return false;
+	}
+	base == Register::None || (base_size == index_size && base != Register::EIP && base != Register::RIP)
+}
+
+// AH, CH, DH and BH can't be encoded if the instruction has a REX prefix. It's needed by legacy instructions with a 64-bit
+// operand size (REX.W) and to encode SPL, BPL, SIL, DIL and the registers with a number >= 8 (R8, XMM8, etc).
+pub(super) fn needs_rex_prefix(instruction: &Instruction, op_code: &OpCodeInfo) -> bool {
+	if op_code.encoding() != EncodingKind::Legacy {
+		return false;
+	}
+	if op_code.operand_size() == 64 {
+		return true;
+	}
+	(0..instruction.op_count()).any(|i| match instruction.op_kind(i) {
+		OpKind::Register => register_index(instruction.op_register(i)) >= 8,
+		OpKind::Memory => register_index(instruction.memory_base()) >= 8 || register_index(instruction.memory_index()) >= 8,
+		_ => false,
+	})
+}
+
+pub(super) fn is_high_byte_register(register: Register) -> bool {
+	Register::AH as u32 <= register as u32 && register as u32 <= Register::BH as u32
+}
+
+fn has_near_branch(instruction: &Instruction) -> bool {
+	(0..instruction.op_count()).any(|i| match instruction.op_kind(i) {
+		OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => true,
+		_ => false,
+	})
+}
+
+fn has_memory(instruction: &Instruction) -> bool {
+	(0..instruction.op_count()).any(|i| match instruction.op_kind(i) {
+		OpKind::Register
+		| OpKind::NearBranch16
+		| OpKind::NearBranch32
+		| OpKind::NearBranch64
+		| OpKind::FarBranch16
+		| OpKind::FarBranch32
+		| OpKind::Immediate8
+		| OpKind::Immediate8_2nd
+		| OpKind::Immediate16
+		| OpKind::Immediate32
+		| OpKind::Immediate64
+		| OpKind::Immediate8to16
+		| OpKind::Immediate8to32
+		| OpKind::Immediate8to64
+		| OpKind::Immediate32to64 => false,
+		_ => true,
+	})
+}
+
+fn validate_operands(instruction: &Instruction, bitness: u32, op_code: &OpCodeInfo, errors: &mut Vec<ValidationError>) {
+	let handler_operands = &HANDLERS_TABLE[instruction.code() as usize].operands;
+	let needs_rex_prefix = needs_rex_prefix(instruction, op_code);
+	for i in 0..op_code.op_count() {
+		let kind = op_code.op_kind(i);
+		let op_kind = instruction.op_kind(i);
+		let handler_op = &handler_operands[i as usize];
+		let is_valid_op_kind = match op_kind {
+			OpKind::Register => {
+				let is_valid = is_register_kind(kind);
+				let register = instruction.op_register(i);
+				if is_valid
+					&& (!matches_register_class(kind, register)
+						|| !can_encode_register(op_code.encoding(), bitness, register)
+						|| (needs_rex_prefix && is_high_byte_register(register)))
+				{
+					errors.push(ValidationError::InvalidRegister { operand: i });
+				}
+				is_valid
+			}
+			OpKind::Memory | OpKind::Memory64 => {
+				let is_valid = match kind {
+					OpCodeOperandKind::seg_rSI | OpCodeOperandKind::seg_rDI | OpCodeOperandKind::es_rDI => false,
+					OpCodeOperandKind::mem_offs => true,
+					_ => op_kind == OpKind::Memory && is_memory(kind),
+				};
+				if is_valid && op_kind == OpKind::Memory && !is_valid_memory(instruction, bitness, op_code, kind) {
+					errors.push(ValidationError::InvalidMemoryOperand { operand: i });
+				}
+				is_valid
+			}
+			OpKind::MemorySegSI
+			| OpKind::MemorySegESI
+			| OpKind::MemorySegRSI
+			| OpKind::MemorySegDI
+			| OpKind::MemorySegEDI
+			| OpKind::MemorySegRDI
+			| OpKind::MemoryESDI
+			| OpKind::MemoryESEDI
+			| OpKind::MemoryESRDI => {
+				let address_size = string_address_size(kind, op_kind);
+				if address_size != 0 && !is_valid_address_size(bitness, address_size) {
+					errors.push(ValidationError::InvalidMemoryOperand { operand: i });
+				}
+				address_size != 0
+			}
+			OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => match kind {
+				// The op kind depends on the bitness, see Instruction::with_xbegin()
+				OpCodeOperandKind::xbegin_2 | OpCodeOperandKind::xbegin_4 => true,
+				_ => handler_op.near_branch_op_kind() == Some(op_kind),
+			},
+			OpKind::FarBranch16 | OpKind::FarBranch32 => handler_op.far_branch_op_kind() == Some(op_kind),
+			OpKind::Immediate8
+			| OpKind::Immediate8_2nd
+			| OpKind::Immediate16
+			| OpKind::Immediate32
+			| OpKind::Immediate64
+			| OpKind::Immediate8to16
+			| OpKind::Immediate8to32
+			| OpKind::Immediate8to64
+			| OpKind::Immediate32to64 => {
+				let is_valid = handler_op.immediate_op_kind() == Some(op_kind);
+				if is_valid {
+					let is_valid_immediate = match kind {
+						OpCodeOperandKind::imm8_const_1 => instruction.immediate8() == 1,
+						OpCodeOperandKind::imm2_m2z => instruction.immediate8() <= 3,
+						_ => true,
+					};
+					if !is_valid_immediate {
+						errors.push(ValidationError::InvalidImmediate { operand: i });
+					}
+				}
+				is_valid
+			}
+		};
+		if !is_valid_op_kind {
+			errors.push(ValidationError::InvalidOpKind { operand: i });
+		}
+	}
+}
+
+// The encoder ignores the low bits of the register number of `xmm+3`, `zmm+3` and `k+1` operands
+fn matches_register_class(kind: OpCodeOperandKind, register: Register) -> bool {
+	let kind = match kind {
+		OpCodeOperandKind::xmmp3_vvvv => OpCodeOperandKind::xmm_vvvv,
+		OpCodeOperandKind::zmmp3_vvvv => OpCodeOperandKind::zmm_vvvv,
+		OpCodeOperandKind::kp1_reg => OpCodeOperandKind::k_reg,
+		_ => kind,
+	};
+	matches_register(kind, register)
+}
+
+// Checks if the operand kind can be a register operand
+fn is_register_kind(kind: OpCodeOperandKind) -> bool {
+	OpCodeOperandKind::r8_or_mem as u32 <= kind as u32 && kind as u32 <= OpCodeOperandKind::sti_opcode as u32
+}
+
+fn validate_prefixes(instruction: &Instruction, op_code: &OpCodeInfo, errors: &mut Vec<ValidationError>) {
+	if instruction.has_lock_prefix() && !op_code.can_use_lock_prefix() {
+		errors.push(ValidationError::LockPrefixNotSupported);
+	}
+	if instruction.has_xacquire_prefix() && !op_code.can_use_xacquire_prefix() {
+		errors.push(ValidationError::XacquirePrefixNotSupported);
+	}
+	if instruction.has_xrelease_prefix() && !op_code.can_use_xrelease_prefix() {
+		errors.push(ValidationError::XreleasePrefixNotSupported);
+	}
+	if instruction.has_rep_prefix() && !op_code.can_use_rep_prefix() {
+		errors.push(ValidationError::RepPrefixNotSupported);
+	}
+	// BND is the REPNE prefix
+	if instruction.has_repne_prefix() && !op_code.can_use_repne_prefix() && !op_code.can_use_bnd_prefix() {
+		if has_near_branch(instruction) {
+			errors.push(ValidationError::BndPrefixNotSupported);
+		} else {
+			errors.push(ValidationError::RepnePrefixNotSupported);
+		}
+	}
+	// NOTRACK and the branch hint prefix are the DS segment prefix
+	if instruction.segment_prefix() == Register::DS
+		&& !has_memory(instruction)
+		&& !op_code.can_use_notrack_prefix()
+		&& !op_code.can_use_hint_taken_prefix()
+	{
+		errors.push(ValidationError::NotrackPrefixNotSupported);
+	}
+}
+
+fn validate_evex(instruction: &Instruction, op_code: &OpCodeInfo, errors: &mut Vec<ValidationError>) {
+	if instruction.op_mask() != Register::None {
+		if !op_code.can_use_op_mask_register() {
+			errors.push(ValidationError::OpMaskNotSupported);
+		}
+	} else if op_code.require_non_zero_op_mask_register() {
+		errors.push(ValidationError::OpMaskRequired);
+	}
+	if instruction.zeroing_masking() && !op_code.can_use_zeroing_masking() {
+		errors.push(ValidationError::ZeroingMaskingNotSupported);
+	}
+	if instruction.is_broadcast() && !op_code.can_broadcast() {
+		errors.push(ValidationError::BroadcastNotSupported);
+	}
+	if instruction.rounding_control() != RoundingControl::None && !op_code.can_use_rounding_control() {
+		errors.push(ValidationError::RoundingControlNotSupported);
+	}
+	if instruction.suppress_all_exceptions() && !op_code.can_suppress_all_exceptions() {
+		errors.push(ValidationError::SuppressAllExceptionsNotSupported);
+	}
+}
+
+impl Instruction {
+	/// Checks if the instruction can be encoded by the [`Encoder`] in `bitness`-bit mode. It verifies the op kinds and registers
+	/// of all operands, the op mask, zeroing-masking, broadcasting, rounding control, `{sae}` and the prefixes
+	/// (`LOCK`, `REP`, `REPNE`, `XACQUIRE`, `XRELEASE`, `BND`, `NOTRACK`). All found errors are returned.
+	///
+	/// The encoder can still fail to encode a valid instruction, eg. if a branch target is too far away.
+	///
+	/// [`Encoder`]: struct.Encoder.html
+	///
+	/// # Errors
+	///
+	/// Returns all errors if the instruction is invalid
+	///
+	/// # Panics
+	///
+	/// Panics if `bitness` is not one of 16, 32, 64.
+	///
+	/// # Arguments
+	///
+	/// * `bitness`: 16, 32 or 64
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// let mut instruction = Instruction::with_reg_reg(Code::Add_rm32_r32, Register::ECX, Register::EDX);
+	/// assert_eq!(Ok(()), instruction.validate(64));
+	///
+	/// instruction.set_op1_register(Register::DX);
+	/// instruction.set_has_rep_prefix(true);
+	/// assert_eq!(
+	///     Err(vec![ValidationError::InvalidRegister { operand: 1 }, ValidationError::RepPrefixNotSupported]),
+	///     instruction.validate(64)
+	/// );
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn validate(&self, bitness: u32) -> Result<(), Vec<ValidationError>> {
+		if bitness != 16 && bitness != 32 && bitness != 64 {
+			panic!();
+		}
+		let mut errors = Vec::new();
+		let code = self.code();
+		let op_code = code.op_code();
+		if code == Code::INVALID {
+			errors.push(ValidationError::InvalidCode);
+		} else if !op_code.is_instruction() {
+			let max_len = match code {
+				Code::DeclareByte => 16,
+				Code::DeclareWord => 8,
+				Code::DeclareDword => 4,
+				_ => 2,
+			};
+			if self.declare_data_len() == 0 || self.declare_data_len() > max_len {
+				errors.push(ValidationError::InvalidDeclareDataLength);
+			}
+		} else {
+			let is_valid_mode = match bitness {
+				16 => op_code.mode16(),
+				32 => op_code.mode32(),
+				_ => op_code.mode64(),
+			};
+			if !is_valid_mode {
+				errors.push(ValidationError::InvalidMode);
+			}
+			validate_operands(self, bitness, op_code, &mut errors);
+			validate_evex(self, op_code, &mut errors);
+			validate_prefixes(self, op_code, &mut errors);
+		}
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors)
+		}
+	}
+}
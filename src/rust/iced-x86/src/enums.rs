@@ -21,7 +21,15 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+#[cfg(feature = "instr_info")]
+use super::name_lookup;
+#[cfg(all(feature = "instr_info", not(feature = "std")))]
+use alloc::string::String;
 use core::fmt;
+#[cfg(feature = "instr_info")]
+use core::mem;
+#[cfg(feature = "instr_info")]
+use core::str::FromStr;
 
 // GENERATOR-BEGIN: CodeSize
 // ⚠️This was generated by GENERATOR!🦹‍♂️
@@ -38,7 +46,7 @@ pub enum CodeSize {
 	Code64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CODE_SIZE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_CODE_SIZE: [&str; 4] = [
 	"Unknown",
 	"Code16",
 	"Code32",
@@ -77,7 +85,7 @@ pub enum RoundingControl {
 	RoundTowardZero,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_ROUNDING_CONTROL: [&str; 5] = [
+pub(crate) static GEN_DEBUG_ROUNDING_CONTROL: [&str; 5] = [
 	"None",
 	"RoundToNearest",
 	"RoundDown",
@@ -246,7 +254,7 @@ pub enum OpKind {
 	Memory,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_KIND: [&str; 26] = [
+pub(crate) static GEN_DEBUG_OP_KIND: [&str; 26] = [
 	"Register",
 	"NearBranch16",
 	"NearBranch32",
@@ -303,7 +311,7 @@ pub(crate) enum VectorLength {
 }
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_VECTOR_LENGTH: [&str; 4] = [
+pub(crate) static GEN_DEBUG_VECTOR_LENGTH: [&str; 4] = [
 	"L128",
 	"L256",
 	"L512",
@@ -340,7 +348,7 @@ pub(crate) enum MandatoryPrefixByte {
 }
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MANDATORY_PREFIX_BYTE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_MANDATORY_PREFIX_BYTE: [&str; 4] = [
 	"None",
 	"P66",
 	"PF3",
@@ -384,7 +392,7 @@ pub enum EncodingKind {
 }
 #[cfg(any(feature = "decoder", feature = "encoder", feature = "instr_info"))]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_ENCODING_KIND: [&str; 5] = [
+pub(crate) static GEN_DEBUG_ENCODING_KIND: [&str; 5] = [
 	"Legacy",
 	"VEX",
 	"EVEX",
@@ -488,7 +496,7 @@ pub enum TupleType {
 }
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_TUPLE_TYPE: [&str; 34] = [
+pub(crate) static GEN_DEBUG_TUPLE_TYPE: [&str; 34] = [
 	"None",
 	"Full_128",
 	"Full_256",
@@ -573,7 +581,7 @@ pub enum FlowControl {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_FLOW_CONTROL: [&str; 10] = [
+pub(crate) static GEN_DEBUG_FLOW_CONTROL: [&str; 10] = [
 	"Next",
 	"UnconditionalBranch",
 	"IndirectBranch",
@@ -832,7 +840,7 @@ pub enum OpCodeOperandKind {
 }
 #[cfg(feature = "encoder")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_CODE_OPERAND_KIND: [&str; 105] = [
+pub(crate) static GEN_DEBUG_OP_CODE_OPERAND_KIND: [&str; 105] = [
 	"None",
 	"farbr2_2",
 	"farbr4_2",
@@ -1238,7 +1246,7 @@ pub enum CpuidFeature {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CPUID_FEATURE: [&str; 130] = [
+pub(crate) static GEN_DEBUG_CPUID_FEATURE: [&str; 130] = [
 	"INTEL8086",
 	"INTEL8086_ONLY",
 	"INTEL186",
@@ -1388,6 +1396,37 @@ impl Default for CpuidFeature {
 }
 // GENERATOR-END: CpuidFeature
 
+#[cfg(feature = "instr_info")]
+impl CpuidFeature {
+	/// Gets the name of this value. It's the same as the variant name, eg. `"AVX512F"` for `CpuidFeature::AVX512F`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!("AVX512F", CpuidFeature::AVX512F.as_str());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		GEN_DEBUG_CPUID_FEATURE[self as usize]
+	}
+}
+
+#[cfg(feature = "instr_info")]
+impl FromStr for CpuidFeature {
+	type Err = String;
+
+	/// Converts a name to a `CpuidFeature`. The name must match the variant name exactly, eg. `"AVX512F"`.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match name_lookup::find(&GEN_DEBUG_CPUID_FEATURE, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u8) }),
+			None => Err(name_lookup::invalid_name("CpuidFeature", value)),
+		}
+	}
+}
+
 // GENERATOR-BEGIN: RflagsBits
 // ⚠️This was generated by GENERATOR!🦹‍♂️
 /// `RFLAGS` bits supported by the instruction info code
@@ -1436,7 +1475,7 @@ pub enum OpAccess {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_ACCESS: [&str; 8] = [
+pub(crate) static GEN_DEBUG_OP_ACCESS: [&str; 8] = [
 	"None",
 	"Read",
 	"CondRead",
@@ -1508,7 +1547,7 @@ pub enum ConditionCode {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CONDITION_CODE: [&str; 17] = [
+pub(crate) static GEN_DEBUG_CONDITION_CODE: [&str; 17] = [
 	"None",
 	"o",
 	"no",
@@ -1564,7 +1603,7 @@ pub enum MandatoryPrefix {
 }
 #[cfg(feature = "encoder")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MANDATORY_PREFIX: [&str; 5] = [
+pub(crate) static GEN_DEBUG_MANDATORY_PREFIX: [&str; 5] = [
 	"None",
 	"PNP",
 	"P66",
@@ -1613,7 +1652,7 @@ pub enum OpCodeTableKind {
 }
 #[cfg(feature = "encoder")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_CODE_TABLE_KIND: [&str; 7] = [
+pub(crate) static GEN_DEBUG_OP_CODE_TABLE_KIND: [&str; 7] = [
 	"Normal",
 	"T0F",
 	"T0F38",
@@ -0,0 +1,102 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// Used by a [`Formatter`] to add a trailing comment to the formatted instruction, eg. the value at a
+/// `RIP` relative address, the string it points to or register values from an emulator.
+///
+/// All annotations of an instruction are written after the operands, separated by `", "` and
+/// prefixed with [`FormatterOptions::comment_prefix()`], eg. `mov eax,[rip+10h] ; "hello"`.
+///
+/// [`Formatter`]: trait.Formatter.html
+/// [`FormatterOptions::comment_prefix()`]: struct.FormatterOptions.html#method.comment_prefix
+pub trait FormatterAnnotator {
+	/// Called once per formatter operand after all operands have been formatted.
+	/// Returns the annotation or `None` if there's nothing to add.
+	///
+	/// # Arguments
+	///
+	/// - `instruction`: Instruction
+	/// - `operand`: Operand number, 0-based. This is a formatter operand and isn't necessarily the same as an instruction operand.
+	/// - `instruction_operand`: Instruction operand number, 0-based, or `None` if it's an operand created by the formatter.
+	/// - `virtual_address`: The branch target or the address of the memory operand if it doesn't depend on any register,
+	/// eg. `RIP` relative memory operands and absolute addresses using `ES`, `CS`, `SS` or `DS` (assumed to have a base of 0).
+	/// It's `None` if it's not a branch or memory operand or if the address isn't known.
+	fn annotation(
+		&mut self, instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, virtual_address: Option<u64>,
+	) -> Option<String>;
+}
+
+fn get_virtual_address(instruction: &Instruction, instruction_operand: u32) -> Option<u64> {
+	match instruction.op_kind(instruction_operand) {
+		OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => Some(instruction.near_branch_target()),
+		OpKind::Memory64 | OpKind::Memory => {
+			match instruction.memory_segment() {
+				Register::FS | Register::GS => return None,
+				_ => {}
+			}
+			match instruction.memory_base() {
+				Register::None | Register::RIP | Register::EIP => {}
+				_ => return None,
+			}
+			if instruction.memory_index() != Register::None {
+				return None;
+			}
+			Some(instruction.virtual_address(instruction_operand, 0, |_, _, _| 0))
+		}
+		_ => None,
+	}
+}
+
+// Called by the formatters after the operands have been formatted. `get_instruction_operand` converts
+// a formatter operand to an instruction operand.
+pub(super) fn write_annotations<F>(
+	annotator: &mut FormatterAnnotator, options: &FormatterOptions, output: &mut FormatterOutput, instruction: &Instruction, op_count: u32,
+	get_instruction_operand: F,
+) where
+	F: Fn(u32) -> Option<u32>,
+{
+	let mut first = true;
+	for operand in 0..op_count {
+		let instruction_operand = get_instruction_operand(operand);
+		let virtual_address = match instruction_operand {
+			Some(instruction_operand) => get_virtual_address(instruction, instruction_operand),
+			None => None,
+		};
+		if let Some(annotation) = annotator.annotation(instruction, operand, instruction_operand, virtual_address) {
+			if first {
+				first = false;
+				output.write(" ", FormatterTextKind::Text);
+				output.write(options.comment_prefix(), FormatterTextKind::Text);
+				output.write(" ", FormatterTextKind::Text);
+			} else {
+				output.write(", ", FormatterTextKind::Text);
+			}
+			output.write(&annotation, FormatterTextKind::Text);
+		}
+	}
+}
@@ -0,0 +1,269 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::enums::FormatterTextKind;
+use super::FormatterOutput;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::fmt::Write;
+
+const FORMATTER_TEXT_KIND_COUNT: usize = FormatterTextKind::Function as usize + 1;
+
+/// A color used by [`AnsiColorOutput`]
+///
+/// [`AnsiColorOutput`]: struct.AnsiColorOutput.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AnsiColor {
+	/// Don't change the color
+	None,
+	/// Black (SGR 30)
+	Black,
+	/// Red (SGR 31)
+	Red,
+	/// Green (SGR 32)
+	Green,
+	/// Yellow (SGR 33)
+	Yellow,
+	/// Blue (SGR 34)
+	Blue,
+	/// Magenta (SGR 35)
+	Magenta,
+	/// Cyan (SGR 36)
+	Cyan,
+	/// White (SGR 37)
+	White,
+	/// Bright black (SGR 90)
+	BrightBlack,
+	/// Bright red (SGR 91)
+	BrightRed,
+	/// Bright green (SGR 92)
+	BrightGreen,
+	/// Bright yellow (SGR 93)
+	BrightYellow,
+	/// Bright blue (SGR 94)
+	BrightBlue,
+	/// Bright magenta (SGR 95)
+	BrightMagenta,
+	/// Bright cyan (SGR 96)
+	BrightCyan,
+	/// Bright white (SGR 97)
+	BrightWhite,
+	/// A 24-bit color (red, green, blue)
+	Rgb(u8, u8, u8),
+}
+
+impl Default for AnsiColor {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		AnsiColor::None
+	}
+}
+
+impl AnsiColor {
+	fn write_escape(self, output: &mut String) {
+		let code = match self {
+			AnsiColor::None => return,
+			AnsiColor::Black => 30,
+			AnsiColor::Red => 31,
+			AnsiColor::Green => 32,
+			AnsiColor::Yellow => 33,
+			AnsiColor::Blue => 34,
+			AnsiColor::Magenta => 35,
+			AnsiColor::Cyan => 36,
+			AnsiColor::White => 37,
+			AnsiColor::BrightBlack => 90,
+			AnsiColor::BrightRed => 91,
+			AnsiColor::BrightGreen => 92,
+			AnsiColor::BrightYellow => 93,
+			AnsiColor::BrightBlue => 94,
+			AnsiColor::BrightMagenta => 95,
+			AnsiColor::BrightCyan => 96,
+			AnsiColor::BrightWhite => 97,
+			AnsiColor::Rgb(r, g, b) => {
+				let _ = write!(output, "\x1B[38;2;{};{};{}m", r, g, b);
+				return;
+			}
+		};
+		let _ = write!(output, "\x1B[{}m", code);
+	}
+}
+
+/// Colors used by [`AnsiColorOutput`], one color per [`FormatterTextKind`]
+///
+/// [`AnsiColorOutput`]: struct.AnsiColorOutput.html
+/// [`FormatterTextKind`]: enum.FormatterTextKind.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AnsiTheme {
+	colors: [AnsiColor; FORMATTER_TEXT_KIND_COUNT],
+}
+
+impl Default for AnsiTheme {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		let mut theme = AnsiTheme::new();
+		theme.set_color(FormatterTextKind::Directive, AnsiColor::Magenta);
+		theme.set_color(FormatterTextKind::Prefix, AnsiColor::Magenta);
+		theme.set_color(FormatterTextKind::Mnemonic, AnsiColor::BrightYellow);
+		theme.set_color(FormatterTextKind::Keyword, AnsiColor::Blue);
+		theme.set_color(FormatterTextKind::Number, AnsiColor::Cyan);
+		theme.set_color(FormatterTextKind::Register, AnsiColor::BrightGreen);
+		theme.set_color(FormatterTextKind::Decorator, AnsiColor::Blue);
+		theme.set_color(FormatterTextKind::SelectorValue, AnsiColor::Cyan);
+		theme.set_color(FormatterTextKind::LabelAddress, AnsiColor::BrightCyan);
+		theme.set_color(FormatterTextKind::FunctionAddress, AnsiColor::BrightCyan);
+		theme.set_color(FormatterTextKind::Data, AnsiColor::BrightMagenta);
+		theme.set_color(FormatterTextKind::Label, AnsiColor::BrightRed);
+		theme.set_color(FormatterTextKind::Function, AnsiColor::BrightRed);
+		theme
+	}
+}
+
+impl AnsiTheme {
+	/// Creates a theme where no text kind is colored. Use [`default()`] to get the default theme.
+	///
+	/// [`default()`]: #method.default
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		Self { colors: [AnsiColor::None; FORMATTER_TEXT_KIND_COUNT] }
+	}
+
+	/// Gets the color of a text kind
+	///
+	/// # Arguments
+	///
+	/// - `kind`: Text kind
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn color(&self, kind: FormatterTextKind) -> AnsiColor {
+		self.colors[kind as usize]
+	}
+
+	/// Sets the color of a text kind
+	///
+	/// # Arguments
+	///
+	/// - `kind`: Text kind
+	/// - `color`: New color
+	#[inline]
+	pub fn set_color(&mut self, kind: FormatterTextKind, color: AnsiColor) {
+		self.colors[kind as usize] = color;
+	}
+}
+
+/// A [`FormatterOutput`] that writes text with ANSI escape sequences so it's colored when printed to a terminal
+///
+/// [`FormatterOutput`]: trait.FormatterOutput.html
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let bytes = b"\x48\x8B\xC1";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instr = decoder.decode();
+///
+/// let mut theme = AnsiTheme::new();
+/// theme.set_color(FormatterTextKind::Mnemonic, AnsiColor::Yellow);
+/// theme.set_color(FormatterTextKind::Register, AnsiColor::Green);
+/// let mut output = AnsiColorOutput::with_theme(theme);
+/// let mut formatter = MasmFormatter::new();
+/// formatter.format(&instr, &mut output);
+/// assert_eq!("\x1B[33mmov\x1B[0m \x1B[32mrax\x1B[0m,\x1B[32mrcx\x1B[0m", output.as_str());
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct AnsiColorOutput {
+	output: String,
+	theme: AnsiTheme,
+}
+
+impl AnsiColorOutput {
+	/// Creates an output that uses the default theme
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		AnsiColorOutput::with_theme(AnsiTheme::default())
+	}
+
+	/// Creates an output that uses `theme`
+	///
+	/// # Arguments
+	///
+	/// - `theme`: Theme
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn with_theme(theme: AnsiTheme) -> Self {
+		Self { output: String::new(), theme }
+	}
+
+	/// Gets the theme
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn theme(&self) -> &AnsiTheme {
+		&self.theme
+	}
+
+	/// Gets the theme (mutable)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn theme_mut(&mut self) -> &mut AnsiTheme {
+		&mut self.theme
+	}
+
+	/// Gets all text written so far
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		&self.output
+	}
+
+	/// Clears the text but keeps the theme
+	#[inline]
+	pub fn clear(&mut self) {
+		self.output.clear();
+	}
+
+	/// Returns the text written so far
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn into_string(self) -> String {
+		self.output
+	}
+}
+
+impl FormatterOutput for AnsiColorOutput {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write(&mut self, text: &str, kind: FormatterTextKind) {
+		let color = self.theme.color(kind);
+		if color == AnsiColor::None {
+			self.output.push_str(text);
+		} else {
+			color.write_escape(&mut self.output);
+			self.output.push_str(text);
+			self.output.push_str("\x1B[0m");
+		}
+	}
+}
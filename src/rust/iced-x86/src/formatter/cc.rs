@@ -0,0 +1,303 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::Code;
+
+/// Mnemonic condition code selector (eg. `JB` / `JC` / `JNAE`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_b {
+	/// `JB`, `CMOVB`, `SETB`
+	b,
+	/// `JC`, `CMOVC`, `SETC`
+	c,
+	/// `JNAE`, `CMOVNAE`, `SETNAE`
+	nae,
+}
+impl Default for CC_b {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_b::b
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JAE` / `JNB` / `JNC`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_ae {
+	/// `JAE`, `CMOVAE`, `SETAE`
+	ae,
+	/// `JNB`, `CMOVNB`, `SETNB`
+	nb,
+	/// `JNC`, `CMOVNC`, `SETNC`
+	nc,
+}
+impl Default for CC_ae {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_ae::ae
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JE` / `JZ`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_e {
+	/// `JE`, `CMOVE`, `SETE`, `LOOPE`
+	e,
+	/// `JZ`, `CMOVZ`, `SETZ`, `LOOPZ`
+	z,
+}
+impl Default for CC_e {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_e::e
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JNE` / `JNZ`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_ne {
+	/// `JNE`, `CMOVNE`, `SETNE`, `LOOPNE`
+	ne,
+	/// `JNZ`, `CMOVNZ`, `SETNZ`, `LOOPNZ`
+	nz,
+}
+impl Default for CC_ne {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_ne::ne
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JBE` / `JNA`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_be {
+	/// `JBE`, `CMOVBE`, `SETBE`
+	be,
+	/// `JNA`, `CMOVNA`, `SETNA`
+	na,
+}
+impl Default for CC_be {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_be::be
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JA` / `JNBE`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_a {
+	/// `JA`, `CMOVA`, `SETA`
+	a,
+	/// `JNBE`, `CMOVNBE`, `SETNBE`
+	nbe,
+}
+impl Default for CC_a {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_a::a
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JP` / `JPE`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_p {
+	/// `JP`, `CMOVP`, `SETP`
+	p,
+	/// `JPE`, `CMOVPE`, `SETPE`
+	pe,
+}
+impl Default for CC_p {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_p::p
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JNP` / `JPO`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_np {
+	/// `JNP`, `CMOVNP`, `SETNP`
+	np,
+	/// `JPO`, `CMOVPO`, `SETPO`
+	po,
+}
+impl Default for CC_np {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_np::np
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JL` / `JNGE`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_l {
+	/// `JL`, `CMOVL`, `SETL`
+	l,
+	/// `JNGE`, `CMOVNGE`, `SETNGE`
+	nge,
+}
+impl Default for CC_l {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_l::l
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JGE` / `JNL`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_ge {
+	/// `JGE`, `CMOVGE`, `SETGE`
+	ge,
+	/// `JNL`, `CMOVNL`, `SETNL`
+	nl,
+}
+impl Default for CC_ge {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_ge::ge
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JLE` / `JNG`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_le {
+	/// `JLE`, `CMOVLE`, `SETLE`
+	le,
+	/// `JNG`, `CMOVNG`, `SETNG`
+	ng,
+}
+impl Default for CC_le {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_le::le
+	}
+}
+
+/// Mnemonic condition code selector (eg. `JG` / `JNLE`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum CC_g {
+	/// `JG`, `CMOVG`, `SETG`
+	g,
+	/// `JNLE`, `CMOVNLE`, `SETNLE`
+	nle,
+}
+impl Default for CC_g {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		CC_g::g
+	}
+}
+
+// Condition codes in encoding order (`o` = 0, ..., `g` = 15). `o`, `no`, `s` and `ns` only have one spelling.
+pub(super) struct CC;
+impl CC {
+	pub(super) const B: u32 = 2;
+	pub(super) const AE: u32 = 3;
+	pub(super) const E: u32 = 4;
+	pub(super) const NE: u32 = 5;
+	pub(super) const BE: u32 = 6;
+	pub(super) const A: u32 = 7;
+	pub(super) const P: u32 = 10;
+	pub(super) const NP: u32 = 11;
+	pub(super) const L: u32 = 12;
+	pub(super) const GE: u32 = 13;
+	pub(super) const LE: u32 = 14;
+	pub(super) const G: u32 = 15;
+}
+
+// The spelling used by the formatter tables, indexed by the condition code
+pub(super) static DEFAULT_CC_STRINGS: [&str; 16] = ["o", "no", "b", "ae", "e", "ne", "be", "a", "s", "ns", "p", "np", "l", "ge", "le", "g"];
+
+pub(super) static CC_B_STRINGS: [&str; 3] = ["b", "c", "nae"];
+pub(super) static CC_AE_STRINGS: [&str; 3] = ["ae", "nb", "nc"];
+pub(super) static CC_E_STRINGS: [&str; 2] = ["e", "z"];
+pub(super) static CC_NE_STRINGS: [&str; 2] = ["ne", "nz"];
+pub(super) static CC_BE_STRINGS: [&str; 2] = ["be", "na"];
+pub(super) static CC_A_STRINGS: [&str; 2] = ["a", "nbe"];
+pub(super) static CC_P_STRINGS: [&str; 2] = ["p", "pe"];
+pub(super) static CC_NP_STRINGS: [&str; 2] = ["np", "po"];
+pub(super) static CC_L_STRINGS: [&str; 2] = ["l", "nge"];
+pub(super) static CC_GE_STRINGS: [&str; 2] = ["ge", "nl"];
+pub(super) static CC_LE_STRINGS: [&str; 2] = ["le", "ng"];
+pub(super) static CC_G_STRINGS: [&str; 2] = ["g", "nle"];
+
+// Returns the condition code (see `CC`) and the length of the mnemonic prefix (`j`, `set`, `cmov`, `loop`)
+// if it's a `Jcc`, `SETcc`, `CMOVcc` or `LOOPcc` instruction
+pub(super) fn get_condition_code(code: Code) -> Option<(u32, usize)> {
+	let mut t;
+
+	t = (code as u32).wrapping_sub(Code::Jo_rel16 as u32);
+	if t <= (Code::Jg_rel32_64 as u32 - Code::Jo_rel16 as u32) {
+		return Some((t / 3, 1));
+	}
+
+	t = (code as u32).wrapping_sub(Code::Jo_rel8_16 as u32);
+	if t <= (Code::Jg_rel8_64 as u32 - Code::Jo_rel8_16 as u32) {
+		return Some((t / 3, 1));
+	}
+
+	t = (code as u32).wrapping_sub(Code::Cmovo_r16_rm16 as u32);
+	if t <= (Code::Cmovg_r64_rm64 as u32 - Code::Cmovo_r16_rm16 as u32) {
+		return Some((t / 3, 4));
+	}
+
+	t = (code as u32).wrapping_sub(Code::Seto_rm8 as u32);
+	if t <= (Code::Setg_rm8 as u32 - Code::Seto_rm8 as u32) {
+		return Some((t, 3));
+	}
+
+	t = (code as u32).wrapping_sub(Code::Loopne_rel8_16_CX as u32);
+	if t <= (Code::Loopne_rel8_64_RCX as u32 - Code::Loopne_rel8_16_CX as u32) {
+		return Some((CC::NE, 4));
+	}
+
+	t = (code as u32).wrapping_sub(Code::Loope_rel8_16_CX as u32);
+	if t <= (Code::Loope_rel8_64_RCX as u32 - Code::Loope_rel8_16_CX as u32) {
+		return Some((CC::E, 4));
+	}
+
+	None
+}
@@ -38,7 +38,7 @@ pub enum NumberBase {
 	Binary,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_NUMBER_BASE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_NUMBER_BASE: [&str; 4] = [
 	"Hexadecimal",
 	"Decimal",
 	"Octal",
@@ -75,7 +75,7 @@ pub enum MemorySizeOptions {
 	Never,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MEMORY_SIZE_OPTIONS: [&str; 4] = [
+pub(crate) static GEN_DEBUG_MEMORY_SIZE_OPTIONS: [&str; 4] = [
 	"Default",
 	"Always",
 	"Minimum",
@@ -139,7 +139,7 @@ pub enum PrefixKind {
 	Xrelease,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_PREFIX_KIND: [&str; 18] = [
+pub(crate) static GEN_DEBUG_PREFIX_KIND: [&str; 18] = [
 	"ES",
 	"CS",
 	"SS",
@@ -191,7 +191,7 @@ pub enum DecoratorKind {
 	ZeroingMasking,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_DECORATOR_KIND: [&str; 4] = [
+pub(crate) static GEN_DEBUG_DECORATOR_KIND: [&str; 4] = [
 	"Broadcast",
 	"RoundingControl",
 	"SuppressAllExceptions",
@@ -230,7 +230,7 @@ pub enum NumberKind {
 	UInt64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_NUMBER_KIND: [&str; 8] = [
+pub(crate) static GEN_DEBUG_NUMBER_KIND: [&str; 8] = [
 	"Int8",
 	"UInt8",
 	"Int16",
@@ -296,7 +296,7 @@ pub enum FormatterTextKind {
 	Function,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_FORMATTER_TEXT_KIND: [&str; 16] = [
+pub(crate) static GEN_DEBUG_FORMATTER_TEXT_KIND: [&str; 16] = [
 	"Text",
 	"Directive",
 	"Prefix",
@@ -376,7 +376,7 @@ pub(crate) enum PseudoOpsKind {
 	vpcomuq,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_PSEUDO_OPS_KIND: [&str; 18] = [
+pub(crate) static GEN_DEBUG_PSEUDO_OPS_KIND: [&str; 18] = [
 	"cmpps",
 	"vcmpps",
 	"cmppd",
@@ -426,7 +426,7 @@ pub(crate) enum FormatterFlowControl {
 	Xbegin,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_FORMATTER_FLOW_CONTROL: [&str; 7] = [
+pub(crate) static GEN_DEBUG_FORMATTER_FLOW_CONTROL: [&str; 7] = [
 	"AlwaysShortBranch",
 	"ShortBranch",
 	"NearBranch",
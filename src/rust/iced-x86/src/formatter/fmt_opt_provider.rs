@@ -41,6 +41,47 @@ pub trait FormatterOptionsProvider {
 		&mut self, instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, options: &mut FormatterOperandOptions,
 		number_options: &mut NumberFormattingOptions,
 	);
+
+	/// Called by the formatter before it formats an immediate operand. If it returns a name, eg. the name of an enum
+	/// constant, it's written instead of the value.
+	///
+	/// # Arguments
+	///
+	/// - `instruction`: Instruction
+	/// - `operand`: Operand number, 0-based. This is a formatter operand and isn't necessarily the same as an instruction operand.
+	/// - `instruction_operand`: Instruction operand number, 0-based, or `None` if it's an operand created by the formatter.
+	/// - `value`: Value of the immediate. It's sign extended if `number_kind` is a signed number kind.
+	/// - `number_kind`: Number kind
+	#[inline]
+	#[allow(unused_variables)]
+	fn constant_name(
+		&mut self, instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, value: u64, number_kind: NumberKind,
+	) -> Option<&str> {
+		None
+	}
+}
+
+/// How an immediate operand is shown, see [`FormatterOperandOptions::immediate_presentation()`]
+///
+/// [`FormatterOperandOptions::immediate_presentation()`]: struct.FormatterOperandOptions.html#method.immediate_presentation
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ImmediatePresentation {
+	/// Show it as a number, see [`NumberFormattingOptions`]
+	///
+	/// [`NumberFormattingOptions`]: struct.NumberFormattingOptions.html
+	Number,
+	/// Show it as a character literal, eg. `'A'`, if it's a printable ASCII character, else show it as a number
+	Char,
+	/// Show a 32-bit immediate as a `f32` and a 64-bit immediate as a `f64`, else show it as a number
+	Float,
+}
+
+impl Default for ImmediatePresentation {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		ImmediatePresentation::Number
+	}
 }
 
 pub(super) struct FormatterOperandOptionsFlags;
@@ -49,6 +90,10 @@ impl FormatterOperandOptionsFlags {
 	pub(super) const NONE: u32 = 0x0000_0000;
 	pub(super) const NO_BRANCH_SIZE: u32 = 0x0000_0001;
 	const RIP_RELATIVE_ADDRESSES: u32 = 0x0000_0002;
+	const NUMBER_BASE_SHIFT: u32 = 2;
+	const NUMBER_BASE_MASK: u32 = 7 << FormatterOperandOptionsFlags::NUMBER_BASE_SHIFT;
+	const IMMEDIATE_PRESENTATION_SHIFT: u32 = 5;
+	const IMMEDIATE_PRESENTATION_MASK: u32 = 3 << FormatterOperandOptionsFlags::IMMEDIATE_PRESENTATION_SHIFT;
 	const MEMORY_SIZE_SHIFT: u32 = 30;
 	const MEMORY_SIZE_MASK: u32 = 3 << FormatterOperandOptionsFlags::MEMORY_SIZE_SHIFT;
 }
@@ -133,4 +178,57 @@ impl FormatterOperandOptions {
 		self.flags =
 			(self.flags & !FormatterOperandOptionsFlags::MEMORY_SIZE_MASK) | ((value as u32) << FormatterOperandOptionsFlags::MEMORY_SIZE_SHIFT)
 	}
+
+	/// Number base used by this operand or `None` to use [`FormatterOptions::number_base()`]. The prefix, suffix and digit
+	/// group size of the base are also used.
+	///
+	/// [`FormatterOptions::number_base()`]: struct.FormatterOptions.html#method.number_base
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn number_base(&self) -> Option<NumberBase> {
+		match (self.flags & FormatterOperandOptionsFlags::NUMBER_BASE_MASK) >> FormatterOperandOptionsFlags::NUMBER_BASE_SHIFT {
+			0 => None,
+			value => Some(unsafe { mem::transmute((value - 1) as u8) }),
+		}
+	}
+
+	/// Number base used by this operand or `None` to use [`FormatterOptions::number_base()`]. The prefix, suffix and digit
+	/// group size of the base are also used.
+	///
+	/// [`FormatterOptions::number_base()`]: struct.FormatterOptions.html#method.number_base
+	///
+	/// # Arguments
+	///
+	/// - `value`: New value
+	#[inline]
+	pub fn set_number_base(&mut self, value: Option<NumberBase>) {
+		let value = match value {
+			None => 0,
+			Some(base) => base as u32 + 1,
+		};
+		self.flags = (self.flags & !FormatterOperandOptionsFlags::NUMBER_BASE_MASK) | (value << FormatterOperandOptionsFlags::NUMBER_BASE_SHIFT)
+	}
+
+	/// How an immediate operand is shown. It's ignored if it's not an immediate operand.
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn immediate_presentation(&self) -> ImmediatePresentation {
+		unsafe {
+			mem::transmute(
+				((self.flags & FormatterOperandOptionsFlags::IMMEDIATE_PRESENTATION_MASK)
+					>> FormatterOperandOptionsFlags::IMMEDIATE_PRESENTATION_SHIFT) as u8,
+			)
+		}
+	}
+
+	/// How an immediate operand is shown. It's ignored if it's not an immediate operand.
+	///
+	/// # Arguments
+	///
+	/// - `value`: New value
+	#[inline]
+	pub fn set_immediate_presentation(&mut self, value: ImmediatePresentation) {
+		self.flags = (self.flags & !FormatterOperandOptionsFlags::IMMEDIATE_PRESENTATION_MASK)
+			| ((value as u32) << FormatterOperandOptionsFlags::IMMEDIATE_PRESENTATION_SHIFT)
+	}
 }
@@ -21,9 +21,13 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+use super::super::{Code, Mnemonic};
+use super::cc::*;
 use super::enums::*;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 struct Flags1;
 impl Flags1 {
@@ -79,6 +83,7 @@ pub struct FormatterOptions {
 	binary_prefix: String,
 	binary_suffix: String,
 	digit_separator: String,
+	comment_prefix: String,
 	hex_digit_group_size: u32,
 	decimal_digit_group_size: u32,
 	octal_digit_group_size: u32,
@@ -89,6 +94,21 @@ pub struct FormatterOptions {
 	tab_size: u32,
 	number_base: NumberBase,
 	memory_size_options: MemorySizeOptions,
+	cc_b: CC_b,
+	cc_ae: CC_ae,
+	cc_e: CC_e,
+	cc_ne: CC_ne,
+	cc_be: CC_be,
+	cc_a: CC_a,
+	cc_p: CC_p,
+	cc_np: CC_np,
+	cc_l: CC_l,
+	cc_ge: CC_ge,
+	cc_le: CC_le,
+	cc_g: CC_g,
+	// Sorted by the key
+	code_mnemonics: Vec<(Code, String)>,
+	mnemonic_overrides: Vec<(Mnemonic, String)>,
 }
 
 impl FormatterOptions {
@@ -106,6 +126,7 @@ impl FormatterOptions {
 			binary_prefix: String::default(),
 			binary_suffix: String::default(),
 			digit_separator: String::default(),
+			comment_prefix: String::from(";"),
 			hex_digit_group_size: 4,
 			decimal_digit_group_size: 3,
 			octal_digit_group_size: 4,
@@ -125,6 +146,20 @@ impl FormatterOptions {
 			tab_size: 0,
 			number_base: NumberBase::Hexadecimal,
 			memory_size_options: MemorySizeOptions::Default,
+			cc_b: CC_b::b,
+			cc_ae: CC_ae::ae,
+			cc_e: CC_e::e,
+			cc_ne: CC_ne::ne,
+			cc_be: CC_be::be,
+			cc_a: CC_a::a,
+			cc_p: CC_p::p,
+			cc_np: CC_np::np,
+			cc_l: CC_l::l,
+			cc_ge: CC_ge::ge,
+			cc_le: CC_le::le,
+			cc_g: CC_g::g,
+			code_mnemonics: Vec::new(),
+			mnemonic_overrides: Vec::new(),
 		}
 	}
 
@@ -137,6 +172,7 @@ impl FormatterOptions {
 		options.set_hex_prefix(String::from("0x"));
 		options.set_octal_prefix(String::from("0"));
 		options.set_binary_prefix(String::from("0b"));
+		options.set_comment_prefix(String::from("#"));
 		options
 	}
 
@@ -986,6 +1022,31 @@ impl FormatterOptions {
 		self.digit_separator = value
 	}
 
+	/// Comment prefix written before the annotations returned by a [`FormatterAnnotator`]
+	///
+	/// - Default: `";"` (masm/nasm/intel), `"#"` (gas)
+	///
+	/// [`FormatterAnnotator`]: trait.FormatterAnnotator.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn comment_prefix(&self) -> &str {
+		&self.comment_prefix
+	}
+
+	/// Comment prefix written before the annotations returned by a [`FormatterAnnotator`]
+	///
+	/// - Default: `";"` (masm/nasm/intel), `"#"` (gas)
+	///
+	/// [`FormatterAnnotator`]: trait.FormatterAnnotator.html
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_comment_prefix(&mut self, value: String) {
+		self.comment_prefix = value
+	}
+
 	/// Add leading zeroes to hexadecimal/octal/binary numbers.
 	/// This option has no effect on branch targets and displacements, use [`branch_leading_zeroes`]
 	/// and [`displacement_leading_zeroes`].
@@ -1667,6 +1728,430 @@ impl FormatterOptions {
 			self.options2 &= !Flags2::PREFER_ST0;
 		}
 	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_b::b` | `jb short label`
+	/// - | `CC_b::c` | `jc short label`
+	/// - | `CC_b::nae` | `jnae short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_b(&self) -> CC_b {
+		self.cc_b
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_b::b` | `jb short label`
+	/// - | `CC_b::c` | `jc short label`
+	/// - | `CC_b::nae` | `jnae short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_b(&mut self, value: CC_b) {
+		self.cc_b = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ae::ae` | `jae short label`
+	/// - | `CC_ae::nb` | `jnb short label`
+	/// - | `CC_ae::nc` | `jnc short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_ae(&self) -> CC_ae {
+		self.cc_ae
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ae::ae` | `jae short label`
+	/// - | `CC_ae::nb` | `jnb short label`
+	/// - | `CC_ae::nc` | `jnc short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_ae(&mut self, value: CC_ae) {
+		self.cc_ae = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc`, `CMOVcc` and `LOOPcc` instructions that test `ZF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_e::e` | `je short label`
+	/// - | `CC_e::z` | `jz short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_e(&self) -> CC_e {
+		self.cc_e
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc`, `CMOVcc` and `LOOPcc` instructions that test `ZF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_e::e` | `je short label`
+	/// - | `CC_e::z` | `jz short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_e(&mut self, value: CC_e) {
+		self.cc_e = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc`, `CMOVcc` and `LOOPcc` instructions that test `ZF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ne::ne` | `jne short label`
+	/// - | `CC_ne::nz` | `jnz short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_ne(&self) -> CC_ne {
+		self.cc_ne
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc`, `CMOVcc` and `LOOPcc` instructions that test `ZF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ne::ne` | `jne short label`
+	/// - | `CC_ne::nz` | `jnz short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_ne(&mut self, value: CC_ne) {
+		self.cc_ne = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=1 or ZF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_be::be` | `jbe short label`
+	/// - | `CC_be::na` | `jna short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_be(&self) -> CC_be {
+		self.cc_be
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=1 or ZF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_be::be` | `jbe short label`
+	/// - | `CC_be::na` | `jna short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_be(&mut self, value: CC_be) {
+		self.cc_be = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=0 and ZF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_a::a` | `ja short label`
+	/// - | `CC_a::nbe` | `jnbe short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_a(&self) -> CC_a {
+		self.cc_a
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `CF=0 and ZF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_a::a` | `ja short label`
+	/// - | `CC_a::nbe` | `jnbe short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_a(&mut self, value: CC_a) {
+		self.cc_a = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `PF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_p::p` | `jp short label`
+	/// - | `CC_p::pe` | `jpe short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_p(&self) -> CC_p {
+		self.cc_p
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `PF=1`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_p::p` | `jp short label`
+	/// - | `CC_p::pe` | `jpe short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_p(&mut self, value: CC_p) {
+		self.cc_p = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `PF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_np::np` | `jnp short label`
+	/// - | `CC_np::po` | `jpo short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_np(&self) -> CC_np {
+		self.cc_np
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `PF=0`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_np::np` | `jnp short label`
+	/// - | `CC_np::po` | `jpo short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_np(&mut self, value: CC_np) {
+		self.cc_np = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `SF!=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_l::l` | `jl short label`
+	/// - | `CC_l::nge` | `jnge short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_l(&self) -> CC_l {
+		self.cc_l
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `SF!=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_l::l` | `jl short label`
+	/// - | `CC_l::nge` | `jnge short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_l(&mut self, value: CC_l) {
+		self.cc_l = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `SF=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ge::ge` | `jge short label`
+	/// - | `CC_ge::nl` | `jnl short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_ge(&self) -> CC_ge {
+		self.cc_ge
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `SF=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_ge::ge` | `jge short label`
+	/// - | `CC_ge::nl` | `jnl short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_ge(&mut self, value: CC_ge) {
+		self.cc_ge = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `ZF=1 or SF!=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_le::le` | `jle short label`
+	/// - | `CC_le::ng` | `jng short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_le(&self) -> CC_le {
+		self.cc_le
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `ZF=1 or SF!=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_le::le` | `jle short label`
+	/// - | `CC_le::ng` | `jng short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_le(&mut self, value: CC_le) {
+		self.cc_le = value
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `ZF=0 and SF=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_g::g` | `jg short label`
+	/// - | `CC_g::nle` | `jnle short label`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cc_g(&self) -> CC_g {
+		self.cc_g
+	}
+
+	/// Mnemonic condition code selector of `Jcc`, `SETcc` and `CMOVcc` instructions that test `ZF=0 and SF=OF`
+	///
+	/// Default | Value | Example
+	/// --------|-------|--------
+	/// Yes | `CC_g::g` | `jg short label`
+	/// - | `CC_g::nle` | `jnle short label`
+	///
+	/// # Arguments
+	///
+	/// * `value`: New value
+	#[inline]
+	pub fn set_cc_g(&mut self, value: CC_g) {
+		self.cc_g = value
+	}
+
+	/// Gets the mnemonic that is used instead of the formatter's mnemonic (without prefixes) for `code`, see also
+	/// [`mnemonic_override()`]. It has priority over the mnemonic override and the condition code options.
+	///
+	/// [`mnemonic_override()`]: #method.mnemonic_override
+	///
+	/// # Arguments
+	///
+	/// * `code`: Code value
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn code_mnemonic(&self, code: Code) -> Option<&str> {
+		match self.code_mnemonics.binary_search_by_key(&code, |x| x.0) {
+			Ok(index) => Some(&self.code_mnemonics[index].1),
+			Err(_) => None,
+		}
+	}
+
+	/// Sets the mnemonic that is used instead of the formatter's mnemonic (without prefixes) for `code`.
+	/// The string is written as is, the uppercase options are ignored.
+	///
+	/// # Arguments
+	///
+	/// * `code`: Code value
+	/// * `value`: New mnemonic or `None` to use the formatter's mnemonic
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn set_code_mnemonic(&mut self, code: Code, value: Option<String>) {
+		FormatterOptions::set_override(&mut self.code_mnemonics, code, value)
+	}
+
+	/// Gets the mnemonic that is used instead of the formatter's mnemonic (without prefixes) for all instructions
+	/// with mnemonic `mnemonic`, see also [`code_mnemonic()`]. It has priority over the condition code options.
+	///
+	/// [`code_mnemonic()`]: #method.code_mnemonic
+	///
+	/// # Arguments
+	///
+	/// * `mnemonic`: Mnemonic, see [`Instruction::mnemonic()`]
+	///
+	/// [`Instruction::mnemonic()`]: ../struct.Instruction.html#method.mnemonic
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn mnemonic_override(&self, mnemonic: Mnemonic) -> Option<&str> {
+		match self.mnemonic_overrides.binary_search_by_key(&mnemonic, |x| x.0) {
+			Ok(index) => Some(&self.mnemonic_overrides[index].1),
+			Err(_) => None,
+		}
+	}
+
+	/// Sets the mnemonic that is used instead of the formatter's mnemonic (without prefixes) for all instructions
+	/// with mnemonic `mnemonic`. The string is written as is, the uppercase options are ignored.
+	///
+	/// # Arguments
+	///
+	/// * `mnemonic`: Mnemonic, see [`Instruction::mnemonic()`]
+	/// * `value`: New mnemonic or `None` to use the formatter's mnemonic
+	///
+	/// [`Instruction::mnemonic()`]: ../struct.Instruction.html#method.mnemonic
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn set_mnemonic_override(&mut self, mnemonic: Mnemonic, value: Option<String>) {
+		FormatterOptions::set_override(&mut self.mnemonic_overrides, mnemonic, value)
+	}
+
+	fn set_override<T: Copy + Ord>(overrides: &mut Vec<(T, String)>, key: T, value: Option<String>) {
+		match (overrides.binary_search_by_key(&key, |x| x.0), value) {
+			(Ok(index), Some(value)) => overrides[index].1 = value,
+			(Ok(index), None) => {
+				let _ = overrides.remove(index);
+			}
+			(Err(index), Some(value)) => overrides.insert(index, (key, value)),
+			(Err(_), None) => {}
+		}
+	}
+
+	pub(super) fn has_mnemonic_overrides(&self) -> bool {
+		!self.code_mnemonics.is_empty() || !self.mnemonic_overrides.is_empty()
+	}
+
+	// Returns the spelling of a condition code (see `CC`)
+	pub(super) fn cc_string(&self, cc: u32) -> &'static str {
+		match cc {
+			CC::B => CC_B_STRINGS[self.cc_b as usize],
+			CC::AE => CC_AE_STRINGS[self.cc_ae as usize],
+			CC::E => CC_E_STRINGS[self.cc_e as usize],
+			CC::NE => CC_NE_STRINGS[self.cc_ne as usize],
+			CC::BE => CC_BE_STRINGS[self.cc_be as usize],
+			CC::A => CC_A_STRINGS[self.cc_a as usize],
+			CC::P => CC_P_STRINGS[self.cc_p as usize],
+			CC::NP => CC_NP_STRINGS[self.cc_np as usize],
+			CC::L => CC_L_STRINGS[self.cc_l as usize],
+			CC::GE => CC_GE_STRINGS[self.cc_ge as usize],
+			CC::LE => CC_LE_STRINGS[self.cc_le as usize],
+			CC::G => CC_G_STRINGS[self.cc_g as usize],
+			_ => DEFAULT_CC_STRINGS[cc as usize],
+		}
+	}
 }
 
 impl Default for FormatterOptions {
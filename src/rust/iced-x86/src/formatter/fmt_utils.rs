@@ -22,8 +22,9 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
 use super::super::{Code, Instruction, Register};
+use super::cc::{get_condition_code, DEFAULT_CC_STRINGS};
 use super::enums::{FormatterFlowControl, FormatterTextKind, PrefixKind};
-use super::FormatterOutput;
+use super::{FormatterOptions, FormatterOutput, FormatterString};
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 #[cfg(not(feature = "std"))]
@@ -83,6 +84,43 @@ fn add_strings(output: &mut FormatterOutput, strings: &[String], count: u32) {
 	}
 }
 
+// Writes the mnemonic (without prefixes) and returns its length. The user's mnemonic overrides and condition
+// code options are used if they change the mnemonic.
+pub(super) fn write_mnemonic(options: &FormatterOptions, output: &mut FormatterOutput, instruction: &Instruction, mnemonic: &FormatterString) -> u32 {
+	if options.has_mnemonic_overrides() {
+		let user_mnemonic = match options.code_mnemonic(instruction.code()) {
+			Some(s) => Some(s),
+			None => options.mnemonic_override(instruction.mnemonic()),
+		};
+		if let Some(user_mnemonic) = user_mnemonic {
+			output.write_mnemonic(instruction, user_mnemonic);
+			return user_mnemonic.len() as u32;
+		}
+	}
+
+	let upper = options.uppercase_mnemonics() || options.uppercase_all();
+	if let Some((cc, prefix_len)) = get_condition_code(instruction.code()) {
+		let default_cc = DEFAULT_CC_STRINGS[cc as usize];
+		let cc_str = options.cc_string(cc);
+		if cc_str != default_cc && mnemonic.len() >= prefix_len && mnemonic.lower[prefix_len..].starts_with(default_cc) {
+			let orig = mnemonic.get(upper);
+			let mut s = String::with_capacity(orig.len() - default_cc.len() + cc_str.len());
+			s.push_str(&orig[..prefix_len]);
+			if upper {
+				s.push_str(&cc_str.to_uppercase());
+			} else {
+				s.push_str(cc_str);
+			}
+			s.push_str(&orig[prefix_len + default_cc.len()..]);
+			output.write_mnemonic(instruction, &s);
+			return s.len() as u32;
+		}
+	}
+
+	output.write_mnemonic(instruction, mnemonic.get(upper));
+	mnemonic.len() as u32
+}
+
 #[cfg_attr(has_must_use, must_use)]
 #[inline]
 pub(super) fn is_call(kind: FormatterFlowControl) -> bool {
@@ -75,7 +75,7 @@ pub(crate) enum CtorKind {
 	xbegin,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CTOR_KIND: [&str; 44] = [
+pub(crate) static GEN_DEBUG_CTOR_KIND: [&str; 44] = [
 	"Previous",
 	"Normal_1",
 	"Normal_2a",
@@ -149,7 +149,7 @@ pub(crate) enum SizeOverride {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
 	"None",
 	"Size16",
 	"Size32",
@@ -241,7 +241,7 @@ pub(crate) enum InstrOpKind {
 	DeclareQword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_INSTR_OP_KIND: [&str; 35] = [
+pub(crate) static GEN_DEBUG_INSTR_OP_KIND: [&str; 35] = [
 	"Register",
 	"NearBranch16",
 	"NearBranch32",
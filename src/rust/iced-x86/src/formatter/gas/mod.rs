@@ -104,6 +104,7 @@ pub struct GasFormatter<'a> {
 	number_formatter: NumberFormatter,
 	symbol_resolver: Option<&'a mut SymbolResolver>,
 	options_provider: Option<&'a mut FormatterOptionsProvider>,
+	annotator: Option<&'a mut FormatterAnnotator>,
 }
 
 impl<'a> Default for GasFormatter<'a> {
@@ -157,9 +158,22 @@ impl<'a> GasFormatter<'a> {
 			number_formatter: NumberFormatter::new(),
 			symbol_resolver,
 			options_provider,
+			annotator: None,
 		}
 	}
 
+	/// Sets the annotator that adds a trailing comment to instructions formatted by [`format()`]
+	///
+	/// [`format()`]: trait.Formatter.html#tymethod.format
+	///
+	/// # Arguments
+	///
+	/// - `annotator`: Annotator or `None`
+	#[inline]
+	pub fn set_annotator(&mut self, annotator: Option<&'a mut FormatterAnnotator>) {
+		self.annotator = annotator;
+	}
+
 	fn all_registers(d: &SelfData) -> &'static Vec<FormatterString> {
 		if d.options.gas_naked_registers() {
 			d.all_registers_naked
@@ -289,10 +303,10 @@ impl<'a> GasFormatter<'a> {
 			let mnemonic = op_info.mnemonic;
 			if (op_info.flags & InstrOpInfoFlags::MNEMONIC_IS_DIRECTIVE as u16) != 0 {
 				output.write(mnemonic.get(self.d.options.uppercase_keywords() || self.d.options.uppercase_all()), FormatterTextKind::Directive);
+				*column += mnemonic.len() as u32;
 			} else {
-				output.write_mnemonic(instruction, mnemonic.get(self.d.options.uppercase_mnemonics() || self.d.options.uppercase_all()));
+				*column += write_mnemonic(&self.d.options, output, instruction, mnemonic);
 			}
-			*column += mnemonic.len() as u32;
 		}
 		if (mnemonic_options & FormatMnemonicOptions::NO_PREFIXES) == 0 {
 			if (op_info.flags & InstrOpInfoFlags::JCC_NOT_TAKEN as u16) != 0 {
@@ -428,6 +442,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm64, imm_size)
@@ -496,6 +511,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				let mut vec: Vec<SymResTextPart> = Vec::new();
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
@@ -624,6 +640,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm8 as u64, 1)
@@ -643,19 +660,31 @@ impl<'a> GasFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm8 as i8 as u64;
-						number_kind = NumberKind::Int8;
-						if (imm8 as i8) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm8 = -(imm8 as i8) as u8;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm8 as u64,
+						1,
+					) {
+						if number_options.signed_number {
+							imm64 = imm8 as i8 as u64;
+							number_kind = NumberKind::Int8;
+							if (imm8 as i8) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm8 = -(imm8 as i8) as u8;
+							}
+						} else {
+							imm64 = imm8 as u64;
+							number_kind = NumberKind::UInt8;
 						}
-					} else {
-						imm64 = imm8 as u64;
-						number_kind = NumberKind::UInt8;
+						let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -674,6 +703,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm16 as u64, 2)
@@ -693,19 +723,31 @@ impl<'a> GasFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm16 as i16 as u64;
-						number_kind = NumberKind::Int16;
-						if (imm16 as i16) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm16 = -(imm16 as i16) as u16;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm16 as u64,
+						2,
+					) {
+						if number_options.signed_number {
+							imm64 = imm16 as i16 as u64;
+							number_kind = NumberKind::Int16;
+							if (imm16 as i16) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm16 = -(imm16 as i16) as u16;
+							}
+						} else {
+							imm64 = imm16 as u64;
+							number_kind = NumberKind::UInt16;
 						}
-					} else {
-						imm64 = imm16 as u64;
-						number_kind = NumberKind::UInt16;
+						let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -724,6 +766,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm32 as u64, 4)
@@ -743,19 +786,31 @@ impl<'a> GasFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm32 as i32 as u64;
-						number_kind = NumberKind::Int32;
-						if (imm32 as i32) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm32 = -(imm32 as i32) as u32;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm32 as u64,
+						4,
+					) {
+						if number_options.signed_number {
+							imm64 = imm32 as i32 as u64;
+							number_kind = NumberKind::Int32;
+							if (imm32 as i32) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm32 = -(imm32 as i32) as u32;
+							}
+						} else {
+							imm64 = imm32 as u64;
+							number_kind = NumberKind::UInt32;
 						}
-					} else {
-						imm64 = imm32 as u64;
-						number_kind = NumberKind::UInt32;
+						let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -776,6 +831,7 @@ impl<'a> GasFormatter<'a> {
 				operand_options = FormatterOperandOptions::default();
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm64, 8)
@@ -796,17 +852,29 @@ impl<'a> GasFormatter<'a> {
 					);
 				} else {
 					value64 = imm64;
-					if number_options.signed_number {
-						number_kind = NumberKind::Int64;
-						if (imm64 as i64) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm64 = -(imm64 as i64) as u64;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm64,
+						8,
+					) {
+						if number_options.signed_number {
+							number_kind = NumberKind::Int64;
+							if (imm64 as i64) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm64 = -(imm64 as i64) as u64;
+							}
+						} else {
+							number_kind = NumberKind::UInt64;
 						}
-					} else {
-						number_kind = NumberKind::UInt64;
+						let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
+						output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
-					output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -1106,6 +1174,7 @@ impl<'a> GasFormatter<'a> {
 		if let Some(ref mut options_provider) = self.options_provider {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+			number_options.apply_number_base(&self.d.options, &operand_options);
 		}
 
 		let abs_addr;
@@ -1153,6 +1222,7 @@ impl<'a> GasFormatter<'a> {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			if let Some(ref mut options_provider) = self.options_provider {
 				options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+				number_options.apply_number_base(&self.d.options, &operand_options);
 			}
 			if let Some(ref symbol) = symbol {
 				FormatterOutputMethods::write1(
@@ -1386,6 +1456,12 @@ impl<'a> Formatter for GasFormatter<'a> {
 			add_tabs(output, column, self.d.options.first_operand_char_index(), self.d.options.tab_size());
 			self.format_operands(instruction, output, &op_info);
 		}
+
+		if let Some(ref mut annotator) = self.annotator {
+			write_annotations(*annotator, &self.d.options, output, instruction, op_info.op_count as u32, |operand| {
+				op_info.instruction_index(operand)
+			});
+		}
 	}
 
 	#[cfg_attr(has_must_use, must_use)]
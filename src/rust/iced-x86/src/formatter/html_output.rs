@@ -0,0 +1,220 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::Instruction;
+use super::enums::*;
+use super::symres::*;
+use super::FormatterOutput;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::fmt::Write;
+
+/// A [`FormatterOutput`] that writes HTML. Each token is wrapped in a `<span class="...">` element where
+/// the class is the [`FormatterTextKind`] (see [`class_name()`]), and branch targets and label/function
+/// symbols are written as links to an anchor created by [`write_anchor()`].
+///
+/// Plain text (eg. spaces) isn't wrapped in a `<span>`. All text is escaped.
+///
+/// [`FormatterOutput`]: trait.FormatterOutput.html
+/// [`FormatterTextKind`]: enum.FormatterTextKind.html
+/// [`class_name()`]: #method.class_name
+/// [`write_anchor()`]: #method.write_anchor
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let bytes = b"\x74\x5A";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// decoder.set_ip(0x1000);
+/// let instr = decoder.decode();
+///
+/// let mut output = HtmlOutput::new();
+/// output.write_anchor(instr.ip());
+/// let mut formatter = MasmFormatter::new();
+/// formatter.format(&instr, &mut output);
+/// assert_eq!("<a id=\"loc_1000\"></a><span class=\"mnemonic\">je</span> \
+///     <span class=\"keyword\">short</span> \
+///     <a class=\"label-address\" href=\"#loc_105C\">000000000000105Ch</a>", output.as_str());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HtmlOutput {
+	output: String,
+	class_prefix: String,
+	anchor_prefix: String,
+}
+
+impl Default for HtmlOutput {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		HtmlOutput::new()
+	}
+}
+
+impl HtmlOutput {
+	/// Creates an output with an empty class prefix and the anchor prefix `loc_`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		HtmlOutput::with_prefixes("", "loc_")
+	}
+
+	/// Creates an output
+	///
+	/// # Arguments
+	///
+	/// - `class_prefix`: Prepended to all class names, eg. `asm-` gives `asm-mnemonic`
+	/// - `anchor_prefix`: Prepended to the hex address of all anchors and links, eg. `loc_` gives `loc_1000`
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn with_prefixes(class_prefix: &str, anchor_prefix: &str) -> Self {
+		Self { output: String::new(), class_prefix: String::from(class_prefix), anchor_prefix: String::from(anchor_prefix) }
+	}
+
+	/// Gets the CSS class name of a text kind (without the class prefix)
+	///
+	/// # Arguments
+	///
+	/// - `kind`: Text kind
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn class_name(kind: FormatterTextKind) -> &'static str {
+		match kind {
+			FormatterTextKind::Text => "text",
+			FormatterTextKind::Directive => "directive",
+			FormatterTextKind::Prefix => "prefix",
+			FormatterTextKind::Mnemonic => "mnemonic",
+			FormatterTextKind::Keyword => "keyword",
+			FormatterTextKind::Operator => "operator",
+			FormatterTextKind::Punctuation => "punctuation",
+			FormatterTextKind::Number => "number",
+			FormatterTextKind::Register => "register",
+			FormatterTextKind::Decorator => "decorator",
+			FormatterTextKind::SelectorValue => "selector-value",
+			FormatterTextKind::LabelAddress => "label-address",
+			FormatterTextKind::FunctionAddress => "function-address",
+			FormatterTextKind::Data => "data",
+			FormatterTextKind::Label => "label",
+			FormatterTextKind::Function => "function",
+		}
+	}
+
+	/// Writes an anchor (`<a id="..."></a>`) for `address`, eg. the address of an instruction. Branch targets and symbols link to it.
+	///
+	/// # Arguments
+	///
+	/// - `address`: Address
+	#[inline]
+	pub fn write_anchor(&mut self, address: u64) {
+		let _ = write!(self.output, "<a id=\"{}{:X}\"></a>", self.anchor_prefix, address);
+	}
+
+	/// Gets all HTML written so far
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		&self.output
+	}
+
+	/// Clears the HTML but keeps the prefixes
+	#[inline]
+	pub fn clear(&mut self) {
+		self.output.clear();
+	}
+
+	/// Returns the HTML written so far
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn into_string(self) -> String {
+		self.output
+	}
+
+	fn write_escaped(&mut self, text: &str) {
+		for c in text.chars() {
+			match c {
+				'&' => self.output.push_str("&amp;"),
+				'<' => self.output.push_str("&lt;"),
+				'>' => self.output.push_str("&gt;"),
+				'"' => self.output.push_str("&quot;"),
+				'\'' => self.output.push_str("&#39;"),
+				_ => self.output.push(c),
+			}
+		}
+	}
+
+	fn write_link(&mut self, text: &str, kind: FormatterTextKind, address: u64) {
+		let _ =
+			write!(self.output, "<a class=\"{}{}\" href=\"#{}{:X}\">", self.class_prefix, HtmlOutput::class_name(kind), self.anchor_prefix, address);
+		self.write_escaped(text);
+		self.output.push_str("</a>");
+	}
+}
+
+impl FormatterOutput for HtmlOutput {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write(&mut self, text: &str, kind: FormatterTextKind) {
+		if kind == FormatterTextKind::Text {
+			self.write_escaped(text);
+		} else {
+			let _ = write!(self.output, "<span class=\"{}{}\">", self.class_prefix, HtmlOutput::class_name(kind));
+			self.write_escaped(text);
+			self.output.push_str("</span>");
+		}
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_number(
+		&mut self, _instruction: &Instruction, _operand: u32, _instruction_operand: Option<u32>, text: &str, value: u64, _number_kind: NumberKind,
+		kind: FormatterTextKind,
+	) {
+		match kind {
+			FormatterTextKind::LabelAddress | FormatterTextKind::FunctionAddress => self.write_link(text, kind, value),
+			_ => self.write(text, kind),
+		}
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_symbol(&mut self, _instruction: &Instruction, _operand: u32, _instruction_operand: Option<u32>, _address: u64, symbol: &SymbolResult) {
+		let is_address = (symbol.flags & SymbolFlags::RELATIVE) == 0;
+		let write_part = |this: &mut HtmlOutput, part: &SymResTextPart| {
+			let s = match &part.text {
+				&SymResString::Str(s) => s,
+				&SymResString::String(ref s) => s.as_str(),
+			};
+			match part.color {
+				FormatterTextKind::Label | FormatterTextKind::Function if is_address => this.write_link(s, part.color, symbol.address),
+				_ => this.write(s, part.color),
+			}
+		};
+		match symbol.text {
+			SymResTextInfo::Text(ref part) => write_part(self, part),
+			SymResTextInfo::TextVec(v) => {
+				for part in v.iter() {
+					write_part(self, part);
+				}
+			}
+		}
+	}
+}
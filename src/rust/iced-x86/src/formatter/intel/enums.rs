@@ -69,7 +69,7 @@ pub(crate) enum CtorKind {
 	YA,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CTOR_KIND: [&str; 38] = [
+pub(crate) static GEN_DEBUG_CTOR_KIND: [&str; 38] = [
 	"Previous",
 	"Normal_1",
 	"Normal_2",
@@ -137,7 +137,7 @@ pub(crate) enum SizeOverride {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
 	"None",
 	"Size16",
 	"Size32",
@@ -169,7 +169,7 @@ pub(crate) enum BranchSizeInfo {
 	Short,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_BRANCH_SIZE_INFO: [&str; 2] = [
+pub(crate) static GEN_DEBUG_BRANCH_SIZE_INFO: [&str; 2] = [
 	"None",
 	"Short",
 ];
@@ -259,7 +259,7 @@ pub(crate) enum InstrOpKind {
 	DeclareQword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_INSTR_OP_KIND: [&str; 30] = [
+pub(crate) static GEN_DEBUG_INSTR_OP_KIND: [&str; 30] = [
 	"Register",
 	"NearBranch16",
 	"NearBranch32",
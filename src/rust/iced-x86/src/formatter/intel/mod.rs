@@ -104,6 +104,7 @@ pub struct IntelFormatter<'a> {
 	number_formatter: NumberFormatter,
 	symbol_resolver: Option<&'a mut SymbolResolver>,
 	options_provider: Option<&'a mut FormatterOptionsProvider>,
+	annotator: Option<&'a mut FormatterAnnotator>,
 }
 
 impl<'a> Default for IntelFormatter<'a> {
@@ -153,9 +154,22 @@ impl<'a> IntelFormatter<'a> {
 			number_formatter: NumberFormatter::new(),
 			symbol_resolver,
 			options_provider,
+			annotator: None,
 		}
 	}
 
+	/// Sets the annotator that adds a trailing comment to instructions formatted by [`format()`]
+	///
+	/// [`format()`]: trait.Formatter.html#tymethod.format
+	///
+	/// # Arguments
+	///
+	/// - `annotator`: Annotator or `None`
+	#[inline]
+	pub fn set_annotator(&mut self, annotator: Option<&'a mut FormatterAnnotator>) {
+		self.annotator = annotator;
+	}
+
 	fn format_mnemonic(
 		&mut self, instruction: &Instruction, output: &mut FormatterOutput, op_info: &InstrOpInfo, column: &mut u32, mnemonic_options: u32,
 	) {
@@ -274,10 +288,10 @@ impl<'a> IntelFormatter<'a> {
 			let mnemonic = op_info.mnemonic;
 			if (op_info.flags & InstrOpInfoFlags::MNEMONIC_IS_DIRECTIVE as u16) != 0 {
 				output.write(mnemonic.get(self.d.options.uppercase_keywords() || self.d.options.uppercase_all()), FormatterTextKind::Directive);
+				*column += mnemonic.len() as u32;
 			} else {
-				output.write_mnemonic(instruction, mnemonic.get(self.d.options.uppercase_mnemonics() || self.d.options.uppercase_all()));
+				*column += write_mnemonic(&self.d.options, output, instruction, mnemonic);
 			}
-			*column += mnemonic.len() as u32;
 
 			if (op_info.flags & InstrOpInfoFlags::FAR_MNEMONIC as u16) != 0 {
 				output.write(" ", FormatterTextKind::Text);
@@ -408,6 +422,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -427,6 +442,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let s = if op_kind == InstrOpKind::NearBranch32 {
 						self.number_formatter.format_u32_zeroes(
@@ -488,6 +504,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -515,6 +532,7 @@ impl<'a> IntelFormatter<'a> {
 						let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 						if let Some(ref mut options_provider) = self.options_provider {
 							options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+							number_options.apply_number_base(&self.d.options, &operand_options);
 						}
 						FormatterOutputMethods::write1(
 							output,
@@ -532,6 +550,7 @@ impl<'a> IntelFormatter<'a> {
 						let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 						if let Some(ref mut options_provider) = self.options_provider {
 							options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+							number_options.apply_number_base(&self.d.options, &operand_options);
 						}
 						let s = self.number_formatter.format_u16_zeroes(
 							&self.d.options,
@@ -563,6 +582,7 @@ impl<'a> IntelFormatter<'a> {
 									&mut operand_options,
 									&mut number_options,
 								);
+								number_options.apply_number_base(&self.d.options, &operand_options);
 							}
 							self.number_formatter.format_u32_zeroes(
 								&self.d.options,
@@ -580,6 +600,7 @@ impl<'a> IntelFormatter<'a> {
 									&mut operand_options,
 									&mut number_options,
 								);
+								number_options.apply_number_base(&self.d.options, &operand_options);
 							}
 							self.number_formatter.format_u16_zeroes(
 								&self.d.options,
@@ -605,6 +626,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let s = self.number_formatter.format_u16_zeroes(
 						&self.d.options,
@@ -641,6 +663,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -658,20 +681,33 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm8 as i8 as u64;
-						number_kind = NumberKind::Int8;
-						if (imm8 as i8) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm8 = -(imm8 as i8) as u8;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm8 as u64,
+						1,
+					) {
+						if number_options.signed_number {
+							imm64 = imm8 as i8 as u64;
+							number_kind = NumberKind::Int8;
+							if (imm8 as i8) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm8 = -(imm8 as i8) as u8;
+							}
+						} else {
+							imm64 = imm8 as u64;
+							number_kind = NumberKind::UInt8;
 						}
-					} else {
-						imm64 = imm8 as u64;
-						number_kind = NumberKind::UInt8;
+						let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -692,6 +728,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -709,20 +746,33 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm16 as i16 as u64;
-						number_kind = NumberKind::Int16;
-						if (imm16 as i16) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm16 = -(imm16 as i16) as u16;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm16 as u64,
+						2,
+					) {
+						if number_options.signed_number {
+							imm64 = imm16 as i16 as u64;
+							number_kind = NumberKind::Int16;
+							if (imm16 as i16) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm16 = -(imm16 as i16) as u16;
+							}
+						} else {
+							imm64 = imm16 as u64;
+							number_kind = NumberKind::UInt16;
 						}
-					} else {
-						imm64 = imm16 as u64;
-						number_kind = NumberKind::UInt16;
+						let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -743,6 +793,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -760,20 +811,33 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm32 as i32 as u64;
-						number_kind = NumberKind::Int32;
-						if (imm32 as i32) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm32 = -(imm32 as i32) as u32;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm32 as u64,
+						4,
+					) {
+						if number_options.signed_number {
+							imm64 = imm32 as i32 as u64;
+							number_kind = NumberKind::Int32;
+							if (imm32 as i32) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm32 = -(imm32 as i32) as u32;
+							}
+						} else {
+							imm64 = imm32 as u64;
+							number_kind = NumberKind::UInt32;
 						}
-					} else {
-						imm64 = imm32 as u64;
-						number_kind = NumberKind::UInt32;
+						let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -796,6 +860,7 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -814,18 +879,31 @@ impl<'a> IntelFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						number_kind = NumberKind::Int64;
-						if (imm64 as i64) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm64 = -(imm64 as i64) as u64;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm64,
+						8,
+					) {
+						if number_options.signed_number {
+							number_kind = NumberKind::Int64;
+							if (imm64 as i64) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm64 = -(imm64 as i64) as u64;
+							}
+						} else {
+							number_kind = NumberKind::UInt64;
 						}
-					} else {
-						number_kind = NumberKind::UInt64;
+						let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
+						output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
-					output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -1131,6 +1209,7 @@ impl<'a> IntelFormatter<'a> {
 		if let Some(ref mut options_provider) = self.options_provider {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+			number_options.apply_number_base(&self.d.options, &operand_options);
 		}
 
 		let abs_addr;
@@ -1251,6 +1330,7 @@ impl<'a> IntelFormatter<'a> {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			if let Some(ref mut options_provider) = self.options_provider {
 				options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+				number_options.apply_number_base(&self.d.options, &operand_options);
 			}
 			if let Some(ref symbol) = symbol {
 				if need_plus {
@@ -1546,6 +1626,12 @@ impl<'a> Formatter for IntelFormatter<'a> {
 			add_tabs(output, column, self.d.options.first_operand_char_index(), self.d.options.tab_size());
 			self.format_operands(instruction, output, &op_info);
 		}
+
+		if let Some(ref mut annotator) = self.annotator {
+			write_annotations(*annotator, &self.d.options, output, instruction, op_info.op_count as u32, |operand| {
+				op_info.instruction_index(operand)
+			});
+		}
 	}
 
 	#[cfg_attr(has_must_use, must_use)]
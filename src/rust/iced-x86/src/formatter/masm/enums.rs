@@ -76,7 +76,7 @@ pub(crate) enum CtorKind {
 	YX,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CTOR_KIND: [&str; 45] = [
+pub(crate) static GEN_DEBUG_CTOR_KIND: [&str; 45] = [
 	"Previous",
 	"Normal_1",
 	"Normal_2",
@@ -201,7 +201,7 @@ pub(crate) enum InstrOpKind {
 	DeclareQword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_INSTR_OP_KIND: [&str; 31] = [
+pub(crate) static GEN_DEBUG_INSTR_OP_KIND: [&str; 31] = [
 	"Register",
 	"NearBranch16",
 	"NearBranch32",
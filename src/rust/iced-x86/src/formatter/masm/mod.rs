@@ -105,6 +105,7 @@ pub struct MasmFormatter<'a> {
 	number_formatter: NumberFormatter,
 	symbol_resolver: Option<&'a mut SymbolResolver>,
 	options_provider: Option<&'a mut FormatterOptionsProvider>,
+	annotator: Option<&'a mut FormatterAnnotator>,
 }
 
 impl<'a> Default for MasmFormatter<'a> {
@@ -154,9 +155,22 @@ impl<'a> MasmFormatter<'a> {
 			number_formatter: NumberFormatter::new(),
 			symbol_resolver,
 			options_provider,
+			annotator: None,
 		}
 	}
 
+	/// Sets the annotator that adds a trailing comment to instructions formatted by [`format()`]
+	///
+	/// [`format()`]: trait.Formatter.html#tymethod.format
+	///
+	/// # Arguments
+	///
+	/// - `annotator`: Annotator or `None`
+	#[inline]
+	pub fn set_annotator(&mut self, annotator: Option<&'a mut FormatterAnnotator>) {
+		self.annotator = annotator;
+	}
+
 	fn format_mnemonic(
 		&mut self, instruction: &Instruction, output: &mut FormatterOutput, op_info: &InstrOpInfo, column: &mut u32, mnemonic_options: u32,
 	) {
@@ -253,10 +267,10 @@ impl<'a> MasmFormatter<'a> {
 			let mnemonic = op_info.mnemonic;
 			if (op_info.flags & InstrOpInfoFlags::MNEMONIC_IS_DIRECTIVE as u16) != 0 {
 				output.write(mnemonic.get(self.d.options.uppercase_keywords() || self.d.options.uppercase_all()), FormatterTextKind::Directive);
+				*column += mnemonic.len() as u32;
 			} else {
-				output.write_mnemonic(instruction, mnemonic.get(self.d.options.uppercase_mnemonics() || self.d.options.uppercase_all()));
+				*column += write_mnemonic(&self.d.options, output, instruction, mnemonic);
 			}
-			*column += mnemonic.len() as u32;
 		}
 	}
 
@@ -381,6 +395,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -400,6 +415,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let s = if op_kind == InstrOpKind::NearBranch32 {
 						self.number_formatter.format_u32_zeroes(
@@ -461,6 +477,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let selector_symbol = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 						symbol_resolver.symbol(instruction, operand + 1, instruction_operand, instruction.far_branch_selector() as u64, 2)
@@ -516,6 +533,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					{
 						let s = self.number_formatter.format_u16_zeroes(
@@ -585,6 +603,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -602,20 +621,33 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm8 as i8 as u64;
-						number_kind = NumberKind::Int8;
-						if (imm8 as i8) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm8 = -(imm8 as i8) as u8;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm8 as u64,
+						1,
+					) {
+						if number_options.signed_number {
+							imm64 = imm8 as i8 as u64;
+							number_kind = NumberKind::Int8;
+							if (imm8 as i8) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm8 = -(imm8 as i8) as u8;
+							}
+						} else {
+							imm64 = imm8 as u64;
+							number_kind = NumberKind::UInt8;
 						}
-					} else {
-						imm64 = imm8 as u64;
-						number_kind = NumberKind::UInt8;
+						let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -640,6 +672,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -657,20 +690,33 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm16 as i16 as u64;
-						number_kind = NumberKind::Int16;
-						if (imm16 as i16) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm16 = -(imm16 as i16) as u16;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm16 as u64,
+						2,
+					) {
+						if number_options.signed_number {
+							imm64 = imm16 as i16 as u64;
+							number_kind = NumberKind::Int16;
+							if (imm16 as i16) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm16 = -(imm16 as i16) as u16;
+							}
+						} else {
+							imm64 = imm16 as u64;
+							number_kind = NumberKind::UInt16;
 						}
-					} else {
-						imm64 = imm16 as u64;
-						number_kind = NumberKind::UInt16;
+						let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -695,6 +741,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -712,20 +759,33 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						imm64 = imm32 as i32 as u64;
-						number_kind = NumberKind::Int32;
-						if (imm32 as i32) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm32 = -(imm32 as i32) as u32;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm32 as u64,
+						4,
+					) {
+						if number_options.signed_number {
+							imm64 = imm32 as i32 as u64;
+							number_kind = NumberKind::Int32;
+							if (imm32 as i32) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm32 = -(imm32 as i32) as u32;
+							}
+						} else {
+							imm64 = imm32 as u64;
+							number_kind = NumberKind::UInt32;
 						}
-					} else {
-						imm64 = imm32 as u64;
-						number_kind = NumberKind::UInt32;
+						let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -752,6 +812,7 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -770,18 +831,31 @@ impl<'a> MasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
-					if number_options.signed_number {
-						number_kind = NumberKind::Int64;
-						if (imm64 as i64) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm64 = -(imm64 as i64) as u64;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm64,
+						8,
+					) {
+						if number_options.signed_number {
+							number_kind = NumberKind::Int64;
+							if (imm64 as i64) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm64 = -(imm64 as i64) as u64;
+							}
+						} else {
+							number_kind = NumberKind::UInt64;
 						}
-					} else {
-						number_kind = NumberKind::UInt64;
+						let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
+						output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
-					output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -1077,6 +1151,7 @@ impl<'a> MasmFormatter<'a> {
 		if let Some(ref mut options_provider) = self.options_provider {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+			number_options.apply_number_base(&self.d.options, &operand_options);
 		}
 
 		let abs_addr;
@@ -1270,6 +1345,7 @@ impl<'a> MasmFormatter<'a> {
 		let mut number_options = NumberFormattingOptions::with_displacement(&d.options);
 		if let &mut Some(ref mut options_provider) = options_provider {
 			options_provider.operand_options(instruction, operand, instruction_operand, operand_options, &mut number_options);
+			number_options.apply_number_base(&d.options, operand_options);
 		}
 		if let &Some(ref symbol) = symbol {
 			if need_plus {
@@ -1621,6 +1697,12 @@ impl<'a> Formatter for MasmFormatter<'a> {
 			add_tabs(output, column, self.d.options.first_operand_char_index(), self.d.options.tab_size());
 			self.format_operands(instruction, output, &op_info);
 		}
+
+		if let Some(ref mut annotator) = self.annotator {
+			write_annotations(*annotator, &self.d.options, output, instruction, op_info.op_count as u32, |operand| {
+				op_info.instruction_index(operand)
+			});
+		}
 	}
 
 	#[cfg_attr(has_must_use, must_use)]
@@ -21,6 +21,9 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+mod annotator;
+mod ansi_output;
+mod cc;
 mod enums;
 mod fmt_consts;
 mod fmt_opt_provider;
@@ -28,6 +31,7 @@ mod fmt_opts;
 mod fmt_utils;
 #[cfg(feature = "gas")]
 mod gas;
+mod html_output;
 #[cfg(feature = "intel")]
 mod intel;
 #[cfg(feature = "masm")]
@@ -44,12 +48,17 @@ mod strings_tbl;
 mod symres;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tree_output;
 
+pub use self::annotator::*;
+pub use self::ansi_output::*;
+pub use self::cc::*;
 pub use self::enums::*;
 pub use self::fmt_opt_provider::*;
 pub use self::fmt_opts::*;
 #[cfg(feature = "gas")]
 pub use self::gas::*;
+pub use self::html_output::*;
 #[cfg(feature = "intel")]
 pub use self::intel::*;
 #[cfg(feature = "masm")]
@@ -60,6 +69,7 @@ use self::num_fmt::NumberFormatter;
 pub use self::num_fmt_opts::*;
 pub use self::string_output::*;
 pub use self::symres::*;
+pub use self::tree_output::*;
 use super::*;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
@@ -326,6 +336,58 @@ impl FormatterOutputMethods {
 			output.write(")", FormatterTextKind::Punctuation);
 		}
 	}
+
+	// Writes an immediate as a constant name (see `FormatterOptionsProvider::constant_name()`), a char or a float
+	// if that's what the options provider wants. Returns `false` if the caller should write the number.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::too_many_arguments))]
+	fn write_immediate<'a>(
+		options_provider: &mut Option<&'a mut FormatterOptionsProvider>, output: &mut FormatterOutput, instruction: &Instruction, operand: u32,
+		instruction_operand: Option<u32>, operand_options: &FormatterOperandOptions, number_options: &NumberFormattingOptions, value: u64, size: u32,
+	) -> bool {
+		let (value, number_kind) = match (size, number_options.signed_number) {
+			(1, false) => (value as u8 as u64, NumberKind::UInt8),
+			(1, true) => (value as i8 as u64, NumberKind::Int8),
+			(2, false) => (value as u16 as u64, NumberKind::UInt16),
+			(2, true) => (value as i16 as u64, NumberKind::Int16),
+			(4, false) => (value as u32 as u64, NumberKind::UInt32),
+			(4, true) => (value as i32 as u64, NumberKind::Int32),
+			(_, false) => (value, NumberKind::UInt64),
+			(_, true) => (value, NumberKind::Int64),
+		};
+		if let &mut Some(ref mut options_provider) = options_provider {
+			if let Some(name) = options_provider.constant_name(instruction, operand, instruction_operand, value, number_kind) {
+				output.write_number(instruction, operand, instruction_operand, name, value, number_kind, FormatterTextKind::Data);
+				return true;
+			}
+		}
+		match operand_options.immediate_presentation() {
+			ImmediatePresentation::Number => false,
+			ImmediatePresentation::Char => {
+				if value >= 0x20 && value <= 0x7E && value != '\'' as u64 && value != '\\' as u64 {
+					let text = format!("'{}'", value as u8 as char);
+					output.write_number(instruction, operand, instruction_operand, &text, value, number_kind, FormatterTextKind::Number);
+					true
+				} else {
+					false
+				}
+			}
+			ImmediatePresentation::Float => {
+				// Sign extended immediates are floats of the encoded size, not of the operand size
+				let encoded_size = match instruction_operand.map(|op| instruction.op_kind(op)) {
+					Some(OpKind::Immediate8to16) | Some(OpKind::Immediate8to32) | Some(OpKind::Immediate8to64) => 1,
+					Some(OpKind::Immediate32to64) => 4,
+					_ => size,
+				};
+				let text = match encoded_size {
+					4 => format!("{:?}", f32::from_bits(value as u32)),
+					8 => format!("{:?}", f64::from_bits(value)),
+					_ => return false,
+				};
+				output.write_number(instruction, operand, instruction_operand, &text, value, number_kind, FormatterTextKind::Number);
+				true
+			}
+		}
+	}
 }
 
 /// Formats instructions
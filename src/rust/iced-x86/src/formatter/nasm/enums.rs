@@ -93,7 +93,7 @@ pub(crate) enum CtorKind {
 	YX,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CTOR_KIND: [&str; 62] = [
+pub(crate) static GEN_DEBUG_CTOR_KIND: [&str; 62] = [
 	"Previous",
 	"Normal_1",
 	"Normal_2",
@@ -189,7 +189,7 @@ pub(crate) enum SignExtendInfo {
 	Sex4,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_SIGN_EXTEND_INFO: [&str; 8] = [
+pub(crate) static GEN_DEBUG_SIGN_EXTEND_INFO: [&str; 8] = [
 	"None",
 	"Sex1to2",
 	"Sex1to4",
@@ -227,7 +227,7 @@ pub(crate) enum SizeOverride {
 	Size64,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
+pub(crate) static GEN_DEBUG_SIZE_OVERRIDE: [&str; 4] = [
 	"None",
 	"Size16",
 	"Size32",
@@ -264,7 +264,7 @@ pub(crate) enum BranchSizeInfo {
 	Short,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_BRANCH_SIZE_INFO: [&str; 7] = [
+pub(crate) static GEN_DEBUG_BRANCH_SIZE_INFO: [&str; 7] = [
 	"None",
 	"Near",
 	"NearWord",
@@ -368,7 +368,7 @@ pub(crate) enum InstrOpKind {
 	DeclareQword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_INSTR_OP_KIND: [&str; 35] = [
+pub(crate) static GEN_DEBUG_INSTR_OP_KIND: [&str; 35] = [
 	"Register",
 	"NearBranch16",
 	"NearBranch32",
@@ -433,7 +433,7 @@ pub(crate) enum MemorySizeInfo {
 	Qword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MEMORY_SIZE_INFO: [&str; 4] = [
+pub(crate) static GEN_DEBUG_MEMORY_SIZE_INFO: [&str; 4] = [
 	"None",
 	"Word",
 	"Dword",
@@ -466,7 +466,7 @@ pub(crate) enum FarMemorySizeInfo {
 	Dword,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_FAR_MEMORY_SIZE_INFO: [&str; 3] = [
+pub(crate) static GEN_DEBUG_FAR_MEMORY_SIZE_INFO: [&str; 3] = [
 	"None",
 	"Word",
 	"Dword",
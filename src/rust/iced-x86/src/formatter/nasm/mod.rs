@@ -104,6 +104,7 @@ pub struct NasmFormatter<'a> {
 	number_formatter: NumberFormatter,
 	symbol_resolver: Option<&'a mut SymbolResolver>,
 	options_provider: Option<&'a mut FormatterOptionsProvider>,
+	annotator: Option<&'a mut FormatterAnnotator>,
 }
 
 impl<'a> Default for NasmFormatter<'a> {
@@ -153,9 +154,22 @@ impl<'a> NasmFormatter<'a> {
 			number_formatter: NumberFormatter::new(),
 			symbol_resolver,
 			options_provider,
+			annotator: None,
 		}
 	}
 
+	/// Sets the annotator that adds a trailing comment to instructions formatted by [`format()`]
+	///
+	/// [`format()`]: trait.Formatter.html#tymethod.format
+	///
+	/// # Arguments
+	///
+	/// - `annotator`: Annotator or `None`
+	#[inline]
+	pub fn set_annotator(&mut self, annotator: Option<&'a mut FormatterAnnotator>) {
+		self.annotator = annotator;
+	}
+
 	fn format_mnemonic(
 		&mut self, instruction: &Instruction, output: &mut FormatterOutput, op_info: &InstrOpInfo, column: &mut u32, mnemonic_options: u32,
 	) {
@@ -252,10 +266,10 @@ impl<'a> NasmFormatter<'a> {
 			let mnemonic = op_info.mnemonic;
 			if (op_info.flags & InstrOpInfoFlags::MNEMONIC_IS_DIRECTIVE) != 0 {
 				output.write(mnemonic.get(self.d.options.uppercase_keywords() || self.d.options.uppercase_all()), FormatterTextKind::Directive);
+				*column += mnemonic.len() as u32;
 			} else {
-				output.write_mnemonic(instruction, mnemonic.get(self.d.options.uppercase_mnemonics() || self.d.options.uppercase_all()));
+				*column += write_mnemonic(&self.d.options, output, instruction, mnemonic);
 			}
-			*column += mnemonic.len() as u32;
 		}
 	}
 
@@ -387,6 +401,7 @@ impl<'a> NasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					FormatterOutputMethods::write1(
 						output,
@@ -411,6 +426,7 @@ impl<'a> NasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let s = if op_kind == InstrOpKind::NearBranch32 {
 						self.number_formatter.format_u32_zeroes(
@@ -472,6 +488,7 @@ impl<'a> NasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					let selector_symbol = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 						symbol_resolver.symbol(instruction, operand + 1, instruction_operand, instruction.far_branch_selector() as u64, 2)
@@ -527,6 +544,7 @@ impl<'a> NasmFormatter<'a> {
 					let mut number_options = NumberFormattingOptions::with_branch(&self.d.options);
 					if let Some(ref mut options_provider) = self.options_provider {
 						options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+						number_options.apply_number_base(&self.d.options, &operand_options);
 					}
 					{
 						let s = self.number_formatter.format_u16_zeroes(
@@ -585,6 +603,7 @@ impl<'a> NasmFormatter<'a> {
 				let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm8 as u64, 1)
@@ -604,19 +623,31 @@ impl<'a> NasmFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm8 as i8 as u64;
-						number_kind = NumberKind::Int8;
-						if (imm8 as i8) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm8 = -(imm8 as i8) as u8;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm8 as u64,
+						1,
+					) {
+						if number_options.signed_number {
+							imm64 = imm8 as i8 as u64;
+							number_kind = NumberKind::Int8;
+							if (imm8 as i8) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm8 = -(imm8 as i8) as u8;
+							}
+						} else {
+							imm64 = imm8 as u64;
+							number_kind = NumberKind::UInt8;
 						}
-					} else {
-						imm64 = imm8 as u64;
-						number_kind = NumberKind::UInt8;
+						let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u8(&self.d.options, &number_options, imm8);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -633,6 +664,7 @@ impl<'a> NasmFormatter<'a> {
 				let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm16 as u64, 2)
@@ -652,19 +684,31 @@ impl<'a> NasmFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm16 as i16 as u64;
-						number_kind = NumberKind::Int16;
-						if (imm16 as i16) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm16 = -(imm16 as i16) as u16;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm16 as u64,
+						2,
+					) {
+						if number_options.signed_number {
+							imm64 = imm16 as i16 as u64;
+							number_kind = NumberKind::Int16;
+							if (imm16 as i16) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm16 = -(imm16 as i16) as u16;
+							}
+						} else {
+							imm64 = imm16 as u64;
+							number_kind = NumberKind::UInt16;
 						}
-					} else {
-						imm64 = imm16 as u64;
-						number_kind = NumberKind::UInt16;
+						let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u16(&self.d.options, &number_options, imm16);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -681,6 +725,7 @@ impl<'a> NasmFormatter<'a> {
 				let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm32 as u64, 4)
@@ -700,19 +745,31 @@ impl<'a> NasmFormatter<'a> {
 						self.d.options.show_symbol_address(),
 					);
 				} else {
-					if number_options.signed_number {
-						imm64 = imm32 as i32 as u64;
-						number_kind = NumberKind::Int32;
-						if (imm32 as i32) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm32 = -(imm32 as i32) as u32;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm32 as u64,
+						4,
+					) {
+						if number_options.signed_number {
+							imm64 = imm32 as i32 as u64;
+							number_kind = NumberKind::Int32;
+							if (imm32 as i32) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm32 = -(imm32 as i32) as u32;
+							}
+						} else {
+							imm64 = imm32 as u64;
+							number_kind = NumberKind::UInt32;
 						}
-					} else {
-						imm64 = imm32 as u64;
-						number_kind = NumberKind::UInt32;
+						let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
+						output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u32(&self.d.options, &number_options, imm32);
-					output.write_number(instruction, operand, instruction_operand, s, imm64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -731,6 +788,7 @@ impl<'a> NasmFormatter<'a> {
 				let mut number_options = NumberFormattingOptions::with_immediate(&self.d.options);
 				if let Some(ref mut options_provider) = self.options_provider {
 					options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+					number_options.apply_number_base(&self.d.options, &operand_options);
 				}
 				if let Some(ref symbol) = if let Some(ref mut symbol_resolver) = self.symbol_resolver {
 					symbol_resolver.symbol(instruction, operand, instruction_operand, imm64, 8)
@@ -751,17 +809,29 @@ impl<'a> NasmFormatter<'a> {
 					);
 				} else {
 					value64 = imm64;
-					if number_options.signed_number {
-						number_kind = NumberKind::Int64;
-						if (imm64 as i64) < 0 {
-							output.write("-", FormatterTextKind::Operator);
-							imm64 = -(imm64 as i64) as u64;
+					if !FormatterOutputMethods::write_immediate(
+						&mut self.options_provider,
+						output,
+						instruction,
+						operand,
+						instruction_operand,
+						&operand_options,
+						&number_options,
+						imm64,
+						8,
+					) {
+						if number_options.signed_number {
+							number_kind = NumberKind::Int64;
+							if (imm64 as i64) < 0 {
+								output.write("-", FormatterTextKind::Operator);
+								imm64 = -(imm64 as i64) as u64;
+							}
+						} else {
+							number_kind = NumberKind::UInt64;
 						}
-					} else {
-						number_kind = NumberKind::UInt64;
+						let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
+						output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 					}
-					let s = self.number_formatter.format_u64(&self.d.options, &number_options, imm64);
-					output.write_number(instruction, operand, instruction_operand, s, value64, number_kind, FormatterTextKind::Number);
 				}
 			}
 
@@ -1093,6 +1163,7 @@ impl<'a> NasmFormatter<'a> {
 		if let Some(ref mut options_provider) = self.options_provider {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+			number_options.apply_number_base(&self.d.options, &operand_options);
 		}
 
 		let abs_addr;
@@ -1232,6 +1303,7 @@ impl<'a> NasmFormatter<'a> {
 			let mut number_options = NumberFormattingOptions::with_displacement(&self.d.options);
 			if let Some(ref mut options_provider) = self.options_provider {
 				options_provider.operand_options(instruction, operand, instruction_operand, &mut operand_options, &mut number_options);
+				number_options.apply_number_base(&self.d.options, &operand_options);
 			}
 			if let Some(ref symbol) = symbol {
 				if need_plus {
@@ -1504,6 +1576,12 @@ impl<'a> Formatter for NasmFormatter<'a> {
 			add_tabs(output, column, self.d.options.first_operand_char_index(), self.d.options.tab_size());
 			self.format_operands(instruction, output, &op_info);
 		}
+
+		if let Some(ref mut annotator) = self.annotator {
+			write_annotations(*annotator, &self.d.options, output, instruction, op_info.op_count as u32, |operand| {
+				op_info.instruction_index(operand)
+			});
+		}
 	}
 
 	#[cfg_attr(has_must_use, must_use)]
@@ -99,17 +99,12 @@ impl<'a> NumberFormattingOptions<'a> {
 	#[cfg_attr(has_must_use, must_use)]
 	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
 	pub fn new(options: &'a FormatterOptions, leading_zeroes: bool, signed_number: bool, displacement_leading_zeroes: bool) -> Self {
-		let (digit_group_size, prefix, suffix) = match options.number_base() {
-			NumberBase::Hexadecimal => (options.hex_digit_group_size(), options.hex_prefix(), options.hex_suffix()),
-			NumberBase::Decimal => (options.decimal_digit_group_size(), options.decimal_prefix(), options.decimal_suffix()),
-			NumberBase::Octal => (options.octal_digit_group_size(), options.octal_prefix(), options.octal_suffix()),
-			NumberBase::Binary => (options.binary_digit_group_size(), options.binary_prefix(), options.binary_suffix()),
-		};
+		let (digit_group_size, prefix, suffix) = NumberFormattingOptions::get_base_info(options, options.number_base());
 		Self {
 			prefix,
 			suffix,
 			digit_separator: options.digit_separator(),
-			digit_group_size: cmp::min(u8::MAX as u32, digit_group_size) as u8,
+			digit_group_size,
 			number_base: options.number_base(),
 			uppercase_hex: options.uppercase_hex(),
 			small_hex_numbers_in_decimal: options.small_hex_numbers_in_decimal(),
@@ -119,4 +114,35 @@ impl<'a> NumberFormattingOptions<'a> {
 			displacement_leading_zeroes,
 		}
 	}
+
+	/// Changes the number base and uses the prefix, suffix and digit group size of the new base
+	///
+	/// # Arguments
+	///
+	/// * `options`: Formatter options to use
+	/// * `number_base`: New number base
+	#[inline]
+	pub fn set_number_base(&mut self, options: &'a FormatterOptions, number_base: NumberBase) {
+		let (digit_group_size, prefix, suffix) = NumberFormattingOptions::get_base_info(options, number_base);
+		self.prefix = prefix;
+		self.suffix = suffix;
+		self.digit_group_size = digit_group_size;
+		self.number_base = number_base;
+	}
+
+	pub(super) fn apply_number_base(&mut self, options: &'a FormatterOptions, operand_options: &FormatterOperandOptions) {
+		if let Some(number_base) = operand_options.number_base() {
+			self.set_number_base(options, number_base);
+		}
+	}
+
+	fn get_base_info(options: &'a FormatterOptions, number_base: NumberBase) -> (u8, &'a str, &'a str) {
+		let (digit_group_size, prefix, suffix) = match number_base {
+			NumberBase::Hexadecimal => (options.hex_digit_group_size(), options.hex_prefix(), options.hex_suffix()),
+			NumberBase::Decimal => (options.decimal_digit_group_size(), options.decimal_prefix(), options.decimal_suffix()),
+			NumberBase::Octal => (options.octal_digit_group_size(), options.octal_prefix(), options.octal_suffix()),
+			NumberBase::Binary => (options.binary_digit_group_size(), options.binary_prefix(), options.binary_suffix()),
+		};
+		(cmp::min(u8::MAX as u32, digit_group_size) as u8, prefix, suffix)
+	}
 }
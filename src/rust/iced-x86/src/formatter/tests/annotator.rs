@@ -0,0 +1,149 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::test_utils::decode_at;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+struct MyAnnotator {
+	calls: Vec<(u32, Option<u32>, Option<u64>)>,
+	all_operands: bool,
+}
+
+impl MyAnnotator {
+	fn new(all_operands: bool) -> Self {
+		Self { calls: Vec::new(), all_operands }
+	}
+}
+
+impl FormatterAnnotator for MyAnnotator {
+	fn annotation(
+		&mut self, _instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, virtual_address: Option<u64>,
+	) -> Option<String> {
+		self.calls.push((operand, instruction_operand, virtual_address));
+		match virtual_address {
+			Some(address) => Some(format!("[{:X}] = 1234", address)),
+			None if self.all_operands => Some(format!("op{}", operand)),
+			None => None,
+		}
+	}
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn no_annotator() {
+	let instruction = decode_at(64, "8B05 10000000", 0x1000);
+	let mut output = String::new();
+	MasmFormatter::new().format(&instruction, &mut output);
+	assert_eq!("mov eax,[1016h]", output);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn rip_relative_memory() {
+	let instruction = decode_at(64, "8B05 10000000", 0x1000);
+	let mut annotator = MyAnnotator::new(false);
+	let mut output = String::new();
+	{
+		let mut formatter = MasmFormatter::new();
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("mov eax,[1016h] ; [1016] = 1234", output);
+	assert_eq!(vec![(0, Some(0), None), (1, Some(1), Some(0x1016))], annotator.calls);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn register_relative_memory() {
+	let instruction = decode_at(64, "8B40 10", 0x1000);
+	let mut annotator = MyAnnotator::new(false);
+	let mut output = String::new();
+	{
+		let mut formatter = MasmFormatter::new();
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("mov eax,[rax+10h]", output);
+	assert_eq!(vec![(0, Some(0), None), (1, Some(1), None)], annotator.calls);
+}
+
+#[test]
+#[cfg(feature = "nasm")]
+fn branch_target() {
+	let instruction = decode_at(64, "E8 FB000000", 0x1000);
+	let mut annotator = MyAnnotator::new(false);
+	let mut output = String::new();
+	{
+		let mut formatter = NasmFormatter::new();
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("call 0000000000001100h ; [1100] = 1234", output);
+}
+
+#[test]
+#[cfg(feature = "intel")]
+fn multiple_annotations() {
+	let instruction = decode_at(64, "01C8", 0x1000);
+	let mut annotator = MyAnnotator::new(true);
+	let mut output = String::new();
+	{
+		let mut formatter = IntelFormatter::new();
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("add eax,ecx ; op0, op1", output);
+}
+
+#[test]
+#[cfg(feature = "gas")]
+fn gas_comment_prefix() {
+	let instruction = decode_at(64, "8B05 10000000", 0x1000);
+	let mut annotator = MyAnnotator::new(false);
+	let mut output = String::new();
+	{
+		let mut formatter = GasFormatter::new();
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("mov 0x1016,%eax # [1016] = 1234", output);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn custom_comment_prefix() {
+	let instruction = decode_at(64, "8B05 10000000", 0x1000);
+	let mut annotator = MyAnnotator::new(false);
+	let mut output = String::new();
+	{
+		let mut formatter = MasmFormatter::new();
+		formatter.options_mut().set_comment_prefix(String::from("//"));
+		formatter.set_annotator(Some(&mut annotator));
+		formatter.format(&instruction, &mut output);
+	}
+	assert_eq!("mov eax,[1016h] // [1016] = 1234", output);
+}
@@ -0,0 +1,155 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::test_utils::format;
+use super::super::*;
+#[cfg(feature = "gas")]
+use super::gas;
+#[cfg(feature = "masm")]
+use super::masm;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[test]
+fn default_cc_options() {
+	let options = FormatterOptions::new();
+	assert_eq!(CC_b::b, options.cc_b());
+	assert_eq!(CC_ae::ae, options.cc_ae());
+	assert_eq!(CC_e::e, options.cc_e());
+	assert_eq!(CC_ne::ne, options.cc_ne());
+	assert_eq!(CC_be::be, options.cc_be());
+	assert_eq!(CC_a::a, options.cc_a());
+	assert_eq!(CC_p::p, options.cc_p());
+	assert_eq!(CC_np::np, options.cc_np());
+	assert_eq!(CC_l::l, options.cc_l());
+	assert_eq!(CC_ge::ge, options.cc_ge());
+	assert_eq!(CC_le::le, options.cc_le());
+	assert_eq!(CC_g::g, options.cc_g());
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn jcc() {
+	assert_eq!("je short 800000000000000Ah", format(64, "74 18", masm(None, |_| {})));
+	assert_eq!("jz short 800000000000000Ah", format(64, "74 18", masm(None, |o| o.set_cc_e(CC_e::z))));
+	assert_eq!("jc short 800000000000000Ah", format(64, "72 18", masm(None, |o| o.set_cc_b(CC_b::c))));
+	assert_eq!("jnae short 800000000000000Ah", format(64, "72 18", masm(None, |o| o.set_cc_b(CC_b::nae))));
+	assert_eq!("jnle near ptr 800000000000000Eh", format(64, "0F8F 18000000", masm(None, |o| o.set_cc_g(CC_g::nle))));
+	// Other condition codes aren't affected
+	assert_eq!("jbe short 800000000000000Ah", format(64, "76 18", masm(None, |o| o.set_cc_b(CC_b::c))));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn setcc_cmovcc_loopcc() {
+	assert_eq!("setnc al", format(64, "0F93 C0", masm(None, |o| o.set_cc_ae(CC_ae::nc))));
+	assert_eq!(
+		"SETNZ AL",
+		format(
+			64,
+			"0F95 C0",
+			masm(None, |o| {
+				o.set_cc_ne(CC_ne::nz);
+				o.set_uppercase_all(true);
+			})
+		)
+	);
+	assert_eq!("cmovpo eax,ecx", format(64, "0F4B C1", masm(None, |o| o.set_cc_np(CC_np::po))));
+	assert_eq!("loopz 800000000000000Ah", format(64, "E1 18", masm(None, |o| o.set_cc_e(CC_e::z))));
+	assert_eq!("loopnz 800000000000000Ah", format(64, "E0 18", masm(None, |o| o.set_cc_ne(CC_ne::nz))));
+}
+
+#[test]
+#[cfg(feature = "gas")]
+fn gas_mnemonic_size_suffix() {
+	assert_eq!(
+		"cmovnbl %ecx,%eax",
+		format(
+			64,
+			"0F43 C1",
+			gas(None, |o| {
+				o.set_cc_ae(CC_ae::nb);
+				o.set_gas_show_mnemonic_size_suffix(true);
+			})
+		)
+	);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn code_and_mnemonic_overrides() {
+	let mut options = FormatterOptions::new();
+	assert_eq!(None, options.code_mnemonic(Code::Nopd));
+	options.set_code_mnemonic(Code::Nopd, Some(String::from("xchg")));
+	options.set_mnemonic_override(Mnemonic::Nop, Some(String::from("NOP")));
+	assert_eq!(Some("xchg"), options.code_mnemonic(Code::Nopd));
+	assert_eq!(Some("NOP"), options.mnemonic_override(Mnemonic::Nop));
+	options.set_code_mnemonic(Code::Nopd, None);
+	assert_eq!(None, options.code_mnemonic(Code::Nopd));
+
+	// Code has priority over Mnemonic which has priority over the condition code options
+	assert_eq!(
+		"xchg",
+		format(
+			32,
+			"90",
+			masm(None, |o| {
+				o.set_code_mnemonic(Code::Nopd, Some(String::from("xchg")));
+				o.set_mnemonic_override(Mnemonic::Nop, Some(String::from("NOP")));
+			})
+		)
+	);
+	assert_eq!(
+		"NOP",
+		format(
+			16,
+			"90",
+			masm(None, |o| {
+				o.set_code_mnemonic(Code::Nopd, Some(String::from("xchg")));
+				o.set_mnemonic_override(Mnemonic::Nop, Some(String::from("NOP")));
+			})
+		)
+	);
+	assert_eq!(
+		"jump_if_zero short 800000000000000Ah",
+		format(
+			64,
+			"74 18",
+			masm(None, |o| {
+				o.set_cc_e(CC_e::z);
+				o.set_mnemonic_override(Mnemonic::Je, Some(String::from("jump_if_zero")));
+			})
+		)
+	);
+	// Prefixes aren't part of the mnemonic
+	assert_eq!(
+		"lock plus [rax],ecx",
+		format(
+			64,
+			"F0 01 08",
+			masm(None, |o| {
+				o.set_mnemonic_override(Mnemonic::Add, Some(String::from("plus")));
+			})
+		)
+	);
+}
@@ -79,7 +79,7 @@ pub(crate) enum OptionsProps {
 	UsePseudoOps,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OPTIONS_PROPS: [&str; 49] = [
+pub(crate) static GEN_DEBUG_OPTIONS_PROPS: [&str; 49] = [
 	"AddLeadingZeroToHexNumbers",
 	"AlwaysShowScale",
 	"AlwaysShowSegmentRegister",
@@ -21,15 +21,19 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+mod annotator;
+mod cc;
 pub(crate) mod enums;
 mod instr_infos;
 pub(super) mod misc;
 pub(super) mod mnemonic_opts_parser;
 pub(super) mod number;
+mod opt_provider;
 pub(super) mod options;
 pub(super) mod options_test_case_parser;
 pub(super) mod opts_info;
 mod opts_infos;
+mod outputs;
 pub(super) mod registers;
 
 use self::instr_infos::*;
@@ -38,7 +42,11 @@ use super::super::encoder::tests::non_decoded_tests;
 use super::super::test_utils::create_decoder;
 use super::super::test_utils::from_str_conv::to_vec_u8;
 use super::super::{Code, Decoder, Instruction};
-use super::Formatter;
+#[cfg(feature = "gas")]
+use super::GasFormatter;
+#[cfg(feature = "masm")]
+use super::MasmFormatter;
+use super::{Formatter, FormatterOptions, FormatterOptionsProvider};
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
 #[cfg(not(feature = "std"))]
@@ -46,6 +54,22 @@ use alloc::string::String;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+// Creates a masm formatter, `f` can change the options
+#[cfg(feature = "masm")]
+pub(super) fn masm<'a, F: FnOnce(&mut FormatterOptions)>(options_provider: Option<&'a mut FormatterOptionsProvider>, f: F) -> Box<Formatter + 'a> {
+	let mut formatter = MasmFormatter::with_options(None, options_provider);
+	f(formatter.options_mut());
+	Box::new(formatter)
+}
+
+// Creates a gas formatter, `f` can change the options
+#[cfg(feature = "gas")]
+pub(super) fn gas<'a, F: FnOnce(&mut FormatterOptions)>(options_provider: Option<&'a mut FormatterOptionsProvider>, f: F) -> Box<Formatter + 'a> {
+	let mut formatter = GasFormatter::with_options(None, options_provider);
+	f(formatter.options_mut());
+	Box::new(formatter)
+}
+
 pub(super) fn formatter_test(bitness: u32, dir: &str, filename: &str, is_misc: bool, fmt_factory: fn() -> Box<Formatter>) {
 	let infos = get_infos(bitness, is_misc);
 	let lines = get_formatted_lines(bitness, dir, filename);
@@ -0,0 +1,166 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::test_utils::format;
+use super::super::*;
+#[cfg(feature = "gas")]
+use super::gas;
+#[cfg(feature = "masm")]
+use super::masm;
+
+struct MyOptionsProvider {
+	number_base: Option<NumberBase>,
+	presentation: ImmediatePresentation,
+	signed: bool,
+	constant: Option<(u64, NumberKind, &'static str)>,
+}
+
+impl MyOptionsProvider {
+	fn new() -> Self {
+		Self { number_base: None, presentation: ImmediatePresentation::Number, signed: false, constant: None }
+	}
+}
+
+impl FormatterOptionsProvider for MyOptionsProvider {
+	fn operand_options(
+		&mut self, _instruction: &Instruction, _operand: u32, _instruction_operand: Option<u32>, options: &mut FormatterOperandOptions,
+		number_options: &mut NumberFormattingOptions,
+	) {
+		options.set_number_base(self.number_base);
+		options.set_immediate_presentation(self.presentation);
+		if self.signed {
+			number_options.signed_number = true;
+		}
+	}
+
+	fn constant_name(
+		&mut self, _instruction: &Instruction, _operand: u32, _instruction_operand: Option<u32>, value: u64, number_kind: NumberKind,
+	) -> Option<&str> {
+		match self.constant {
+			Some((v, kind, name)) if v == value && kind == number_kind => Some(name),
+			_ => None,
+		}
+	}
+}
+
+#[test]
+fn operand_options_default_values() {
+	let options = FormatterOperandOptions::default();
+	assert_eq!(None, options.number_base());
+	assert_eq!(ImmediatePresentation::Number, options.immediate_presentation());
+}
+
+#[test]
+fn operand_options_can_set_values() {
+	let mut options = FormatterOperandOptions::default();
+	options.set_memory_size_options(MemorySizeOptions::Never);
+	for &base in [NumberBase::Hexadecimal, NumberBase::Decimal, NumberBase::Octal, NumberBase::Binary].iter() {
+		options.set_number_base(Some(base));
+		assert_eq!(Some(base), options.number_base());
+	}
+	for &presentation in [ImmediatePresentation::Char, ImmediatePresentation::Float, ImmediatePresentation::Number].iter() {
+		options.set_immediate_presentation(presentation);
+		assert_eq!(presentation, options.immediate_presentation());
+	}
+	options.set_number_base(None);
+	assert_eq!(None, options.number_base());
+	assert_eq!(MemorySizeOptions::Never, options.memory_size_options());
+	assert!(options.branch_size());
+	assert!(!options.rip_relative_addresses());
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn immediate_number_base() {
+	let mut provider = MyOptionsProvider::new();
+	assert_eq!("mov eax,10h", format(64, "B8 10 00 00 00", masm(Some(&mut provider), |_| {})));
+	provider.number_base = Some(NumberBase::Decimal);
+	assert_eq!("mov eax,16", format(64, "B8 10 00 00 00", masm(Some(&mut provider), |_| {})));
+	provider.number_base = Some(NumberBase::Binary);
+	assert_eq!("mov eax,10000b", format(64, "B8 10 00 00 00", masm(Some(&mut provider), |_| {})));
+	provider.number_base = Some(NumberBase::Decimal);
+	assert_eq!("mov eax,[rax+16]", format(64, "8B 40 10", masm(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "gas")]
+fn immediate_number_base_gas() {
+	let mut provider = MyOptionsProvider::new();
+	provider.number_base = Some(NumberBase::Decimal);
+	assert_eq!("mov $16,%eax", format(64, "B8 10 00 00 00", gas(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn immediate_char() {
+	let mut provider = MyOptionsProvider::new();
+	provider.presentation = ImmediatePresentation::Char;
+	assert_eq!("mov al,'A'", format(64, "B0 41", masm(Some(&mut provider), |_| {})));
+	assert_eq!("cmp eax,'z'", format(64, "83 F8 7A", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov al,1", format(64, "B0 01", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov al,27h", format(64, "B0 27", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov eax,12345678h", format(64, "B8 78 56 34 12", masm(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "gas")]
+fn immediate_char_gas() {
+	let mut provider = MyOptionsProvider::new();
+	provider.presentation = ImmediatePresentation::Char;
+	assert_eq!("mov $'A',%al", format(64, "B0 41", gas(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn immediate_float() {
+	let mut provider = MyOptionsProvider::new();
+	provider.presentation = ImmediatePresentation::Float;
+	assert_eq!("mov eax,1.0", format(64, "B8 00 00 80 3F", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov dword ptr [rax],-2.5", format(64, "C7 00 00 00 20 C0", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov rax,1.5", format(64, "48 B8 00 00 00 00 00 00 F8 3F", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov ax,1234h", format(64, "66 B8 34 12", masm(Some(&mut provider), |_| {})));
+	assert_eq!("mov rax,1.0", format(64, "48 C7 C0 00 00 80 3F", masm(Some(&mut provider), |_| {})));
+	assert_eq!("add eax,0FFFFFFFFh", format(64, "83 C0 FF", masm(Some(&mut provider), |_| {})));
+	assert_eq!("add rax,0FFFFFFFFFFFFFFFFh", format(64, "48 83 C0 FF", masm(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn immediate_signed() {
+	let mut provider = MyOptionsProvider::new();
+	assert_eq!("add eax,0FFFFFFFFh", format(64, "83 C0 FF", masm(Some(&mut provider), |_| {})));
+	provider.signed = true;
+	assert_eq!("add eax,-1", format(64, "83 C0 FF", masm(Some(&mut provider), |_| {})));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn immediate_constant_name() {
+	let mut provider = MyOptionsProvider::new();
+	provider.constant = Some((5, NumberKind::UInt32, "ERROR_ACCESS_DENIED"));
+	assert_eq!("cmp eax,ERROR_ACCESS_DENIED", format(64, "83 F8 05", masm(Some(&mut provider), |_| {})));
+	assert_eq!("cmp eax,6", format(64, "83 F8 06", masm(Some(&mut provider), |_| {})));
+	provider.signed = true;
+	provider.constant = Some((0xFFFF_FFFF_FFFF_FFFF, NumberKind::Int32, "INVALID_HANDLE"));
+	assert_eq!("cmp eax,INVALID_HANDLE", format(64, "83 F8 FF", masm(Some(&mut provider), |_| {})));
+}
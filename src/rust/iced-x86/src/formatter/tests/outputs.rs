@@ -0,0 +1,253 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::test_utils::decode_at;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[test]
+fn ansi_theme_new_has_no_colors() {
+	let theme = AnsiTheme::new();
+	assert_eq!(AnsiColor::None, theme.color(FormatterTextKind::Text));
+	assert_eq!(AnsiColor::None, theme.color(FormatterTextKind::Mnemonic));
+	assert_eq!(AnsiColor::None, theme.color(FormatterTextKind::Function));
+}
+
+#[test]
+fn ansi_default_theme_doesnt_color_text() {
+	let theme = AnsiTheme::default();
+	assert_eq!(AnsiColor::None, theme.color(FormatterTextKind::Text));
+	assert_ne!(AnsiColor::None, theme.color(FormatterTextKind::Mnemonic));
+	assert_ne!(AnsiColor::None, theme.color(FormatterTextKind::Register));
+}
+
+#[test]
+fn ansi_output_writes_escape_sequences() {
+	let mut theme = AnsiTheme::new();
+	theme.set_color(FormatterTextKind::Mnemonic, AnsiColor::BrightYellow);
+	theme.set_color(FormatterTextKind::Number, AnsiColor::Rgb(1, 2, 3));
+	let mut output = AnsiColorOutput::with_theme(theme);
+	output.write("add", FormatterTextKind::Mnemonic);
+	output.write(" ", FormatterTextKind::Text);
+	output.write("5", FormatterTextKind::Number);
+	assert_eq!("\x1B[93madd\x1B[0m \x1B[38;2;1;2;3m5\x1B[0m", output.as_str());
+	output.clear();
+	assert_eq!("", output.as_str());
+	assert_eq!(AnsiColor::BrightYellow, output.theme().color(FormatterTextKind::Mnemonic));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn ansi_output_without_colors_is_same_as_string() {
+	let instruction = decode_at(64, "F0 48 01 84 8B 78 56 34 12", 0);
+	let mut formatter = MasmFormatter::new();
+	let mut expected = String::new();
+	formatter.format(&instruction, &mut expected);
+	let mut output = AnsiColorOutput::with_theme(AnsiTheme::new());
+	formatter.format(&instruction, &mut output);
+	assert_eq!(expected, output.into_string());
+}
+
+#[test]
+fn html_class_names() {
+	assert_eq!("mnemonic", HtmlOutput::class_name(FormatterTextKind::Mnemonic));
+	assert_eq!("label-address", HtmlOutput::class_name(FormatterTextKind::LabelAddress));
+	assert_eq!("function", HtmlOutput::class_name(FormatterTextKind::Function));
+}
+
+#[test]
+fn html_output_escapes_text() {
+	let mut output = HtmlOutput::with_prefixes("asm-", "L");
+	output.write("a<b>&\"'", FormatterTextKind::Data);
+	output.write("<", FormatterTextKind::Text);
+	assert_eq!("<span class=\"asm-data\">a&lt;b&gt;&amp;&quot;&#39;</span>&lt;", output.as_str());
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn html_output_links_branch_targets() {
+	let instruction = decode_at(64, "E8 FB 0F 00 00", 0x1000);
+	let mut output = HtmlOutput::with_prefixes("", "L");
+	output.write_anchor(instruction.ip());
+	let mut formatter = MasmFormatter::new();
+	formatter.format(&instruction, &mut output);
+	assert_eq!(
+		"<a id=\"L1000\"></a><span class=\"mnemonic\">call</span> <a class=\"function-address\" href=\"#L2000\">0000000000002000h</a>",
+		output.as_str()
+	);
+}
+
+#[cfg(feature = "masm")]
+struct MySymbolResolver;
+#[cfg(feature = "masm")]
+impl SymbolResolver for MySymbolResolver {
+	fn symbol(
+		&mut self, _instruction: &Instruction, _operand: u32, _instruction_operand: Option<u32>, address: u64, _address_size: u32,
+	) -> Option<SymbolResult> {
+		if address == 0x2000 {
+			Some(SymbolResult::with_str_kind(address, "f<int>", FormatterTextKind::Function))
+		} else {
+			None
+		}
+	}
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn html_output_links_symbols() {
+	let instruction = decode_at(64, "E8 FB 0F 00 00", 0x1000);
+	let mut output = HtmlOutput::new();
+	let mut resolver = MySymbolResolver;
+	let mut formatter = MasmFormatter::with_options(Some(&mut resolver), None);
+	formatter.format(&instruction, &mut output);
+	assert_eq!("<span class=\"mnemonic\">call</span> <a class=\"function\" href=\"#loc_2000\">f&lt;int&gt;</a>", output.as_str());
+}
+
+#[cfg(any(feature = "gas", feature = "masm"))]
+fn component_texts(output: &OperandTreeOutput, operand: usize) -> Vec<(OperandComponentKind, &str)> {
+	output.operands()[operand].components.iter().map(|c| (c.kind, &output.text()[c.range.clone()])).collect()
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn operand_tree_memory_components() {
+	let instruction = decode_at(64, "48 8B 5C 8B 10", 0);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = MasmFormatter::new();
+	output.format(&mut formatter, &instruction);
+	assert_eq!("mov rbx,[rbx+rcx*4+10h]", output.text());
+	assert_eq!(0..3, output.mnemonic_range());
+	assert_eq!(2, output.operands().len());
+
+	let op0 = &output.operands()[0];
+	assert_eq!(0, op0.operand);
+	assert_eq!(Some(0), op0.instruction_operand);
+	assert_eq!(Some(OpKind::Register), op0.op_kind);
+	assert_eq!(4..7, op0.range);
+	assert_eq!(vec![(OperandComponentKind::Register, "rbx")], component_texts(&output, 0));
+
+	let op1 = &output.operands()[1];
+	assert_eq!(Some(OpKind::Memory), op1.op_kind);
+	assert_eq!("[rbx+rcx*4+10h]", &output.text()[op1.range.clone()]);
+	assert_eq!(
+		vec![
+			(OperandComponentKind::Base, "rbx"),
+			(OperandComponentKind::Index, "rcx"),
+			(OperandComponentKind::Scale, "4"),
+			(OperandComponentKind::Displacement, "10h"),
+		],
+		component_texts(&output, 1)
+	);
+	assert_eq!(Register::RBX, op1.components[0].register);
+	assert_eq!(4, op1.components[2].value);
+	assert_eq!(0x10, op1.components[3].value);
+	assert_eq!(vec![4..7, 9..12], output.register_ranges(Register::RBX));
+	assert_eq!(vec![13..16], output.register_ranges(Register::RCX));
+}
+
+#[test]
+#[cfg(feature = "gas")]
+fn operand_tree_gas_memory_components() {
+	let instruction = decode_at(64, "48 8B 5C 8B 10", 0);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = GasFormatter::new();
+	output.format(&mut formatter, &instruction);
+	assert_eq!("mov 0x10(%rbx,%rcx,4),%rbx", output.text());
+	assert_eq!(
+		vec![
+			(OperandComponentKind::Displacement, "0x10"),
+			(OperandComponentKind::Base, "%rbx"),
+			(OperandComponentKind::Index, "%rcx"),
+			(OperandComponentKind::Scale, "4"),
+		],
+		component_texts(&output, 0)
+	);
+	assert_eq!(Some(1), output.operands()[0].instruction_operand);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn operand_tree_segment_and_immediate() {
+	let instruction = decode_at(64, "64 C7 00 78 56 34 12", 0);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = MasmFormatter::new();
+	output.format(&mut formatter, &instruction);
+	assert_eq!("mov dword ptr fs:[rax],12345678h", output.text());
+	assert_eq!(vec![(OperandComponentKind::Segment, "fs"), (OperandComponentKind::Base, "rax")], component_texts(&output, 0));
+	assert_eq!(vec![(OperandComponentKind::Immediate, "12345678h")], component_texts(&output, 1));
+	assert_eq!(0x1234_5678, output.operands()[1].components[0].value);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn operand_tree_op_mask_and_decorators() {
+	let instruction = decode_at(64, "62 F2 4F DD 72 50 01", 0);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = MasmFormatter::new();
+	output.format(&mut formatter, &instruction);
+	assert_eq!("vcvtne2ps2bf16 zmm2{k5}{z},zmm6,dword bcst [rax+4]", output.text());
+	assert_eq!(
+		vec![
+			(OperandComponentKind::Register, "zmm2"),
+			(OperandComponentKind::OpMask, "k5"),
+			(OperandComponentKind::Decorator(DecoratorKind::ZeroingMasking), "z"),
+		],
+		component_texts(&output, 0)
+	);
+	assert_eq!(vec![(OperandComponentKind::Base, "rax"), (OperandComponentKind::Displacement, "4")], component_texts(&output, 2));
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn operand_tree_branch_target_and_symbol() {
+	let instruction = decode_at(64, "E8 FB 0F 00 00", 0x1000);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = MasmFormatter::new();
+	output.format(&mut formatter, &instruction);
+	assert_eq!(vec![(OperandComponentKind::BranchTarget, "0000000000002000h")], component_texts(&output, 0));
+
+	let mut resolver = MySymbolResolver;
+	let mut formatter = MasmFormatter::with_options(Some(&mut resolver), None);
+	output.format(&mut formatter, &instruction);
+	assert_eq!("call f<int>", output.text());
+	assert_eq!(vec![(OperandComponentKind::Symbol, "f<int>")], component_texts(&output, 0));
+	assert_eq!(0x2000, output.operands()[0].components[0].value);
+}
+
+#[test]
+#[cfg(feature = "masm")]
+fn operand_tree_records_tokens_when_used_as_output() {
+	let instruction = decode_at(64, "48 8B 5C 8B 10", 0);
+	let mut output = OperandTreeOutput::new();
+	let mut formatter = MasmFormatter::new();
+	formatter.format(&instruction, &mut output);
+	assert_eq!("mov rbx,[rbx+rcx*4+10h]", output.text());
+	assert!(output.operands().is_empty());
+	let token = &output.tokens()[0];
+	assert_eq!(FormatterTextKind::Mnemonic, token.kind);
+	assert_eq!(None, token.operand);
+	let rcx = output.tokens().iter().find(|t| t.register == Register::RCX).unwrap();
+	assert_eq!(Some(1), rcx.operand);
+	assert_eq!(Some(1), rcx.instruction_operand);
+}
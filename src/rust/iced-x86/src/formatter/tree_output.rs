@@ -0,0 +1,445 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::enums::*;
+use super::symres::*;
+use super::{Formatter, FormatterOutput};
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A token written by a formatter to an [`OperandTreeOutput`]
+///
+/// [`OperandTreeOutput`]: struct.OperandTreeOutput.html
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FormatterToken {
+	/// Byte range of the token in [`OperandTreeOutput::text()`]
+	///
+	/// [`OperandTreeOutput::text()`]: struct.OperandTreeOutput.html#method.text
+	pub range: Range<usize>,
+	/// Text kind
+	pub kind: FormatterTextKind,
+	/// Formatter operand or `None` if it's not part of an operand (eg. the mnemonic)
+	pub operand: Option<u32>,
+	/// Instruction operand or `None` if it's not part of an operand or if it's an operand created by the formatter
+	pub instruction_operand: Option<u32>,
+	/// The register if it's a register token, else [`Register::None`]
+	///
+	/// [`Register::None`]: enum.Register.html#variant.None
+	pub register: Register,
+	/// The value if it's a number token, or the symbol's address if it's a symbol
+	pub value: u64,
+	/// Set if it's a number token
+	pub number_kind: Option<NumberKind>,
+	/// Set if it's a decorator token
+	pub decorator: Option<DecoratorKind>,
+	/// Set if it's (part of) a symbol
+	pub is_symbol: bool,
+}
+
+/// What an [`OperandComponent`] is
+///
+/// [`OperandComponent`]: struct.OperandComponent.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OperandComponentKind {
+	/// A register operand
+	Register,
+	/// An op mask register, eg. `k1` in `zmm1{k1}`
+	OpMask,
+	/// Memory segment register
+	Segment,
+	/// Memory base register
+	Base,
+	/// Memory index register
+	Index,
+	/// Memory index scale
+	Scale,
+	/// Memory displacement or absolute address
+	Displacement,
+	/// An immediate value
+	Immediate,
+	/// A near or far branch target (or the selector of a far branch)
+	BranchTarget,
+	/// A symbol, see [`SymbolResolver`]
+	///
+	/// [`SymbolResolver`]: trait.SymbolResolver.html
+	Symbol,
+	/// A decorator, eg. `{z}` or `{sae}`
+	Decorator(DecoratorKind),
+}
+
+/// A component of a [`FormattedOperand`], eg. the base register of a memory operand
+///
+/// [`FormattedOperand`]: struct.FormattedOperand.html
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OperandComponent {
+	/// Component kind
+	pub kind: OperandComponentKind,
+	/// Byte range of the component in [`OperandTreeOutput::text()`]
+	///
+	/// [`OperandTreeOutput::text()`]: struct.OperandTreeOutput.html#method.text
+	pub range: Range<usize>,
+	/// The register or [`Register::None`] if it's not a register component
+	///
+	/// [`Register::None`]: enum.Register.html#variant.None
+	pub register: Register,
+	/// The value of a number or the address of a symbol, else 0
+	pub value: u64,
+}
+
+/// A formatted operand and its components
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FormattedOperand {
+	/// Formatter operand
+	pub operand: u32,
+	/// Instruction operand or `None` if it's an operand created by the formatter
+	pub instruction_operand: Option<u32>,
+	/// Operand kind or `None` if it's an operand created by the formatter
+	pub op_kind: Option<OpKind>,
+	/// Byte range of the operand in [`OperandTreeOutput::text()`]
+	///
+	/// [`OperandTreeOutput::text()`]: struct.OperandTreeOutput.html#method.text
+	pub range: Range<usize>,
+	/// All components, in the order they were written
+	pub components: Vec<OperandComponent>,
+}
+
+/// A [`FormatterOutput`] that remembers every token it gets and, when used by [`format()`], splits the
+/// formatted instruction into operands and their components (registers, memory base, index, scale,
+/// displacement, segment, decorators, ...) with byte ranges into the formatted text.
+///
+/// [`FormatterOutput`]: trait.FormatterOutput.html
+/// [`format()`]: #method.format
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let bytes = b"\x48\x8B\x5C\x8B\x10";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instr = decoder.decode();
+///
+/// let mut output = OperandTreeOutput::new();
+/// let mut formatter = MasmFormatter::new();
+/// output.format(&mut formatter, &instr);
+/// assert_eq!("mov rbx,[rbx+rcx*4+10h]", output.text());
+///
+/// let operand = &output.operands()[1];
+/// assert_eq!(Some(OpKind::Memory), operand.op_kind);
+/// assert_eq!("[rbx+rcx*4+10h]", &output.text()[operand.range.clone()]);
+/// let base = &operand.components[0];
+/// assert_eq!(OperandComponentKind::Base, base.kind);
+/// assert_eq!("rbx", &output.text()[base.range.clone()]);
+///
+/// // All uses of RBX
+/// assert_eq!(vec![4..7, 9..12], output.register_ranges(Register::RBX));
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct OperandTreeOutput {
+	text: String,
+	tokens: Vec<FormatterToken>,
+	operands: Vec<FormattedOperand>,
+	mnemonic_range: Range<usize>,
+	operand: Option<u32>,
+	instruction_operand: Option<u32>,
+}
+
+impl OperandTreeOutput {
+	/// Creates an empty output
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		OperandTreeOutput::default()
+	}
+
+	/// Clears the text, tokens and operands
+	#[inline]
+	pub fn clear(&mut self) {
+		self.text.clear();
+		self.tokens.clear();
+		self.operands.clear();
+		self.mnemonic_range = 0..0;
+		self.operand = None;
+		self.instruction_operand = None;
+	}
+
+	/// Gets the formatted text
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	/// Gets all tokens
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn tokens(&self) -> &[FormatterToken] {
+		&self.tokens
+	}
+
+	/// Gets all operands. This is only initialized by [`format()`].
+	///
+	/// [`format()`]: #method.format
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn operands(&self) -> &[FormattedOperand] {
+		&self.operands
+	}
+
+	/// Gets the byte range of the prefixes and mnemonic. This is only initialized by [`format()`].
+	///
+	/// [`format()`]: #method.format
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn mnemonic_range(&self) -> Range<usize> {
+		self.mnemonic_range.clone()
+	}
+
+	/// Gets the byte ranges of all register tokens that are `register`
+	///
+	/// # Arguments
+	///
+	/// - `register`: Register
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn register_ranges(&self, register: Register) -> Vec<Range<usize>> {
+		self.tokens.iter().filter(|t| t.register == register && register != Register::None).map(|t| t.range.clone()).collect()
+	}
+
+	/// Clears the output and formats the instruction. Unlike [`Formatter::format()`], one space is always
+	/// used to separate the mnemonic from the operands, see [`FormatterOptions::first_operand_char_index()`].
+	///
+	/// [`Formatter::format()`]: trait.Formatter.html#tymethod.format
+	/// [`FormatterOptions::first_operand_char_index()`]: struct.FormatterOptions.html#method.first_operand_char_index
+	///
+	/// # Arguments
+	///
+	/// - `formatter`: Formatter
+	/// - `instruction`: Instruction
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn format(&mut self, formatter: &mut Formatter, instruction: &Instruction) {
+		self.clear();
+		formatter.format_mnemonic(instruction, self);
+		self.mnemonic_range = 0..self.text.len();
+		let op_count = formatter.operand_count(instruction);
+		for operand in 0..op_count {
+			if operand == 0 {
+				self.write(" ", FormatterTextKind::Text);
+			} else {
+				formatter.format_operand_separator(instruction, self);
+			}
+			let instruction_operand = formatter.get_instruction_operand(instruction, operand);
+			self.operand = Some(operand);
+			self.instruction_operand = instruction_operand;
+			let token_start = self.tokens.len();
+			let start = self.text.len();
+			formatter.format_operand(instruction, self, operand);
+			self.operand = None;
+			self.instruction_operand = None;
+			let op_kind = instruction_operand.map(|op| instruction.op_kind(op));
+			let components = OperandTreeOutput::create_components(&self.text, instruction, op_kind, &self.tokens[token_start..]);
+			self.operands.push(FormattedOperand { operand, instruction_operand, op_kind, range: start..self.text.len(), components });
+		}
+	}
+
+	fn create_components(text: &str, instruction: &Instruction, op_kind: Option<OpKind>, tokens: &[FormatterToken]) -> Vec<OperandComponent> {
+		let is_memory = match op_kind {
+			Some(OpKind::Memory) | Some(OpKind::Memory64) => true,
+			Some(kind) => is_string_memory(kind),
+			None => false,
+		};
+		let mut components = Vec::new();
+		let mut found_base = false;
+		let mut index = 0;
+		while index < tokens.len() {
+			let token = &tokens[index];
+			let prev = prev_token(tokens, index);
+			let next = next_token(tokens, index);
+			let kind = if let Some(decorator) = token.decorator {
+				OperandComponentKind::Decorator(decorator)
+			} else if token.is_symbol {
+				// Merge all parts of the symbol
+				let mut end = token.range.end;
+				while index + 1 < tokens.len() && tokens[index + 1].is_symbol {
+					index += 1;
+					end = tokens[index].range.end;
+				}
+				components.push(OperandComponent {
+					kind: OperandComponentKind::Symbol,
+					range: token.range.start..end,
+					register: Register::None,
+					value: token.value,
+				});
+				index += 1;
+				continue;
+			} else if token.register != Register::None {
+				let register = token.register;
+				if prev.map_or(false, |t| t.kind == FormatterTextKind::Punctuation && text_is(text, t, "{")) {
+					OperandComponentKind::OpMask
+				} else if !is_memory {
+					OperandComponentKind::Register
+				} else if next.map_or(false, |t| text_is(text, t, ":")) {
+					OperandComponentKind::Segment
+				} else if !found_base && register == instruction.memory_base() {
+					found_base = true;
+					OperandComponentKind::Base
+				} else if register == instruction.memory_index() {
+					OperandComponentKind::Index
+				} else {
+					found_base = true;
+					OperandComponentKind::Base
+				}
+			} else if let Some(number_kind) = token.number_kind {
+				if is_memory {
+					let is_mul = |t: Option<&FormatterToken>| t.map_or(false, |t| t.kind == FormatterTextKind::Operator && text_is(text, t, "*"));
+					// gas: disp(base,index,scale)
+					let is_gas_scale = prev_token_index(tokens, index).map_or(false, |i| {
+						text_is(text, &tokens[i], ",")
+							&& prev_token(tokens, i).map_or(false, |t| t.register != Register::None && t.register == instruction.memory_index())
+					});
+					if number_kind == NumberKind::Int32 && (is_mul(prev) || is_mul(next) || is_gas_scale) {
+						OperandComponentKind::Scale
+					} else {
+						OperandComponentKind::Displacement
+					}
+				} else {
+					match token.kind {
+						FormatterTextKind::LabelAddress | FormatterTextKind::FunctionAddress | FormatterTextKind::SelectorValue => {
+							OperandComponentKind::BranchTarget
+						}
+						_ => OperandComponentKind::Immediate,
+					}
+				}
+			} else {
+				index += 1;
+				continue;
+			};
+			components.push(OperandComponent { kind, range: token.range.clone(), register: token.register, value: token.value });
+			index += 1;
+		}
+		components
+	}
+
+	fn add_token(&mut self, text: &str, kind: FormatterTextKind, operand: Option<u32>, instruction_operand: Option<u32>) -> &mut FormatterToken {
+		let start = self.text.len();
+		self.text.push_str(text);
+		let (operand, instruction_operand) =
+			if operand.is_some() { (operand, instruction_operand) } else { (self.operand, self.instruction_operand) };
+		self.tokens.push(FormatterToken {
+			range: start..self.text.len(),
+			kind,
+			operand,
+			instruction_operand,
+			register: Register::None,
+			value: 0,
+			number_kind: None,
+			decorator: None,
+			is_symbol: false,
+		});
+		let index = self.tokens.len() - 1;
+		&mut self.tokens[index]
+	}
+}
+
+fn is_string_memory(op_kind: OpKind) -> bool {
+	match op_kind {
+		OpKind::MemorySegSI
+		| OpKind::MemorySegESI
+		| OpKind::MemorySegRSI
+		| OpKind::MemorySegDI
+		| OpKind::MemorySegEDI
+		| OpKind::MemorySegRDI
+		| OpKind::MemoryESDI
+		| OpKind::MemoryESEDI
+		| OpKind::MemoryESRDI => true,
+		_ => false,
+	}
+}
+
+fn prev_token_index(tokens: &[FormatterToken], index: usize) -> Option<usize> {
+	tokens[..index].iter().rposition(|t| t.kind != FormatterTextKind::Text)
+}
+
+fn prev_token(tokens: &[FormatterToken], index: usize) -> Option<&FormatterToken> {
+	prev_token_index(tokens, index).map(|i| &tokens[i])
+}
+
+fn next_token(tokens: &[FormatterToken], index: usize) -> Option<&FormatterToken> {
+	tokens[index + 1..].iter().find(|t| t.kind != FormatterTextKind::Text)
+}
+
+fn text_is(text: &str, token: &FormatterToken, s: &str) -> bool {
+	&text[token.range.clone()] == s
+}
+
+impl FormatterOutput for OperandTreeOutput {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write(&mut self, text: &str, kind: FormatterTextKind) {
+		let _ = self.add_token(text, kind, None, None);
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_number(
+		&mut self, _instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, text: &str, value: u64, number_kind: NumberKind,
+		kind: FormatterTextKind,
+	) {
+		let token = self.add_token(text, kind, Some(operand), instruction_operand);
+		token.value = value;
+		token.number_kind = Some(number_kind);
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_decorator(&mut self, _instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, text: &str, decorator: DecoratorKind) {
+		self.add_token(text, FormatterTextKind::Decorator, Some(operand), instruction_operand).decorator = Some(decorator);
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_register(&mut self, _instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, text: &str, register: Register) {
+		self.add_token(text, FormatterTextKind::Register, Some(operand), instruction_operand).register = register;
+	}
+
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn write_symbol(&mut self, _instruction: &Instruction, operand: u32, instruction_operand: Option<u32>, _address: u64, symbol: &SymbolResult) {
+		let write_part = |this: &mut OperandTreeOutput, part: &SymResTextPart| {
+			let s = match &part.text {
+				&SymResString::Str(s) => s,
+				&SymResString::String(ref s) => s.as_str(),
+			};
+			let token = this.add_token(s, part.color, Some(operand), instruction_operand);
+			token.value = symbol.address;
+			token.is_symbol = true;
+		};
+		match symbol.text {
+			SymResTextInfo::Text(ref part) => write_part(self, part),
+			SymResTextInfo::TextVec(v) => {
+				for part in v.iter() {
+					write_part(self, part);
+				}
+			}
+		}
+	}
+}
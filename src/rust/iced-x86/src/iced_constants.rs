@@ -45,6 +45,7 @@ impl IcedConstants {
 	pub(crate) const YMM_LAST: Register = Register::YMM31;
 	pub(crate) const ZMM_LAST: Register = Register::ZMM31;
 	pub(crate) const MAX_CPUID_FEATURE_INTERNAL_VALUES: usize = 148;
+	pub(crate) const NUMBER_OF_CPUID_FEATURES: usize = 130;
 	pub(crate) const FIRST_BROADCAST_MEMORY_SIZE: MemorySize = MemorySize::Broadcast64_UInt32;
 }
 // GENERATOR-END: IcedConstants
@@ -82,4 +82,12 @@ impl IcedFeatures {
 	pub fn has_instruction_info() -> bool {
 		cfg!(feature = "instr_info")
 	}
+
+	/// `true` if the instruction timing tables are available
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	#[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
+	pub fn has_timing() -> bool {
+		cfg!(feature = "timing")
+	}
 }
@@ -127,7 +127,7 @@ pub(crate) enum OpInfo0 {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_INFO0: [&str; 10] = [
+pub(crate) static GEN_DEBUG_OP_INFO0: [&str; 10] = [
 	"None",
 	"CondWrite",
 	"CondWrite32_ReadWrite64",
@@ -174,7 +174,7 @@ pub(crate) enum OpInfo1 {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_INFO1: [&str; 7] = [
+pub(crate) static GEN_DEBUG_OP_INFO1: [&str; 7] = [
 	"None",
 	"CondRead",
 	"NoMemAccess",
@@ -214,7 +214,7 @@ pub(crate) enum OpInfo2 {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_INFO2: [&str; 3] = [
+pub(crate) static GEN_DEBUG_OP_INFO2: [&str; 3] = [
 	"None",
 	"Read",
 	"ReadWrite",
@@ -249,7 +249,7 @@ pub(crate) enum OpInfo3 {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_INFO3: [&str; 2] = [
+pub(crate) static GEN_DEBUG_OP_INFO3: [&str; 2] = [
 	"None",
 	"Read",
 ];
@@ -283,7 +283,7 @@ pub(crate) enum OpInfo4 {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_OP_INFO4: [&str; 2] = [
+pub(crate) static GEN_DEBUG_OP_INFO4: [&str; 2] = [
 	"None",
 	"Read",
 ];
@@ -412,7 +412,7 @@ pub(crate) enum CodeInfo {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CODE_INFO: [&str; 97] = [
+pub(crate) static GEN_DEBUG_CODE_INFO: [&str; 97] = [
 	"None",
 	"Cdq",
 	"Cdqe",
@@ -593,7 +593,7 @@ pub(crate) enum RflagsInfo {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_RFLAGS_INFO: [&str; 54] = [
+pub(crate) static GEN_DEBUG_RFLAGS_INFO: [&str; 54] = [
 	"None",
 	"C_AC",
 	"C_c",
@@ -825,7 +825,7 @@ pub(crate) enum CpuidFeatureInternal {
 }
 #[cfg(feature = "instr_info")]
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_CPUID_FEATURE_INTERNAL: [&str; 148] = [
+pub(crate) static GEN_DEBUG_CPUID_FEATURE_INTERNAL: [&str; 148] = [
 	"ADX",
 	"AES",
 	"AES_and_AVX",
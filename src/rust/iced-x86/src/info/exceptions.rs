@@ -0,0 +1,743 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::iced_constants::IcedConstants;
+use super::super::instruction_memory_sizes;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::mem;
+
+/// Exceptions an instruction can raise, see [`ExceptionInfo::exceptions()`].
+/// Bit `N` is set if exception vector `N` can be raised.
+///
+/// [`ExceptionInfo::exceptions()`]: struct.ExceptionInfo.html#method.exceptions
+#[allow(missing_copy_implementations)]
+#[allow(missing_debug_implementations)]
+pub struct ExceptionBits;
+#[allow(missing_docs)]
+impl ExceptionBits {
+	pub const NONE: u32 = 0;
+	pub const DE: u32 = 1 << 0;
+	pub const BR: u32 = 1 << 5;
+	pub const UD: u32 = 1 << 6;
+	pub const NM: u32 = 1 << 7;
+	pub const SS: u32 = 1 << 12;
+	pub const GP: u32 = 1 << 13;
+	pub const PF: u32 = 1 << 14;
+	pub const MF: u32 = 1 << 16;
+	pub const AC: u32 = 1 << 17;
+	pub const XM: u32 = 1 << 19;
+}
+
+/// SSE/AVX (`TypeN`) and EVEX (`EN`) exception class of an instruction as defined in the Intel SDM.
+/// The `NF` (no floating point exception) sub classes aren't distinguished.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(missing_docs)]
+pub enum ExceptionClass {
+	/// It's not an SSE/AVX/EVEX instruction
+	None,
+	Type1,
+	Type2,
+	Type3,
+	Type4,
+	Type5,
+	Type6,
+	Type7,
+	Type8,
+	Type11,
+	Type12,
+	E1,
+	E2,
+	E3,
+	E4,
+	E5,
+	E6,
+	E7NM,
+	E11,
+	E12,
+}
+
+impl Default for ExceptionClass {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		ExceptionClass::None
+	}
+}
+
+struct Flags;
+impl Flags {
+	const SERIALIZING: u8 = 0x01;
+	const MEMORY_FENCE: u8 = 0x02;
+	const WEAKLY_ORDERED: u8 = 0x04;
+	const LOCKED: u8 = 0x08;
+}
+
+/// Exceptions, exception class, alignment and memory ordering info of a [`Code`], see [`Code::exception_info()`]
+///
+/// [`Code`]: enum.Code.html
+/// [`Code::exception_info()`]: enum.Code.html#method.exception_info
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ExceptionInfo {
+	exceptions: u32,
+	alignment: u32,
+	exception_class: ExceptionClass,
+	flags: u8,
+}
+
+impl ExceptionInfo {
+	/// Gets all exceptions the instruction can raise, see [`ExceptionBits`]
+	///
+	/// [`ExceptionBits`]: struct.ExceptionBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn exceptions(&self) -> u32 {
+		self.exceptions
+	}
+
+	/// Checks if the instruction can raise all exceptions in `exceptions`, see [`ExceptionBits`]
+	///
+	/// [`ExceptionBits`]: struct.ExceptionBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn can_raise(&self, exceptions: u32) -> bool {
+		(self.exceptions & exceptions) == exceptions
+	}
+
+	/// Gets the SSE/AVX/EVEX exception class or [`ExceptionClass::None`] if it's not a vector instruction
+	///
+	/// [`ExceptionClass::None`]: enum.ExceptionClass.html#variant.None
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn exception_class(&self) -> ExceptionClass {
+		self.exception_class
+	}
+
+	/// Gets the required alignment in bytes of the memory operand or 0 if it doesn't have to be aligned.
+	/// A misaligned operand raises `#GP`.
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn alignment(&self) -> u32 {
+		self.alignment
+	}
+
+	/// `true` if it's a serializing instruction (all previous instructions complete before the next one is fetched).
+	///
+	/// `MOV CR8, r` isn't serializing but it uses the same [`Code`] as the other `MOV CR, r` instructions so
+	/// [`Code::exception_info()`] returns `true`. Use [`InstructionInfo::exception_info()`] to get the correct value.
+	///
+	/// [`Code`]: enum.Code.html
+	/// [`Code::exception_info()`]: enum.Code.html#method.exception_info
+	/// [`InstructionInfo::exception_info()`]: struct.InstructionInfo.html#method.exception_info
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_serializing(&self) -> bool {
+		(self.flags & Flags::SERIALIZING) != 0
+	}
+
+	/// `true` if it's a memory fence (`LFENCE`, `SFENCE`, `MFENCE`)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_memory_fence(&self) -> bool {
+		(self.flags & Flags::MEMORY_FENCE) != 0
+	}
+
+	/// `true` if its memory accesses are weakly ordered (non-temporal stores, direct stores, `CLFLUSHOPT`, `CLWB`)
+	/// and a fence is needed to order them with other stores
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_weakly_ordered(&self) -> bool {
+		(self.flags & Flags::WEAKLY_ORDERED) != 0
+	}
+
+	/// `true` if it's a locked read-modify-write instruction (`LOCK` prefix or `XCHG` with a memory operand)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_locked(&self) -> bool {
+		(self.flags & Flags::LOCKED) != 0
+	}
+
+	/// `true` if the instruction orders memory accesses or needs a fence to be ordered: it's serializing, a memory fence,
+	/// locked or weakly ordered, see [`is_serializing()`], [`is_memory_fence()`], [`is_locked()`] and [`is_weakly_ordered()`]
+	///
+	/// [`is_serializing()`]: #method.is_serializing
+	/// [`is_memory_fence()`]: #method.is_memory_fence
+	/// [`is_locked()`]: #method.is_locked
+	/// [`is_weakly_ordered()`]: #method.is_weakly_ordered
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_ordering_sensitive(&self) -> bool {
+		self.flags != 0
+	}
+
+	pub(crate) fn new(code: Code) -> Self {
+		let has_memory = instruction_memory_sizes::SIZES[code as usize] != MemorySize::Unknown;
+		ExceptionInfo::create(code, has_memory, has_memory && is_xchg_rm(code), false)
+	}
+
+	// The memory exceptions and the alignment are only used if the instruction has a memory operand
+	pub(crate) fn with_operands(code: Code, has_memory_operand: bool, has_lock_prefix: bool, is_mov_to_cr8: bool) -> Self {
+		let has_memory = has_memory_operand && instruction_memory_sizes::SIZES[code as usize] != MemorySize::Unknown;
+		ExceptionInfo::create(code, has_memory, has_lock_prefix || (has_memory && is_xchg_rm(code)), is_mov_to_cr8)
+	}
+
+	fn create(code: Code, has_memory: bool, is_locked: bool, is_mov_to_cr8: bool) -> Self {
+		let mut info = ExceptionInfo::default();
+		if code == Code::INVALID {
+			info.exceptions = ExceptionBits::UD;
+			return info;
+		}
+		let mnemonic = code.mnemonic();
+		let encoding = code.encoding();
+		let cpuid = code.cpuid_features();
+		let memory_size = instruction_memory_sizes::SIZES[code as usize];
+		let mut exceptions = 0;
+
+		if !cpuid.iter().all(|&f| is_baseline_feature(f)) || code.is_protected_mode() {
+			exceptions |= ExceptionBits::UD;
+		}
+		if has_memory {
+			exceptions |= ExceptionBits::GP | ExceptionBits::SS | ExceptionBits::PF;
+		}
+		if code.is_stack_instruction() {
+			exceptions |= ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::AC;
+		}
+		if code.is_privileged() || code.is_protected_mode() || can_raise_gp(code, mnemonic) {
+			exceptions |= ExceptionBits::GP;
+		}
+		match mnemonic {
+			Mnemonic::Ud0 | Mnemonic::Ud1 | Mnemonic::Ud2 => exceptions |= ExceptionBits::UD,
+			Mnemonic::Div | Mnemonic::Idiv | Mnemonic::Aam => exceptions |= ExceptionBits::DE,
+			Mnemonic::Bound | Mnemonic::Bndcl | Mnemonic::Bndcu | Mnemonic::Bndcn => exceptions |= ExceptionBits::BR,
+			Mnemonic::Wait => exceptions |= ExceptionBits::NM | ExceptionBits::MF,
+			_ => {}
+		}
+
+		if cpuid.iter().any(|&f| is_x87_feature(f)) {
+			exceptions |= ExceptionBits::NM;
+			if !is_no_wait_x87(mnemonic) {
+				exceptions |= ExceptionBits::MF;
+			}
+		} else if encoding == EncodingKind::D3NOW || cpuid.iter().any(|&f| f == CpuidFeature::MMX || f == CpuidFeature::D3NOWEXT) {
+			exceptions |= ExceptionBits::NM | ExceptionBits::MF;
+		} else if is_vector(encoding, cpuid, mnemonic) {
+			exceptions |= ExceptionBits::NM;
+			let class = vector_exception_class(mnemonic, cpuid, memory_size, memory_size != MemorySize::Unknown);
+			if class == ExceptionClass::Type2 || class == ExceptionClass::Type3 || class == ExceptionClass::Type11 {
+				exceptions |= ExceptionBits::XM;
+			}
+			if class == ExceptionClass::Type1 {
+				info.alignment = memory_size.size() as u32;
+			} else if encoding == EncodingKind::Legacy
+				&& (class == ExceptionClass::Type2 || class == ExceptionClass::Type4)
+				&& memory_size.size() == 16
+				&& !is_unaligned_legacy(mnemonic)
+			{
+				info.alignment = 16;
+			}
+			info.exception_class = if encoding == EncodingKind::EVEX { to_evex_class(class) } else { class };
+		}
+
+		if !has_memory {
+			info.alignment = 0;
+		} else if info.alignment == 0 {
+			info.alignment = match code {
+				Code::Cmpxchg16b_m128 | Code::Fxsave_m512byte | Code::Fxsave64_m512byte | Code::Fxrstor_m512byte | Code::Fxrstor64_m512byte => 16,
+				_ => match mnemonic {
+					Mnemonic::Xsave
+					| Mnemonic::Xsave64
+					| Mnemonic::Xsaveopt
+					| Mnemonic::Xsaveopt64
+					| Mnemonic::Xsavec
+					| Mnemonic::Xsavec64
+					| Mnemonic::Xsaves
+					| Mnemonic::Xsaves64
+					| Mnemonic::Xrstor
+					| Mnemonic::Xrstor64
+					| Mnemonic::Xrstors
+					| Mnemonic::Xrstors64
+					| Mnemonic::Movdir64b
+					| Mnemonic::Enqcmd
+					| Mnemonic::Enqcmds => 64,
+					_ => 0,
+				},
+			};
+		}
+		if has_memory && info.alignment == 0 {
+			exceptions |= ExceptionBits::AC;
+		}
+		info.exceptions = exceptions;
+
+		// The SDM excludes `MOV CR8, r` from the serializing `MOV CR, r` instructions
+		if is_serializing(code, mnemonic) && !is_mov_to_cr8 {
+			info.flags |= Flags::SERIALIZING;
+		}
+		if is_locked {
+			info.flags |= Flags::LOCKED;
+		}
+		match mnemonic {
+			Mnemonic::Lfence | Mnemonic::Sfence | Mnemonic::Mfence => info.flags |= Flags::MEMORY_FENCE,
+			Mnemonic::Movnti
+			| Mnemonic::Movntq
+			| Mnemonic::Movntps
+			| Mnemonic::Movntpd
+			| Mnemonic::Movntdq
+			| Mnemonic::Movntss
+			| Mnemonic::Movntsd
+			| Mnemonic::Maskmovq
+			| Mnemonic::Maskmovdqu
+			| Mnemonic::Vmovntps
+			| Mnemonic::Vmovntpd
+			| Mnemonic::Vmovntdq
+			| Mnemonic::Vmaskmovdqu
+			| Mnemonic::Movdiri
+			| Mnemonic::Movdir64b
+			| Mnemonic::Clflushopt
+			| Mnemonic::Clwb => info.flags |= Flags::WEAKLY_ORDERED,
+			_ => {}
+		}
+
+		info
+	}
+}
+
+lazy_static! {
+	// Bit `code` is set if it can raise #XM
+	static ref CAN_RAISE_XM: Vec<u32> = {
+		let mut result = vec![0; IcedConstants::NUMBER_OF_CODE_VALUES / 32 + 1];
+		for i in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+			let code: Code = unsafe { mem::transmute(i as u16) };
+			if ExceptionInfo::new(code).can_raise(ExceptionBits::XM) {
+				result[i / 32] |= 1 << (i % 32);
+			}
+		}
+		result
+	};
+}
+
+pub(super) fn can_raise_xm(code: Code) -> bool {
+	let index = code as usize;
+	(CAN_RAISE_XM[index / 32] & (1 << (index % 32))) != 0
+}
+
+// XCHG with a memory operand is always locked
+fn is_xchg_rm(code: Code) -> bool {
+	match code {
+		Code::Xchg_rm8_r8 | Code::Xchg_rm16_r16 | Code::Xchg_rm32_r32 | Code::Xchg_rm64_r64 => true,
+		_ => false,
+	}
+}
+
+fn is_baseline_feature(feature: CpuidFeature) -> bool {
+	match feature {
+		CpuidFeature::INTEL8086
+		| CpuidFeature::INTEL186
+		| CpuidFeature::INTEL286
+		| CpuidFeature::INTEL386
+		| CpuidFeature::INTEL486
+		| CpuidFeature::FPU
+		| CpuidFeature::FPU287
+		| CpuidFeature::FPU387 => true,
+		_ => false,
+	}
+}
+
+pub(super) fn is_x87_feature(feature: CpuidFeature) -> bool {
+	match feature {
+		CpuidFeature::FPU | CpuidFeature::FPU287 | CpuidFeature::FPU287XL_ONLY | CpuidFeature::FPU387 | CpuidFeature::FPU387SL_ONLY => true,
+		_ => false,
+	}
+}
+
+// These don't check for pending x87 exceptions
+fn is_no_wait_x87(mnemonic: Mnemonic) -> bool {
+	match mnemonic {
+		Mnemonic::Fninit
+		| Mnemonic::Fnclex
+		| Mnemonic::Fnstsw
+		| Mnemonic::Fnstcw
+		| Mnemonic::Fnstenv
+		| Mnemonic::Fnsave
+		| Mnemonic::Fndisi
+		| Mnemonic::Fneni
+		| Mnemonic::Fnsetpm => true,
+		_ => false,
+	}
+}
+
+fn can_raise_gp(code: Code, mnemonic: Mnemonic) -> bool {
+	match code {
+		Code::Mov_Sreg_rm16
+		| Code::Mov_Sreg_r32m16
+		| Code::Mov_Sreg_r64m16
+		| Code::Call_ptr1616
+		| Code::Call_ptr1632
+		| Code::Jmp_ptr1616
+		| Code::Jmp_ptr1632 => return true,
+		_ => {}
+	}
+	match mnemonic {
+		Mnemonic::In
+		| Mnemonic::Out
+		| Mnemonic::Insb
+		| Mnemonic::Insw
+		| Mnemonic::Insd
+		| Mnemonic::Outsb
+		| Mnemonic::Outsw
+		| Mnemonic::Outsd
+		| Mnemonic::Cli
+		| Mnemonic::Sti
+		| Mnemonic::Int
+		| Mnemonic::Into
+		| Mnemonic::Iret
+		| Mnemonic::Retf
+		| Mnemonic::Ldmxcsr
+		| Mnemonic::Vldmxcsr => true,
+		_ => false,
+	}
+}
+
+fn is_vector(encoding: EncodingKind, cpuid: &[CpuidFeature], mnemonic: Mnemonic) -> bool {
+	match encoding {
+		EncodingKind::VEX | EncodingKind::XOP => {
+			!cpuid.iter().any(|&f| f == CpuidFeature::BMI1 || f == CpuidFeature::BMI2 || f == CpuidFeature::TBM || f == CpuidFeature::LWP)
+		}
+		EncodingKind::EVEX => true,
+		EncodingKind::Legacy => {
+			match mnemonic {
+				Mnemonic::Lfence
+				| Mnemonic::Sfence
+				| Mnemonic::Mfence
+				| Mnemonic::Movnti
+				| Mnemonic::Crc32
+				| Mnemonic::Prefetchnta
+				| Mnemonic::Prefetcht0
+				| Mnemonic::Prefetcht1
+				| Mnemonic::Prefetcht2 => return false,
+				_ => {}
+			}
+			cpuid.iter().any(|&f| match f {
+				CpuidFeature::SSE
+				| CpuidFeature::SSE2
+				| CpuidFeature::SSE3
+				| CpuidFeature::SSSE3
+				| CpuidFeature::SSE4_1
+				| CpuidFeature::SSE4_2
+				| CpuidFeature::SSE4A
+				| CpuidFeature::AES
+				| CpuidFeature::PCLMULQDQ
+				| CpuidFeature::SHA
+				| CpuidFeature::GFNI => true,
+				_ => false,
+			})
+		}
+		EncodingKind::D3NOW => false,
+	}
+}
+
+fn vector_exception_class(mnemonic: Mnemonic, cpuid: &[CpuidFeature], memory_size: MemorySize, has_memory: bool) -> ExceptionClass {
+	match mnemonic {
+		Mnemonic::Vzeroall | Mnemonic::Vzeroupper => return ExceptionClass::Type8,
+		Mnemonic::Vgatherdps
+		| Mnemonic::Vgatherdpd
+		| Mnemonic::Vgatherqps
+		| Mnemonic::Vgatherqpd
+		| Mnemonic::Vpgatherdd
+		| Mnemonic::Vpgatherdq
+		| Mnemonic::Vpgatherqd
+		| Mnemonic::Vpgatherqq
+		| Mnemonic::Vscatterdps
+		| Mnemonic::Vscatterdpd
+		| Mnemonic::Vscatterqps
+		| Mnemonic::Vscatterqpd
+		| Mnemonic::Vpscatterdd
+		| Mnemonic::Vpscatterdq
+		| Mnemonic::Vpscatterqd
+		| Mnemonic::Vpscatterqq
+		| Mnemonic::Vgatherpf0dps
+		| Mnemonic::Vgatherpf0dpd
+		| Mnemonic::Vgatherpf0qps
+		| Mnemonic::Vgatherpf0qpd
+		| Mnemonic::Vgatherpf1dps
+		| Mnemonic::Vgatherpf1dpd
+		| Mnemonic::Vgatherpf1qps
+		| Mnemonic::Vgatherpf1qpd
+		| Mnemonic::Vscatterpf0dps
+		| Mnemonic::Vscatterpf0dpd
+		| Mnemonic::Vscatterpf0qps
+		| Mnemonic::Vscatterpf0qpd
+		| Mnemonic::Vscatterpf1dps
+		| Mnemonic::Vscatterpf1dpd
+		| Mnemonic::Vscatterpf1qps
+		| Mnemonic::Vscatterpf1qpd => return ExceptionClass::Type12,
+		Mnemonic::Vcvtph2ps | Mnemonic::Vcvtps2ph => return ExceptionClass::Type11,
+		_ => {}
+	}
+	if cpuid.contains(&CpuidFeature::F16C) {
+		return ExceptionClass::Type11;
+	}
+	if !has_memory {
+		return ExceptionClass::Type7;
+	}
+	match mnemonic {
+		Mnemonic::Movaps
+		| Mnemonic::Movapd
+		| Mnemonic::Movdqa
+		| Mnemonic::Movntps
+		| Mnemonic::Movntpd
+		| Mnemonic::Movntdq
+		| Mnemonic::Movntdqa
+		| Mnemonic::Vmovaps
+		| Mnemonic::Vmovapd
+		| Mnemonic::Vmovdqa
+		| Mnemonic::Vmovdqa32
+		| Mnemonic::Vmovdqa64
+		| Mnemonic::Vmovntps
+		| Mnemonic::Vmovntpd
+		| Mnemonic::Vmovntdq
+		| Mnemonic::Vmovntdqa => return ExceptionClass::Type1,
+		Mnemonic::Vbroadcastss
+		| Mnemonic::Vbroadcastsd
+		| Mnemonic::Vbroadcastf128
+		| Mnemonic::Vbroadcasti128
+		| Mnemonic::Vbroadcastf32x2
+		| Mnemonic::Vbroadcastf32x4
+		| Mnemonic::Vbroadcastf32x8
+		| Mnemonic::Vbroadcastf64x2
+		| Mnemonic::Vbroadcastf64x4
+		| Mnemonic::Vbroadcasti32x2
+		| Mnemonic::Vbroadcasti32x4
+		| Mnemonic::Vbroadcasti32x8
+		| Mnemonic::Vbroadcasti64x2
+		| Mnemonic::Vbroadcasti64x4
+		| Mnemonic::Vpbroadcastb
+		| Mnemonic::Vpbroadcastw
+		| Mnemonic::Vpbroadcastd
+		| Mnemonic::Vpbroadcastq
+		| Mnemonic::Vinsertf128
+		| Mnemonic::Vinserti128
+		| Mnemonic::Vinsertf32x4
+		| Mnemonic::Vinsertf32x8
+		| Mnemonic::Vinsertf64x2
+		| Mnemonic::Vinsertf64x4
+		| Mnemonic::Vinserti32x4
+		| Mnemonic::Vinserti32x8
+		| Mnemonic::Vinserti64x2
+		| Mnemonic::Vinserti64x4
+		| Mnemonic::Vextractf128
+		| Mnemonic::Vextracti128
+		| Mnemonic::Vextractf32x4
+		| Mnemonic::Vextractf32x8
+		| Mnemonic::Vextractf64x2
+		| Mnemonic::Vextractf64x4
+		| Mnemonic::Vextracti32x4
+		| Mnemonic::Vextracti32x8
+		| Mnemonic::Vextracti64x2
+		| Mnemonic::Vextracti64x4
+		| Mnemonic::Vperm2f128
+		| Mnemonic::Vperm2i128
+		| Mnemonic::Vmaskmovps
+		| Mnemonic::Vmaskmovpd
+		| Mnemonic::Vpmaskmovd
+		| Mnemonic::Vpmaskmovq => return ExceptionClass::Type6,
+		// Integer sources but they can raise a precision exception
+		Mnemonic::Cvtsi2ss | Mnemonic::Cvtsi2sd | Mnemonic::Vcvtsi2ss | Mnemonic::Vcvtsi2sd | Mnemonic::Vcvtusi2ss | Mnemonic::Vcvtusi2sd => {
+			return ExceptionClass::Type3
+		}
+		Mnemonic::Cvtdq2ps
+		| Mnemonic::Cvtpi2ps
+		| Mnemonic::Vcvtdq2ps
+		| Mnemonic::Vcvtudq2ps
+		| Mnemonic::Vcvtqq2ps
+		| Mnemonic::Vcvtuqq2ps
+		| Mnemonic::Vcvtqq2pd
+		| Mnemonic::Vcvtuqq2pd => return ExceptionClass::Type2,
+		_ => {}
+	}
+	match memory_size.element_type() {
+		MemorySize::Float16 | MemorySize::Float32 | MemorySize::Float64 | MemorySize::Float80 if !is_float_data_movement(mnemonic) => {
+			if memory_size.is_packed() {
+				ExceptionClass::Type2
+			} else {
+				ExceptionClass::Type3
+			}
+		}
+		_ => {
+			if memory_size.is_packed() && memory_size.size() >= 16 {
+				ExceptionClass::Type4
+			} else {
+				ExceptionClass::Type5
+			}
+		}
+	}
+}
+
+// Instructions with floating point operands that only move, shuffle or test bits and never raise #XM
+fn is_float_data_movement(mnemonic: Mnemonic) -> bool {
+	match mnemonic {
+		Mnemonic::Andnpd
+		| Mnemonic::Andnps
+		| Mnemonic::Andpd
+		| Mnemonic::Andps
+		| Mnemonic::Blendpd
+		| Mnemonic::Blendps
+		| Mnemonic::Blendvpd
+		| Mnemonic::Blendvps
+		| Mnemonic::Extractps
+		| Mnemonic::Insertps
+		| Mnemonic::Movddup
+		| Mnemonic::Movhlps
+		| Mnemonic::Movhpd
+		| Mnemonic::Movhps
+		| Mnemonic::Movlhps
+		| Mnemonic::Movlpd
+		| Mnemonic::Movlps
+		| Mnemonic::Movmskpd
+		| Mnemonic::Movmskps
+		| Mnemonic::Movsd
+		| Mnemonic::Movshdup
+		| Mnemonic::Movsldup
+		| Mnemonic::Movss
+		| Mnemonic::Movupd
+		| Mnemonic::Movups
+		| Mnemonic::Orpd
+		| Mnemonic::Orps
+		| Mnemonic::Shufpd
+		| Mnemonic::Shufps
+		| Mnemonic::Unpckhpd
+		| Mnemonic::Unpckhps
+		| Mnemonic::Unpcklpd
+		| Mnemonic::Unpcklps
+		| Mnemonic::Xorpd
+		| Mnemonic::Xorps
+		| Mnemonic::Vandnpd
+		| Mnemonic::Vandnps
+		| Mnemonic::Vandpd
+		| Mnemonic::Vandps
+		| Mnemonic::Vblendmpd
+		| Mnemonic::Vblendmps
+		| Mnemonic::Vblendpd
+		| Mnemonic::Vblendps
+		| Mnemonic::Vblendvpd
+		| Mnemonic::Vblendvps
+		| Mnemonic::Vcompresspd
+		| Mnemonic::Vcompressps
+		| Mnemonic::Vexpandpd
+		| Mnemonic::Vexpandps
+		| Mnemonic::Vextractps
+		| Mnemonic::Vinsertps
+		| Mnemonic::Vmovddup
+		| Mnemonic::Vmovhlps
+		| Mnemonic::Vmovhpd
+		| Mnemonic::Vmovhps
+		| Mnemonic::Vmovlhps
+		| Mnemonic::Vmovlpd
+		| Mnemonic::Vmovlps
+		| Mnemonic::Vmovmskpd
+		| Mnemonic::Vmovmskps
+		| Mnemonic::Vmovsd
+		| Mnemonic::Vmovshdup
+		| Mnemonic::Vmovsldup
+		| Mnemonic::Vmovss
+		| Mnemonic::Vmovupd
+		| Mnemonic::Vmovups
+		| Mnemonic::Vorpd
+		| Mnemonic::Vorps
+		| Mnemonic::Vpermi2pd
+		| Mnemonic::Vpermi2ps
+		| Mnemonic::Vpermilpd
+		| Mnemonic::Vpermilps
+		| Mnemonic::Vpermpd
+		| Mnemonic::Vpermps
+		| Mnemonic::Vpermt2pd
+		| Mnemonic::Vpermt2ps
+		| Mnemonic::Vshuff32x4
+		| Mnemonic::Vshuff64x2
+		| Mnemonic::Vshufpd
+		| Mnemonic::Vshufps
+		| Mnemonic::Vtestpd
+		| Mnemonic::Vtestps
+		| Mnemonic::Vunpckhpd
+		| Mnemonic::Vunpckhps
+		| Mnemonic::Vunpcklpd
+		| Mnemonic::Vunpcklps
+		| Mnemonic::Vxorpd
+		| Mnemonic::Vxorps => true,
+		_ => false,
+	}
+}
+
+fn to_evex_class(class: ExceptionClass) -> ExceptionClass {
+	match class {
+		ExceptionClass::Type1 => ExceptionClass::E1,
+		ExceptionClass::Type2 => ExceptionClass::E2,
+		ExceptionClass::Type3 => ExceptionClass::E3,
+		ExceptionClass::Type4 => ExceptionClass::E4,
+		ExceptionClass::Type5 => ExceptionClass::E5,
+		ExceptionClass::Type6 => ExceptionClass::E6,
+		ExceptionClass::Type11 => ExceptionClass::E11,
+		ExceptionClass::Type12 => ExceptionClass::E12,
+		_ => ExceptionClass::E7NM,
+	}
+}
+
+// Legacy SSE instructions with a 16-byte memory operand that doesn't have to be aligned
+fn is_unaligned_legacy(mnemonic: Mnemonic) -> bool {
+	match mnemonic {
+		Mnemonic::Movups
+		| Mnemonic::Movupd
+		| Mnemonic::Movdqu
+		| Mnemonic::Lddqu
+		| Mnemonic::Pcmpestri
+		| Mnemonic::Pcmpestrm
+		| Mnemonic::Pcmpistri
+		| Mnemonic::Pcmpistrm => true,
+		_ => false,
+	}
+}
+
+// The serializing instructions in the SDM (Vol. 3A, 9.3 Serializing Instructions)
+fn is_serializing(code: Code, mnemonic: Mnemonic) -> bool {
+	match code {
+		Code::Mov_cr_r32 | Code::Mov_cr_r64 | Code::Mov_dr_r32 | Code::Mov_dr_r64 => return true,
+		_ => {}
+	}
+	match mnemonic {
+		Mnemonic::Cpuid
+		| Mnemonic::Iret
+		| Mnemonic::Rsm
+		| Mnemonic::Lgdt
+		| Mnemonic::Lidt
+		| Mnemonic::Lldt
+		| Mnemonic::Ltr
+		| Mnemonic::Wrmsr
+		| Mnemonic::Invd
+		| Mnemonic::Wbinvd
+		| Mnemonic::Wbnoinvd
+		| Mnemonic::Invlpg
+		| Mnemonic::Invept
+		| Mnemonic::Invvpid => true,
+		_ => false,
+	}
+}
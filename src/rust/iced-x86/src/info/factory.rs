@@ -41,9 +41,10 @@ impl InstructionInfoOptions {
 	/// [`InstructionInfo::used_memory()`]: struct.InstructionInfo.html#method.used_memory
 	/// [`InstructionInfo::used_registers()`]: struct.InstructionInfo.html#method.used_registers
 	pub const NO_MEMORY_USAGE: u32 = 0x0000_0001;
-	/// Don't include register usage, i.e., [`InstructionInfo::used_registers()`] will return an empty vector
+	/// Don't include register usage, i.e., [`InstructionInfo::used_registers()`] and [`InstructionInfo::used_implicit_state()`] will return an empty vector
 	///
 	/// [`InstructionInfo::used_registers()`]: struct.InstructionInfo.html#method.used_registers
+	/// [`InstructionInfo::used_implicit_state()`]: struct.InstructionInfo.html#method.used_implicit_state
 	pub const NO_REGISTER_USAGE: u32 = 0x0000_0002;
 }
 
@@ -190,6 +191,7 @@ impl InstructionInfoFactory {
 	pub(crate) fn create<'a>(info: &'a mut InstructionInfo, instruction: &Instruction, options: u32) -> &'a InstructionInfo {
 		info.used_registers.clear();
 		info.used_memory_locations.clear();
+		info.used_implicit_state.clear();
 
 		let index = (instruction.code() as usize) << 1;
 		let flags1 = unsafe { *super::info_table::TABLE.get_unchecked(index) };
@@ -203,6 +205,7 @@ impl InstructionInfoFactory {
 		info.cpuid_feature_internal = ((flags2 >> InfoFlags2::CPUID_FEATURE_INTERNAL_SHIFT) & InfoFlags2::CPUID_FEATURE_INTERNAL_MASK) as usize;
 		info.flow_control = unsafe { mem::transmute(((flags2 >> InfoFlags2::FLOW_CONTROL_SHIFT) & InfoFlags2::FLOW_CONTROL_MASK) as u8) };
 		info.encoding = unsafe { mem::transmute(((flags2 >> InfoFlags2::ENCODING_SHIFT) & InfoFlags2::ENCODING_MASK) as u8) };
+		info.code = instruction.code();
 		info.rflags_info = ((flags1 >> InfoFlags1::RFLAGS_INFO_SHIFT) & InfoFlags1::RFLAGS_INFO_MASK) as usize;
 
 		const_assert_eq!(0x0800_0000, InfoFlags1::SAVE_RESTORE);
@@ -215,6 +218,20 @@ impl InstructionInfoFactory {
 		const_assert_eq!(0x08, IIFlags::PRIVILEGED);
 		// Bit 4 could be set but we don't use it so we don't need to mask it out
 		info.flags = (flags1 >> 27) as u8;
+		// All memory op kinds are at the end of the enum
+		const_assert_eq!(IcedConstants::NUMBER_OF_OP_KINDS as u32 - 1, OpKind::Memory as u32);
+		for i in 0..instruction.op_count() {
+			if instruction.op_kind(i) as u32 >= OpKind::MemorySegSI as u32 {
+				info.flags |= IIFlags::MEMORY_OPERAND;
+				break;
+			}
+		}
+		if instruction.has_lock_prefix() {
+			info.flags |= IIFlags::LOCK_PREFIX;
+		}
+		if (info.code == Code::Mov_cr_r32 || info.code == Code::Mov_cr_r64) && instruction.op0_register() == Register::CR8 {
+			info.flags |= IIFlags::MOV_TO_CR8;
+		}
 
 		let code_size = instruction.code_size();
 		const_assert_eq!(Flags::NO_MEMORY_USAGE, InstructionInfoOptions::NO_MEMORY_USAGE);
@@ -422,6 +439,9 @@ impl InstructionInfoFactory {
 				if (flags2 & InfoFlags2::OP_MASK_REG_READ_WRITE) != 0 { OpAccess::ReadWrite } else { OpAccess::Read },
 			);
 		}
+		if (flags & Flags::NO_REGISTER_USAGE) == 0 {
+			super::implicit_state::add_used_implicit_state(instruction, &mut info.used_implicit_state);
+		}
 		info
 	}
 
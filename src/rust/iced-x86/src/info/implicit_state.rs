@@ -0,0 +1,270 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+use super::exceptions::{can_raise_xm, is_x87_feature};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Processor state that isn't a [`Register`] but is implicitly read or written by an instruction.
+/// Control registers aren't included, eg. `CLTS`, `LMSW` and `SMSW` return [`Register::CR0`] in [`InstructionInfo::used_registers()`].
+/// MMX instructions write [`X87TagWord`] and [`X87Top`] since they share the x87 registers.
+///
+/// [`Register`]: enum.Register.html
+/// [`Register::CR0`]: enum.Register.html#variant.CR0
+/// [`InstructionInfo::used_registers()`]: struct.InstructionInfo.html#method.used_registers
+/// [`X87TagWord`]: #variant.X87TagWord
+/// [`X87Top`]: #variant.X87Top
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ImplicitState {
+	/// No state
+	None,
+	/// `MXCSR` (SSE/AVX control and status register)
+	MXCSR,
+	/// x87 control word
+	X87ControlWord,
+	/// x87 status word (condition codes and exception flags)
+	X87StatusWord,
+	/// x87 tag word
+	X87TagWord,
+	/// x87 top of stack (`TOP` field of the status word)
+	X87Top,
+	/// `XCR0` or another extended control register selected by `ECX`
+	XCR0,
+	/// A model specific register, the `MSR` index is usually in `ECX`
+	MSR,
+	/// `PKRU` (protection key rights for user pages)
+	PKRU,
+	/// `SSP` (shadow stack pointer). Only used if shadow stacks are enabled.
+	SSP,
+	/// Time stamp counter
+	TSC,
+	/// `IA32_TSC_AUX` (read by `RDTSCP` and `RDPID`)
+	TscAux,
+}
+
+impl Default for ImplicitState {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		ImplicitState::None
+	}
+}
+
+/// Implicit processor state used by an instruction, see [`InstructionInfo::used_implicit_state()`]
+///
+/// [`InstructionInfo::used_implicit_state()`]: struct.InstructionInfo.html#method.used_implicit_state
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct UsedImplicitState {
+	state: ImplicitState,
+	access: OpAccess,
+}
+
+#[cfg_attr(feature = "cargo-clippy", allow(clippy::trivially_copy_pass_by_ref))]
+impl UsedImplicitState {
+	/// Creates a new instance
+	///
+	/// # Arguments
+	///
+	/// * `state`: Implicit state
+	/// * `access`: Access
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new(state: ImplicitState, access: OpAccess) -> Self {
+		Self { state, access }
+	}
+
+	/// Gets the implicit state
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn state(&self) -> ImplicitState {
+		self.state
+	}
+
+	/// Gets the access
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn access(&self) -> OpAccess {
+		self.access
+	}
+}
+
+impl fmt::Debug for UsedImplicitState {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+		write!(f, "{:?}:{:?}", self.state(), self.access())?;
+		Ok(())
+	}
+}
+
+const X87_ALL: [ImplicitState; 4] = [ImplicitState::X87ControlWord, ImplicitState::X87StatusWord, ImplicitState::X87TagWord, ImplicitState::X87Top];
+
+#[inline]
+fn add(used: &mut Vec<UsedImplicitState>, state: ImplicitState, access: OpAccess) {
+	used.push(UsedImplicitState::new(state, access));
+}
+
+fn add_x87_all(used: &mut Vec<UsedImplicitState>, access: OpAccess) {
+	for &state in X87_ALL.iter() {
+		add(used, state, access);
+	}
+}
+
+// Saves or restores the x87, SSE and (if enabled in XCR0) AVX/AVX-512/PKRU state
+fn add_save_restore(used: &mut Vec<UsedImplicitState>, access: OpAccess, xsave: bool) {
+	add_x87_all(used, access);
+	add(used, ImplicitState::MXCSR, access);
+	if xsave {
+		add(used, ImplicitState::XCR0, OpAccess::Read);
+		add(used, ImplicitState::PKRU, access);
+	}
+}
+
+pub(super) fn add_used_implicit_state(instruction: &Instruction, used: &mut Vec<UsedImplicitState>) {
+	let code = instruction.code();
+	match code.mnemonic() {
+		Mnemonic::Ldmxcsr | Mnemonic::Vldmxcsr => add(used, ImplicitState::MXCSR, OpAccess::Write),
+		Mnemonic::Stmxcsr | Mnemonic::Vstmxcsr => add(used, ImplicitState::MXCSR, OpAccess::Read),
+
+		Mnemonic::Fxsave | Mnemonic::Fxsave64 => add_save_restore(used, OpAccess::Read, false),
+		Mnemonic::Fxrstor | Mnemonic::Fxrstor64 => add_save_restore(used, OpAccess::Write, false),
+		Mnemonic::Xsave
+		| Mnemonic::Xsave64
+		| Mnemonic::Xsaveopt
+		| Mnemonic::Xsaveopt64
+		| Mnemonic::Xsavec
+		| Mnemonic::Xsavec64
+		| Mnemonic::Xsaves
+		| Mnemonic::Xsaves64 => add_save_restore(used, OpAccess::Read, true),
+		Mnemonic::Xrstor | Mnemonic::Xrstor64 | Mnemonic::Xrstors | Mnemonic::Xrstors64 => add_save_restore(used, OpAccess::Write, true),
+
+		Mnemonic::Fninit | Mnemonic::Finit => add_x87_all(used, OpAccess::Write),
+		Mnemonic::Fldenv | Mnemonic::Frstor => add_x87_all(used, OpAccess::Write),
+		Mnemonic::Fnstenv | Mnemonic::Fstenv => add_x87_all(used, OpAccess::Read),
+		// It also reinitializes the FPU
+		Mnemonic::Fnsave | Mnemonic::Fsave => add_x87_all(used, OpAccess::ReadWrite),
+		Mnemonic::Fldcw => add(used, ImplicitState::X87ControlWord, OpAccess::Write),
+		Mnemonic::Fnstcw | Mnemonic::Fstcw => add(used, ImplicitState::X87ControlWord, OpAccess::Read),
+		Mnemonic::Fnstsw | Mnemonic::Fstsw => {
+			add(used, ImplicitState::X87StatusWord, OpAccess::Read);
+			add(used, ImplicitState::X87Top, OpAccess::Read);
+		}
+		Mnemonic::Fnclex | Mnemonic::Fclex => add(used, ImplicitState::X87StatusWord, OpAccess::ReadWrite),
+		Mnemonic::Fincstp | Mnemonic::Fdecstp => add(used, ImplicitState::X87Top, OpAccess::ReadWrite),
+		Mnemonic::Ffree => add(used, ImplicitState::X87TagWord, OpAccess::ReadWrite),
+		// Marks all x87 registers as empty
+		Mnemonic::Emms | Mnemonic::Femms => add(used, ImplicitState::X87TagWord, OpAccess::Write),
+
+		Mnemonic::Xgetbv => add(used, ImplicitState::XCR0, OpAccess::Read),
+		Mnemonic::Xsetbv => add(used, ImplicitState::XCR0, OpAccess::Write),
+		Mnemonic::Rdmsr => add(used, ImplicitState::MSR, OpAccess::Read),
+		Mnemonic::Wrmsr => add(used, ImplicitState::MSR, OpAccess::Write),
+		// STAR, LSTAR, SYSENTER_CS, etc
+		Mnemonic::Syscall | Mnemonic::Sysret | Mnemonic::Sysenter | Mnemonic::Sysexit => add(used, ImplicitState::MSR, OpAccess::Read),
+		// IA32_KERNEL_GS_BASE
+		Mnemonic::Swapgs => add(used, ImplicitState::MSR, OpAccess::ReadWrite),
+		Mnemonic::Rdpkru => add(used, ImplicitState::PKRU, OpAccess::Read),
+		Mnemonic::Wrpkru => add(used, ImplicitState::PKRU, OpAccess::Write),
+		Mnemonic::Rdtsc => add(used, ImplicitState::TSC, OpAccess::Read),
+		Mnemonic::Rdtscp => {
+			add(used, ImplicitState::TSC, OpAccess::Read);
+			add(used, ImplicitState::TscAux, OpAccess::Read);
+		}
+		Mnemonic::Rdpid => add(used, ImplicitState::TscAux, OpAccess::Read),
+
+		Mnemonic::Rdsspd | Mnemonic::Rdsspq => add(used, ImplicitState::SSP, OpAccess::Read),
+		Mnemonic::Incsspd | Mnemonic::Incsspq | Mnemonic::Saveprevssp | Mnemonic::Rstorssp => add(used, ImplicitState::SSP, OpAccess::ReadWrite),
+		Mnemonic::Setssbsy | Mnemonic::Clrssbsy => add(used, ImplicitState::SSP, OpAccess::Write),
+
+		mnemonic => {
+			match code.flow_control() {
+				FlowControl::Call | FlowControl::IndirectCall | FlowControl::Return => add(used, ImplicitState::SSP, OpAccess::ReadWrite),
+				_ => {}
+			}
+			if code.cpuid_features().iter().any(|&f| is_x87_feature(f)) {
+				add_x87_arith(used, mnemonic);
+			} else if can_raise_xm(code) {
+				// Reads the rounding mode and exception masks and updates the exception flags
+				add(used, ImplicitState::MXCSR, OpAccess::ReadWrite);
+			}
+			if uses_mmx(instruction) {
+				// All MMX instructions set TOP to 0 and mark all x87 registers as valid
+				add(used, ImplicitState::X87TagWord, OpAccess::Write);
+				add(used, ImplicitState::X87Top, OpAccess::Write);
+			}
+		}
+	}
+}
+
+// MMX instructions and other instructions (SSE, 3DNow!) with an MMX register operand
+fn uses_mmx(instruction: &Instruction) -> bool {
+	instruction.code().cpuid_features().contains(&CpuidFeature::MMX)
+		|| (0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Register && instruction.op_register(i).is_mm())
+}
+
+fn add_x87_arith(used: &mut Vec<UsedImplicitState>, mnemonic: Mnemonic) {
+	let changes_top = match mnemonic {
+		Mnemonic::Fld
+		| Mnemonic::Fild
+		| Mnemonic::Fbld
+		| Mnemonic::Fld1
+		| Mnemonic::Fldl2t
+		| Mnemonic::Fldl2e
+		| Mnemonic::Fldpi
+		| Mnemonic::Fldlg2
+		| Mnemonic::Fldln2
+		| Mnemonic::Fldz
+		| Mnemonic::Fxtract
+		| Mnemonic::Fptan
+		| Mnemonic::Fsincos
+		| Mnemonic::Fstp
+		| Mnemonic::Fstpnce
+		| Mnemonic::Fistp
+		| Mnemonic::Fisttp
+		| Mnemonic::Fbstp
+		| Mnemonic::Faddp
+		| Mnemonic::Fsubp
+		| Mnemonic::Fsubrp
+		| Mnemonic::Fmulp
+		| Mnemonic::Fdivp
+		| Mnemonic::Fdivrp
+		| Mnemonic::Fcomp
+		| Mnemonic::Fcompp
+		| Mnemonic::Fucomp
+		| Mnemonic::Fucompp
+		| Mnemonic::Ficomp
+		| Mnemonic::Fcomip
+		| Mnemonic::Fucomip
+		| Mnemonic::Ffreep
+		| Mnemonic::Fyl2x
+		| Mnemonic::Fyl2xp1
+		| Mnemonic::Fpatan => true,
+		_ => false,
+	};
+	let stack_access = if changes_top { OpAccess::ReadWrite } else { OpAccess::Read };
+	add(used, ImplicitState::X87ControlWord, OpAccess::Read);
+	add(used, ImplicitState::X87StatusWord, OpAccess::ReadWrite);
+	add(used, ImplicitState::X87TagWord, stack_access);
+	add(used, ImplicitState::X87Top, stack_access);
+}
@@ -23,13 +23,19 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 pub(crate) mod cpuid_table;
 pub(crate) mod enums;
+mod exceptions;
 pub(crate) mod factory;
+mod implicit_state;
 pub(crate) mod info_table;
+pub(crate) mod rflags_result;
 pub(crate) mod rflags_table;
 #[cfg(test)]
 mod tests;
 
+pub use self::exceptions::*;
 pub use self::factory::*;
+pub use self::implicit_state::*;
+pub use self::rflags_result::*;
 use super::iced_constants::IcedConstants;
 use super::*;
 #[cfg(not(feature = "std"))]
@@ -206,6 +212,10 @@ impl IIFlags {
 	const STACK_INSTRUCTION: u8 = 0x02;
 	const PROTECTED_MODE: u8 = 0x04;
 	const PRIVILEGED: u8 = 0x08;
+	// 0x10 can be set, see factory.rs
+	const MEMORY_OPERAND: u8 = 0x20;
+	const LOCK_PREFIX: u8 = 0x40;
+	const MOV_TO_CR8: u8 = 0x80;
 }
 
 /// Contains information about an instruction, eg. read/written registers, read/written `RFLAGS` bits, `CPUID` feature bit, etc.
@@ -216,11 +226,13 @@ impl IIFlags {
 pub struct InstructionInfo {
 	pub(crate) used_registers: Vec<UsedRegister>,
 	pub(crate) used_memory_locations: Vec<UsedMemory>,
+	pub(crate) used_implicit_state: Vec<UsedImplicitState>,
 	cpuid_feature_internal: usize,
 	rflags_info: usize,
 	flow_control: FlowControl,
 	op_accesses: [OpAccess; IcedConstants::MAX_OP_COUNT],
 	encoding: EncodingKind,
+	code: Code,
 	flags: u8,
 }
 
@@ -240,11 +252,13 @@ impl InstructionInfo {
 			} else {
 				Vec::new()
 			},
+			used_implicit_state: Vec::new(),
 			cpuid_feature_internal: 0,
 			rflags_info: 0,
 			flow_control: FlowControl::default(),
 			op_accesses: [OpAccess::default(); IcedConstants::MAX_OP_COUNT],
 			encoding: EncodingKind::default(),
+			code: Code::default(),
 			flags: 0,
 		}
 	}
@@ -265,6 +279,30 @@ impl InstructionInfo {
 		self.used_memory_locations.as_slice()
 	}
 
+	/// Gets all accessed processor state that isn't a register, eg. `MXCSR`, the x87 control and status words, `XCR0`, MSRs,
+	/// `PKRU`, `SSP` and the `TSC`. It's empty if [`InstructionInfoOptions::NO_REGISTER_USAGE`] is used.
+	///
+	/// [`InstructionInfoOptions::NO_REGISTER_USAGE`]: struct.InstructionInfoOptions.html#associatedconstant.NO_REGISTER_USAGE
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // ldmxcsr [rax]
+	/// let bytes = b"\x0F\xAE\x10";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	/// let mut info_factory = InstructionInfoFactory::new();
+	/// let info = info_factory.info(&instr);
+	/// assert_eq!(&[UsedImplicitState::new(ImplicitState::MXCSR, OpAccess::Write)], info.used_implicit_state());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn used_implicit_state(&self) -> &[UsedImplicitState] {
+		self.used_implicit_state.as_slice()
+	}
+
 	/// `true` if the instruction isn't available in real mode or virtual 8086 mode
 	#[cfg_attr(has_must_use, must_use)]
 	#[inline]
@@ -306,6 +344,49 @@ impl InstructionInfo {
 		self.encoding
 	}
 
+	/// Gets the exceptions the instruction can raise, its exception class, alignment requirements and memory ordering info.
+	/// Unlike [`Code::exception_info()`], the memory exceptions (eg. `#PF`) and the alignment are only used if the instruction
+	/// has a memory operand and [`is_locked()`] is `true` if it has a `LOCK` prefix.
+	///
+	/// [`Code::exception_info()`]: enum.Code.html#method.exception_info
+	/// [`is_locked()`]: struct.ExceptionInfo.html#method.is_locked
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // movaps xmm0,[rax]
+	/// let bytes = b"\x0F\x28\x00";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	/// let mut info_factory = InstructionInfoFactory::new();
+	/// let info = info_factory.info(&instr).exception_info();
+	/// assert_eq!(ExceptionClass::Type1, info.exception_class());
+	/// assert_eq!(16, info.alignment());
+	/// assert!(info.can_raise(ExceptionBits::GP | ExceptionBits::PF | ExceptionBits::NM));
+	/// assert!(!info.can_raise(ExceptionBits::XM));
+	///
+	/// // movaps xmm0,xmm1
+	/// let bytes = b"\x0F\x28\xC1";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	/// let info = info_factory.info(&instr).exception_info();
+	/// assert_eq!(0, info.alignment());
+	/// assert!(!info.can_raise(ExceptionBits::GP));
+	/// assert!(!info.can_raise(ExceptionBits::PF));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn exception_info(&self) -> ExceptionInfo {
+		ExceptionInfo::with_operands(
+			self.code,
+			(self.flags & IIFlags::MEMORY_OPERAND) != 0,
+			(self.flags & IIFlags::LOCK_PREFIX) != 0,
+			(self.flags & IIFlags::MOV_TO_CR8) != 0,
+		)
+	}
+
 	/// Gets the CPU or CPUID feature flags
 	#[cfg_attr(has_must_use, must_use)]
 	#[inline]
@@ -0,0 +1,297 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::int_utils::{mask, mul_high, sign_extend};
+use super::super::*;
+
+/// The values of the status flags (`OF`, `SF`, `ZF`, `AF`, `CF`, `PF`) written by an instruction, see [`compute()`]
+///
+/// [`compute()`]: #method.compute
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RflagsResult {
+	modified: u32,
+	values: u32,
+}
+
+const STATUS_FLAGS: u32 = RflagsBits::OF | RflagsBits::SF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::CF | RflagsBits::PF;
+
+#[inline]
+fn flag_if(condition: bool, flag: u32) -> u32 {
+	if condition {
+		flag
+	} else {
+		0
+	}
+}
+
+fn szp(size: u32, result: u64) -> u32 {
+	flag_if((result >> (size * 8 - 1)) & 1 != 0, RflagsBits::SF)
+		| flag_if(result == 0, RflagsBits::ZF)
+		| flag_if((result as u8).count_ones() & 1 == 0, RflagsBits::PF)
+}
+
+fn add_flags(size: u32, lhs: u64, rhs: u64, carry: bool, result: u64) -> u32 {
+	let sign = 1u64 << (size * 8 - 1);
+	szp(size, result)
+		| flag_if(result < lhs || (carry && result == lhs), RflagsBits::CF)
+		| flag_if((lhs ^ result) & (rhs ^ result) & sign != 0, RflagsBits::OF)
+		| flag_if((lhs ^ rhs ^ result) & 0x10 != 0, RflagsBits::AF)
+}
+
+fn sub_flags(size: u32, lhs: u64, rhs: u64, borrow: bool, result: u64) -> u32 {
+	let sign = 1u64 << (size * 8 - 1);
+	szp(size, result)
+		| flag_if(lhs < rhs || (borrow && lhs == rhs), RflagsBits::CF)
+		| flag_if((lhs ^ rhs) & (lhs ^ result) & sign != 0, RflagsBits::OF)
+		| flag_if((lhs ^ rhs ^ result) & 0x10 != 0, RflagsBits::AF)
+}
+
+impl RflagsResult {
+	/// Creates a new instance
+	///
+	/// # Arguments
+	///
+	/// * `modified`: All modified status flags, see [`RflagsBits`]
+	/// * `values`: The modified flags that are set, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new(modified: u32, values: u32) -> Self {
+		Self { modified, values: values & modified }
+	}
+
+	/// All status flags that are modified, including the undefined flags, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn modified(&self) -> u32 {
+		self.modified
+	}
+
+	/// The modified flags that are set, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn values(&self) -> u32 {
+		self.values
+	}
+
+	/// Updates the modified flags in `rflags` (a combination of [`RflagsBits`] values) and returns the new flags
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	///
+	/// # Arguments
+	///
+	/// * `rflags`: Old flags
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn apply(&self, rflags: u32) -> u32 {
+		(rflags & !self.modified) | self.values
+	}
+
+	/// Computes the status flags written by an instruction.
+	///
+	/// Returns `None` if the mnemonic isn't supported. All inputs are truncated to `size` bytes.
+	///
+	/// | Mnemonic | `lhs` | `rhs` | `result` |
+	/// |----------|-------|-------|----------|
+	/// | `ADD`, `ADC`, `XADD`, `ADCX`, `ADOX` | Destination | Source | Sum |
+	/// | `SUB`, `SBB`, `CMP`, `CMPS*`, `SCAS*` | Destination | Source | Difference |
+	/// | `CMPXCHG` | Accumulator | Destination | Accumulator - destination |
+	/// | `NEG` | Source | - | Negated value |
+	/// | `INC`, `DEC` | Source | - | New value |
+	/// | `AND`, `OR`, `XOR`, `TEST`, `ANDN` | - | - | Result |
+	/// | `SHL`, `SAL`, `SHR`, `SAR`, `ROL`, `ROR`, `RCL`, `RCR` | Value | Count (not masked) | Shifted/rotated value |
+	/// | `MUL`, `IMUL` | Factor | Factor | - |
+	/// | `BSF`, `BSR` | Source | - | Bit index |
+	/// | `TZCNT`, `LZCNT`, `POPCNT` | Source | - | Count |
+	/// | `BT`, `BTS`, `BTR`, `BTC` | Bit base | Bit offset (not masked) | - |
+	/// | `DIV`, `IDIV` | - | - | - |
+	///
+	/// `ADC`, `SBB`, `RCL` and `RCR` read `CF` from `rflags` and `ADOX` reads `OF`. The shift and rotate instructions
+	/// don't modify any flags if the masked count is 0.
+	///
+	/// The undefined flags get the values written by Intel CPUs (AMD CPUs can write other values):
+	///
+	/// - `AND`, `OR`, `XOR`, `TEST`, `ANDN`: `AF` is cleared
+	/// - `SHL`, `SAL`, `ROL`, `RCL`: `OF` = `CF` XOR the top bit of the result
+	/// - `SHR`, `ROR`, `RCR`: `OF` = the top two bits of the result XORed together (`SHR` clears it if the count is > 1)
+	/// - Shifts: `AF` is cleared
+	/// - `MUL`, `IMUL`: `SF`, `ZF` and `PF` are computed from the low half of the product, `AF` is cleared
+	/// - `BSF`, `BSR`: `SF` and `PF` are computed from the bit index, `AF`, `CF` and `OF` are cleared. If the source is 0,
+	///   only `ZF` is changed.
+	/// - `TZCNT`, `LZCNT`: all undefined flags are cleared
+	/// - `BT*`: the undefined flags aren't changed
+	/// - `DIV`, `IDIV`: no flags are changed
+	///
+	/// Flags that aren't changed are still included in [`modified()`] but get their values from `rflags`.
+	///
+	/// # Panics
+	///
+	/// Panics if `size` is not one of 1, 2, 4, 8
+	///
+	/// # Arguments
+	///
+	/// * `mnemonic`: Mnemonic
+	/// * `size`: Operand size in bytes
+	/// * `lhs`: First input, see the table above
+	/// * `rhs`: Second input, see the table above
+	/// * `result`: Result, see the table above
+	/// * `rflags`: Current flags, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	/// [`modified()`]: #method.modified
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // add al,1 (al = 7Fh)
+	/// let result = RflagsResult::compute(Mnemonic::Add, 1, 0x7F, 1, 0x80, RflagsBits::NONE).unwrap();
+	/// assert_eq!(RflagsBits::OF | RflagsBits::SF | RflagsBits::AF, result.values());
+	/// assert_eq!(RflagsBits::OF | RflagsBits::SF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::CF | RflagsBits::PF, result.modified());
+	///
+	/// // inc al (al = 0FFh) doesn't modify CF
+	/// let result = RflagsResult::compute(Mnemonic::Inc, 1, 0xFF, 0, 0, RflagsBits::NONE).unwrap();
+	/// assert_eq!(RflagsBits::CF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::PF, result.apply(RflagsBits::CF));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn compute(mnemonic: Mnemonic, size: u32, lhs: u64, rhs: u64, result: u64, rflags: u32) -> Option<Self> {
+		match size {
+			1 | 2 | 4 | 8 => {}
+			_ => panic!(),
+		}
+		let bits = size * 8;
+		let sign = 1u64 << (bits - 1);
+		let (lhs, rhs, result) = (lhs & mask(size), rhs & mask(size), result & mask(size));
+		let cf_in = (rflags & RflagsBits::CF) != 0;
+		let old_flags = rflags & STATUS_FLAGS;
+		let (modified, values) = match mnemonic {
+			Mnemonic::Add | Mnemonic::Xadd => (STATUS_FLAGS, add_flags(size, lhs, rhs, false, result)),
+			Mnemonic::Adc => (STATUS_FLAGS, add_flags(size, lhs, rhs, cf_in, result)),
+			Mnemonic::Adcx => (RflagsBits::CF, add_flags(size, lhs, rhs, cf_in, result)),
+			Mnemonic::Adox => {
+				let of_in = (rflags & RflagsBits::OF) != 0;
+				(RflagsBits::OF, flag_if((add_flags(size, lhs, rhs, of_in, result) & RflagsBits::CF) != 0, RflagsBits::OF))
+			}
+			Mnemonic::Sub
+			| Mnemonic::Cmp
+			| Mnemonic::Cmpxchg
+			| Mnemonic::Cmpsb
+			| Mnemonic::Cmpsw
+			| Mnemonic::Cmpsd
+			| Mnemonic::Cmpsq
+			| Mnemonic::Scasb
+			| Mnemonic::Scasw
+			| Mnemonic::Scasd
+			| Mnemonic::Scasq => (STATUS_FLAGS, sub_flags(size, lhs, rhs, false, result)),
+			Mnemonic::Sbb => (STATUS_FLAGS, sub_flags(size, lhs, rhs, cf_in, result)),
+			Mnemonic::Neg => (STATUS_FLAGS, sub_flags(size, 0, lhs, false, result)),
+			Mnemonic::Inc => (STATUS_FLAGS & !RflagsBits::CF, add_flags(size, lhs, 1, false, result)),
+			Mnemonic::Dec => (STATUS_FLAGS & !RflagsBits::CF, sub_flags(size, lhs, 1, false, result)),
+			Mnemonic::And | Mnemonic::Or | Mnemonic::Xor | Mnemonic::Test | Mnemonic::Andn => (STATUS_FLAGS, szp(size, result)),
+			Mnemonic::Shl | Mnemonic::Sal | Mnemonic::Shr | Mnemonic::Sar | Mnemonic::Rol | Mnemonic::Ror | Mnemonic::Rcl | Mnemonic::Rcr => {
+				let count = rhs & if size == 8 { 0x3F } else { 0x1F };
+				if count == 0 {
+					(0, 0)
+				} else {
+					let result_sign = (result & sign) != 0;
+					match mnemonic {
+						Mnemonic::Shl | Mnemonic::Sal => {
+							let cf = count <= bits as u64 && (lhs >> (bits as u64 - count)) & 1 != 0;
+							(STATUS_FLAGS, szp(size, result) | flag_if(cf, RflagsBits::CF) | flag_if(result_sign != cf, RflagsBits::OF))
+						}
+						Mnemonic::Shr => {
+							let cf = (lhs >> (count - 1)) & 1 != 0;
+							let of = result_sign != ((result << 1) & sign != 0);
+							(STATUS_FLAGS, szp(size, result) | flag_if(cf, RflagsBits::CF) | flag_if(of, RflagsBits::OF))
+						}
+						Mnemonic::Sar => {
+							let cf = ((sign_extend(lhs, size) as i64) >> (count - 1)) & 1 != 0;
+							(STATUS_FLAGS, szp(size, result) | flag_if(cf, RflagsBits::CF))
+						}
+						Mnemonic::Rol => {
+							let cf = result & 1 != 0;
+							(RflagsBits::CF | RflagsBits::OF, flag_if(cf, RflagsBits::CF) | flag_if(result_sign != cf, RflagsBits::OF))
+						}
+						Mnemonic::Ror => {
+							let of = result_sign != ((result << 1) & sign != 0);
+							(RflagsBits::CF | RflagsBits::OF, flag_if(result_sign, RflagsBits::CF) | flag_if(of, RflagsBits::OF))
+						}
+						Mnemonic::Rcl => {
+							// Rotates `bits + 1` bits (the value and CF)
+							let count = count % (bits as u64 + 1);
+							let cf = if count == 0 { cf_in } else { (lhs >> (bits as u64 - count)) & 1 != 0 };
+							(RflagsBits::CF | RflagsBits::OF, flag_if(cf, RflagsBits::CF) | flag_if(result_sign != cf, RflagsBits::OF))
+						}
+						_ => {
+							let count = count % (bits as u64 + 1);
+							let cf = if count == 0 { cf_in } else { (lhs >> (count - 1)) & 1 != 0 };
+							let of = result_sign != ((result << 1) & sign != 0);
+							(RflagsBits::CF | RflagsBits::OF, flag_if(cf, RflagsBits::CF) | flag_if(of, RflagsBits::OF))
+						}
+					}
+				}
+			}
+			Mnemonic::Mul | Mnemonic::Imul => {
+				let low = lhs.wrapping_mul(rhs) & mask(size);
+				let overflow = if mnemonic == Mnemonic::Mul {
+					if size == 8 {
+						mul_high(lhs, rhs) != 0
+					} else {
+						(lhs * rhs) >> bits != 0
+					}
+				} else {
+					let (slhs, srhs) = (sign_extend(lhs, size) as i64, sign_extend(rhs, size) as i64);
+					if size == 8 {
+						slhs.checked_mul(srhs).is_none()
+					} else {
+						sign_extend(low, size) as i64 != slhs * srhs
+					}
+				};
+				(STATUS_FLAGS, szp(size, low) | flag_if(overflow, RflagsBits::CF | RflagsBits::OF))
+			}
+			Mnemonic::Bsf | Mnemonic::Bsr => {
+				if lhs == 0 {
+					(STATUS_FLAGS, old_flags | RflagsBits::ZF)
+				} else {
+					(STATUS_FLAGS, szp(size, result) & !RflagsBits::ZF)
+				}
+			}
+			Mnemonic::Tzcnt | Mnemonic::Lzcnt => (STATUS_FLAGS, flag_if(result == 0, RflagsBits::ZF) | flag_if(lhs == 0, RflagsBits::CF)),
+			Mnemonic::Popcnt => (STATUS_FLAGS, flag_if(lhs == 0, RflagsBits::ZF)),
+			Mnemonic::Bt | Mnemonic::Bts | Mnemonic::Btr | Mnemonic::Btc => {
+				let offset = rhs & (bits as u64 - 1);
+				(STATUS_FLAGS & !RflagsBits::ZF, (old_flags & !RflagsBits::CF) | flag_if((lhs >> offset) & 1 != 0, RflagsBits::CF))
+			}
+			Mnemonic::Div | Mnemonic::Idiv => (0, 0),
+			_ => return None,
+		};
+		Some(RflagsResult::new(modified, values))
+	}
+}
@@ -0,0 +1,193 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::iced_constants::IcedConstants;
+use super::super::super::*;
+use core::mem;
+
+#[test]
+fn exception_class_and_alignment() {
+	#[rustfmt::skip]
+	let tests: &[(Code, ExceptionClass, u32, bool)] = &[
+		(Code::Movaps_xmm_xmmm128, ExceptionClass::Type1, 16, false),
+		(Code::Addps_xmm_xmmm128, ExceptionClass::Type2, 16, true),
+		(Code::Addss_xmm_xmmm32, ExceptionClass::Type3, 0, true),
+		(Code::Paddd_xmm_xmmm128, ExceptionClass::Type4, 16, false),
+		(Code::Movups_xmm_xmmm128, ExceptionClass::Type4, 0, false),
+		(Code::Pmovzxbw_xmm_xmmm64, ExceptionClass::Type5, 0, false),
+		(Code::Cvtsi2ss_xmm_rm32, ExceptionClass::Type3, 0, true),
+		(Code::VEX_Vbroadcastss_ymm_xmmm32, ExceptionClass::Type6, 0, false),
+		(Code::Movhlps_xmm_xmm, ExceptionClass::Type7, 0, false),
+		(Code::VEX_Vzeroupper, ExceptionClass::Type8, 0, false),
+		(Code::VEX_Vcvtph2ps_ymm_xmmm128, ExceptionClass::Type11, 0, true),
+		(Code::VEX_Vgatherdps_xmm_vm32x_xmm, ExceptionClass::Type12, 0, false),
+		(Code::VEX_Vaddps_ymm_ymm_ymmm256, ExceptionClass::Type2, 0, true),
+		(Code::EVEX_Vmovdqa32_zmm_k1z_zmmm512, ExceptionClass::E1, 64, false),
+		(Code::EVEX_Vpaddd_zmm_k1z_zmm_zmmm512b32, ExceptionClass::E4, 0, false),
+		(Code::EVEX_Vpgatherdd_zmm_k1_vm32z, ExceptionClass::E12, 0, false),
+		(Code::VEX_Andn_r32_r32_rm32, ExceptionClass::None, 0, false),
+		(Code::Add_rm32_r32, ExceptionClass::None, 0, false),
+		(Code::Cmpxchg16b_m128, ExceptionClass::None, 16, false),
+		(Code::Xsave_mem, ExceptionClass::None, 64, false),
+	];
+	for &(code, class, alignment, xm) in tests {
+		let info = code.exception_info();
+		assert_eq!(class, info.exception_class(), "{:?}", code);
+		assert_eq!(alignment, info.alignment(), "{:?}", code);
+		assert_eq!(xm, info.can_raise(ExceptionBits::XM), "{:?}", code);
+	}
+}
+
+#[test]
+fn exceptions() {
+	#[rustfmt::skip]
+	let tests: &[(Code, u32, u32)] = &[
+		(Code::INVALID, ExceptionBits::UD, !ExceptionBits::UD),
+		(Code::Add_rm32_r32, ExceptionBits::GP | ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::AC, ExceptionBits::UD | ExceptionBits::NM),
+		(Code::Push_r64, ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::UD, ExceptionBits::GP | ExceptionBits::NM),
+		(Code::Div_rm32, ExceptionBits::DE, ExceptionBits::UD),
+		(Code::Bound_r32_m3232, ExceptionBits::BR | ExceptionBits::GP, ExceptionBits::DE),
+		(Code::Ud2, ExceptionBits::UD, ExceptionBits::GP),
+		(Code::Fadd_m32fp, ExceptionBits::NM | ExceptionBits::MF | ExceptionBits::PF, ExceptionBits::UD | ExceptionBits::XM),
+		(Code::Fnstsw_AX, ExceptionBits::NM, ExceptionBits::MF),
+		(Code::Emms, ExceptionBits::NM | ExceptionBits::MF | ExceptionBits::UD, ExceptionBits::XM),
+		(Code::Addps_xmm_xmmm128, ExceptionBits::NM | ExceptionBits::UD | ExceptionBits::XM | ExceptionBits::GP, ExceptionBits::MF | ExceptionBits::AC),
+		(Code::Wrmsr, ExceptionBits::GP | ExceptionBits::UD, ExceptionBits::PF),
+		(Code::In_AL_imm8, ExceptionBits::GP, ExceptionBits::UD),
+		(Code::Mov_cr_r64, ExceptionBits::GP, ExceptionBits::PF),
+	];
+	for &(code, set, clear) in tests {
+		let info = code.exception_info();
+		assert!(info.can_raise(set), "{:?}: {:X}", code, info.exceptions());
+		assert_eq!(0, info.exceptions() & clear, "{:?}: {:X}", code, info.exceptions());
+	}
+}
+
+#[test]
+fn memory_ordering() {
+	assert!(Code::Cpuid.exception_info().is_serializing());
+	assert!(Code::Mov_cr_r64.exception_info().is_serializing());
+	assert!(!Code::Add_rm32_r32.exception_info().is_serializing());
+	assert!(Code::Wbinvd.exception_info().is_serializing());
+	assert!(Code::Invlpg_m.exception_info().is_serializing());
+	assert!(!Code::Invpcid_r64_m128.exception_info().is_serializing());
+	assert!(!Code::Xsetbv.exception_info().is_serializing());
+	assert!(Code::Lfence.exception_info().is_memory_fence());
+	assert!(!Code::Cpuid.exception_info().is_memory_fence());
+	assert!(Code::Movnti_m32_r32.exception_info().is_weakly_ordered());
+	assert!(!Code::Movaps_xmm_xmmm128.exception_info().is_weakly_ordered());
+	assert!(Code::Xchg_rm32_r32.exception_info().is_locked());
+	assert!(!Code::Add_rm32_r32.exception_info().is_locked());
+	assert!(Code::Cpuid.exception_info().is_ordering_sensitive());
+	assert!(Code::Lfence.exception_info().is_ordering_sensitive());
+	assert!(Code::Movnti_m32_r32.exception_info().is_ordering_sensitive());
+	assert!(Code::Xchg_rm32_r32.exception_info().is_ordering_sensitive());
+	assert!(!Code::Add_rm32_r32.exception_info().is_ordering_sensitive());
+}
+
+#[test]
+fn instruction_info_matches_code() {
+	let bytes = [0x0F, 0x28, 0x00, 0x0F, 0xAE, 0xE8, 0x62, 0xF1, 0x7D, 0x48, 0xFE, 0x00];
+	let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+	let mut factory = InstructionInfoFactory::new();
+	let mut count = 0;
+	while decoder.can_decode() {
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code());
+		assert_eq!(instruction.code().exception_info(), factory.info(&instruction).exception_info());
+		count += 1;
+	}
+	assert_eq!(3, count);
+}
+
+#[test]
+fn instruction_info_uses_operands() {
+	#[rustfmt::skip]
+	let tests: &[(&[u8], u32, u32, bool)] = &[
+		// div ecx
+		(&[0xF7, 0xF1], ExceptionBits::DE, ExceptionBits::GP | ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::AC, false),
+		// div dword ptr [rax]
+		(&[0xF7, 0x30], ExceptionBits::DE | ExceptionBits::GP | ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::AC, 0, false),
+		// movaps xmm0,xmm1
+		(&[0x0F, 0x28, 0xC1], ExceptionBits::NM | ExceptionBits::UD, ExceptionBits::GP | ExceptionBits::PF, false),
+		// push rax
+		(&[0x50], ExceptionBits::SS | ExceptionBits::PF | ExceptionBits::AC, 0, false),
+		// add [rax],eax
+		(&[0x01, 0x00], ExceptionBits::PF, 0, false),
+		// lock add [rax],eax
+		(&[0xF0, 0x01, 0x00], ExceptionBits::PF, 0, true),
+		// xchg [rax],eax
+		(&[0x87, 0x00], ExceptionBits::PF, 0, true),
+		// xchg ecx,eax
+		(&[0x87, 0xC1], 0, ExceptionBits::PF, false),
+	];
+	let mut factory = InstructionInfoFactory::new();
+	for &(bytes, set, clear, locked) in tests {
+		let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code());
+		let info = factory.info(&instruction).exception_info();
+		assert!(info.can_raise(set), "{:?}: {:X}", instruction.code(), info.exceptions());
+		assert_eq!(0, info.exceptions() & clear, "{:?}: {:X}", instruction.code(), info.exceptions());
+		assert_eq!(locked, info.is_locked(), "{:?}", instruction.code());
+		assert_eq!(locked, info.is_ordering_sensitive(), "{:?}", instruction.code());
+		if (clear & ExceptionBits::GP) != 0 {
+			assert_eq!(0, info.alignment(), "{:?}", instruction.code());
+		}
+	}
+}
+
+#[test]
+fn mov_to_cr8_isnt_serializing() {
+	#[rustfmt::skip]
+	let tests: &[(&[u8], bool)] = &[
+		// mov cr0,rax
+		(&[0x0F, 0x22, 0xC0], true),
+		// mov cr8,rax
+		(&[0x44, 0x0F, 0x22, 0xC0], false),
+		// mov rax,cr8
+		(&[0x44, 0x0F, 0x20, 0xC0], false),
+		// mov dr7,rax
+		(&[0x0F, 0x23, 0xF8], true),
+	];
+	let mut factory = InstructionInfoFactory::new();
+	for &(bytes, serializing) in tests {
+		let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code());
+		assert_eq!(serializing, factory.info(&instruction).exception_info().is_serializing(), "{:?}", instruction);
+	}
+}
+
+#[test]
+fn vector_instructions_can_raise_ud_and_nm() {
+	for i in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+		let code: Code = unsafe { mem::transmute(i as u16) };
+		let info = code.exception_info();
+		#[cfg(feature = "encoder")]
+		assert_eq!(info, code.op_code().exception_info());
+		if info.exception_class() != ExceptionClass::None {
+			assert!(info.can_raise(ExceptionBits::UD | ExceptionBits::NM), "{:?}", code);
+		}
+	}
+}
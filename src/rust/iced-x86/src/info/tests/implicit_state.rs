@@ -0,0 +1,112 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::iced_constants::IcedConstants;
+use super::super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::super::*;
+use super::super::exceptions::can_raise_xm;
+use core::mem;
+
+#[test]
+fn used_implicit_state() {
+	const R: OpAccess = OpAccess::Read;
+	const W: OpAccess = OpAccess::Write;
+	const RW: OpAccess = OpAccess::ReadWrite;
+	#[rustfmt::skip]
+	let tests: &[(&str, &[(ImplicitState, OpAccess)])] = &[
+		("01 C8", &[]),// add eax,ecx
+		("0F AE 10", &[(ImplicitState::MXCSR, W)]),// ldmxcsr [rax]
+		("0F AE 18", &[(ImplicitState::MXCSR, R)]),// stmxcsr [rax]
+		("0F 58 C1", &[(ImplicitState::MXCSR, RW)]),// addps xmm0,xmm1
+		("0F 28 C1", &[]),// movaps xmm0,xmm1
+		("DB E3", &[(ImplicitState::X87ControlWord, W), (ImplicitState::X87StatusWord, W), (ImplicitState::X87TagWord, W), (ImplicitState::X87Top, W)]),// fninit
+		("D9 00", &[(ImplicitState::X87ControlWord, R), (ImplicitState::X87StatusWord, RW), (ImplicitState::X87TagWord, RW), (ImplicitState::X87Top, RW)]),// fld dword ptr [rax]
+		("D8 C1", &[(ImplicitState::X87ControlWord, R), (ImplicitState::X87StatusWord, RW), (ImplicitState::X87TagWord, R), (ImplicitState::X87Top, R)]),// fadd st,st(1)
+		("D9 28", &[(ImplicitState::X87ControlWord, W)]),// fldcw [rax]
+		("0F 01 D1", &[(ImplicitState::XCR0, W)]),// xsetbv
+		("0F 01 D0", &[(ImplicitState::XCR0, R)]),// xgetbv
+		("0F 30", &[(ImplicitState::MSR, W)]),// wrmsr
+		("0F 32", &[(ImplicitState::MSR, R)]),// rdmsr
+		("0F 01 EF", &[(ImplicitState::PKRU, W)]),// wrpkru
+		("0F 01 EE", &[(ImplicitState::PKRU, R)]),// rdpkru
+		("0F 31", &[(ImplicitState::TSC, R)]),// rdtsc
+		("0F 01 F9", &[(ImplicitState::TSC, R), (ImplicitState::TscAux, R)]),// rdtscp
+		("F3 48 0F AE E8", &[(ImplicitState::SSP, RW)]),// incsspq rax
+		("E8 00 00 00 00", &[(ImplicitState::SSP, RW)]),// call near
+		("C3", &[(ImplicitState::SSP, RW)]),// ret
+		("0F 77", &[(ImplicitState::X87TagWord, W)]),// emms
+		("0F 0E", &[(ImplicitState::X87TagWord, W)]),// femms
+		("0F FE C1", &[(ImplicitState::X87TagWord, W), (ImplicitState::X87Top, W)]),// paddd mm0,mm1
+		("0F 6E C0", &[(ImplicitState::X87TagWord, W), (ImplicitState::X87Top, W)]),// movd mm0,eax
+		("66 0F FE C1", &[]),// paddd xmm0,xmm1
+		("0F 2A C1", &[(ImplicitState::MXCSR, RW), (ImplicitState::X87TagWord, W), (ImplicitState::X87Top, W)]),// cvtpi2ps xmm0,mm1
+		("0F E0 C1", &[(ImplicitState::X87TagWord, W), (ImplicitState::X87Top, W)]),// pavgb mm0,mm1
+	];
+	let mut factory = InstructionInfoFactory::new();
+	for &(hex_bytes, expected) in tests {
+		let bytes = to_vec_u8(hex_bytes).unwrap();
+		let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code(), "{}", hex_bytes);
+		let expected: Vec<UsedImplicitState> = expected.iter().map(|&(state, access)| UsedImplicitState::new(state, access)).collect();
+		assert_eq!(expected.as_slice(), factory.info(&instruction).used_implicit_state(), "{}", hex_bytes);
+	}
+}
+
+#[test]
+fn no_register_usage_option_clears_implicit_state() {
+	let bytes = [0x0F, 0x30];
+	let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+	let instruction = decoder.decode();
+	let mut factory = InstructionInfoFactory::new();
+	assert_eq!(1, factory.info(&instruction).used_implicit_state().len());
+	assert!(factory.info_options(&instruction, InstructionInfoOptions::NO_REGISTER_USAGE).used_implicit_state().is_empty());
+}
+
+#[test]
+fn default_is_none() {
+	assert_eq!(ImplicitState::None, ImplicitState::default());
+	assert_eq!(UsedImplicitState::new(ImplicitState::None, OpAccess::None), UsedImplicitState::default());
+}
+
+#[test]
+fn control_registers_are_used_registers() {
+	for &(hex_bytes, access) in [("0F 06", OpAccess::ReadWrite), ("0F 01 F0", OpAccess::ReadWrite), ("0F 01 E0", OpAccess::Read)].iter() {
+		let bytes = to_vec_u8(hex_bytes).unwrap();
+		let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code(), "{}", hex_bytes);
+		let mut factory = InstructionInfoFactory::new();
+		let info = factory.info(&instruction);
+		assert!(info.used_registers().contains(&UsedRegister::new(Register::CR0, access)), "{}", hex_bytes);
+		assert!(info.used_implicit_state().is_empty(), "{}", hex_bytes);
+	}
+}
+
+#[test]
+fn mxcsr_matches_exception_info() {
+	for i in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+		let code: Code = unsafe { mem::transmute(i as u16) };
+		assert_eq!(code.exception_info().can_raise(ExceptionBits::XM), can_raise_xm(code), "{:?}", code);
+	}
+}
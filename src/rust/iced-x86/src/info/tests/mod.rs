@@ -22,12 +22,16 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
 mod constants;
+mod exceptions;
+mod implicit_state;
 mod info_test_case;
 mod mem_size_test_case;
 mod mem_size_test_parser;
 mod misc_test_data;
 mod reg_info_test_case;
 mod reg_test_parser;
+mod register;
+mod rflags_result;
 mod test_parser;
 
 use self::constants::*;
@@ -0,0 +1,105 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::iced_constants::IcedConstants;
+use super::super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::mem;
+
+fn all_registers() -> Vec<Register> {
+	(0..IcedConstants::NUMBER_OF_REGISTERS).map(|i| unsafe { mem::transmute(i as u8) }).collect()
+}
+
+#[test]
+fn with_size_returns_the_same_register() {
+	for &register in all_registers().iter() {
+		if register == Register::None {
+			continue;
+		}
+		let is_high_byte = Register::AH <= register && register <= Register::BH;
+		assert_eq!(Some(register), register.full_register().with_size(register.size(), is_high_byte), "{:?}", register);
+		assert_eq!(Some(register), register.with_size(register.size(), is_high_byte), "{:?}", register);
+		assert_eq!(Some(register.full_register()), register.with_size(register.full_register().size(), false), "{:?}", register);
+	}
+	assert_eq!(None, Register::None.with_size(0, false));
+	assert_eq!(None, Register::R8.with_size(1, true));
+	assert_eq!(None, Register::RAX.with_size(2, true));
+	assert_eq!(None, Register::ES.with_size(4, false));
+	assert_eq!(None, Register::ZMM0.with_size(8, false));
+}
+
+#[test]
+fn overlaps() {
+	let registers = all_registers();
+	for &register1 in registers.iter() {
+		for &register2 in registers.iter() {
+			let overlaps = register1.overlaps(register2);
+			assert_eq!(overlaps, register2.overlaps(register1), "{:?} {:?}", register1, register2);
+			if register1 == Register::None || register2 == Register::None {
+				assert!(!overlaps);
+			} else if register1 == register2 {
+				assert!(overlaps, "{:?}", register1);
+			} else if register1.full_register() != register2.full_register() {
+				assert!(!overlaps, "{:?} {:?}", register1, register2);
+			}
+		}
+	}
+	assert!(Register::AH.overlaps(Register::EAX));
+	assert!(Register::AL.overlaps(Register::AX));
+	assert!(!Register::AL.overlaps(Register::AH));
+	assert!(!Register::CH.overlaps(Register::CL));
+	assert!(Register::SPL.overlaps(Register::RSP));
+	assert!(Register::XMM1.overlaps(Register::YMM1));
+	assert!(!Register::XMM1.overlaps(Register::XMM2));
+	assert!(!Register::MM0.overlaps(Register::ST0));
+}
+
+#[test]
+fn bit_range_in_full() {
+	for &register in all_registers().iter() {
+		let range = register.bit_range_in_full();
+		let full_range = register.full_register().bit_range_in_full();
+		assert_eq!(register.size() as u32 * 8, range.end - range.start, "{:?}", register);
+		assert!(full_range.start <= range.start && range.end <= full_range.end, "{:?}", register);
+	}
+}
+
+#[test]
+fn vector_registers() {
+	for i in 0..IcedConstants::VMM_COUNT {
+		let xmm = Register::XMM0 + i;
+		let ymm = Register::YMM0 + i;
+		let zmm = Register::ZMM0 + i;
+		for &register in [xmm, ymm, zmm].iter() {
+			assert_eq!(Some(xmm), register.to_xmm());
+			assert_eq!(Some(ymm), register.to_ymm());
+			assert_eq!(Some(zmm), register.to_zmm());
+		}
+	}
+	for &register in [Register::None, Register::RAX, Register::MM0, Register::K1, Register::ST0].iter() {
+		assert_eq!(None, register.to_xmm());
+		assert_eq!(None, register.to_ymm());
+		assert_eq!(None, register.to_zmm());
+	}
+}
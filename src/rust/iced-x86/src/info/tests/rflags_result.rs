@@ -0,0 +1,176 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::super::test_utils::create_decoder;
+use super::super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::super::*;
+
+const STATUS_FLAGS: u32 = RflagsBits::OF | RflagsBits::SF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::CF | RflagsBits::PF;
+
+fn compute(mnemonic: Mnemonic, size: u32, lhs: u64, rhs: u64, result: u64, rflags: u32) -> u32 {
+	RflagsResult::compute(mnemonic, size, lhs, rhs, result, rflags).unwrap().values()
+}
+
+#[test]
+fn modified_flags_match_instruction_info() {
+	#[rustfmt::skip]
+	let tests: &[(&str, u64, u64)] = &[
+		("01 C8", 1, 2),// add eax,ecx
+		("11 C8", 1, 2),// adc eax,ecx
+		("29 C8", 1, 2),// sub eax,ecx
+		("19 C8", 1, 2),// sbb eax,ecx
+		("39 C8", 1, 2),// cmp eax,ecx
+		("F7 D8", 1, 0),// neg eax
+		("FF C0", 1, 0),// inc eax
+		("FF C8", 1, 0),// dec eax
+		("21 C8", 1, 2),// and eax,ecx
+		("31 C8", 1, 2),// xor eax,ecx
+		("85 C8", 1, 2),// test eax,ecx
+		("C1 E0 05", 1, 5),// shl eax,5
+		("C1 E8 05", 1, 5),// shr eax,5
+		("C1 F8 05", 1, 5),// sar eax,5
+		("C1 C0 05", 1, 5),// rol eax,5
+		("C1 C8 05", 1, 5),// ror eax,5
+		("C1 D0 05", 1, 5),// rcl eax,5
+		("C1 D8 05", 1, 5),// rcr eax,5
+		("F7 E1", 1, 2),// mul ecx
+		("0F AF C1", 1, 2),// imul eax,ecx
+		("0F BC C1", 1, 0),// bsf eax,ecx
+		("0F BD C1", 1, 0),// bsr eax,ecx
+		("F3 0F BC C1", 1, 0),// tzcnt eax,ecx
+		("F3 0F BD C1", 1, 0),// lzcnt eax,ecx
+		("F3 0F B8 C1", 1, 0),// popcnt eax,ecx
+		("0F A3 C8", 1, 2),// bt eax,ecx
+		("0F AB C8", 1, 2),// bts eax,ecx
+		("0F C1 C8", 1, 2),// xadd eax,ecx
+		("0F B1 C8", 1, 2),// cmpxchg eax,ecx
+		("A6", 1, 2),// cmpsb
+		("AF", 1, 2),// scasd
+		("66 0F 38 F6 C1", 1, 2),// adcx eax,ecx
+		("F3 0F 38 F6 C1", 1, 2),// adox eax,ecx
+		("C4 E2 70 F2 C2", 1, 2),// andn eax,ecx,edx
+	];
+	let mut factory = InstructionInfoFactory::new();
+	for &(hex_bytes, lhs, rhs) in tests {
+		let bytes = to_vec_u8(hex_bytes).unwrap();
+		let mut decoder = create_decoder(64, &bytes, DecoderOptions::NONE).0;
+		let instruction = decoder.decode();
+		assert_ne!(Code::INVALID, instruction.code());
+		let info = factory.info(&instruction);
+		let expected = info.rflags_written() | info.rflags_cleared() | info.rflags_set() | info.rflags_undefined();
+		let result = RflagsResult::compute(instruction.mnemonic(), 4, lhs, rhs, 0, RflagsBits::NONE).unwrap();
+		assert_eq!(expected & STATUS_FLAGS, result.modified(), "{}", hex_bytes);
+		assert_eq!(RflagsBits::NONE, result.values() & info.rflags_cleared(), "{}", hex_bytes);
+		assert_eq!(info.rflags_set(), result.values() & info.rflags_set(), "{}", hex_bytes);
+	}
+}
+
+#[test]
+fn add_sub() {
+	assert_eq!(RflagsBits::ZF | RflagsBits::PF, compute(Mnemonic::Add, 4, 0, 0, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::PF, compute(Mnemonic::Add, 2, 0xFFFF, 1, 0, RflagsBits::NONE));
+	assert_eq!(
+		RflagsBits::OF | RflagsBits::SF | RflagsBits::AF | RflagsBits::PF,
+		compute(Mnemonic::Add, 8, 0x7FFF_FFFF_FFFF_FFFF, 1, 0x8000_0000_0000_0000, 0)
+	);
+	// adc with CF=1: FFh + FFh + 1 = 1FFh
+	assert_eq!(RflagsBits::CF | RflagsBits::SF | RflagsBits::AF | RflagsBits::PF, compute(Mnemonic::Adc, 1, 0xFF, 0xFF, 0xFF, RflagsBits::CF));
+	assert_eq!(RflagsBits::CF | RflagsBits::SF | RflagsBits::AF | RflagsBits::PF, compute(Mnemonic::Sub, 1, 0, 1, 0xFF, RflagsBits::NONE));
+	assert_eq!(
+		RflagsBits::OF | RflagsBits::SF,
+		compute(Mnemonic::Sub, 1, 0x7F, 0xFF, 0x80, RflagsBits::NONE) & !(RflagsBits::CF | RflagsBits::AF | RflagsBits::PF)
+	);
+	// sbb with CF=1: 5 - 5 - 1
+	assert_eq!(RflagsBits::CF | RflagsBits::SF | RflagsBits::AF | RflagsBits::PF, compute(Mnemonic::Sbb, 4, 5, 5, 0xFFFF_FFFF, RflagsBits::CF));
+	assert_eq!(RflagsBits::OF | RflagsBits::SF | RflagsBits::CF, compute(Mnemonic::Neg, 1, 0x80, 0, 0x80, RflagsBits::NONE));
+	// Inputs are truncated
+	assert_eq!(RflagsBits::ZF | RflagsBits::PF, compute(Mnemonic::Add, 1, 0x100, 0x200, 0x300, RflagsBits::NONE));
+}
+
+#[test]
+fn shifts_and_rotates() {
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Shl, 1, 0x81, 1, 0x02, RflagsBits::NONE));
+	assert_eq!(RflagsBits::OF | RflagsBits::CF | RflagsBits::ZF | RflagsBits::PF, compute(Mnemonic::Shl, 1, 0x81, 8, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Shr, 1, 0x81, 1, 0x40, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF | RflagsBits::SF | RflagsBits::PF, compute(Mnemonic::Sar, 1, 0x81, 1, 0xC0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Rol, 1, 0x81, 1, 0x03, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF, compute(Mnemonic::Ror, 1, 0x81, 1, 0xC0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Rcl, 1, 0x80, 1, 0x00, RflagsBits::NONE));
+	assert_eq!(RflagsBits::OF, compute(Mnemonic::Rcr, 1, 0x00, 1, 0x80, RflagsBits::CF));
+
+	// The masked count is 0
+	let result = RflagsResult::compute(Mnemonic::Shl, 4, 0x1234, 0x20, 0x1234, RflagsBits::NONE).unwrap();
+	assert_eq!(RflagsBits::NONE, result.modified());
+	assert_eq!(RflagsBits::CF | RflagsBits::ZF, result.apply(RflagsBits::CF | RflagsBits::ZF));
+}
+
+#[test]
+fn mul_bit_ops() {
+	assert_eq!(RflagsBits::SF | RflagsBits::PF, compute(Mnemonic::Mul, 8, 0x1_0000_0000, 0xFFFF_FFFF, 0, RflagsBits::NONE));
+	assert_eq!(
+		RflagsBits::CF | RflagsBits::OF | RflagsBits::ZF | RflagsBits::PF,
+		compute(Mnemonic::Mul, 8, 0x1_0000_0000, 0x1_0000_0000, 0, RflagsBits::NONE)
+	);
+	assert_eq!(
+		RflagsBits::SF | RflagsBits::PF,
+		compute(Mnemonic::Imul, 8, u64::max_value(), 0x8000_0000_0000_0000, 0, RflagsBits::NONE) & !(RflagsBits::CF | RflagsBits::OF)
+	);
+	assert_eq!(
+		RflagsBits::CF | RflagsBits::OF,
+		compute(Mnemonic::Imul, 8, u64::max_value(), 0x8000_0000_0000_0000, 0, RflagsBits::NONE) & (RflagsBits::CF | RflagsBits::OF)
+	);
+	assert_eq!(RflagsBits::NONE, compute(Mnemonic::Imul, 2, 0xFFFF, 0x7FFF, 0, RflagsBits::NONE) & (RflagsBits::CF | RflagsBits::OF));
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Imul, 1, 0x40, 2, 0, RflagsBits::NONE) & (RflagsBits::CF | RflagsBits::OF));
+
+	assert_eq!(RflagsBits::ZF, compute(Mnemonic::Bsf, 4, 0, 0, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::NONE, compute(Mnemonic::Bsr, 4, 0x10, 0, 4, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF, compute(Mnemonic::Tzcnt, 4, 0, 0, 32, RflagsBits::NONE));
+	assert_eq!(RflagsBits::ZF, compute(Mnemonic::Lzcnt, 4, 0x8000_0000, 0, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::ZF, compute(Mnemonic::Popcnt, 4, 0, 0, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::CF, compute(Mnemonic::Bt, 4, 0x20, 0x25, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::NONE, compute(Mnemonic::Bt, 4, 0x20, 0x24, 0, RflagsBits::NONE));
+}
+
+#[test]
+fn intel_undefined_flags() {
+	// OF is only set by SHR if the count is 1
+	assert_eq!(RflagsBits::CF | RflagsBits::OF, compute(Mnemonic::Shr, 1, 0x81, 1, 0x40, RflagsBits::NONE));
+	assert_eq!(RflagsBits::NONE, compute(Mnemonic::Shr, 1, 0x81, 2, 0x20, RflagsBits::NONE));
+	assert_eq!(RflagsBits::PF, compute(Mnemonic::Bsr, 8, 0x8000_0000_0000_0000, 0, 63, RflagsBits::NONE));
+	assert_eq!(RflagsBits::PF, compute(Mnemonic::Bsf, 4, 1, 0, 0, RflagsBits::ZF | RflagsBits::CF | RflagsBits::AF));
+	let old_flags = RflagsBits::OF | RflagsBits::SF | RflagsBits::AF | RflagsBits::CF | RflagsBits::PF;
+	assert_eq!(old_flags | RflagsBits::ZF, compute(Mnemonic::Bsf, 4, 0, 0, 0, old_flags));
+	assert_eq!(RflagsBits::OF | RflagsBits::SF | RflagsBits::AF | RflagsBits::PF, compute(Mnemonic::Bt, 4, 0x20, 0x24, 0, old_flags));
+}
+
+#[test]
+fn unsupported_mnemonic() {
+	assert_eq!(None, RflagsResult::compute(Mnemonic::Nop, 4, 0, 0, 0, RflagsBits::NONE));
+	assert_eq!(RflagsBits::NONE, RflagsResult::compute(Mnemonic::Div, 4, 1, 2, 3, RflagsBits::NONE).unwrap().modified());
+}
+
+#[test]
+#[should_panic]
+fn invalid_size_panics() {
+	let _ = RflagsResult::compute(Mnemonic::Add, 3, 0, 0, 0, RflagsBits::NONE);
+}
@@ -0,0 +1,536 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(test)]
+mod tests;
+
+use super::code::GEN_DEBUG_CODE;
+use super::enums::{GEN_DEBUG_CODE_SIZE, GEN_DEBUG_OP_KIND, GEN_DEBUG_ROUNDING_CONTROL};
+use super::iced_constants::IcedConstants;
+use super::instruction::{CodeFlags, MemoryFlags, OpKindFlags};
+use super::register::GEN_DEBUG_REGISTER;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{fmt, mem, u16, u32};
+
+// Layout:
+//	header: "IXS" VERSION u32 schema_hash (little endian, see schema_hash())
+//	instruction:
+//		u8 tag: bits 0-5 = shape cache index or SHAPE_LITERAL, TAG_IP_DELTA, TAG_FIELDS
+//		if SHAPE_LITERAL: varint code_flags, varint op_kind_flags
+//		if TAG_IP_DELTA: varint zigzag(ip - previous next_ip)
+//		if TAG_FIELDS: u8 field mask (FIELD_*), followed by the fields in FIELD_* order
+//
+// The shape (code_flags and op_kind_flags) cache is a move-to-front list that's updated the same way by the
+// writer and the reader.
+const HEADER: [u8; 4] = [b'I', b'X', b'S', VERSION];
+const VERSION: u8 = 2;
+const HEADER_SIZE: usize = 8;
+
+const SHAPE_CACHE_SIZE: usize = 63;
+const SHAPE_LITERAL: u8 = SHAPE_CACHE_SIZE as u8;
+const TAG_SHAPE_MASK: u8 = 0x3F;
+const TAG_IP_DELTA: u8 = 0x40;
+const TAG_FIELDS: u8 = 0x80;
+
+const FIELD_IMMEDIATE: u8 = 0x01;
+const FIELD_MEM_DISPL: u8 = 0x02;
+const FIELD_MEMORY_FLAGS: u8 = 0x04;
+const FIELD_MEM_BASE_REG: u8 = 0x08;
+const FIELD_MEM_INDEX_REG: u8 = 0x10;
+const FIELD_REG0: u8 = 0x20;
+const FIELD_REG1: u8 = 0x40;
+const FIELD_REG2_REG3: u8 = 0x80;
+
+struct ShapeCache {
+	shapes: [u64; SHAPE_CACHE_SIZE],
+	len: usize,
+}
+
+impl ShapeCache {
+	fn new() -> Self {
+		Self { shapes: [0; SHAPE_CACHE_SIZE], len: 0 }
+	}
+
+	fn find(&self, shape: u64) -> Option<usize> {
+		self.shapes[..self.len].iter().position(|&s| s == shape)
+	}
+
+	fn get(&mut self, index: usize) -> u64 {
+		let shape = self.shapes[index];
+		self.move_to_front(index, shape);
+		shape
+	}
+
+	fn add(&mut self, shape: u64) {
+		if self.len < SHAPE_CACHE_SIZE {
+			self.len += 1;
+		}
+		let last = self.len - 1;
+		self.move_to_front(last, shape);
+	}
+
+	fn move_to_front(&mut self, index: usize, shape: u64) {
+		let mut i = index;
+		while i > 0 {
+			self.shapes[i] = self.shapes[i - 1];
+			i -= 1;
+		}
+		self.shapes[0] = shape;
+	}
+}
+
+impl fmt::Debug for ShapeCache {
+	fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+		f.debug_list().entries(self.shapes[..self.len].iter()).finish()
+	}
+}
+
+// The stream stores the instruction's internal fields, so the Code, Register, OpKind, CodeSize and RoundingControl
+// values and the layout of the flags must be identical when it's read. They're hashed (FNV-1a) and the hash is
+// stored in the header so streams written by an incompatible version of iced are rejected.
+lazy_static! {
+	static ref SCHEMA_HASH: u32 = schema_hash();
+}
+
+fn schema_hash() -> u32 {
+	fn add(hash: u32, bytes: &[u8]) -> u32 {
+		bytes.iter().fold(hash, |hash, &b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+	}
+	let mut hash = 0x811C_9DC5;
+	for names in
+		[&GEN_DEBUG_CODE[..], &GEN_DEBUG_REGISTER[..], &GEN_DEBUG_OP_KIND[..], &GEN_DEBUG_CODE_SIZE[..], &GEN_DEBUG_ROUNDING_CONTROL[..]].iter()
+	{
+		for name in names.iter() {
+			hash = add(hash, name.as_bytes());
+			hash = add(hash, &[0]);
+		}
+		hash = add(hash, &[0xFF]);
+	}
+	for &value in [
+		CodeFlags::CODE_MASK,
+		CodeFlags::ROUNDING_CONTROL_MASK,
+		CodeFlags::ROUNDING_CONTROL_SHIFT,
+		CodeFlags::OP_MASK_MASK,
+		CodeFlags::OP_MASK_SHIFT,
+		CodeFlags::INSTR_LENGTH_MASK,
+		CodeFlags::INSTR_LENGTH_SHIFT,
+		CodeFlags::SUPPRESS_ALL_EXCEPTIONS,
+		CodeFlags::ZEROING_MASKING,
+		CodeFlags::XACQUIRE_PREFIX,
+		CodeFlags::XRELEASE_PREFIX,
+		CodeFlags::REPE_PREFIX,
+		CodeFlags::REPNE_PREFIX,
+		CodeFlags::LOCK_PREFIX,
+		OpKindFlags::OP_KIND_MASK,
+		OpKindFlags::OP1_KIND_SHIFT,
+		OpKindFlags::OP2_KIND_SHIFT,
+		OpKindFlags::OP3_KIND_SHIFT,
+		OpKindFlags::DATA_LENGTH_MASK,
+		OpKindFlags::DATA_LENGTH_SHIFT,
+		OpKindFlags::CODE_SIZE_MASK,
+		OpKindFlags::CODE_SIZE_SHIFT,
+		MemoryFlags::SCALE_MASK,
+		MemoryFlags::DISPL_SIZE_SHIFT,
+		MemoryFlags::DISPL_SIZE_MASK,
+		MemoryFlags::SEGMENT_PREFIX_SHIFT,
+		MemoryFlags::SEGMENT_PREFIX_MASK,
+		MemoryFlags::BROADCAST,
+	]
+	.iter()
+	{
+		hash = add(hash, &[value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]);
+	}
+	hash
+}
+
+#[inline]
+fn is_declare_data(code: u32) -> bool {
+	code == Code::DeclareByte as u32 || code == Code::DeclareWord as u32 || code == Code::DeclareDword as u32 || code == Code::DeclareQword as u32
+}
+
+/// Writes instructions to a compact binary instruction stream that can be read by [`InstructionStreamReader`].
+///
+/// The IP of each instruction is stored relative to the end of the previous instruction, the code, prefixes and operand
+/// kinds are stored in a small per-stream table of recently used values, and all other fields are only stored if they're
+/// not zero. Reading the stream returns instructions that are identical to the written ones, see [`Instruction::eq_all_bits()`].
+///
+/// [`InstructionStreamReader`]: struct.InstructionStreamReader.html
+/// [`Instruction::eq_all_bits()`]: struct.Instruction.html#method.eq_all_bits
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let bytes = b"\x48\x89\x5C\x24\x10\x55\x57";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// decoder.set_ip(0x1234_5678);
+/// let mut writer = InstructionStreamWriter::new();
+/// for instruction in &mut decoder {
+///     writer.write(&instruction);
+/// }
+/// let data = writer.take_buffer();
+///
+/// let mut reader = InstructionStreamReader::new(&data).unwrap();
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// decoder.set_ip(0x1234_5678);
+/// for instruction in &mut decoder {
+///     assert!(instruction.eq_all_bits(&reader.read().unwrap()));
+/// }
+/// assert!(!reader.can_read());
+/// ```
+#[derive(Debug)]
+pub struct InstructionStreamWriter {
+	buffer: Vec<u8>,
+	next_ip: u64,
+	shapes: ShapeCache,
+}
+
+impl InstructionStreamWriter {
+	/// Creates a new writer. The stream header is written to the buffer.
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new() -> Self {
+		let mut buffer = Vec::new();
+		buffer.extend_from_slice(&HEADER);
+		let hash = *SCHEMA_HASH;
+		buffer.extend_from_slice(&[hash as u8, (hash >> 8) as u8, (hash >> 16) as u8, (hash >> 24) as u8]);
+		Self { buffer, next_ip: 0, shapes: ShapeCache::new() }
+	}
+
+	/// Returns the written bytes and initializes the internal buffer to an empty vector. The writer's state isn't reset, so
+	/// you can write more instructions and append the new bytes to the old bytes. This makes it possible to write large
+	/// streams in chunks.
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn take_buffer(&mut self) -> Vec<u8> {
+		mem::replace(&mut self.buffer, Vec::new())
+	}
+
+	/// Gets the bytes that haven't been returned by [`take_buffer()`] yet
+	///
+	/// [`take_buffer()`]: #method.take_buffer
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn buffer(&self) -> &[u8] {
+		&self.buffer
+	}
+
+	/// Writes an instruction
+	///
+	/// # Arguments
+	///
+	/// * `instruction`: Instruction to write
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn write(&mut self, instruction: &Instruction) {
+		let shape = instruction.code_flags as u64 | ((instruction.op_kind_flags as u64) << 32);
+		let mut tag = match self.shapes.find(shape) {
+			Some(index) => {
+				let _ = self.shapes.get(index);
+				index as u8
+			}
+			None => {
+				self.shapes.add(shape);
+				SHAPE_LITERAL
+			}
+		};
+		let ip_delta = instruction.ip().wrapping_sub(self.next_ip) as i64;
+		if ip_delta != 0 {
+			tag |= TAG_IP_DELTA;
+		}
+		let mut fields = 0;
+		if instruction.immediate != 0 {
+			fields |= FIELD_IMMEDIATE;
+		}
+		if instruction.mem_displ != 0 {
+			fields |= FIELD_MEM_DISPL;
+		}
+		if instruction.memory_flags != 0 {
+			fields |= FIELD_MEMORY_FLAGS;
+		}
+		if instruction.mem_base_reg != 0 {
+			fields |= FIELD_MEM_BASE_REG;
+		}
+		if instruction.mem_index_reg != 0 {
+			fields |= FIELD_MEM_INDEX_REG;
+		}
+		if instruction.reg0 != 0 {
+			fields |= FIELD_REG0;
+		}
+		if instruction.reg1 != 0 {
+			fields |= FIELD_REG1;
+		}
+		if (instruction.reg2 | instruction.reg3) != 0 {
+			fields |= FIELD_REG2_REG3;
+		}
+		if fields != 0 {
+			tag |= TAG_FIELDS;
+		}
+
+		self.buffer.push(tag);
+		if (tag & TAG_SHAPE_MASK) == SHAPE_LITERAL {
+			self.write_varint(instruction.code_flags as u64);
+			self.write_varint(instruction.op_kind_flags as u64);
+		}
+		if ip_delta != 0 {
+			self.write_varint(((ip_delta << 1) ^ (ip_delta >> 63)) as u64);
+		}
+		if fields != 0 {
+			self.buffer.push(fields);
+			if (fields & FIELD_IMMEDIATE) != 0 {
+				self.write_varint(instruction.immediate as u64);
+			}
+			if (fields & FIELD_MEM_DISPL) != 0 {
+				self.write_varint(instruction.mem_displ as u64);
+			}
+			if (fields & FIELD_MEMORY_FLAGS) != 0 {
+				self.write_varint(instruction.memory_flags as u64);
+			}
+			if (fields & FIELD_MEM_BASE_REG) != 0 {
+				self.buffer.push(instruction.mem_base_reg);
+			}
+			if (fields & FIELD_MEM_INDEX_REG) != 0 {
+				self.buffer.push(instruction.mem_index_reg);
+			}
+			if (fields & FIELD_REG0) != 0 {
+				self.buffer.push(instruction.reg0);
+			}
+			if (fields & FIELD_REG1) != 0 {
+				self.buffer.push(instruction.reg1);
+			}
+			if (fields & FIELD_REG2_REG3) != 0 {
+				self.buffer.push(instruction.reg2);
+				self.buffer.push(instruction.reg3);
+			}
+		}
+		self.next_ip = instruction.next_ip();
+	}
+
+	fn write_varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.buffer.push(value as u8 | 0x80);
+			value >>= 7;
+		}
+		self.buffer.push(value as u8);
+	}
+}
+
+impl Default for InstructionStreamWriter {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn default() -> Self {
+		InstructionStreamWriter::new()
+	}
+}
+
+/// Reads instructions written by an [`InstructionStreamWriter`]
+///
+/// [`InstructionStreamWriter`]: struct.InstructionStreamWriter.html
+#[derive(Debug)]
+pub struct InstructionStreamReader<'a> {
+	data: &'a [u8],
+	position: usize,
+	next_ip: u64,
+	shapes: ShapeCache,
+}
+
+impl<'a> InstructionStreamReader<'a> {
+	/// Creates a new reader
+	///
+	/// # Errors
+	///
+	/// Fails if `data` doesn't start with a valid stream header or if it was written by an unsupported version or by a
+	/// version of iced with different [`Code`] or [`Register`] values
+	///
+	/// # Arguments
+	///
+	/// * `data`: The stream, starting with the header
+	///
+	/// [`Code`]: enum.Code.html
+	/// [`Register`]: enum.Register.html
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new(data: &'a [u8]) -> Result<Self, String> {
+		if data.len() < HEADER.len() || data[..HEADER.len() - 1] != HEADER[..HEADER.len() - 1] {
+			return Err(String::from("Invalid instruction stream header"));
+		}
+		if data[HEADER.len() - 1] != VERSION || data.len() < HEADER_SIZE {
+			return Err(String::from("Unsupported instruction stream version"));
+		}
+		let hash = data[4] as u32 | (data[5] as u32) << 8 | (data[6] as u32) << 16 | (data[7] as u32) << 24;
+		if hash != *SCHEMA_HASH {
+			return Err(String::from("The instruction stream was written by an incompatible version of iced"));
+		}
+		Ok(Self { data, position: HEADER_SIZE, next_ip: 0, shapes: ShapeCache::new() })
+	}
+
+	/// Gets the current position in the stream
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// Returns `true` if there's at least one more byte to read. It doesn't verify that the rest of the stream is valid.
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn can_read(&self) -> bool {
+		self.position < self.data.len()
+	}
+
+	/// Reads the next instruction
+	///
+	/// # Errors
+	///
+	/// Fails if there's no more data or if the data is invalid. The reader can't be used after it has failed.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn read(&mut self) -> Result<Instruction, String> {
+		let tag = self.read_u8()?;
+		let shape_index = tag & TAG_SHAPE_MASK;
+		let shape = if shape_index == SHAPE_LITERAL {
+			let code_flags = self.read_varint_u32()?;
+			let op_kind_flags = self.read_varint_u32()?;
+			let shape = code_flags as u64 | ((op_kind_flags as u64) << 32);
+			self.shapes.add(shape);
+			shape
+		} else if (shape_index as usize) < self.shapes.len {
+			self.shapes.get(shape_index as usize)
+		} else {
+			return Err(Self::invalid_data());
+		};
+
+		let mut instruction = Instruction { code_flags: shape as u32, op_kind_flags: (shape >> 32) as u32, ..Instruction::default() };
+		let ip_delta = if (tag & TAG_IP_DELTA) != 0 {
+			let value = self.read_varint()?;
+			((value >> 1) as i64) ^ -((value & 1) as i64)
+		} else {
+			0
+		};
+
+		if (tag & TAG_FIELDS) != 0 {
+			let fields = self.read_u8()?;
+			if (fields & FIELD_IMMEDIATE) != 0 {
+				instruction.immediate = self.read_varint_u32()?;
+			}
+			if (fields & FIELD_MEM_DISPL) != 0 {
+				instruction.mem_displ = self.read_varint_u32()?;
+			}
+			if (fields & FIELD_MEMORY_FLAGS) != 0 {
+				let value = self.read_varint_u32()?;
+				if value > u16::MAX as u32 {
+					return Err(Self::invalid_data());
+				}
+				instruction.memory_flags = value as u16;
+			}
+			if (fields & FIELD_MEM_BASE_REG) != 0 {
+				instruction.mem_base_reg = self.read_u8()?;
+			}
+			if (fields & FIELD_MEM_INDEX_REG) != 0 {
+				instruction.mem_index_reg = self.read_u8()?;
+			}
+			if (fields & FIELD_REG0) != 0 {
+				instruction.reg0 = self.read_u8()?;
+			}
+			if (fields & FIELD_REG1) != 0 {
+				instruction.reg1 = self.read_u8()?;
+			}
+			if (fields & FIELD_REG2_REG3) != 0 {
+				instruction.reg2 = self.read_u8()?;
+				instruction.reg3 = self.read_u8()?;
+			}
+		}
+		if !Self::is_valid(&instruction) {
+			return Err(Self::invalid_data());
+		}
+
+		let ip = self.next_ip.wrapping_add(ip_delta as u64);
+		instruction.set_next_ip(ip.wrapping_add(instruction.len() as u64));
+		self.next_ip = instruction.next_ip();
+		Ok(instruction)
+	}
+
+	// Verifies that all enum fields are valid so the getters can't return invalid enum values
+	fn is_valid(instruction: &Instruction) -> bool {
+		let code = instruction.code_flags & CodeFlags::CODE_MASK;
+		if code as usize >= IcedConstants::NUMBER_OF_CODE_VALUES {
+			return false;
+		}
+		let rounding_control = (instruction.code_flags >> CodeFlags::ROUNDING_CONTROL_SHIFT) & CodeFlags::ROUNDING_CONTROL_MASK;
+		if rounding_control as usize >= IcedConstants::NUMBER_OF_ROUNDING_CONTROL_VALUES {
+			return false;
+		}
+		// db/dw/dd/dq store the data in the operand and register fields
+		if is_declare_data(code) {
+			return true;
+		}
+		for &shift in &[0, OpKindFlags::OP1_KIND_SHIFT, OpKindFlags::OP2_KIND_SHIFT, OpKindFlags::OP3_KIND_SHIFT] {
+			if ((instruction.op_kind_flags >> shift) & OpKindFlags::OP_KIND_MASK) as usize >= IcedConstants::NUMBER_OF_OP_KINDS {
+				return false;
+			}
+		}
+		[instruction.mem_base_reg, instruction.mem_index_reg, instruction.reg0, instruction.reg1, instruction.reg2, instruction.reg3]
+			.iter()
+			.all(|&reg| (reg as usize) < IcedConstants::NUMBER_OF_REGISTERS)
+	}
+
+	fn invalid_data() -> String {
+		String::from("Invalid instruction stream data")
+	}
+
+	fn read_u8(&mut self) -> Result<u8, String> {
+		match self.data.get(self.position) {
+			Some(&b) => {
+				self.position += 1;
+				Ok(b)
+			}
+			None => Err(String::from("Unexpected end of instruction stream")),
+		}
+	}
+
+	fn read_varint(&mut self) -> Result<u64, String> {
+		let mut result = 0u64;
+		let mut shift = 0;
+		loop {
+			let b = self.read_u8()?;
+			if shift == 63 && b > 1 {
+				return Err(Self::invalid_data());
+			}
+			result |= ((b & 0x7F) as u64) << shift;
+			if (b & 0x80) == 0 {
+				return Ok(result);
+			}
+			shift += 7;
+		}
+	}
+
+	fn read_varint_u32(&mut self) -> Result<u32, String> {
+		let value = self.read_varint()?;
+		if value > u32::MAX as u64 {
+			return Err(Self::invalid_data());
+		}
+		Ok(value as u32)
+	}
+}
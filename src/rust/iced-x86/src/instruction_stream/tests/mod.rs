@@ -0,0 +1,164 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(feature = "decoder")]
+use super::super::decoder::tests::test_utils::decoder_tests;
+#[cfg(feature = "encoder")]
+use super::super::encoder::tests::non_decoded_tests;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::create_decoder;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn round_trip(instructions: &[Instruction]) -> Vec<u8> {
+	let mut writer = InstructionStreamWriter::new();
+	for instruction in instructions {
+		writer.write(instruction);
+	}
+	let data = writer.take_buffer();
+	let mut reader = InstructionStreamReader::new(&data).unwrap();
+	for instruction in instructions {
+		let instruction2 = reader.read().unwrap();
+		assert!(instruction.eq_all_bits(&instruction2), "{:?} {:?}", instruction, instruction2);
+	}
+	assert!(!reader.can_read());
+	assert!(reader.read().is_err());
+	data
+}
+
+#[test]
+#[cfg(feature = "decoder")]
+fn decoded_instructions_round_trip() {
+	let mut instructions = Vec::new();
+	for info in decoder_tests(true, true) {
+		let bytes = to_vec_u8(info.hex_bytes()).unwrap();
+		let mut decoder = create_decoder(info.bitness(), &bytes, info.decoder_options()).0;
+		decoder.set_ip(0xFFFF_FFFF_FFFF_FFF0 - instructions.len() as u64);
+		instructions.push(decoder.decode());
+	}
+	let _ = round_trip(&instructions);
+	for instruction in &instructions {
+		let _ = round_trip(&[*instruction]);
+	}
+}
+
+#[test]
+#[cfg(feature = "encoder")]
+fn non_decoded_instructions_round_trip() {
+	let instructions: Vec<Instruction> = non_decoded_tests::get_tests().iter().map(|t| t.2).collect();
+	let _ = round_trip(&instructions);
+}
+
+#[test]
+#[cfg(feature = "decoder")]
+fn sequential_instructions_are_compact() {
+	// push rbp / mov rbp,rsp / push rbp / mov rbp,rsp / nop
+	let bytes = b"\x55\x48\x89\xE5\x55\x48\x89\xE5\x90";
+	let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	decoder.set_ip(0x1000);
+	let instructions: Vec<Instruction> = decoder.into_iter().collect();
+	let _ = round_trip(&instructions);
+	let mut writer = InstructionStreamWriter::new();
+	let mut sizes = Vec::new();
+	for instruction in &instructions {
+		let old_len = writer.buffer().len();
+		writer.write(instruction);
+		sizes.push(writer.buffer().len() - old_len);
+	}
+	// Repeated instructions only need a tag, a field mask and the registers
+	assert_eq!(sizes[2], 3);
+	assert_eq!(sizes[3], 4);
+	// nop has a new shape (tag + two varints) but no other fields
+	assert_eq!(sizes[4], 1 + 3 + 5);
+}
+
+#[test]
+fn chunks_can_be_concatenated() {
+	let mut instructions = Vec::new();
+	for i in 0..100 {
+		let mut instruction = Instruction::with_reg_u32(Code::Add_rm32_imm32, Register::EAX, i * 3);
+		instruction.set_len(5);
+		instruction.set_next_ip(0x1000 + (i as u64 + 1) * 5);
+		instructions.push(instruction);
+	}
+	let mut writer = InstructionStreamWriter::new();
+	let mut data = Vec::new();
+	for chunk in instructions.chunks(7) {
+		for instruction in chunk {
+			writer.write(instruction);
+		}
+		data.extend(writer.take_buffer());
+	}
+	let mut reader = InstructionStreamReader::new(&data).unwrap();
+	for instruction in &instructions {
+		assert!(instruction.eq_all_bits(&reader.read().unwrap()));
+	}
+	assert!(!reader.can_read());
+}
+
+// Returns a stream with a valid header followed by `data`
+fn stream(data: &[u8]) -> Vec<u8> {
+	let mut stream = InstructionStreamWriter::new().take_buffer();
+	stream.extend_from_slice(data);
+	stream
+}
+
+#[test]
+fn invalid_streams() {
+	assert_eq!(8, stream(b"").len());
+	assert!(InstructionStreamReader::new(b"").is_err());
+	assert!(InstructionStreamReader::new(b"IXS").is_err());
+	let mut data = stream(b"");
+	data[0] = b'T';
+	assert!(InstructionStreamReader::new(&data).is_err());
+	// Old or unknown version
+	assert!(InstructionStreamReader::new(b"IXS\x01").is_err());
+	let mut data = stream(b"");
+	data[3] = 3;
+	assert!(InstructionStreamReader::new(&data).is_err());
+	// Truncated header
+	assert!(InstructionStreamReader::new(&stream(b"")[..7]).is_err());
+	// Written by a version with different Code, Register, etc values
+	let mut data = stream(b"");
+	data[4] ^= 1;
+	assert!(InstructionStreamReader::new(&data).is_err());
+
+	// Empty stream
+	let data = stream(b"");
+	let mut reader = InstructionStreamReader::new(&data).unwrap();
+	assert!(!reader.can_read());
+	assert!(reader.read().is_err());
+
+	// Shape index isn't in the cache
+	assert!(InstructionStreamReader::new(&stream(b"\x00")).unwrap().read().is_err());
+	// Truncated varint
+	assert!(InstructionStreamReader::new(&stream(b"\x3F\x80")).unwrap().read().is_err());
+	// Invalid Code value
+	assert!(InstructionStreamReader::new(&stream(b"\x3F\xFF\x3F\x00")).unwrap().read().is_err());
+	// Invalid register
+	assert!(InstructionStreamReader::new(&stream(b"\xBF\x00\x00\x20\xFF")).unwrap().read().is_err());
+	assert!(InstructionStreamReader::new(&stream(b"\xBF\x00\x00\x20\x01")).unwrap().read().is_ok());
+}
@@ -0,0 +1,51 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+/// Returns a mask with the low `size` bytes set
+#[inline]
+pub(crate) fn mask(size: u32) -> u64 {
+	if size >= 8 {
+		u64::max_value()
+	} else {
+		(1u64 << (size * 8)) - 1
+	}
+}
+
+/// Sign extends the low `size` bytes of `value` to 64 bits
+#[inline]
+pub(crate) fn sign_extend(value: u64, size: u32) -> u64 {
+	let shift = 64 - size * 8;
+	(((value << shift) as i64) >> shift) as u64
+}
+
+/// Returns the high 64 bits of the unsigned 128-bit product
+pub(crate) fn mul_high(lhs: u64, rhs: u64) -> u64 {
+	let (a_lo, a_hi) = (lhs & 0xFFFF_FFFF, lhs >> 32);
+	let (b_lo, b_hi) = (rhs & 0xFFFF_FFFF, rhs >> 32);
+	let lo_lo = a_lo * b_lo;
+	let hi_lo = a_hi * b_lo;
+	let lo_hi = a_lo * b_hi;
+	let hi_hi = a_hi * b_hi;
+	let middle = (lo_lo >> 32) + (hi_lo & 0xFFFF_FFFF) + (lo_hi & 0xFFFF_FFFF);
+	hi_hi + (hi_lo >> 32) + (lo_hi >> 32) + (middle >> 32)
+}
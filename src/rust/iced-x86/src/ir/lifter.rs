@@ -0,0 +1,1279 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::instruction_internal::get_address_size_in_bytes;
+use super::super::int_utils::mask;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Lifts decoded instructions to IR, see [`IrBlock`] and [`IrStmt`]
+///
+/// The integer, flag, string (incl. `REP` prefixes) and SSE scalar instructions are supported. All other
+/// instructions (and far branches, VSIB memory operands etc.) return an error.
+///
+/// [`IrBlock`]: struct.IrBlock.html
+/// [`IrStmt`]: enum.IrStmt.html
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// // add rax,[rcx+10h]
+/// let bytes = b"\x48\x03\x41\x10";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instr = decoder.decode();
+///
+/// let mut lifter = IrLifter::new(64);
+/// let block = lifter.lift(&instr).unwrap();
+/// assert!(block.statements().iter().any(|stmt| match stmt {
+///     &IrStmt::Load { size, .. } => size == 8,
+///     _ => false,
+/// }));
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IrLifter {
+	bitness: u32,
+	info_factory: InstructionInfoFactory,
+}
+
+impl IrLifter {
+	/// Creates a lifter
+	///
+	/// # Panics
+	///
+	/// Panics if `bitness` is not one of 16, 32, 64.
+	///
+	/// # Arguments
+	///
+	/// * `bitness`: 16, 32 or 64
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new(bitness: u32) -> Self {
+		match bitness {
+			16 | 32 | 64 => {}
+			_ => panic!(),
+		}
+		Self { bitness, info_factory: InstructionInfoFactory::new() }
+	}
+
+	/// Gets the bitness (16, 32 or 64)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn bitness(&self) -> u32 {
+		self.bitness
+	}
+
+	/// Lifts an instruction
+	///
+	/// # Errors
+	///
+	/// Fails if the instruction (or one of its operands) isn't supported
+	///
+	/// # Arguments
+	///
+	/// * `instruction`: Instruction
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn lift(&mut self, instruction: &Instruction) -> Result<IrBlock, String> {
+		let info = self.info_factory.info_options(instruction, InstructionInfoOptions::NO_MEMORY_USAGE | InstructionInfoOptions::NO_REGISTER_USAGE);
+		let mut b = Builder {
+			instruction,
+			info,
+			bitness: self.bitness,
+			block: IrBlock { ip: instruction.ip(), next_ip: instruction.next_ip(), statements: Vec::new(), temp_sizes: Vec::new() },
+		};
+		b.lift()?;
+		Ok(b.block)
+	}
+}
+
+struct Builder<'a> {
+	instruction: &'a Instruction,
+	info: &'a InstructionInfo,
+	bitness: u32,
+	block: IrBlock,
+}
+
+impl<'a> Builder<'a> {
+	fn unsupported(&self) -> Result<(), String> {
+		Err(format!("Unsupported instruction: {:?}", self.instruction.code()))
+	}
+
+	fn temp(&mut self, size: u32) -> IrTemp {
+		let temp = IrTemp(self.block.temp_sizes.len() as u32);
+		self.block.temp_sizes.push(size as u8);
+		temp
+	}
+
+	#[inline]
+	fn emit(&mut self, stmt: IrStmt) {
+		self.block.statements.push(stmt);
+	}
+
+	fn unary(&mut self, op: IrUnaryOp, src: IrValue, size: u32) -> IrValue {
+		let dst_size = match op {
+			IrUnaryOp::ZeroExtend(dst_size)
+			| IrUnaryOp::SignExtend(dst_size)
+			| IrUnaryOp::Truncate(dst_size)
+			| IrUnaryOp::FloatConvert(dst_size)
+			| IrUnaryOp::FloatToInt(dst_size, _)
+			| IrUnaryOp::IntToFloat(dst_size) => dst_size,
+			_ => size,
+		};
+		let dst = self.temp(dst_size);
+		self.emit(IrStmt::Unary { dst, op, src, size });
+		IrValue::Temp(dst)
+	}
+
+	fn binary(&mut self, op: IrBinaryOp, lhs: IrValue, rhs: IrValue, size: u32) -> IrValue {
+		let dst = self.temp(size);
+		self.emit(IrStmt::Binary { dst, op, lhs, rhs, size });
+		IrValue::Temp(dst)
+	}
+
+	fn compare(&mut self, op: IrCompareOp, lhs: IrValue, rhs: IrValue, size: u32) -> IrValue {
+		let dst = self.temp(1);
+		self.emit(IrStmt::Compare { dst, op, lhs, rhs, size });
+		IrValue::Temp(dst)
+	}
+
+	fn select(&mut self, condition: IrValue, if_true: IrValue, if_false: IrValue, size: u32) -> IrValue {
+		let dst = self.temp(size);
+		self.emit(IrStmt::Select { dst, condition, if_true, if_false, size });
+		IrValue::Temp(dst)
+	}
+
+	fn resize(&mut self, value: IrValue, size: u32, new_size: u32) -> IrValue {
+		if size == new_size {
+			value
+		} else if let IrValue::Const(value) = value {
+			IrValue::Const(value & mask(new_size))
+		} else if new_size < size {
+			self.unary(IrUnaryOp::Truncate(new_size), value, size)
+		} else {
+			self.unary(IrUnaryOp::ZeroExtend(new_size), value, size)
+		}
+	}
+
+	fn read_reg_size(&mut self, register: Register, size: u32) -> IrValue {
+		let dst = self.temp(size);
+		self.emit(IrStmt::ReadReg { dst, register, size });
+		IrValue::Temp(dst)
+	}
+
+	fn read_reg(&mut self, register: Register) -> IrValue {
+		self.read_reg_size(register, register.size() as u32)
+	}
+
+	fn write_reg_size(&mut self, register: Register, size: u32, src: IrValue) {
+		self.emit(IrStmt::WriteReg { register, size, src });
+	}
+
+	// Writes a register, a 32-bit GPR write in 64-bit mode zero extends the value to 64 bits
+	fn write_reg(&mut self, register: Register, src: IrValue) {
+		if self.bitness == 64 && register.is_gpr32() {
+			let value = self.resize(src, 4, 8);
+			self.write_reg_size(register.full_register(), 8, value);
+		} else {
+			let size = register.size() as u32;
+			self.write_reg_size(register, size, src);
+		}
+	}
+
+	fn read_flag(&mut self, flag: u32) -> IrValue {
+		let dst = self.temp(1);
+		self.emit(IrStmt::ReadFlag { dst, flag });
+		IrValue::Temp(dst)
+	}
+
+	fn load(&mut self, segment: Register, address: IrValue, size: u32) -> IrValue {
+		let dst = self.temp(size);
+		self.emit(IrStmt::Load { dst, segment, address, size });
+		IrValue::Temp(dst)
+	}
+
+	fn store(&mut self, segment: Register, address: IrValue, size: u32, src: IrValue) {
+		self.emit(IrStmt::Store { segment, address, size, src });
+	}
+
+	// Written flags are computed by a `SetFlags` statement, cleared and set flags are written with `WriteFlag`
+	fn set_flags(&mut self, kind: IrFlagsKind, size: u32, lhs: IrValue, rhs: IrValue, carry: IrValue, result: IrValue) {
+		let flags = self.instruction.rflags_written();
+		if flags != 0 {
+			self.emit(IrStmt::SetFlags { flags, kind, size, lhs, rhs, carry, result });
+		}
+		self.write_cleared_set_undefined_flags();
+	}
+
+	fn write_cleared_set_undefined_flags(&mut self) {
+		for &(flags, value) in &[(self.instruction.rflags_cleared(), 0), (self.instruction.rflags_set(), 1)] {
+			let mut flags = flags;
+			while flags != 0 {
+				let flag = flags & flags.wrapping_neg();
+				flags &= !flag;
+				self.emit(IrStmt::WriteFlag { flag, src: IrValue::Const(value) });
+			}
+		}
+		let undefined = self.instruction.rflags_undefined();
+		if undefined != 0 {
+			self.emit(IrStmt::UndefinedFlags { flags: undefined });
+		}
+	}
+
+	fn branch(&mut self, kind: IrBranchKind, condition: Option<IrValue>, target: IrValue) {
+		self.emit(IrStmt::Branch { kind, condition, target });
+	}
+
+	fn stack_pointer(&self) -> (Register, u32) {
+		match self.bitness {
+			64 => (Register::RSP, 8),
+			32 => (Register::ESP, 4),
+			_ => (Register::SP, 2),
+		}
+	}
+
+	fn push(&mut self, value: IrValue, size: u32) {
+		let (sp, sp_size) = self.stack_pointer();
+		let old_sp = self.read_reg(sp);
+		let new_sp = self.binary(IrBinaryOp::Sub, old_sp, IrValue::Const(size as u64), sp_size);
+		self.store(Register::SS, new_sp, size, value);
+		self.write_reg(sp, new_sp);
+	}
+
+	fn pop(&mut self, size: u32) -> IrValue {
+		let (sp, sp_size) = self.stack_pointer();
+		let old_sp = self.read_reg(sp);
+		let value = self.load(Register::SS, old_sp, size);
+		let new_sp = self.binary(IrBinaryOp::Add, old_sp, IrValue::Const(size as u64), sp_size);
+		self.write_reg(sp, new_sp);
+		value
+	}
+
+	// Same as `Instruction::virtual_address()` but without the segment base
+	fn memory_address(&mut self) -> Result<IrValue, String> {
+		let instruction = self.instruction;
+		if instruction.is_ip_rel_memory_operand() {
+			return Ok(IrValue::Const(instruction.ip_rel_memory_address()));
+		}
+		let base = instruction.memory_base();
+		let index = instruction.memory_index();
+		if index.is_vector_register() {
+			return Err(String::from("VSIB memory operands aren't supported"));
+		}
+		let addr_size = get_address_size_in_bytes(base, index, instruction.memory_displ_size(), instruction.code_size());
+		let mut address = None;
+		if base != Register::None {
+			address = Some(self.read_reg(base));
+		}
+		if index != Register::None {
+			let mut value = self.read_reg(index);
+			let scale = instruction.memory_index_scale();
+			if scale != 1 {
+				value = self.binary(IrBinaryOp::Mul, value, IrValue::Const(scale as u64), addr_size);
+			}
+			address = Some(match address {
+				Some(address) => self.binary(IrBinaryOp::Add, address, value, addr_size),
+				None => value,
+			});
+		}
+		let displ = IrValue::Const(instruction.memory_displacement64() & mask(addr_size));
+		Ok(match address {
+			Some(address) if displ != IrValue::Const(0) => self.binary(IrBinaryOp::Add, address, displ, addr_size),
+			Some(address) => address,
+			None => displ,
+		})
+	}
+
+	fn op_size(&self, operand: u32) -> u32 {
+		match self.instruction.op_kind(operand) {
+			OpKind::Register => self.instruction.op_register(operand).size() as u32,
+			OpKind::Immediate8 | OpKind::Immediate8_2nd => 1,
+			OpKind::Immediate16 => 2,
+			OpKind::Immediate32 => 4,
+			OpKind::Immediate64 => 8,
+			OpKind::Immediate8to16 => 2,
+			OpKind::Immediate8to32 => 4,
+			OpKind::Immediate8to64 | OpKind::Immediate32to64 => 8,
+			_ => self.instruction.memory_size().size() as u32,
+		}
+	}
+
+	// Reads an operand. Immediates are sign/zero extended to `size` bytes.
+	fn read_op(&mut self, operand: u32, size: u32) -> Result<IrValue, String> {
+		let instruction = self.instruction;
+		Ok(match instruction.op_kind(operand) {
+			OpKind::Register => {
+				let register = instruction.op_register(operand);
+				self.read_reg_size(register, size)
+			}
+			OpKind::Immediate8
+			| OpKind::Immediate8_2nd
+			| OpKind::Immediate16
+			| OpKind::Immediate32
+			| OpKind::Immediate64
+			| OpKind::Immediate8to16
+			| OpKind::Immediate8to32
+			| OpKind::Immediate8to64
+			| OpKind::Immediate32to64 => IrValue::Const(instruction.immediate(operand) & mask(size)),
+			OpKind::Memory => {
+				let address = self.memory_address()?;
+				self.load(instruction.memory_segment(), address, size)
+			}
+			OpKind::Memory64 => self.load(instruction.memory_segment(), IrValue::Const(instruction.memory_address64()), size),
+			_ => return Err(format!("Unsupported operand: {:?}", instruction.op_kind(operand))),
+		})
+	}
+
+	fn write_op(&mut self, operand: u32, value: IrValue, size: u32) -> Result<(), String> {
+		let instruction = self.instruction;
+		match instruction.op_kind(operand) {
+			OpKind::Register => {
+				let register = instruction.op_register(operand);
+				if register.is_xmm() || register.is_mm() {
+					self.write_reg_size(register, size, value);
+				} else {
+					self.write_reg(register, value);
+				}
+			}
+			OpKind::Memory => {
+				let address = self.memory_address()?;
+				self.store(instruction.memory_segment(), address, size, value);
+			}
+			OpKind::Memory64 => self.store(instruction.memory_segment(), IrValue::Const(instruction.memory_address64()), size, value),
+			_ => return Err(format!("Unsupported operand: {:?}", instruction.op_kind(operand))),
+		}
+		Ok(())
+	}
+
+	// Reads and writes the same memory operand without computing the address twice
+	fn read_modify_write<F>(&mut self, operand: u32, size: u32, f: F) -> Result<(), String>
+	where
+		F: FnOnce(&mut Self, IrValue) -> Result<Option<IrValue>, String>,
+	{
+		let instruction = self.instruction;
+		match instruction.op_kind(operand) {
+			OpKind::Memory | OpKind::Memory64 => {
+				let address = if instruction.op_kind(operand) == OpKind::Memory {
+					self.memory_address()?
+				} else {
+					IrValue::Const(instruction.memory_address64())
+				};
+				let segment = instruction.memory_segment();
+				let value = self.load(segment, address, size);
+				if let Some(result) = f(self, value)? {
+					self.store(segment, address, size, result);
+				}
+				Ok(())
+			}
+			_ => {
+				let value = self.read_op(operand, size)?;
+				if let Some(result) = f(self, value)? {
+					self.write_op(operand, result, size)?;
+				}
+				Ok(())
+			}
+		}
+	}
+
+	fn condition(&mut self, cc: ConditionCode) -> IrValue {
+		let (flag_value, negate) = match cc {
+			ConditionCode::o | ConditionCode::no => (self.read_flag(RflagsBits::OF), cc == ConditionCode::no),
+			ConditionCode::b | ConditionCode::ae => (self.read_flag(RflagsBits::CF), cc == ConditionCode::ae),
+			ConditionCode::e | ConditionCode::ne => (self.read_flag(RflagsBits::ZF), cc == ConditionCode::ne),
+			ConditionCode::be | ConditionCode::a => {
+				let cf = self.read_flag(RflagsBits::CF);
+				let zf = self.read_flag(RflagsBits::ZF);
+				(self.binary(IrBinaryOp::Or, cf, zf, 1), cc == ConditionCode::a)
+			}
+			ConditionCode::s | ConditionCode::ns => (self.read_flag(RflagsBits::SF), cc == ConditionCode::ns),
+			ConditionCode::p | ConditionCode::np => (self.read_flag(RflagsBits::PF), cc == ConditionCode::np),
+			ConditionCode::l | ConditionCode::ge => {
+				let sf = self.read_flag(RflagsBits::SF);
+				let of = self.read_flag(RflagsBits::OF);
+				(self.binary(IrBinaryOp::Xor, sf, of, 1), cc == ConditionCode::ge)
+			}
+			ConditionCode::le | ConditionCode::g => {
+				let sf = self.read_flag(RflagsBits::SF);
+				let of = self.read_flag(RflagsBits::OF);
+				let zf = self.read_flag(RflagsBits::ZF);
+				let lt = self.binary(IrBinaryOp::Xor, sf, of, 1);
+				(self.binary(IrBinaryOp::Or, lt, zf, 1), cc == ConditionCode::g)
+			}
+			ConditionCode::None => (IrValue::Const(1), false),
+		};
+		if negate {
+			self.binary(IrBinaryOp::Xor, flag_value, IrValue::Const(1), 1)
+		} else {
+			flag_value
+		}
+	}
+
+	fn is_written(&self, operand: u32) -> bool {
+		match self.info.op_access(operand) {
+			OpAccess::Write | OpAccess::CondWrite | OpAccess::ReadWrite | OpAccess::ReadCondWrite => true,
+			_ => false,
+		}
+	}
+
+	fn lift(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		match instruction.mnemonic() {
+			Mnemonic::Nop
+			| Mnemonic::Pause
+			| Mnemonic::Lfence
+			| Mnemonic::Sfence
+			| Mnemonic::Mfence
+			| Mnemonic::Prefetchnta
+			| Mnemonic::Prefetcht0
+			| Mnemonic::Prefetcht1
+			| Mnemonic::Prefetcht2
+			| Mnemonic::Prefetchw
+			| Mnemonic::Endbr32
+			| Mnemonic::Endbr64 => Ok(()),
+			Mnemonic::Mov => self.lift_mov(),
+			Mnemonic::Movzx | Mnemonic::Movsx | Mnemonic::Movsxd => self.lift_movzx_movsx(),
+			Mnemonic::Lea => self.lift_lea(),
+			Mnemonic::Xchg => self.lift_xchg(),
+			Mnemonic::Add
+			| Mnemonic::Adc
+			| Mnemonic::Sub
+			| Mnemonic::Sbb
+			| Mnemonic::Cmp
+			| Mnemonic::And
+			| Mnemonic::Or
+			| Mnemonic::Xor
+			| Mnemonic::Test => self.lift_alu(),
+			Mnemonic::Not | Mnemonic::Neg | Mnemonic::Inc | Mnemonic::Dec => self.lift_unary(),
+			Mnemonic::Shl | Mnemonic::Sal | Mnemonic::Shr | Mnemonic::Sar | Mnemonic::Rol | Mnemonic::Ror => self.lift_shift(),
+			Mnemonic::Mul | Mnemonic::Imul => self.lift_mul(),
+			Mnemonic::Div | Mnemonic::Idiv => self.lift_div(),
+			Mnemonic::Xadd => self.lift_xadd(),
+			Mnemonic::Cmpxchg => self.lift_cmpxchg(),
+			Mnemonic::Push => self.lift_push(),
+			Mnemonic::Pop => self.lift_pop(),
+			Mnemonic::Leave => self.lift_leave(),
+			Mnemonic::Call | Mnemonic::Jmp | Mnemonic::Ret => self.lift_branch(),
+			Mnemonic::Jo
+			| Mnemonic::Jno
+			| Mnemonic::Jb
+			| Mnemonic::Jae
+			| Mnemonic::Je
+			| Mnemonic::Jne
+			| Mnemonic::Jbe
+			| Mnemonic::Ja
+			| Mnemonic::Js
+			| Mnemonic::Jns
+			| Mnemonic::Jp
+			| Mnemonic::Jnp
+			| Mnemonic::Jl
+			| Mnemonic::Jge
+			| Mnemonic::Jle
+			| Mnemonic::Jg => {
+				let condition = self.condition(instruction.condition_code());
+				self.branch(IrBranchKind::Jump, Some(condition), IrValue::Const(instruction.near_branch_target()));
+				Ok(())
+			}
+			Mnemonic::Jcxz | Mnemonic::Jecxz | Mnemonic::Jrcxz | Mnemonic::Loop | Mnemonic::Loope | Mnemonic::Loopne => self.lift_loop(),
+			Mnemonic::Seto
+			| Mnemonic::Setno
+			| Mnemonic::Setb
+			| Mnemonic::Setae
+			| Mnemonic::Sete
+			| Mnemonic::Setne
+			| Mnemonic::Setbe
+			| Mnemonic::Seta
+			| Mnemonic::Sets
+			| Mnemonic::Setns
+			| Mnemonic::Setp
+			| Mnemonic::Setnp
+			| Mnemonic::Setl
+			| Mnemonic::Setge
+			| Mnemonic::Setle
+			| Mnemonic::Setg => {
+				let condition = self.condition(instruction.condition_code());
+				self.write_op(0, condition, 1)
+			}
+			Mnemonic::Cmovo
+			| Mnemonic::Cmovno
+			| Mnemonic::Cmovb
+			| Mnemonic::Cmovae
+			| Mnemonic::Cmove
+			| Mnemonic::Cmovne
+			| Mnemonic::Cmovbe
+			| Mnemonic::Cmova
+			| Mnemonic::Cmovs
+			| Mnemonic::Cmovns
+			| Mnemonic::Cmovp
+			| Mnemonic::Cmovnp
+			| Mnemonic::Cmovl
+			| Mnemonic::Cmovge
+			| Mnemonic::Cmovle
+			| Mnemonic::Cmovg => {
+				let size = self.op_size(0);
+				let condition = self.condition(instruction.condition_code());
+				let src = self.read_op(1, size)?;
+				let dst = self.read_op(0, size)?;
+				let value = self.select(condition, src, dst, size);
+				self.write_op(0, value, size)
+			}
+			Mnemonic::Cbw | Mnemonic::Cwde | Mnemonic::Cdqe => {
+				let (dst, src) = match instruction.mnemonic() {
+					Mnemonic::Cbw => (Register::AX, Register::AL),
+					Mnemonic::Cwde => (Register::EAX, Register::AX),
+					_ => (Register::RAX, Register::EAX),
+				};
+				let value = self.read_reg(src);
+				let value = self.unary(IrUnaryOp::SignExtend(dst.size() as u32), value, src.size() as u32);
+				self.write_reg(dst, value);
+				Ok(())
+			}
+			Mnemonic::Cwd | Mnemonic::Cdq | Mnemonic::Cqo => {
+				let (dst, src) = match instruction.mnemonic() {
+					Mnemonic::Cwd => (Register::DX, Register::AX),
+					Mnemonic::Cdq => (Register::EDX, Register::EAX),
+					_ => (Register::RDX, Register::RAX),
+				};
+				let size = src.size() as u32;
+				let value = self.read_reg(src);
+				let value = self.binary(IrBinaryOp::Sar, value, IrValue::Const((size * 8 - 1) as u64), size);
+				self.write_reg(dst, value);
+				Ok(())
+			}
+			Mnemonic::Clc | Mnemonic::Stc | Mnemonic::Cld | Mnemonic::Std => {
+				let (flag, value) = match instruction.mnemonic() {
+					Mnemonic::Clc => (RflagsBits::CF, 0),
+					Mnemonic::Stc => (RflagsBits::CF, 1),
+					Mnemonic::Cld => (RflagsBits::DF, 0),
+					_ => (RflagsBits::DF, 1),
+				};
+				self.emit(IrStmt::WriteFlag { flag, src: IrValue::Const(value) });
+				Ok(())
+			}
+			Mnemonic::Cmc => {
+				let cf = self.read_flag(RflagsBits::CF);
+				let cf = self.binary(IrBinaryOp::Xor, cf, IrValue::Const(1), 1);
+				self.emit(IrStmt::WriteFlag { flag: RflagsBits::CF, src: cf });
+				Ok(())
+			}
+			Mnemonic::Bswap => {
+				let size = self.op_size(0);
+				let value = self.read_op(0, size)?;
+				let value = self.unary(IrUnaryOp::ByteSwap, value, size);
+				self.write_op(0, value, size)
+			}
+			Mnemonic::Bsf | Mnemonic::Bsr | Mnemonic::Tzcnt | Mnemonic::Lzcnt | Mnemonic::Popcnt => self.lift_bit_count(),
+			Mnemonic::Bt => self.lift_bt(),
+			Mnemonic::Int | Mnemonic::Int1 => {
+				// INT3's mnemonic is also Mnemonic::Int
+				let vector = match instruction.code() {
+					Code::Int_imm8 => instruction.immediate8(),
+					Code::Int3 => 3,
+					Code::Int1 => 1,
+					_ => return self.unsupported(),
+				};
+				self.emit(IrStmt::Interrupt { vector, condition: None });
+				Ok(())
+			}
+			Mnemonic::Into => {
+				let of = self.read_flag(RflagsBits::OF);
+				self.emit(IrStmt::Interrupt { vector: 4, condition: Some(of) });
+				Ok(())
+			}
+			Mnemonic::Hlt => {
+				self.emit(IrStmt::Halt);
+				Ok(())
+			}
+			Mnemonic::Movsb
+			| Mnemonic::Movsw
+			| Mnemonic::Movsq
+			| Mnemonic::Stosb
+			| Mnemonic::Stosw
+			| Mnemonic::Stosd
+			| Mnemonic::Stosq
+			| Mnemonic::Lodsb
+			| Mnemonic::Lodsw
+			| Mnemonic::Lodsd
+			| Mnemonic::Lodsq
+			| Mnemonic::Cmpsb
+			| Mnemonic::Cmpsw
+			| Mnemonic::Cmpsq
+			| Mnemonic::Scasb
+			| Mnemonic::Scasw
+			| Mnemonic::Scasd
+			| Mnemonic::Scasq => self.lift_string(),
+			// Also used by SSE2 instructions
+			Mnemonic::Movsd | Mnemonic::Cmpsd if is_string_instruction(instruction) => self.lift_string(),
+			Mnemonic::Movss | Mnemonic::Movsd => self.lift_sse_mov_scalar(),
+			Mnemonic::Movd | Mnemonic::Movq => self.lift_movd_movq(),
+			Mnemonic::Addss
+			| Mnemonic::Addsd
+			| Mnemonic::Subss
+			| Mnemonic::Subsd
+			| Mnemonic::Mulss
+			| Mnemonic::Mulsd
+			| Mnemonic::Divss
+			| Mnemonic::Divsd
+			| Mnemonic::Minss
+			| Mnemonic::Minsd
+			| Mnemonic::Maxss
+			| Mnemonic::Maxsd => self.lift_sse_binary(),
+			Mnemonic::Sqrtss
+			| Mnemonic::Sqrtsd
+			| Mnemonic::Cvtss2sd
+			| Mnemonic::Cvtsd2ss
+			| Mnemonic::Cvtsi2ss
+			| Mnemonic::Cvtsi2sd
+			| Mnemonic::Cvtss2si
+			| Mnemonic::Cvtsd2si
+			| Mnemonic::Cvttss2si
+			| Mnemonic::Cvttsd2si => self.lift_sse_unary(),
+			Mnemonic::Comiss | Mnemonic::Comisd | Mnemonic::Ucomiss | Mnemonic::Ucomisd => {
+				let size = if instruction.mnemonic() == Mnemonic::Comiss || instruction.mnemonic() == Mnemonic::Ucomiss { 4 } else { 8 };
+				let lhs = self.read_op(0, size)?;
+				let rhs = self.read_op(1, size)?;
+				self.set_flags(IrFlagsKind::FloatCompare, size, lhs, rhs, IrValue::Const(0), IrValue::Const(0));
+				Ok(())
+			}
+			_ => self.unsupported(),
+		}
+	}
+
+	fn lift_mov(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		for &operand in &[0, 1] {
+			if instruction.op_kind(operand) == OpKind::Register {
+				let register = instruction.op_register(operand);
+				if register.is_cr() || register.is_dr() || register.is_tr() {
+					return self.unsupported();
+				}
+			}
+		}
+		let size = self.op_size(0);
+		let src_size = if instruction.op_kind(1) == OpKind::Register { self.op_size(1) } else { size };
+		let value = self.read_op(1, src_size)?;
+		let value = self.resize(value, src_size, size);
+		if instruction.op_kind(0) == OpKind::Register && instruction.op_register(0).is_segment_register() {
+			self.write_reg_size(instruction.op_register(0), 2, value);
+			Ok(())
+		} else {
+			self.write_op(0, value, size)
+		}
+	}
+
+	fn lift_movzx_movsx(&mut self) -> Result<(), String> {
+		let size = self.op_size(0);
+		let src_size = self.op_size(1);
+		let mut value = self.read_op(1, src_size)?;
+		if src_size < size {
+			let op = if self.instruction.mnemonic() == Mnemonic::Movzx { IrUnaryOp::ZeroExtend(size) } else { IrUnaryOp::SignExtend(size) };
+			value = self.unary(op, value, src_size);
+		}
+		self.write_op(0, value, size)
+	}
+
+	fn lift_lea(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		if instruction.op_kind(1) != OpKind::Memory {
+			return self.unsupported();
+		}
+		let size = self.op_size(0);
+		let addr_size = if instruction.is_ip_rel_memory_operand() {
+			8
+		} else {
+			get_address_size_in_bytes(instruction.memory_base(), instruction.memory_index(), instruction.memory_displ_size(), instruction.code_size())
+		};
+		let address = self.memory_address()?;
+		let value = self.resize(address, addr_size, size);
+		self.write_op(0, value, size)
+	}
+
+	fn lift_xchg(&mut self) -> Result<(), String> {
+		let size = self.op_size(0);
+		let value1 = self.read_op(1, size)?;
+		self.read_modify_write(0, size, |b, value0| {
+			b.write_op(1, value0, size)?;
+			Ok(Some(value1))
+		})
+	}
+
+	fn lift_alu(&mut self) -> Result<(), String> {
+		let mnemonic = self.instruction.mnemonic();
+		let size = self.op_size(0);
+		let rhs = self.read_op(1, size)?;
+		let is_written = self.is_written(0);
+		self.read_modify_write(0, size, |b, lhs| {
+			let (kind, result, carry) = match mnemonic {
+				Mnemonic::Add => (IrFlagsKind::Add, b.binary(IrBinaryOp::Add, lhs, rhs, size), IrValue::Const(0)),
+				Mnemonic::Sub | Mnemonic::Cmp => (IrFlagsKind::Sub, b.binary(IrBinaryOp::Sub, lhs, rhs, size), IrValue::Const(0)),
+				Mnemonic::Adc | Mnemonic::Sbb => {
+					let cf = b.read_flag(RflagsBits::CF);
+					let cf_ext = b.resize(cf, 1, size);
+					if mnemonic == Mnemonic::Adc {
+						let value = b.binary(IrBinaryOp::Add, lhs, rhs, size);
+						(IrFlagsKind::Add, b.binary(IrBinaryOp::Add, value, cf_ext, size), cf)
+					} else {
+						let value = b.binary(IrBinaryOp::Sub, lhs, rhs, size);
+						(IrFlagsKind::Sub, b.binary(IrBinaryOp::Sub, value, cf_ext, size), cf)
+					}
+				}
+				Mnemonic::And | Mnemonic::Test => (IrFlagsKind::Logic, b.binary(IrBinaryOp::And, lhs, rhs, size), IrValue::Const(0)),
+				Mnemonic::Or => (IrFlagsKind::Logic, b.binary(IrBinaryOp::Or, lhs, rhs, size), IrValue::Const(0)),
+				_ => (IrFlagsKind::Logic, b.binary(IrBinaryOp::Xor, lhs, rhs, size), IrValue::Const(0)),
+			};
+			b.set_flags(kind, size, lhs, rhs, carry, result);
+			Ok(if is_written { Some(result) } else { None })
+		})
+	}
+
+	fn lift_unary(&mut self) -> Result<(), String> {
+		let mnemonic = self.instruction.mnemonic();
+		let size = self.op_size(0);
+		self.read_modify_write(0, size, |b, value| {
+			let result = match mnemonic {
+				Mnemonic::Not => b.unary(IrUnaryOp::Not, value, size),
+				Mnemonic::Neg => {
+					let result = b.unary(IrUnaryOp::Neg, value, size);
+					b.set_flags(IrFlagsKind::Neg, size, value, IrValue::Const(0), IrValue::Const(0), result);
+					result
+				}
+				Mnemonic::Inc => {
+					let result = b.binary(IrBinaryOp::Add, value, IrValue::Const(1), size);
+					b.set_flags(IrFlagsKind::Inc, size, value, IrValue::Const(1), IrValue::Const(0), result);
+					result
+				}
+				_ => {
+					let result = b.binary(IrBinaryOp::Sub, value, IrValue::Const(1), size);
+					b.set_flags(IrFlagsKind::Dec, size, value, IrValue::Const(1), IrValue::Const(0), result);
+					result
+				}
+			};
+			Ok(Some(result))
+		})
+	}
+
+	fn lift_shift(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = self.op_size(0);
+		let count_mask = if size == 8 { 0x3F } else { 0x1F };
+		let count = if instruction.op_count() < 2 {
+			IrValue::Const(1)
+		} else if instruction.op_kind(1) == OpKind::Register {
+			let count = self.read_op(1, 1)?;
+			let count = self.resize(count, 1, size);
+			self.binary(IrBinaryOp::And, count, IrValue::Const(count_mask), size)
+		} else {
+			IrValue::Const(instruction.immediate(1) & count_mask)
+		};
+		let (op, kind) = match instruction.mnemonic() {
+			Mnemonic::Shl | Mnemonic::Sal => (IrBinaryOp::Shl, IrFlagsKind::Shl),
+			Mnemonic::Shr => (IrBinaryOp::Shr, IrFlagsKind::Shr),
+			Mnemonic::Sar => (IrBinaryOp::Sar, IrFlagsKind::Sar),
+			Mnemonic::Rol => (IrBinaryOp::Rol, IrFlagsKind::Rol),
+			_ => (IrBinaryOp::Ror, IrFlagsKind::Ror),
+		};
+		self.read_modify_write(0, size, |b, value| {
+			let result = b.binary(op, value, count, size);
+			b.set_flags(kind, size, value, count, IrValue::Const(0), result);
+			Ok(Some(result))
+		})
+	}
+
+	fn accumulator(size: u32) -> (Register, Register) {
+		match size {
+			1 => (Register::AL, Register::AH),
+			2 => (Register::AX, Register::DX),
+			4 => (Register::EAX, Register::EDX),
+			_ => (Register::RAX, Register::RDX),
+		}
+	}
+
+	fn lift_mul(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let (high_op, kind) = if instruction.mnemonic() == Mnemonic::Mul {
+			(IrBinaryOp::UMulHigh, IrFlagsKind::Mul)
+		} else {
+			(IrBinaryOp::SMulHigh, IrFlagsKind::Imul)
+		};
+		let size = self.op_size(0);
+		if instruction.op_count() == 1 {
+			let (low_reg, high_reg) = Builder::accumulator(size);
+			let src = self.read_op(0, size)?;
+			let acc = self.read_reg(low_reg);
+			let low = self.binary(IrBinaryOp::Mul, acc, src, size);
+			let high = self.binary(high_op, acc, src, size);
+			self.set_flags(kind, size, acc, src, IrValue::Const(0), low);
+			if size == 1 {
+				let low = self.resize(low, 1, 2);
+				let high = self.resize(high, 1, 2);
+				let high = self.binary(IrBinaryOp::Shl, high, IrValue::Const(8), 2);
+				let value = self.binary(IrBinaryOp::Or, high, low, 2);
+				self.write_reg(Register::AX, value);
+			} else {
+				self.write_reg(low_reg, low);
+				self.write_reg(high_reg, high);
+			}
+			Ok(())
+		} else {
+			let (src1, src2) = if instruction.op_count() == 2 {
+				(self.read_op(0, size)?, self.read_op(1, size)?)
+			} else {
+				(self.read_op(1, size)?, self.read_op(2, size)?)
+			};
+			let low = self.binary(IrBinaryOp::Mul, src1, src2, size);
+			self.set_flags(kind, size, src1, src2, IrValue::Const(0), low);
+			self.write_op(0, low, size)
+		}
+	}
+
+	fn lift_div(&mut self) -> Result<(), String> {
+		let size = self.op_size(0);
+		let (low_reg, high_reg) = Builder::accumulator(size);
+		let divisor = self.read_op(0, size)?;
+		let high = self.read_reg(high_reg);
+		let low = self.read_reg(low_reg);
+		let quotient = self.temp(size);
+		let remainder = self.temp(size);
+		let signed = self.instruction.mnemonic() == Mnemonic::Idiv;
+		self.emit(IrStmt::Divide { quotient, remainder, high, low, divisor, size, signed });
+		self.write_cleared_set_undefined_flags();
+		self.write_reg(low_reg, IrValue::Temp(quotient));
+		self.write_reg(high_reg, IrValue::Temp(remainder));
+		Ok(())
+	}
+
+	fn lift_xadd(&mut self) -> Result<(), String> {
+		let size = self.op_size(0);
+		let src = self.read_op(1, size)?;
+		self.read_modify_write(0, size, |b, dst| {
+			let result = b.binary(IrBinaryOp::Add, dst, src, size);
+			b.set_flags(IrFlagsKind::Add, size, dst, src, IrValue::Const(0), result);
+			b.write_op(1, dst, size)?;
+			Ok(Some(result))
+		})
+	}
+
+	fn lift_cmpxchg(&mut self) -> Result<(), String> {
+		let size = self.op_size(0);
+		let (acc_reg, _) = Builder::accumulator(size);
+		let src = self.read_op(1, size)?;
+		let bitness = self.bitness;
+		self.read_modify_write(0, size, |b, dst| {
+			let acc = b.read_reg(acc_reg);
+			let result = b.binary(IrBinaryOp::Sub, acc, dst, size);
+			b.set_flags(IrFlagsKind::Sub, size, acc, dst, IrValue::Const(0), result);
+			let equal = b.compare(IrCompareOp::Eq, acc, dst, size);
+			if bitness == 64 && size == 4 {
+				// RAX isn't written if the values are equal
+				let rax = b.read_reg(Register::RAX);
+				let dst64 = b.resize(dst, 4, 8);
+				let value = b.select(equal, rax, dst64, 8);
+				b.write_reg(Register::RAX, value);
+			} else {
+				let value = b.select(equal, acc, dst, size);
+				b.write_reg(acc_reg, value);
+			}
+			Ok(Some(b.select(equal, src, dst, size)))
+		})
+	}
+
+	fn lift_push(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = instruction.stack_pointer_increment().wrapping_neg() as u32;
+		let value = if instruction.op_kind(0) == OpKind::Register && instruction.op_register(0).is_segment_register() {
+			let value = self.read_reg(instruction.op_register(0));
+			self.resize(value, 2, size)
+		} else {
+			self.read_op(0, size)?
+		};
+		self.push(value, size);
+		Ok(())
+	}
+
+	fn lift_pop(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = instruction.stack_pointer_increment() as u32;
+		let value = self.pop(size);
+		if instruction.op_kind(0) == OpKind::Register && instruction.op_register(0).is_segment_register() {
+			let value = self.resize(value, size, 2);
+			self.write_reg_size(instruction.op_register(0), 2, value);
+			Ok(())
+		} else {
+			self.write_op(0, value, size)
+		}
+	}
+
+	fn lift_leave(&mut self) -> Result<(), String> {
+		let bp = match self.instruction.code() {
+			Code::Leavew => Register::BP,
+			Code::Leaved => Register::EBP,
+			_ => Register::RBP,
+		};
+		let (sp, sp_size) = self.stack_pointer();
+		let sp_bp = match sp_size {
+			8 => Register::RBP,
+			4 => Register::EBP,
+			_ => Register::BP,
+		};
+		let value = self.read_reg(sp_bp);
+		self.write_reg(sp, value);
+		let value = self.pop(bp.size() as u32);
+		self.write_reg(bp, value);
+		Ok(())
+	}
+
+	fn lift_branch(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		if instruction.is_call_far() || instruction.is_call_far_indirect() || instruction.is_jmp_far() || instruction.is_jmp_far_indirect() {
+			return self.unsupported();
+		}
+		match instruction.mnemonic() {
+			Mnemonic::Ret => {
+				let size = match instruction.code() {
+					Code::Retnw | Code::Retnw_imm16 => 2,
+					Code::Retnd | Code::Retnd_imm16 => 4,
+					_ => 8,
+				};
+				let target = self.pop(size);
+				if instruction.op_count() == 1 {
+					let (sp, sp_size) = self.stack_pointer();
+					let value = self.read_reg(sp);
+					let value = self.binary(IrBinaryOp::Add, value, IrValue::Const(instruction.immediate16() as u64), sp_size);
+					self.write_reg(sp, value);
+				}
+				self.branch(IrBranchKind::Return, None, target);
+			}
+			mnemonic => {
+				let target = match instruction.op_kind(0) {
+					OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => IrValue::Const(instruction.near_branch_target()),
+					_ => {
+						let size = self.op_size(0);
+						self.read_op(0, size)?
+					}
+				};
+				if mnemonic == Mnemonic::Call {
+					let size = instruction.stack_pointer_increment().wrapping_neg() as u32;
+					self.push(IrValue::Const(instruction.next_ip() & mask(size)), size);
+					self.branch(IrBranchKind::Call, None, target);
+				} else {
+					self.branch(IrBranchKind::Jump, None, target);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn lift_loop(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let code = instruction.code() as u32;
+		let counter = match instruction.mnemonic() {
+			Mnemonic::Jcxz => Register::CX,
+			Mnemonic::Jecxz => Register::ECX,
+			Mnemonic::Jrcxz => Register::RCX,
+			mnemonic => {
+				let first = match mnemonic {
+					Mnemonic::Loopne => Code::Loopne_rel8_16_CX,
+					Mnemonic::Loope => Code::Loope_rel8_16_CX,
+					_ => Code::Loop_rel8_16_CX,
+				};
+				// Each mnemonic has 7 codes: 16_CX, 32_CX, 16_ECX, 32_ECX, 64_ECX, 16_RCX, 64_RCX
+				match code - first as u32 {
+					0 | 1 => Register::CX,
+					2 | 3 | 4 => Register::ECX,
+					_ => Register::RCX,
+				}
+			}
+		};
+		let size = counter.size() as u32;
+		let target = IrValue::Const(instruction.near_branch_target());
+		let count = self.read_reg(counter);
+		let condition = match instruction.mnemonic() {
+			Mnemonic::Jcxz | Mnemonic::Jecxz | Mnemonic::Jrcxz => self.compare(IrCompareOp::Eq, count, IrValue::Const(0), size),
+			mnemonic => {
+				let count = self.binary(IrBinaryOp::Sub, count, IrValue::Const(1), size);
+				self.write_reg(counter, count);
+				let not_zero = self.compare(IrCompareOp::Ne, count, IrValue::Const(0), size);
+				match mnemonic {
+					Mnemonic::Loope | Mnemonic::Loopne => {
+						let zf = self.condition(if mnemonic == Mnemonic::Loope { ConditionCode::e } else { ConditionCode::ne });
+						self.binary(IrBinaryOp::And, not_zero, zf, 1)
+					}
+					_ => not_zero,
+				}
+			}
+		};
+		self.branch(IrBranchKind::Jump, Some(condition), target);
+		Ok(())
+	}
+
+	fn lift_bit_count(&mut self) -> Result<(), String> {
+		let mnemonic = self.instruction.mnemonic();
+		let size = self.op_size(0);
+		let src = self.read_op(1, size)?;
+		let (result, kind) = match mnemonic {
+			Mnemonic::Bsf | Mnemonic::Tzcnt => (self.unary(IrUnaryOp::TrailingZeros, src, size), IrFlagsKind::Count),
+			Mnemonic::Lzcnt => (self.unary(IrUnaryOp::LeadingZeros, src, size), IrFlagsKind::Count),
+			Mnemonic::Bsr => {
+				let count = self.unary(IrUnaryOp::LeadingZeros, src, size);
+				(self.binary(IrBinaryOp::Sub, IrValue::Const((size * 8 - 1) as u64), count, size), IrFlagsKind::Count)
+			}
+			_ => (self.unary(IrUnaryOp::PopCount, src, size), IrFlagsKind::Count),
+		};
+		let (result, kind) = match mnemonic {
+			Mnemonic::Bsf | Mnemonic::Bsr => {
+				// The destination isn't written if the source is 0
+				let old_value = self.read_op(0, size)?;
+				let is_zero = self.compare(IrCompareOp::Eq, src, IrValue::Const(0), size);
+				(self.select(is_zero, old_value, result, size), IrFlagsKind::BitScan)
+			}
+			_ => (result, kind),
+		};
+		self.set_flags(kind, size, src, IrValue::Const(0), IrValue::Const(0), result);
+		self.write_op(0, result, size)
+	}
+
+	fn lift_bt(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = self.op_size(0);
+		let bit_mask = (size * 8 - 1) as u64;
+		let offset = match instruction.op_kind(1) {
+			OpKind::Register => {
+				// A register bit offset can select any bit in memory
+				if instruction.op_kind(0) != OpKind::Register {
+					return self.unsupported();
+				}
+				let offset = self.read_op(1, size)?;
+				self.binary(IrBinaryOp::And, offset, IrValue::Const(bit_mask), size)
+			}
+			_ => IrValue::Const(instruction.immediate(1) & bit_mask),
+		};
+		let value = self.read_op(0, size)?;
+		self.set_flags(IrFlagsKind::BitTest, size, value, offset, IrValue::Const(0), IrValue::Const(0));
+		Ok(())
+	}
+
+	fn lift_string(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let mnemonic = instruction.mnemonic();
+		let size = instruction.memory_size().size() as u32;
+		let mut src_index = Register::None;
+		let mut dst_index = Register::None;
+		for operand in 0..instruction.op_count() {
+			match instruction.op_kind(operand) {
+				OpKind::MemorySegSI => src_index = Register::SI,
+				OpKind::MemorySegESI => src_index = Register::ESI,
+				OpKind::MemorySegRSI => src_index = Register::RSI,
+				OpKind::MemoryESDI => dst_index = Register::DI,
+				OpKind::MemoryESEDI => dst_index = Register::EDI,
+				OpKind::MemoryESRDI => dst_index = Register::RDI,
+				_ => {}
+			}
+		}
+		let index_reg = if src_index != Register::None { src_index } else { dst_index };
+		let addr_size = index_reg.size() as u32;
+		let counter = match addr_size {
+			2 => Register::CX,
+			4 => Register::ECX,
+			_ => Register::RCX,
+		};
+		let (acc, _) = Builder::accumulator(size);
+		let is_compare = match mnemonic {
+			Mnemonic::Cmpsb
+			| Mnemonic::Cmpsw
+			| Mnemonic::Cmpsd
+			| Mnemonic::Cmpsq
+			| Mnemonic::Scasb
+			| Mnemonic::Scasw
+			| Mnemonic::Scasd
+			| Mnemonic::Scasq => true,
+			_ => false,
+		};
+		let has_rep = instruction.has_repe_prefix() || instruction.has_repne_prefix();
+
+		let mut count = IrValue::Const(0);
+		if has_rep {
+			count = self.read_reg(counter);
+			let is_zero = self.compare(IrCompareOp::Eq, count, IrValue::Const(0), addr_size);
+			self.branch(IrBranchKind::Jump, Some(is_zero), IrValue::Const(instruction.next_ip()));
+		}
+
+		let df = self.read_flag(RflagsBits::DF);
+		let delta = self.select(df, IrValue::Const((size as u64).wrapping_neg() & mask(addr_size)), IrValue::Const(size as u64), addr_size);
+		let src = if src_index != Register::None { Some(self.read_reg(src_index)) } else { None };
+		let dst = if dst_index != Register::None { Some(self.read_reg(dst_index)) } else { None };
+		match (src, dst) {
+			(Some(src), Some(dst)) => {
+				let value1 = self.load(instruction.memory_segment(), src, size);
+				if is_compare {
+					let value2 = self.load(Register::ES, dst, size);
+					let result = self.binary(IrBinaryOp::Sub, value1, value2, size);
+					self.set_flags(IrFlagsKind::Sub, size, value1, value2, IrValue::Const(0), result);
+				} else {
+					self.store(Register::ES, dst, size, value1);
+				}
+			}
+			(Some(src), None) => {
+				let value = self.load(instruction.memory_segment(), src, size);
+				self.write_reg(acc, value);
+			}
+			(None, Some(dst)) => {
+				let value1 = self.read_reg(acc);
+				if is_compare {
+					let value2 = self.load(Register::ES, dst, size);
+					let result = self.binary(IrBinaryOp::Sub, value1, value2, size);
+					self.set_flags(IrFlagsKind::Sub, size, value1, value2, IrValue::Const(0), result);
+				} else {
+					self.store(Register::ES, dst, size, value1);
+				}
+			}
+			(None, None) => return self.unsupported(),
+		}
+		if let Some(src) = src {
+			let value = self.binary(IrBinaryOp::Add, src, delta, addr_size);
+			self.write_reg(src_index, value);
+		}
+		if let Some(dst) = dst {
+			let value = self.binary(IrBinaryOp::Add, dst, delta, addr_size);
+			self.write_reg(dst_index, value);
+		}
+
+		if has_rep {
+			let count = self.binary(IrBinaryOp::Sub, count, IrValue::Const(1), addr_size);
+			self.write_reg(counter, count);
+			let mut condition = self.compare(IrCompareOp::Ne, count, IrValue::Const(0), addr_size);
+			if is_compare {
+				let zf = self.condition(if instruction.has_repe_prefix() { ConditionCode::e } else { ConditionCode::ne });
+				condition = self.binary(IrBinaryOp::And, condition, zf, 1);
+			}
+			// Execute the instruction again
+			self.branch(IrBranchKind::Jump, Some(condition), IrValue::Const(instruction.ip()));
+		}
+		Ok(())
+	}
+
+	fn scalar_size(&self) -> u32 {
+		match self.instruction.mnemonic() {
+			Mnemonic::Movss
+			| Mnemonic::Addss
+			| Mnemonic::Subss
+			| Mnemonic::Mulss
+			| Mnemonic::Divss
+			| Mnemonic::Minss
+			| Mnemonic::Maxss
+			| Mnemonic::Sqrtss
+			| Mnemonic::Cvtss2sd
+			| Mnemonic::Cvtsi2ss
+			| Mnemonic::Cvtss2si
+			| Mnemonic::Cvttss2si => 4,
+			_ => 8,
+		}
+	}
+
+	fn lift_sse_mov_scalar(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = self.scalar_size();
+		let value = self.read_op(1, size)?;
+		if instruction.op_kind(0) == OpKind::Register && instruction.op_kind(1) != OpKind::Register {
+			// Loads zero the upper bits
+			self.write_reg_size(instruction.op_register(0), 16, IrValue::Const(0));
+		}
+		self.write_op(0, value, size)
+	}
+
+	fn lift_movd_movq(&mut self) -> Result<(), String> {
+		let instruction = self.instruction;
+		let size = if instruction.mnemonic() == Mnemonic::Movd { 4 } else { 8 };
+		let value = self.read_op(1, size)?;
+		if instruction.op_kind(0) == OpKind::Register {
+			let register = instruction.op_register(0);
+			if register.is_xmm() {
+				self.write_reg_size(register, 16, IrValue::Const(0));
+			} else if register.is_mm() {
+				let value = self.resize(value, size, 8);
+				self.write_reg_size(register, 8, value);
+				return Ok(());
+			}
+		}
+		self.write_op(0, value, size)
+	}
+
+	fn lift_sse_binary(&mut self) -> Result<(), String> {
+		let size = self.scalar_size();
+		let op = match self.instruction.mnemonic() {
+			Mnemonic::Addss | Mnemonic::Addsd => IrBinaryOp::FloatAdd,
+			Mnemonic::Subss | Mnemonic::Subsd => IrBinaryOp::FloatSub,
+			Mnemonic::Mulss | Mnemonic::Mulsd => IrBinaryOp::FloatMul,
+			Mnemonic::Divss | Mnemonic::Divsd => IrBinaryOp::FloatDiv,
+			Mnemonic::Minss | Mnemonic::Minsd => IrBinaryOp::FloatMin,
+			_ => IrBinaryOp::FloatMax,
+		};
+		let lhs = self.read_op(0, size)?;
+		let rhs = self.read_op(1, size)?;
+		let result = self.binary(op, lhs, rhs, size);
+		self.write_op(0, result, size)
+	}
+
+	fn lift_sse_unary(&mut self) -> Result<(), String> {
+		let size = self.scalar_size();
+		match self.instruction.mnemonic() {
+			Mnemonic::Sqrtss | Mnemonic::Sqrtsd => {
+				let src = self.read_op(1, size)?;
+				let result = self.unary(IrUnaryOp::FloatSqrt, src, size);
+				self.write_op(0, result, size)
+			}
+			Mnemonic::Cvtss2sd | Mnemonic::Cvtsd2ss => {
+				let dst_size = 12 - size;
+				let src = self.read_op(1, size)?;
+				let result = self.unary(IrUnaryOp::FloatConvert(dst_size), src, size);
+				self.write_op(0, result, dst_size)
+			}
+			Mnemonic::Cvtsi2ss | Mnemonic::Cvtsi2sd => {
+				let int_size = self.op_size(1);
+				let src = self.read_op(1, int_size)?;
+				let result = self.unary(IrUnaryOp::IntToFloat(size), src, int_size);
+				self.write_op(0, result, size)
+			}
+			mnemonic => {
+				let int_size = self.op_size(0);
+				let truncate = mnemonic == Mnemonic::Cvttss2si || mnemonic == Mnemonic::Cvttsd2si;
+				let src = self.read_op(1, size)?;
+				let result = self.unary(IrUnaryOp::FloatToInt(int_size, truncate), src, size);
+				self.write_op(0, result, int_size)
+			}
+		}
+	}
+}
+
+fn is_string_instruction(instruction: &Instruction) -> bool {
+	for operand in 0..instruction.op_count() {
+		match instruction.op_kind(operand) {
+			OpKind::MemorySegSI | OpKind::MemorySegESI | OpKind::MemorySegRSI | OpKind::MemoryESDI | OpKind::MemoryESEDI | OpKind::MemoryESRDI => {
+				return true
+			}
+			_ => {}
+		}
+	}
+	false
+}
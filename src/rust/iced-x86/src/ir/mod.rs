@@ -0,0 +1,474 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod lifter;
+#[cfg(test)]
+mod tests;
+
+pub use self::lifter::*;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// An SSA temporary. Each temporary is assigned exactly once by an [`IrStmt`] in an [`IrBlock`]
+/// and its size is returned by [`IrBlock::temp_size()`].
+///
+/// [`IrStmt`]: enum.IrStmt.html
+/// [`IrBlock`]: struct.IrBlock.html
+/// [`IrBlock::temp_size()`]: struct.IrBlock.html#method.temp_size
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IrTemp(u32);
+
+impl IrTemp {
+	/// Gets the index of the temporary, 0-based
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn index(self) -> u32 {
+		self.0
+	}
+}
+
+impl fmt::Debug for IrTemp {
+	#[inline]
+	fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+		write!(f, "t{}", self.0)
+	}
+}
+
+/// An input value of an [`IrStmt`]
+///
+/// [`IrStmt`]: enum.IrStmt.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrValue {
+	/// A temporary
+	Temp(IrTemp),
+	/// A constant. It's truncated or zero extended to the size used by the statement.
+	Const(u64),
+}
+
+impl fmt::Debug for IrValue {
+	#[inline]
+	fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+		match self {
+			&IrValue::Temp(temp) => write!(f, "{:?}", temp),
+			&IrValue::Const(value) => write!(f, "0x{:X}", value),
+		}
+	}
+}
+
+/// Unary operations, see [`IrStmt::Unary`]
+///
+/// [`IrStmt::Unary`]: enum.IrStmt.html#variant.Unary
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrUnaryOp {
+	/// Bitwise not
+	Not,
+	/// Two's complement negation
+	Neg,
+	/// Reverse the byte order
+	ByteSwap,
+	/// Number of set bits
+	PopCount,
+	/// Number of trailing zero bits, or the size in bits if the input is 0
+	TrailingZeros,
+	/// Number of leading zero bits, or the size in bits if the input is 0
+	LeadingZeros,
+	/// Zero extend to the size in bytes
+	ZeroExtend(u32),
+	/// Sign extend to the size in bytes
+	SignExtend(u32),
+	/// Keep the low bytes, the value is the new size in bytes
+	Truncate(u32),
+	/// Square root of a floating point value (4 or 8 bytes)
+	FloatSqrt,
+	/// Converts a floating point value to a floating point value with a different size in bytes (4 or 8)
+	FloatConvert(u32),
+	/// Converts a floating point value to a signed integer with the size in bytes. `MXCSR.RC` is used if the
+	/// bool is `false`, else it's truncated. Invalid and out of range values return the integer indefinite value
+	/// (the smallest negative integer).
+	FloatToInt(u32, bool),
+	/// Converts a signed integer to a floating point value with the size in bytes (4 or 8)
+	IntToFloat(u32),
+}
+
+/// Binary operations, see [`IrStmt::Binary`]
+///
+/// [`IrStmt::Binary`]: enum.IrStmt.html#variant.Binary
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrBinaryOp {
+	/// Wrapping addition
+	Add,
+	/// Wrapping subtraction
+	Sub,
+	/// Low half of the product
+	Mul,
+	/// High half of the unsigned product
+	UMulHigh,
+	/// High half of the signed product
+	SMulHigh,
+	/// Bitwise and
+	And,
+	/// Bitwise or
+	Or,
+	/// Bitwise xor
+	Xor,
+	/// Shift left. The count (`rhs`) can be any value, all bits are 0 if it's >= the size in bits.
+	Shl,
+	/// Logical shift right. The count (`rhs`) can be any value, all bits are 0 if it's >= the size in bits.
+	Shr,
+	/// Arithmetic shift right. The count (`rhs`) can be any value, all bits are copies of the sign bit if it's >= the size in bits.
+	Sar,
+	/// Rotate left. The count (`rhs`) can be any value.
+	Rol,
+	/// Rotate right. The count (`rhs`) can be any value.
+	Ror,
+	/// Floating point addition (4 or 8 bytes)
+	FloatAdd,
+	/// Floating point subtraction (4 or 8 bytes)
+	FloatSub,
+	/// Floating point multiplication (4 or 8 bytes)
+	FloatMul,
+	/// Floating point division (4 or 8 bytes)
+	FloatDiv,
+	/// SSE floating point minimum: returns `rhs` unless `lhs < rhs`
+	FloatMin,
+	/// SSE floating point maximum: returns `rhs` unless `lhs > rhs`
+	FloatMax,
+}
+
+/// Compare operations, see [`IrStmt::Compare`]
+///
+/// [`IrStmt::Compare`]: enum.IrStmt.html#variant.Compare
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrCompareOp {
+	/// `lhs == rhs`
+	Eq,
+	/// `lhs != rhs`
+	Ne,
+	/// Unsigned `lhs < rhs`
+	Ult,
+	/// Unsigned `lhs <= rhs`
+	Ule,
+	/// Signed `lhs < rhs`
+	Slt,
+	/// Signed `lhs <= rhs`
+	Sle,
+}
+
+/// The operation whose result is used to update the flags, see [`IrStmt::SetFlags`]
+///
+/// [`IrStmt::SetFlags`]: enum.IrStmt.html#variant.SetFlags
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrFlagsKind {
+	/// `result = lhs + rhs + carry`
+	Add,
+	/// `result = lhs - rhs - carry` (`CMP` and `SUB` use a `carry` of 0, `SBB` uses `CF`)
+	Sub,
+	/// `result = lhs & rhs`, `lhs | rhs` or `lhs ^ rhs`. `CF` and `OF` are cleared.
+	Logic,
+	/// `result = lhs + 1`, `CF` isn't modified
+	Inc,
+	/// `result = lhs - 1`, `CF` isn't modified
+	Dec,
+	/// `result = 0 - lhs`
+	Neg,
+	/// `result = lhs << rhs`, `rhs` is the masked count. The flags aren't modified if the count is 0.
+	Shl,
+	/// `result = lhs >> rhs` (logical), `rhs` is the masked count. The flags aren't modified if the count is 0.
+	Shr,
+	/// `result = lhs >> rhs` (arithmetic), `rhs` is the masked count. The flags aren't modified if the count is 0.
+	Sar,
+	/// `result = lhs.rotate_left(rhs)`, `rhs` is the masked count. The flags aren't modified if the count is 0.
+	Rol,
+	/// `result = lhs.rotate_right(rhs)`, `rhs` is the masked count. The flags aren't modified if the count is 0.
+	Ror,
+	/// Unsigned multiplication, `result` is the low half of `lhs * rhs`
+	Mul,
+	/// Signed multiplication, `result` is the low half of `lhs * rhs`
+	Imul,
+	/// `BSF`/`BSR`: `lhs` is the source operand, `ZF` is set if it's 0
+	BitScan,
+	/// `TZCNT`/`LZCNT`/`POPCNT`: `lhs` is the source operand and `result` is the count. `ZF` is set if `result` is 0
+	/// and `CF` is set if `lhs` is 0.
+	Count,
+	/// `BT`: `lhs` is the bit base, `rhs` the masked bit offset. `CF` is set to the selected bit.
+	BitTest,
+	/// `COMISS`/`UCOMISS`/`COMISD`/`UCOMISD`: compares floating point values `lhs` and `rhs`
+	FloatCompare,
+}
+
+/// A branch kind, see [`IrStmt::Branch`]
+///
+/// [`IrStmt::Branch`]: enum.IrStmt.html#variant.Branch
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrBranchKind {
+	/// A jump
+	Jump,
+	/// A call, the return address has already been pushed
+	Call,
+	/// A return, the return address has already been popped
+	Return,
+}
+
+/// An IR statement. All sizes are in bytes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IrStmt {
+	/// Reads the low `size` bytes of a register. `AH`, `CH`, `DH` and `BH` are bits 8-15 of the full register.
+	ReadReg {
+		/// Destination
+		dst: IrTemp,
+		/// Register
+		register: Register,
+		/// Size of the value
+		size: u32,
+	},
+	/// Writes the low `size` bytes of a register, all other bits are preserved. The lifter explicitly writes the
+	/// full 64-bit register if a 32-bit register is written in 64-bit mode.
+	WriteReg {
+		/// Register
+		register: Register,
+		/// Size of the value
+		size: u32,
+		/// Value
+		src: IrValue,
+	},
+	/// Reads a flag (0 or 1)
+	ReadFlag {
+		/// Destination, 1 byte
+		dst: IrTemp,
+		/// Flag, see [`RflagsBits`]
+		///
+		/// [`RflagsBits`]: struct.RflagsBits.html
+		flag: u32,
+	},
+	/// Writes a flag. The value is 0 or 1.
+	WriteFlag {
+		/// Flag, see [`RflagsBits`]
+		///
+		/// [`RflagsBits`]: struct.RflagsBits.html
+		flag: u32,
+		/// Value, 1 byte
+		src: IrValue,
+	},
+	/// Reads memory
+	Load {
+		/// Destination
+		dst: IrTemp,
+		/// Segment register. The base of the segment must be added to `address` to get the linear address.
+		segment: Register,
+		/// Offset in the segment
+		address: IrValue,
+		/// Size of the value
+		size: u32,
+	},
+	/// Writes memory
+	Store {
+		/// Segment register. The base of the segment must be added to `address` to get the linear address.
+		segment: Register,
+		/// Offset in the segment
+		address: IrValue,
+		/// Size of the value
+		size: u32,
+		/// Value
+		src: IrValue,
+	},
+	/// `dst = op src`. The size of `dst` is `size` unless `op` changes it.
+	Unary {
+		/// Destination
+		dst: IrTemp,
+		/// Operation
+		op: IrUnaryOp,
+		/// Source
+		src: IrValue,
+		/// Size of `src`
+		size: u32,
+	},
+	/// `dst = lhs op rhs`
+	Binary {
+		/// Destination
+		dst: IrTemp,
+		/// Operation
+		op: IrBinaryOp,
+		/// Left operand
+		lhs: IrValue,
+		/// Right operand
+		rhs: IrValue,
+		/// Size of all values
+		size: u32,
+	},
+	/// `dst = lhs op rhs ? 1 : 0`
+	Compare {
+		/// Destination, 1 byte
+		dst: IrTemp,
+		/// Operation
+		op: IrCompareOp,
+		/// Left operand
+		lhs: IrValue,
+		/// Right operand
+		rhs: IrValue,
+		/// Size of `lhs` and `rhs`
+		size: u32,
+	},
+	/// `dst = condition != 0 ? if_true : if_false`
+	Select {
+		/// Destination
+		dst: IrTemp,
+		/// Condition, 1 byte
+		condition: IrValue,
+		/// Value if `condition` is non-zero
+		if_true: IrValue,
+		/// Value if `condition` is zero
+		if_false: IrValue,
+		/// Size of `dst`, `if_true` and `if_false`
+		size: u32,
+	},
+	/// Divides `high:low` by `divisor`. A divide error (`#DE`) occurs if `divisor` is 0 or if the quotient doesn't fit in `size` bytes.
+	Divide {
+		/// Quotient
+		quotient: IrTemp,
+		/// Remainder
+		remainder: IrTemp,
+		/// High half of the dividend
+		high: IrValue,
+		/// Low half of the dividend
+		low: IrValue,
+		/// Divisor
+		divisor: IrValue,
+		/// Size of all values
+		size: u32,
+		/// `true` if it's a signed division
+		signed: bool,
+	},
+	/// Updates `flags` to the values the operation `kind` produces
+	SetFlags {
+		/// Flags that are updated, see [`RflagsBits`]
+		///
+		/// [`RflagsBits`]: struct.RflagsBits.html
+		flags: u32,
+		/// Operation
+		kind: IrFlagsKind,
+		/// Size of the operands
+		size: u32,
+		/// First operand
+		lhs: IrValue,
+		/// Second operand
+		rhs: IrValue,
+		/// Carry/borrow in (0 or 1), only used by [`IrFlagsKind::Add`] and [`IrFlagsKind::Sub`]
+		///
+		/// [`IrFlagsKind::Add`]: enum.IrFlagsKind.html#variant.Add
+		/// [`IrFlagsKind::Sub`]: enum.IrFlagsKind.html#variant.Sub
+		carry: IrValue,
+		/// Result of the operation
+		result: IrValue,
+	},
+	/// The flags are undefined after the instruction, see [`Instruction::rflags_undefined()`]
+	///
+	/// [`Instruction::rflags_undefined()`]: struct.Instruction.html#method.rflags_undefined
+	UndefinedFlags {
+		/// Flags, see [`RflagsBits`]
+		///
+		/// [`RflagsBits`]: struct.RflagsBits.html
+		flags: u32,
+	},
+	/// Branches to `target` (an offset in `CS`) if `condition` is missing or non-zero, else continues with the next statement.
+	/// The instruction ends if the branch is taken.
+	Branch {
+		/// Kind
+		kind: IrBranchKind,
+		/// Condition (1 byte) or `None` if it's always taken
+		condition: Option<IrValue>,
+		/// Target address
+		target: IrValue,
+	},
+	/// A software interrupt (`INT n`, `INT3`, `INTO`, `INT1`) if `condition` is missing or non-zero, else continues with the next statement.
+	/// The instruction ends if the interrupt occurs.
+	Interrupt {
+		/// Interrupt vector
+		vector: u8,
+		/// Condition (1 byte) or `None` if it always occurs (`INTO` only interrupts if `OF` is set)
+		condition: Option<IrValue>,
+	},
+	/// `HLT`
+	Halt,
+}
+
+/// The statements of a lifted instruction, see [`IrLifter`]
+///
+/// All statements are executed in order. If no [`IrStmt::Branch`] is taken, execution continues at [`next_ip()`].
+///
+/// [`IrLifter`]: struct.IrLifter.html
+/// [`IrStmt::Branch`]: enum.IrStmt.html#variant.Branch
+/// [`next_ip()`]: #method.next_ip
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct IrBlock {
+	ip: u64,
+	next_ip: u64,
+	statements: Vec<IrStmt>,
+	temp_sizes: Vec<u8>,
+}
+
+impl IrBlock {
+	/// Gets the address of the instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn ip(&self) -> u64 {
+		self.ip
+	}
+
+	/// Gets the address of the next instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn next_ip(&self) -> u64 {
+		self.next_ip
+	}
+
+	/// Gets all statements
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn statements(&self) -> &[IrStmt] {
+		&self.statements
+	}
+
+	/// Gets the number of temporaries
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn temp_count(&self) -> u32 {
+		self.temp_sizes.len() as u32
+	}
+
+	/// Gets the size of a temporary in bytes
+	///
+	/// # Panics
+	///
+	/// Panics if `temp` isn't a temporary of this block
+	///
+	/// # Arguments
+	///
+	/// * `temp`: Temporary
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn temp_size(&self, temp: IrTemp) -> u32 {
+		self.temp_sizes[temp.0 as usize] as u32
+	}
+}
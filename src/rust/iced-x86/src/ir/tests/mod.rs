@@ -0,0 +1,271 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::create_decoder;
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn lift(bitness: u32, hex_bytes: &str) -> Result<IrBlock, String> {
+	let bytes = to_vec_u8(hex_bytes).unwrap();
+	let mut decoder = create_decoder(bitness, &bytes, DecoderOptions::NONE).0;
+	let instruction = decoder.decode();
+	assert_ne!(Code::INVALID, instruction.code());
+	IrLifter::new(bitness).lift(&instruction)
+}
+
+fn t(index: u32) -> IrValue {
+	IrValue::Temp(IrTemp(index))
+}
+
+#[test]
+#[should_panic]
+fn new_panics_if_invalid_bitness() {
+	let _ = IrLifter::new(0);
+}
+
+#[test]
+fn add_reg_mem() {
+	// add rax,[rcx+10h]
+	let block = lift(64, "48 03 41 10").unwrap();
+	assert_eq!(0x7FFF_FFFF_FFFF_FFF0, block.ip());
+	assert_eq!(0x7FFF_FFFF_FFFF_FFF4, block.next_ip());
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::RCX, size: 8 },
+		IrStmt::Binary { dst: IrTemp(1), op: IrBinaryOp::Add, lhs: t(0), rhs: IrValue::Const(0x10), size: 8 },
+		IrStmt::Load { dst: IrTemp(2), segment: Register::DS, address: t(1), size: 8 },
+		IrStmt::ReadReg { dst: IrTemp(3), register: Register::RAX, size: 8 },
+		IrStmt::Binary { dst: IrTemp(4), op: IrBinaryOp::Add, lhs: t(3), rhs: t(2), size: 8 },
+		IrStmt::SetFlags {
+			flags: RflagsBits::OF | RflagsBits::SF | RflagsBits::ZF | RflagsBits::AF | RflagsBits::CF | RflagsBits::PF,
+			kind: IrFlagsKind::Add,
+			size: 8,
+			lhs: t(3),
+			rhs: t(2),
+			carry: IrValue::Const(0),
+			result: t(4),
+		},
+		IrStmt::WriteReg { register: Register::RAX, size: 8, src: t(4) },
+	];
+	assert_eq!(&expected[..], block.statements());
+	assert_eq!(5, block.temp_count());
+	assert_eq!(8, block.temp_size(IrTemp(4)));
+}
+
+#[test]
+fn mov_r32_zero_extends_in_64_bit_mode() {
+	// mov eax,ecx
+	let block = lift(64, "89 C8").unwrap();
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::ECX, size: 4 },
+		IrStmt::Unary { dst: IrTemp(1), op: IrUnaryOp::ZeroExtend(8), src: t(0), size: 4 },
+		IrStmt::WriteReg { register: Register::RAX, size: 8, src: t(1) },
+	];
+	assert_eq!(&expected[..], block.statements());
+
+	// mov ax,cx
+	let block = lift(64, "66 89 C8").unwrap();
+	let expected =
+		[IrStmt::ReadReg { dst: IrTemp(0), register: Register::CX, size: 2 }, IrStmt::WriteReg { register: Register::AX, size: 2, src: t(0) }];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn logic_clears_flags() {
+	// xor eax,ecx
+	let block = lift(32, "31 C8").unwrap();
+	let stmts = block.statements();
+	assert!(stmts.contains(&IrStmt::WriteFlag { flag: RflagsBits::OF, src: IrValue::Const(0) }));
+	assert!(stmts.contains(&IrStmt::WriteFlag { flag: RflagsBits::CF, src: IrValue::Const(0) }));
+	assert!(stmts.contains(&IrStmt::UndefinedFlags { flags: RflagsBits::AF }));
+	assert!(stmts.iter().any(|stmt| match stmt {
+		&IrStmt::SetFlags { flags, kind: IrFlagsKind::Logic, .. } => flags == RflagsBits::SF | RflagsBits::ZF | RflagsBits::PF,
+		_ => false,
+	}));
+
+	// xor eax,eax
+	let block = lift(32, "31 C0").unwrap();
+	let stmts = block.statements();
+	assert!(stmts.contains(&IrStmt::WriteFlag { flag: RflagsBits::ZF, src: IrValue::Const(1) }));
+	assert!(stmts.contains(&IrStmt::WriteFlag { flag: RflagsBits::SF, src: IrValue::Const(0) }));
+}
+
+#[test]
+fn cmp_doesnt_write_dest() {
+	// cmp [eax],ecx
+	let block = lift(32, "39 08").unwrap();
+	assert!(!block.statements().iter().any(|stmt| match stmt {
+		&IrStmt::Store { .. } | &IrStmt::WriteReg { .. } => true,
+		_ => false,
+	}));
+}
+
+#[test]
+fn rep_movsb() {
+	// rep movsb
+	let block = lift(32, "F3 A4").unwrap();
+	let stmts = block.statements();
+	let branches: Vec<_> = stmts
+		.iter()
+		.filter_map(|stmt| match stmt {
+			&IrStmt::Branch { kind: IrBranchKind::Jump, condition: Some(_), target: IrValue::Const(target) } => Some(target),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(vec![block.next_ip(), block.ip()], branches);
+	assert!(stmts.contains(&IrStmt::ReadReg { dst: IrTemp(0), register: Register::ECX, size: 4 }));
+	assert!(stmts.iter().any(|stmt| match stmt {
+		&IrStmt::Load { segment: Register::DS, size: 1, .. } => true,
+		_ => false,
+	}));
+	assert!(stmts.iter().any(|stmt| match stmt {
+		&IrStmt::Store { segment: Register::ES, size: 1, .. } => true,
+		_ => false,
+	}));
+}
+
+#[test]
+fn push_pop() {
+	// push rbx
+	let block = lift(64, "53").unwrap();
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::RBX, size: 8 },
+		IrStmt::ReadReg { dst: IrTemp(1), register: Register::RSP, size: 8 },
+		IrStmt::Binary { dst: IrTemp(2), op: IrBinaryOp::Sub, lhs: t(1), rhs: IrValue::Const(8), size: 8 },
+		IrStmt::Store { segment: Register::SS, address: t(2), size: 8, src: t(0) },
+		IrStmt::WriteReg { register: Register::RSP, size: 8, src: t(2) },
+	];
+	assert_eq!(&expected[..], block.statements());
+
+	// pop bx
+	let block = lift(32, "66 5B").unwrap();
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::ESP, size: 4 },
+		IrStmt::Load { dst: IrTemp(1), segment: Register::SS, address: t(0), size: 2 },
+		IrStmt::Binary { dst: IrTemp(2), op: IrBinaryOp::Add, lhs: t(0), rhs: IrValue::Const(2), size: 4 },
+		IrStmt::WriteReg { register: Register::ESP, size: 4, src: t(2) },
+		IrStmt::WriteReg { register: Register::BX, size: 2, src: t(1) },
+	];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn call_pushes_return_address() {
+	// call 7FFFFFFFFFFFFFF5h
+	let block = lift(64, "E8 00000000").unwrap();
+	let stmts = block.statements();
+	assert!(stmts.iter().any(|stmt| match stmt {
+		&IrStmt::Store { src: IrValue::Const(value), size: 8, .. } => value == block.next_ip(),
+		_ => false,
+	}));
+	assert_eq!(Some(&IrStmt::Branch { kind: IrBranchKind::Call, condition: None, target: IrValue::Const(block.next_ip()) }), stmts.last());
+}
+
+#[test]
+fn jcc() {
+	// je short 800000000000000Ah
+	let block = lift(64, "74 18").unwrap();
+	let expected = [
+		IrStmt::ReadFlag { dst: IrTemp(0), flag: RflagsBits::ZF },
+		IrStmt::Branch { kind: IrBranchKind::Jump, condition: Some(t(0)), target: IrValue::Const(0x8000_0000_0000_000A) },
+	];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn interrupts() {
+	// int3
+	let block = lift(64, "CC").unwrap();
+	assert_eq!(&[IrStmt::Interrupt { vector: 3, condition: None }][..], block.statements());
+	// int 21h
+	let block = lift(64, "CD 21").unwrap();
+	assert_eq!(&[IrStmt::Interrupt { vector: 0x21, condition: None }][..], block.statements());
+	// int1
+	let block = lift(64, "F1").unwrap();
+	assert_eq!(&[IrStmt::Interrupt { vector: 1, condition: None }][..], block.statements());
+	// into
+	let block = lift(32, "CE").unwrap();
+	let expected = [IrStmt::ReadFlag { dst: IrTemp(0), flag: RflagsBits::OF }, IrStmt::Interrupt { vector: 4, condition: Some(t(0)) }];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn addss() {
+	// addss xmm1,dword ptr [rax]
+	let block = lift(64, "F3 0F58 08").unwrap();
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::XMM1, size: 4 },
+		IrStmt::ReadReg { dst: IrTemp(1), register: Register::RAX, size: 8 },
+		IrStmt::Load { dst: IrTemp(2), segment: Register::DS, address: t(1), size: 4 },
+		IrStmt::Binary { dst: IrTemp(3), op: IrBinaryOp::FloatAdd, lhs: t(0), rhs: t(2), size: 4 },
+		IrStmt::WriteReg { register: Register::XMM1, size: 4, src: t(3) },
+	];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn movsd_string_and_sse() {
+	// movsd
+	let block = lift(32, "A5").unwrap();
+	assert!(block.statements().iter().any(|stmt| match stmt {
+		&IrStmt::Store { segment: Register::ES, size: 4, .. } => true,
+		_ => false,
+	}));
+
+	// movsd xmm0,qword ptr [eax]
+	let block = lift(32, "F2 0F10 00").unwrap();
+	let expected = [
+		IrStmt::ReadReg { dst: IrTemp(0), register: Register::EAX, size: 4 },
+		IrStmt::Load { dst: IrTemp(1), segment: Register::DS, address: t(0), size: 8 },
+		IrStmt::WriteReg { register: Register::XMM0, size: 16, src: IrValue::Const(0) },
+		IrStmt::WriteReg { register: Register::XMM0, size: 8, src: t(1) },
+	];
+	assert_eq!(&expected[..], block.statements());
+}
+
+#[test]
+fn div_raises_no_flags() {
+	// div ecx
+	let block = lift(32, "F7 F1").unwrap();
+	assert!(block.statements().iter().any(|stmt| match stmt {
+		&IrStmt::Divide { size: 4, signed: false, .. } => true,
+		_ => false,
+	}));
+	assert!(!block.statements().iter().any(|stmt| match stmt {
+		&IrStmt::SetFlags { .. } => true,
+		_ => false,
+	}));
+}
+
+#[test]
+fn unsupported_instructions() {
+	// vaddps ymm1,ymm2,ymm3
+	assert!(lift(64, "C5EC 58 CB").is_err());
+	// call far ptr 5678h:1234h
+	assert!(lift(32, "66 9A 3412 7856").is_err());
+	// mov cr0,rax
+	assert!(lift(64, "0F22 C0").is_err());
+}
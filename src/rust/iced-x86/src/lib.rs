@@ -40,6 +40,8 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //! - `intel`: (Enabled by default) Enables the Intel (XED) formatter
 //! - `masm`: (Enabled by default) Enables the masm formatter
 //! - `nasm`: (Enabled by default) Enables the nasm formatter
+//! - `serde`: Implements `Serialize` and `Deserialize` for `Instruction`, the public enums and a few other types (rustc `1.31.0+`)
+//! - `timing`: Enables approximate instruction timings (latency, throughput, ports) and the block throughput estimator. Timings are stored per instruction class (grouped by mnemonic), not per `Code`, and only common integer and SSE/AVX/AVX-512 instructions are supported, see `Microarchitecture::timing()`
 //! - `std`: (Enabled by default) Enables the `std` crate. `std` or `no_std` must be defined, but not both.
 //! - `no_std`: Enables `#![no_std]`. `std` or `no_std` must be defined, but not both. This feature uses the `alloc` crate (rustc `1.36.0+`) and the `hashbrown` crate.
 //! - `exhaustive_enums`: Enables exhaustive enums, i.e., no enum has the `#[non_exhaustive]` attribute
@@ -93,29 +95,55 @@ compile_error!("`std` and `no_std` features can't be used at the same time");
 #[cfg(all(not(feature = "std"), not(feature = "no_std")))]
 compile_error!("`std` or `no_std` feature must be defined");
 
-#[cfg(all(has_alloc, any(not(feature = "std"), feature = "encoder", feature = "gas", feature = "intel", feature = "masm", feature = "nasm")))]
-#[cfg_attr(all(has_alloc, any(feature = "encoder", feature = "gas", feature = "intel", feature = "masm", feature = "nasm")), macro_use)]
+#[cfg(all(
+	has_alloc,
+	any(
+		not(feature = "std"),
+		feature = "encoder",
+		feature = "instr_info",
+		feature = "gas",
+		feature = "intel",
+		feature = "masm",
+		feature = "nasm",
+		feature = "timing"
+	)
+))]
+#[cfg_attr(
+	all(
+		has_alloc,
+		any(feature = "encoder", feature = "instr_info", feature = "gas", feature = "intel", feature = "masm", feature = "nasm", feature = "timing")
+	),
+	macro_use
+)]
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate core;
-#[cfg(any(feature = "decoder", feature = "encoder", feature = "gas", feature = "intel", feature = "masm", feature = "nasm"))]
-#[cfg_attr(any(feature = "decoder", feature = "encoder", feature = "gas", feature = "intel", feature = "masm", feature = "nasm"), macro_use)]
+#[macro_use]
 extern crate lazy_static;
 #[macro_use]
 extern crate static_assertions;
 #[cfg(not(feature = "std"))]
 #[cfg(feature = "encoder")]
 extern crate hashbrown;
+#[cfg(feature = "serde")]
+#[cfg_attr(test, macro_use)]
+extern crate serde;
 
 #[cfg(feature = "encoder")]
 mod block_enc;
 mod code;
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 mod constant_offsets;
+#[cfg(feature = "instr_info")]
+mod cpu_profile;
 #[cfg(any(feature = "decoder", feature = "gas", feature = "intel", feature = "masm", feature = "nasm"))]
 mod data_reader;
+#[cfg(all(feature = "encoder", feature = "instr_info"))]
+mod database;
 #[cfg(feature = "decoder")]
 mod decoder;
+#[cfg(all(feature = "decoder", feature = "instr_info"))]
+mod emulator;
 #[cfg(feature = "encoder")]
 mod encoder;
 mod enums;
@@ -129,22 +157,43 @@ mod instruction;
 mod instruction_internal;
 mod instruction_memory_sizes;
 mod instruction_op_counts;
+mod instruction_stream;
+#[cfg(feature = "instr_info")]
+mod int_utils;
+#[cfg(feature = "instr_info")]
+mod ir;
+#[cfg(feature = "instr_info")]
+mod liveness;
+mod memory_operand_info;
 mod memory_size;
 mod mnemonic;
 mod mnemonics;
+mod name_lookup;
 mod register;
+#[cfg(feature = "serde")]
+mod serialization;
 #[cfg(test)]
 mod test;
 #[cfg(test)]
 pub(crate) mod test_utils;
+#[cfg(feature = "timing")]
+mod timing;
+#[cfg(feature = "instr_info")]
+mod vsib;
 
 #[cfg(feature = "encoder")]
 pub use self::block_enc::*;
 pub use self::code::*;
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 pub use self::constant_offsets::*;
+#[cfg(feature = "instr_info")]
+pub use self::cpu_profile::*;
+#[cfg(all(feature = "encoder", feature = "instr_info"))]
+pub use self::database::*;
 #[cfg(feature = "decoder")]
 pub use self::decoder::*;
+#[cfg(all(feature = "decoder", feature = "instr_info"))]
+pub use self::emulator::*;
 #[cfg(feature = "encoder")]
 pub use self::encoder::*;
 pub use self::enums::*;
@@ -154,6 +203,16 @@ pub use self::iced_features::*;
 #[cfg(feature = "instr_info")]
 pub use self::info::*;
 pub use self::instruction::*;
+pub use self::instruction_stream::*;
+#[cfg(feature = "instr_info")]
+pub use self::ir::*;
+#[cfg(feature = "instr_info")]
+pub use self::liveness::*;
+pub use self::memory_operand_info::*;
 pub use self::memory_size::*;
 pub use self::mnemonic::*;
 pub use self::register::*;
+#[cfg(feature = "timing")]
+pub use self::timing::*;
+#[cfg(feature = "instr_info")]
+pub use self::vsib::*;
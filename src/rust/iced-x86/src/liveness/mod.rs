@@ -0,0 +1,310 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod register_set;
+#[cfg(test)]
+mod tests;
+
+pub use self::register_set::*;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Register and flags liveness and def-use chains of a sequence of instructions.
+///
+/// The instructions are treated as a basic block, ie., they're executed in order and branches are ignored. The register
+/// usage is from [`InstructionInfoFactory`]. Partial writes only define the written parts, eg. writing `AL` doesn't kill
+/// `AH`, a written 32-bit GPR in 64-bit mode defines the full 64-bit register and VEX/EVEX/XOP instructions that write
+/// an `XMM`/`YMM` register define the full `ZMM` register. Conditional writes (eg. `CMOVcc`, shifts with a non-constant
+/// count) don't kill any registers or flags.
+///
+/// [`InstructionInfoFactory`]: struct.InstructionInfoFactory.html
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// // mov eax,1
+/// // add eax,ecx
+/// // mov al,[rsi]
+/// let bytes = b"\xB8\x01\x00\x00\x00\x01\xC8\x8A\x06";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instructions: Vec<_> = decoder.iter().collect();
+///
+/// let mut live_out = RegisterSet::new();
+/// live_out.add(Register::RAX);
+/// let liveness = RegisterLiveness::new(&instructions, &live_out);
+///
+/// assert!(liveness.live_in(0).contains(Register::ECX));
+/// assert!(!liveness.live_in(0).intersects(Register::RAX));
+/// // AL is overwritten but the upper bits of RAX are still live
+/// assert!(liveness.live_out(1).contains(Register::AH));
+/// assert!(!liveness.live_out(1).intersects(Register::AL));
+/// assert_eq!(&[0], liveness.reaching_defs(1));
+/// assert_eq!(&[1], liveness.reached_uses(0));
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct RegisterLiveness {
+	reads: Vec<RegisterSet>,
+	writes: Vec<RegisterSet>,
+	must_writes: Vec<RegisterSet>,
+	live_in: Vec<RegisterSet>,
+	live_out: Vec<RegisterSet>,
+	reaching_defs: Vec<Vec<usize>>,
+	reached_uses: Vec<Vec<usize>>,
+}
+
+impl RegisterLiveness {
+	/// Analyzes the instructions
+	///
+	/// # Arguments
+	///
+	/// * `instructions`: The instructions, executed in order
+	/// * `live_out`: Registers and flags that are live after the last instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new(instructions: &[Instruction], live_out: &RegisterSet) -> Self {
+		let mut factory = InstructionInfoFactory::new();
+		let mut result = RegisterLiveness::default();
+		for instruction in instructions {
+			let info = factory.info_options(instruction, InstructionInfoOptions::NO_MEMORY_USAGE);
+			let mut reads = RegisterSet::new();
+			let mut writes = RegisterSet::new();
+			let mut must_writes = RegisterSet::new();
+			for used_reg in info.used_registers() {
+				let register = used_reg.register();
+				match used_reg.access() {
+					OpAccess::Read | OpAccess::CondRead => reads.add(register),
+					OpAccess::Write => {
+						writes.add(register);
+						must_writes.add(register);
+					}
+					OpAccess::CondWrite => writes.add(register),
+					OpAccess::ReadWrite => {
+						reads.add(register);
+						writes.add(register);
+						must_writes.add(register);
+					}
+					OpAccess::ReadCondWrite => {
+						reads.add(register);
+						writes.add(register);
+					}
+					OpAccess::None | OpAccess::NoMemAccess => {}
+				}
+			}
+			reads.add_rflags(info.rflags_read());
+			writes.add_rflags(info.rflags_modified());
+			if !has_conditional_rflags(instruction) {
+				must_writes.add_rflags(info.rflags_modified());
+			}
+			result.reads.push(reads);
+			result.writes.push(writes);
+			result.must_writes.push(must_writes);
+		}
+
+		let mut live = *live_out;
+		result.live_in = Vec::with_capacity(instructions.len());
+		result.live_out = Vec::with_capacity(instructions.len());
+		for i in (0..instructions.len()).rev() {
+			result.live_out.push(live);
+			live.difference_with(&result.must_writes[i]);
+			live.union_with(&result.reads[i]);
+			result.live_in.push(live);
+		}
+		result.live_in.reverse();
+		result.live_out.reverse();
+
+		// The instructions that could've written the current value of each register part and flag
+		let mut last_defs: Vec<Vec<usize>> = Vec::with_capacity(register_set::PARTS);
+		last_defs.resize(register_set::PARTS, Vec::new());
+		result.reached_uses.resize(instructions.len(), Vec::new());
+		for i in 0..instructions.len() {
+			let mut defs = Vec::new();
+			result.reads[i].for_each_part(|part| defs.extend_from_slice(&last_defs[part]));
+			defs.sort();
+			defs.dedup();
+			for &def in &defs {
+				result.reached_uses[def].push(i);
+			}
+			result.reaching_defs.push(defs);
+
+			let must_writes = &result.must_writes[i];
+			result.writes[i].for_each_part(|part| {
+				let last = &mut last_defs[part];
+				if must_writes.contains_part(part) {
+					last.clear();
+				}
+				last.push(i);
+			});
+		}
+
+		result
+	}
+
+	/// Gets the number of instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.reads.len()
+	}
+
+	/// Checks if there are no instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.reads.is_empty()
+	}
+
+	/// Registers and flags that the instruction can read
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn reads(&self, index: usize) -> &RegisterSet {
+		&self.reads[index]
+	}
+
+	/// Registers and flags that the instruction can write, including conditional writes
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn writes(&self, index: usize) -> &RegisterSet {
+		&self.writes[index]
+	}
+
+	/// Registers and flags that are always written by the instruction
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn must_writes(&self, index: usize) -> &RegisterSet {
+		&self.must_writes[index]
+	}
+
+	/// Registers and flags that are live before the instruction is executed
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn live_in(&self, index: usize) -> &RegisterSet {
+		&self.live_in[index]
+	}
+
+	/// Registers and flags that are live after the instruction is executed
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn live_out(&self, index: usize) -> &RegisterSet {
+		&self.live_out[index]
+	}
+
+	/// Gets the (sorted) indexes of the previous instructions that wrote a value that this instruction can read (use-def chain)
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn reaching_defs(&self, index: usize) -> &[usize] {
+		&self.reaching_defs[index]
+	}
+
+	/// Gets the (sorted) indexes of the following instructions that can read a value written by this instruction (def-use chain)
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds
+	///
+	/// # Arguments
+	///
+	/// * `index`: Instruction index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn reached_uses(&self, index: usize) -> &[usize] {
+		&self.reached_uses[index]
+	}
+}
+
+// Shifts and rotates don't modify the flags if the masked count is 0
+fn has_conditional_rflags(instruction: &Instruction) -> bool {
+	match instruction.mnemonic() {
+		Mnemonic::Shl
+		| Mnemonic::Sal
+		| Mnemonic::Shr
+		| Mnemonic::Sar
+		| Mnemonic::Rol
+		| Mnemonic::Ror
+		| Mnemonic::Rcl
+		| Mnemonic::Rcr
+		| Mnemonic::Shld
+		| Mnemonic::Shrd => {
+			let count_op = instruction.op_count() - 1;
+			match instruction.op_kind(count_op) {
+				OpKind::Immediate8 => {
+					let mask = if instruction.op_register(0).size() == 8 || instruction.memory_size().size() == 8 { 0x3F } else { 0x1F };
+					(instruction.immediate8() & mask) == 0
+				}
+				OpKind::Register => true,
+				_ => false,
+			}
+		}
+		_ => false,
+	}
+}
@@ -0,0 +1,222 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+
+// Bit layout:
+//	   0-127: GPR bytes, 8 bits per GPR
+//	 128-255: vector register 16-byte lanes, 4 bits per register (XMM, YMM upper, ZMM upper 256 bits)
+//	 256-511: all other registers, 1 bit per register
+//	 512-520: RFLAGS bits (RflagsBits)
+const WORDS: usize = 9;
+const RFLAGS_WORD: usize = 8;
+pub(super) const PARTS: usize = WORDS * 64;
+
+/// A set of registers and `RFLAGS` bits. A register is split into parts (GPR bytes and 16-byte vector register lanes)
+/// so sub registers can be added and removed: `AL`, `AH`, `AX` and `EAX` are parts of `RAX` and `XMM0` and `YMM0` are
+/// parts of `ZMM0`. Other registers (segment registers, `K0`-`K7`, `ST0`-`ST7`, etc) aren't split.
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// let mut set = RegisterSet::new();
+/// set.add(Register::RAX);
+/// set.remove(Register::AL);
+/// assert!(set.contains(Register::AH));
+/// assert!(!set.contains(Register::AX));
+/// assert!(set.intersects(Register::AX));
+/// set.add_rflags(RflagsBits::CF | RflagsBits::ZF);
+/// assert_eq!(RflagsBits::CF | RflagsBits::ZF, set.rflags());
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RegisterSet {
+	bits: [u64; WORDS],
+}
+
+// Returns the word index and the bits of all parts of the register
+fn register_bits(register: Register) -> (usize, u64) {
+	if register == Register::None {
+		(0, 0)
+	} else if register.is_gpr() {
+		let index = register.full_register() as usize - Register::RAX as usize;
+		let mask = match register {
+			Register::AH | Register::CH | Register::DH | Register::BH => 2,
+			_ => (1u64 << register.size()) - 1,
+		};
+		(index / 8, mask << ((index % 8) * 8))
+	} else if register.is_vector_register() {
+		let index = register.number();
+		let mask = (1u64 << (register.size() / 16)) - 1;
+		(2 + index / 16, mask << ((index % 16) * 4))
+	} else {
+		let index = register as usize;
+		(4 + index / 64, 1u64 << (index % 64))
+	}
+}
+
+impl RegisterSet {
+	/// Creates an empty set
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn new() -> Self {
+		Self { bits: [0; WORDS] }
+	}
+
+	/// Checks if the set is empty
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.bits.iter().all(|&bits| bits == 0)
+	}
+
+	/// Adds a register
+	///
+	/// # Arguments
+	///
+	/// * `register`: Register
+	#[inline]
+	pub fn add(&mut self, register: Register) {
+		let (index, bits) = register_bits(register);
+		self.bits[index] |= bits;
+	}
+
+	/// Removes a register. Other parts of the full register aren't removed, eg. removing `AL` from `RAX` leaves `AH` and the upper bits.
+	///
+	/// # Arguments
+	///
+	/// * `register`: Register
+	#[inline]
+	pub fn remove(&mut self, register: Register) {
+		let (index, bits) = register_bits(register);
+		self.bits[index] &= !bits;
+	}
+
+	/// Checks if all parts of a register are in the set
+	///
+	/// # Arguments
+	///
+	/// * `register`: Register
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn contains(&self, register: Register) -> bool {
+		let (index, bits) = register_bits(register);
+		bits != 0 && (self.bits[index] & bits) == bits
+	}
+
+	/// Checks if any part of a register is in the set
+	///
+	/// # Arguments
+	///
+	/// * `register`: Register
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn intersects(&self, register: Register) -> bool {
+		let (index, bits) = register_bits(register);
+		(self.bits[index] & bits) != 0
+	}
+
+	/// Gets the `RFLAGS` bits, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn rflags(&self) -> u32 {
+		self.bits[RFLAGS_WORD] as u32
+	}
+
+	/// Adds `RFLAGS` bits, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	///
+	/// # Arguments
+	///
+	/// * `rflags`: Flags to add
+	#[inline]
+	pub fn add_rflags(&mut self, rflags: u32) {
+		self.bits[RFLAGS_WORD] |= rflags as u64;
+	}
+
+	/// Removes `RFLAGS` bits, see [`RflagsBits`]
+	///
+	/// [`RflagsBits`]: struct.RflagsBits.html
+	///
+	/// # Arguments
+	///
+	/// * `rflags`: Flags to remove
+	#[inline]
+	pub fn remove_rflags(&mut self, rflags: u32) {
+		self.bits[RFLAGS_WORD] &= !(rflags as u64);
+	}
+
+	/// Adds all registers and flags in `other` to this set
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[inline]
+	pub fn union_with(&mut self, other: &RegisterSet) {
+		for (bits, &other) in self.bits.iter_mut().zip(other.bits.iter()) {
+			*bits |= other;
+		}
+	}
+
+	/// Removes all registers and flags in `other` from this set
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[inline]
+	pub fn difference_with(&mut self, other: &RegisterSet) {
+		for (bits, &other) in self.bits.iter_mut().zip(other.bits.iter()) {
+			*bits &= !other;
+		}
+	}
+
+	/// Checks if this set and `other` have any registers or flags in common
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other set
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn intersects_with(&self, other: &RegisterSet) -> bool {
+		self.bits.iter().zip(other.bits.iter()).any(|(&a, &b)| (a & b) != 0)
+	}
+
+	// Calls `f` with the index of each register part and flag in the set
+	pub(super) fn for_each_part<F: FnMut(usize)>(&self, mut f: F) {
+		for (index, &bits) in self.bits.iter().enumerate() {
+			let mut bits = bits;
+			while bits != 0 {
+				f(index * 64 + bits.trailing_zeros() as usize);
+				bits &= bits - 1;
+			}
+		}
+	}
+
+	pub(super) fn contains_part(&self, part: usize) -> bool {
+		(self.bits[part / 64] & (1 << (part % 64))) != 0
+	}
+}
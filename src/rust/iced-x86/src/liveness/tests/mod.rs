@@ -0,0 +1,212 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::create_decoder;
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn analyze(bitness: u32, hex_bytes: &str, live_out: &[Register], live_out_rflags: u32) -> RegisterLiveness {
+	let bytes = to_vec_u8(hex_bytes).unwrap();
+	let mut decoder = create_decoder(bitness, &bytes, DecoderOptions::NONE).0;
+	let instructions: Vec<Instruction> = decoder.iter().collect();
+	for instruction in &instructions {
+		assert_ne!(Code::INVALID, instruction.code());
+	}
+	let mut set = RegisterSet::new();
+	for &register in live_out {
+		set.add(register);
+	}
+	set.add_rflags(live_out_rflags);
+	RegisterLiveness::new(&instructions, &set)
+}
+
+#[test]
+fn register_set_partial_registers() {
+	let mut set = RegisterSet::new();
+	assert!(set.is_empty());
+	set.add(Register::AH);
+	assert!(!set.is_empty());
+	assert!(set.contains(Register::AH));
+	assert!(!set.contains(Register::AL));
+	assert!(!set.contains(Register::AX));
+	assert!(set.intersects(Register::AX));
+	assert!(set.intersects(Register::RAX));
+	assert!(!set.intersects(Register::AL));
+	assert!(!set.intersects(Register::SPL));
+	set.add(Register::AL);
+	assert!(set.contains(Register::AX));
+	assert!(!set.contains(Register::EAX));
+	set.remove(Register::AX);
+	assert!(set.is_empty());
+
+	set.add(Register::XMM1);
+	assert!(set.intersects(Register::ZMM1));
+	assert!(!set.contains(Register::YMM1));
+	assert!(!set.intersects(Register::XMM0));
+	set.add(Register::YMM1);
+	set.remove(Register::XMM1);
+	assert!(set.intersects(Register::YMM1));
+	assert!(!set.intersects(Register::XMM1));
+
+	set.add(Register::None);
+	assert!(!set.contains(Register::None));
+	assert!(!set.intersects(Register::None));
+}
+
+#[test]
+fn register_set_rflags() {
+	let mut set = RegisterSet::new();
+	set.add_rflags(RflagsBits::CF | RflagsBits::ZF);
+	assert_eq!(RflagsBits::CF | RflagsBits::ZF, set.rflags());
+	set.remove_rflags(RflagsBits::ZF);
+	assert_eq!(RflagsBits::CF, set.rflags());
+
+	let mut other = RegisterSet::new();
+	other.add(Register::ECX);
+	other.add_rflags(RflagsBits::CF);
+	assert!(set.intersects_with(&other));
+	set.difference_with(&other);
+	assert!(set.is_empty());
+	set.union_with(&other);
+	assert_eq!(other, set);
+}
+
+#[test]
+fn empty() {
+	let liveness = RegisterLiveness::new(&[], &RegisterSet::new());
+	assert!(liveness.is_empty());
+	assert_eq!(0, liveness.len());
+}
+
+#[test]
+fn partial_write_keeps_full_register_live() {
+	// mov al,1
+	// ret
+	let liveness = analyze(64, "B0 01 C3", &[Register::RAX], 0);
+	assert_eq!(2, liveness.len());
+	assert!(!liveness.live_in(0).intersects(Register::AL));
+	assert!(liveness.live_in(0).contains(Register::AH));
+	assert!(liveness.live_in(0).intersects(Register::RAX));
+	assert!(liveness.live_out(0).contains(Register::RAX));
+}
+
+#[test]
+fn gpr32_write_kills_gpr64() {
+	// mov eax,1
+	let liveness = analyze(64, "B8 01000000", &[Register::RAX], 0);
+	assert!(liveness.must_writes(0).contains(Register::RAX));
+	assert!(!liveness.live_in(0).intersects(Register::RAX));
+
+	// mov ax,1
+	let liveness = analyze(64, "66 B8 0100", &[Register::RAX], 0);
+	assert!(liveness.must_writes(0).contains(Register::AX));
+	assert!(!liveness.must_writes(0).contains(Register::EAX));
+	assert!(liveness.live_in(0).intersects(Register::RAX));
+	assert!(!liveness.live_in(0).intersects(Register::AX));
+}
+
+#[test]
+fn vex_write_kills_full_vector_register() {
+	// movaps xmm0,xmm1
+	let liveness = analyze(64, "0F 28 C1", &[Register::ZMM0], 0);
+	assert!(liveness.live_in(0).intersects(Register::ZMM0));
+	assert!(!liveness.live_in(0).intersects(Register::XMM0));
+	assert!(liveness.live_in(0).contains(Register::XMM1));
+
+	// vmovaps xmm0,xmm1
+	let liveness = analyze(64, "C5F8 28 C1", &[Register::ZMM0], 0);
+	assert!(!liveness.live_in(0).intersects(Register::ZMM0));
+	assert!(liveness.live_in(0).contains(Register::XMM1));
+}
+
+#[test]
+fn rflags_are_tracked_separately() {
+	// add eax,ecx
+	// inc edx
+	// jb short ...
+	let liveness = analyze(64, "01 C8 FF C2 72 00", &[], 0);
+	assert_eq!(RflagsBits::CF, liveness.reads(2).rflags());
+	// INC doesn't modify CF so ADD's CF is live
+	assert_eq!(RflagsBits::CF, liveness.live_out(0).rflags());
+	assert_eq!(RflagsBits::NONE, liveness.live_in(0).rflags());
+	assert_eq!(&[0], liveness.reaching_defs(2));
+	assert_eq!(&[2], liveness.reached_uses(0));
+	assert!(liveness.reached_uses(1).is_empty());
+}
+
+#[test]
+fn shift_by_cl_conditionally_writes_rflags() {
+	// add eax,ecx
+	// shl edx,cl
+	// jb short ...
+	let liveness = analyze(64, "01 C8 D3 E2 72 00", &[], 0);
+	assert!(liveness.writes(1).rflags() & RflagsBits::CF != 0);
+	assert_eq!(RflagsBits::NONE, liveness.must_writes(1).rflags() & RflagsBits::CF);
+	assert_eq!(RflagsBits::CF, liveness.live_out(0).rflags());
+	assert_eq!(&[0, 1], liveness.reaching_defs(2));
+
+	// add eax,ecx
+	// shl edx,1
+	// jb short ...
+	let liveness = analyze(64, "01 C8 D1 E2 72 00", &[], 0);
+	assert!(liveness.must_writes(1).rflags() & RflagsBits::CF != 0);
+	assert_eq!(RflagsBits::NONE, liveness.live_out(0).rflags());
+	assert_eq!(&[1], liveness.reaching_defs(2));
+}
+
+#[test]
+fn conditional_write_doesnt_kill() {
+	// mov eax,1
+	// cmove rax,rcx
+	let liveness = analyze(64, "B8 01000000 48 0F44 C1", &[Register::RAX], 0);
+	assert!(liveness.live_out(0).contains(Register::RAX));
+	assert!(!liveness.must_writes(1).intersects(Register::RAX));
+	assert!(liveness.writes(1).contains(Register::RAX));
+	assert!(liveness.live_in(1).contains(Register::RCX));
+	assert!(liveness.live_in(1).rflags() & RflagsBits::ZF != 0);
+}
+
+#[test]
+fn def_use_chains() {
+	// mov al,1
+	// mov ah,2
+	// mov cx,ax
+	// mov al,3
+	// add cx,ax
+	let liveness = analyze(64, "B0 01 B4 02 66 89 C1 B0 03 66 01 C1", &[], 0);
+	assert_eq!(5, liveness.len());
+	assert!(liveness.reaching_defs(0).is_empty());
+	assert!(liveness.reaching_defs(1).is_empty());
+	assert_eq!(&[0, 1], liveness.reaching_defs(2));
+	assert_eq!(&[1, 2, 3], liveness.reaching_defs(4));
+	assert_eq!(&[2], liveness.reached_uses(0));
+	assert_eq!(&[2, 4], liveness.reached_uses(1));
+	assert_eq!(&[4], liveness.reached_uses(2));
+	assert_eq!(&[4], liveness.reached_uses(3));
+	assert!(liveness.reached_uses(4).is_empty());
+	assert!(liveness.live_in(0).is_empty());
+	assert!(liveness.live_in(4).contains(Register::AX));
+	assert!(liveness.live_in(4).contains(Register::CX));
+}
@@ -0,0 +1,384 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::instruction_internal::get_address_size_in_bytes;
+use super::*;
+use core::{u16, u32, u64};
+
+/// A memory operand, see [`Instruction::memory_operand_info()`]. It contains everything needed to calculate the
+/// address of the memory operand, see [`effective_address()`] and [`virtual_address()`].
+///
+/// [`Instruction::memory_operand_info()`]: struct.Instruction.html#method.memory_operand_info
+/// [`effective_address()`]: #method.effective_address
+/// [`virtual_address()`]: #method.virtual_address
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MemoryOperandInfo {
+	/// Segment override or [`Register::None`]
+	///
+	/// [`Register::None`]: enum.Register.html#variant.None
+	pub segment_prefix: Register,
+	/// The segment register used to reference the memory location, see [`Instruction::memory_segment()`].
+	/// It's ignored by [`Instruction::set_memory_operand_info()`].
+	///
+	/// [`Instruction::memory_segment()`]: struct.Instruction.html#method.memory_segment
+	/// [`Instruction::set_memory_operand_info()`]: struct.Instruction.html#method.set_memory_operand_info
+	pub segment: Register,
+	/// Base register or [`Register::None`]. It's `RIP`/`EIP` if it's an IP relative memory operand.
+	/// String instructions use `SI`/`ESI`/`RSI`/`DI`/`EDI`/`RDI`.
+	///
+	/// [`Register::None`]: enum.Register.html#variant.None
+	pub base: Register,
+	/// Index register or [`Register::None`]. It's an `XMM`, `YMM` or `ZMM` register if it's a VSIB memory operand.
+	///
+	/// [`Register::None`]: enum.Register.html#variant.None
+	pub index: Register,
+	/// Index register scale (1, 2, 4, or 8)
+	pub scale: u32,
+	/// Memory displacement. It's sign extended to 64 bits if it's 64-bit addressing. It's the 64-bit address if the
+	/// operand's kind is [`OpKind::Memory64`].
+	///
+	/// [`OpKind::Memory64`]: enum.OpKind.html#variant.Memory64
+	pub displacement: u64,
+	/// Size of the displacement in bytes, see [`Instruction::memory_displ_size()`]
+	///
+	/// [`Instruction::memory_displ_size()`]: struct.Instruction.html#method.memory_displ_size
+	pub displ_size: u32,
+	/// Address size in bytes (2, 4 or 8). It's ignored by [`Instruction::set_memory_operand_info()`].
+	///
+	/// [`Instruction::set_memory_operand_info()`]: struct.Instruction.html#method.set_memory_operand_info
+	pub address_size: u32,
+	/// `true` if the data is broadcasted (EVEX instructions only)
+	pub is_broadcast: bool,
+	/// Size of the memory location, see [`Instruction::memory_size()`]. It's ignored by [`Instruction::set_memory_operand_info()`].
+	///
+	/// [`Instruction::memory_size()`]: struct.Instruction.html#method.memory_size
+	/// [`Instruction::set_memory_operand_info()`]: struct.Instruction.html#method.set_memory_operand_info
+	pub memory_size: MemorySize,
+	/// VSIB memory operands only: `Some(true)` if it's using 64-bit indexes, `Some(false)` if it's using 32-bit indexes,
+	/// see [`Instruction::vsib()`]. It's ignored by [`Instruction::set_memory_operand_info()`].
+	///
+	/// [`Instruction::vsib()`]: struct.Instruction.html#method.vsib
+	/// [`Instruction::set_memory_operand_info()`]: struct.Instruction.html#method.set_memory_operand_info
+	pub vsib: Option<bool>,
+	/// The address of the next instruction. It's used by IP relative memory operands and it's ignored by [`Instruction::set_memory_operand_info()`].
+	///
+	/// [`Instruction::set_memory_operand_info()`]: struct.Instruction.html#method.set_memory_operand_info
+	pub next_ip: u64,
+}
+
+impl MemoryOperandInfo {
+	/// Checks if it's a `RIP`/`EIP` relative memory operand
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn is_ip_relative(&self) -> bool {
+		self.base == Register::RIP || self.base == Register::EIP
+	}
+
+	/// Gets the offset of the memory operand in its segment (`base + index * scale + displacement`), truncated to the address size.
+	/// `RIP`/`EIP` relative memory operands use [`next_ip`].
+	///
+	/// [`next_ip`]: #structfield.next_ip
+	///
+	/// # Arguments
+	///
+	/// * `element_index`: Only used if it's a vsib memory operand. This is the element index of the vector index register.
+	/// * `get_register_value`: Function that returns the value of a register. See [`Instruction::virtual_address()`].
+	///
+	/// [`Instruction::virtual_address()`]: struct.Instruction.html#method.virtual_address
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // add [rdi+r12*8-5AA5EDCCh],esi
+	/// let bytes = b"\x42\x01\xB4\xE7\x34\x12\x5A\xA5";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	/// let info = instr.memory_operand_info(0).unwrap();
+	///
+	/// let address = info.effective_address(0, |register, _element_index, _element_size| match register {
+	///     Register::RDI => 0x0000_0000_1000_0000,
+	///     Register::R12 => 0x0000_0004_0000_0000,
+	///     _ => unimplemented!(),
+	/// });
+	/// assert_eq!(0x0000_001F_B55A_1234, address);
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn effective_address<F>(&self, element_index: usize, get_register_value: F) -> u64
+	where
+		F: Fn(Register, usize, usize) -> u64,
+	{
+		let mut offset = self.displacement;
+		match self.base {
+			Register::None => {}
+			Register::RIP | Register::EIP => offset = offset.wrapping_add(self.next_ip),
+			_ => offset = offset.wrapping_add(get_register_value(self.base, 0, 0)),
+		}
+		if self.index != Register::None {
+			let index_value = match self.vsib {
+				Some(true) => get_register_value(self.index, element_index, 8),
+				Some(false) => get_register_value(self.index, element_index, 4) as u32 as u64,
+				None => get_register_value(self.index, element_index, 0),
+			};
+			offset = offset.wrapping_add(index_value.wrapping_mul(self.scale as u64));
+		}
+		match self.address_size {
+			2 => offset as u16 as u64,
+			4 => offset as u32 as u64,
+			_ => offset,
+		}
+	}
+
+	/// Gets the virtual address of the memory operand (segment base + [`effective_address()`])
+	///
+	/// [`effective_address()`]: #method.effective_address
+	///
+	/// # Arguments
+	///
+	/// * `element_index`: Only used if it's a vsib memory operand. This is the element index of the vector index register.
+	/// * `get_register_value`: Function that returns the value of a register or the base address of a segment register.
+	/// See [`Instruction::virtual_address()`].
+	///
+	/// [`Instruction::virtual_address()`]: struct.Instruction.html#method.virtual_address
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn virtual_address<F>(&self, element_index: usize, get_register_value: F) -> u64
+	where
+		F: Fn(Register, usize, usize) -> u64,
+	{
+		let offset = self.effective_address(element_index, &get_register_value);
+		get_register_value(self.segment, 0, 0).wrapping_add(offset)
+	}
+}
+
+#[cfg(feature = "encoder")]
+impl From<MemoryOperandInfo> for MemoryOperand {
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	fn from(info: MemoryOperandInfo) -> Self {
+		MemoryOperand::new(info.base, info.index, info.scale, info.displacement as i32, info.displ_size, info.is_broadcast, info.segment_prefix)
+	}
+}
+
+/// The [`memory_size`] is [`MemorySize::Unknown`] and [`vsib`] is `None` since they depend on the instruction
+///
+/// [`memory_size`]: struct.MemoryOperandInfo.html#structfield.memory_size
+/// [`MemorySize::Unknown`]: enum.MemorySize.html#variant.Unknown
+/// [`vsib`]: struct.MemoryOperandInfo.html#structfield.vsib
+#[cfg(feature = "encoder")]
+impl From<MemoryOperand> for MemoryOperandInfo {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from(memory: MemoryOperand) -> Self {
+		let address_size = get_address_size_in_bytes(memory.base, memory.index, memory.displ_size, CodeSize::Unknown);
+		let segment = if memory.segment_prefix != Register::None {
+			memory.segment_prefix
+		} else {
+			match memory.base {
+				Register::BP | Register::EBP | Register::ESP | Register::RBP | Register::RSP => Register::SS,
+				_ => Register::DS,
+			}
+		};
+		Self {
+			segment_prefix: memory.segment_prefix,
+			segment,
+			base: memory.base,
+			index: memory.index,
+			scale: memory.scale,
+			displacement: if address_size == 8 { memory.displacement as i64 as u64 } else { memory.displacement as u32 as u64 },
+			displ_size: memory.displ_size,
+			address_size,
+			is_broadcast: memory.is_broadcast,
+			memory_size: MemorySize::Unknown,
+			vsib: None,
+			next_ip: 0,
+		}
+	}
+}
+
+impl Instruction {
+	/// Gets a memory operand's base, index, displacement, etc. See also [`set_memory_operand_info()`].
+	///
+	/// [`set_memory_operand_info()`]: #method.set_memory_operand_info
+	///
+	/// Returns `None` if it's not a memory operand.
+	///
+	/// # Panics
+	///
+	/// Panics if `operand` is invalid
+	///
+	/// # Arguments
+	///
+	/// * `operand`: Operand number, 0-4
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // vpgatherdd ymm1{k2},[rax+ymm3*4+10h]
+	/// let bytes = b"\x62\xF2\x7D\x2A\x90\x4C\x98\x04";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	///
+	/// assert_eq!(None, instr.memory_operand_info(0));
+	/// let info = instr.memory_operand_info(1).unwrap();
+	/// assert_eq!(Register::DS, info.segment);
+	/// assert_eq!(Register::RAX, info.base);
+	/// assert_eq!(Register::YMM3, info.index);
+	/// assert_eq!(4, info.scale);
+	/// assert_eq!(0x10, info.displacement);
+	/// assert_eq!(8, info.address_size);
+	/// assert_eq!(MemorySize::Int32, info.memory_size);
+	/// assert_eq!(Some(false), info.vsib);
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn memory_operand_info(&self, operand: u32) -> Option<MemoryOperandInfo> {
+		let mut info = MemoryOperandInfo {
+			segment_prefix: self.segment_prefix(),
+			segment: self.memory_segment(),
+			base: Register::None,
+			index: Register::None,
+			scale: 1,
+			displacement: 0,
+			displ_size: 0,
+			address_size: 0,
+			is_broadcast: false,
+			memory_size: self.memory_size(),
+			vsib: None,
+			next_ip: self.next_ip(),
+		};
+		let (base, address_size) = match self.op_kind(operand) {
+			OpKind::Memory => {
+				info.base = self.memory_base();
+				info.index = self.memory_index();
+				info.scale = self.memory_index_scale();
+				info.displ_size = self.memory_displ_size();
+				info.address_size = get_address_size_in_bytes(info.base, info.index, info.displ_size, self.code_size());
+				info.displacement = if info.address_size == 8 { self.memory_displacement64() } else { self.memory_displacement() as u64 };
+				info.is_broadcast = self.is_broadcast();
+				info.vsib = self.vsib();
+				return Some(info);
+			}
+			OpKind::Memory64 => {
+				info.displacement = self.memory_address64();
+				info.displ_size = 8;
+				info.address_size = 8;
+				return Some(info);
+			}
+			OpKind::MemorySegSI => (Register::SI, 2),
+			OpKind::MemorySegESI => (Register::ESI, 4),
+			OpKind::MemorySegRSI => (Register::RSI, 8),
+			OpKind::MemorySegDI => (Register::DI, 2),
+			OpKind::MemorySegEDI => (Register::EDI, 4),
+			OpKind::MemorySegRDI => (Register::RDI, 8),
+			OpKind::MemoryESDI => (Register::DI, 2),
+			OpKind::MemoryESEDI => (Register::EDI, 4),
+			OpKind::MemoryESRDI => (Register::RDI, 8),
+			_ => return None,
+		};
+		match self.op_kind(operand) {
+			OpKind::MemoryESDI | OpKind::MemoryESEDI | OpKind::MemoryESRDI => {
+				// The segment can't be overridden
+				info.segment_prefix = Register::None;
+				info.segment = Register::ES;
+			}
+			_ => {}
+		}
+		info.base = base;
+		info.address_size = address_size;
+		Some(info)
+	}
+
+	/// Sets a memory operand's base, index, displacement, etc. See also [`memory_operand_info()`].
+	/// [`segment`], [`address_size`], [`memory_size`], [`vsib`] and [`next_ip`] are ignored.
+	///
+	/// If it's an [`OpKind::Memory64`] operand, only [`displacement`] (the address) and [`segment_prefix`] are used.
+	/// If it's a string instruction operand (eg. [`OpKind::MemorySegRSI`]), only [`segment_prefix`] is used
+	/// and only if the segment can be overridden.
+	///
+	/// [`memory_operand_info()`]: #method.memory_operand_info
+	/// [`segment`]: struct.MemoryOperandInfo.html#structfield.segment
+	/// [`address_size`]: struct.MemoryOperandInfo.html#structfield.address_size
+	/// [`memory_size`]: struct.MemoryOperandInfo.html#structfield.memory_size
+	/// [`vsib`]: struct.MemoryOperandInfo.html#structfield.vsib
+	/// [`next_ip`]: struct.MemoryOperandInfo.html#structfield.next_ip
+	/// [`displacement`]: struct.MemoryOperandInfo.html#structfield.displacement
+	/// [`segment_prefix`]: struct.MemoryOperandInfo.html#structfield.segment_prefix
+	/// [`OpKind::Memory64`]: enum.OpKind.html#variant.Memory64
+	/// [`OpKind::MemorySegRSI`]: enum.OpKind.html#variant.MemorySegRSI
+	///
+	/// # Panics
+	///
+	/// Panics if `operand` is invalid or if it's not a memory operand
+	///
+	/// # Arguments
+	///
+	/// * `operand`: Operand number, 0-4
+	/// * `info`: New memory operand
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // add [rdi+r12*8-5AA5EDCCh],esi
+	/// let bytes = b"\x42\x01\xB4\xE7\x34\x12\x5A\xA5";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let mut instr = decoder.decode();
+	///
+	/// let mut info = instr.memory_operand_info(0).unwrap();
+	/// info.index = Register::None;
+	/// info.displacement = 0x10;
+	/// info.displ_size = 1;
+	/// instr.set_memory_operand_info(0, &info);
+	/// assert_eq!(Register::RDI, instr.memory_base());
+	/// assert_eq!(Register::None, instr.memory_index());
+	/// assert_eq!(0x10, instr.memory_displacement());
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn set_memory_operand_info(&mut self, operand: u32, info: &MemoryOperandInfo) {
+		match self.op_kind(operand) {
+			OpKind::Memory => {
+				self.set_memory_base(info.base);
+				self.set_memory_index(info.index);
+				self.set_memory_index_scale(info.scale);
+				self.set_memory_displ_size(info.displ_size);
+				self.set_memory_displacement(info.displacement as u32);
+				self.set_is_broadcast(info.is_broadcast);
+				self.set_segment_prefix(info.segment_prefix);
+			}
+			OpKind::Memory64 => {
+				self.set_memory_address64(info.displacement);
+				self.set_segment_prefix(info.segment_prefix);
+			}
+			OpKind::MemorySegSI | OpKind::MemorySegESI | OpKind::MemorySegRSI | OpKind::MemorySegDI | OpKind::MemorySegEDI | OpKind::MemorySegRDI => {
+				self.set_segment_prefix(info.segment_prefix)
+			}
+			OpKind::MemoryESDI | OpKind::MemoryESEDI | OpKind::MemoryESRDI => {}
+			_ => panic!(),
+		}
+	}
+}
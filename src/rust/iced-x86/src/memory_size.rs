@@ -21,7 +21,11 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
-use core::fmt;
+use super::name_lookup;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::str::FromStr;
+use core::{fmt, mem};
 
 #[cfg(any(feature = "instr_info", feature = "encoder"))]
 pub use self::info::*;
@@ -654,7 +658,7 @@ pub enum MemorySize {
 	Broadcast512_2xBFloat16,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MEMORY_SIZE: [&str; 136] = [
+pub(crate) static GEN_DEBUG_MEMORY_SIZE: [&str; 136] = [
 	"Unknown",
 	"UInt8",
 	"UInt16",
@@ -808,6 +812,35 @@ impl Default for MemorySize {
 }
 // GENERATOR-END: MemorySize
 
+impl MemorySize {
+	/// Gets the name of this value. It's the same as the variant name, eg. `"UInt32"` for `MemorySize::UInt32`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!("UInt32", MemorySize::UInt32.as_str());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		GEN_DEBUG_MEMORY_SIZE[self as usize]
+	}
+}
+
+impl FromStr for MemorySize {
+	type Err = String;
+
+	/// Converts a name to a `MemorySize`. The name must match the variant name exactly, eg. `"UInt32"`.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match name_lookup::find(&GEN_DEBUG_MEMORY_SIZE, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u8) }),
+			None => Err(name_lookup::invalid_name("MemorySize", value)),
+		}
+	}
+}
+
 #[cfg(any(feature = "instr_info", feature = "encoder"))]
 impl MemorySize {
 	/// Gets the memory size info
@@ -21,7 +21,11 @@ TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
 SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
-use core::fmt;
+use super::name_lookup;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::str::FromStr;
+use core::{fmt, mem};
 
 // GENERATOR-BEGIN: Mnemonic
 // ⚠️This was generated by GENERATOR!🦹‍♂️
@@ -1570,7 +1574,7 @@ pub enum Mnemonic {
 	Xtest,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_MNEMONIC: [&str; 1538] = [
+pub(crate) static GEN_DEBUG_MNEMONIC: [&str; 1538] = [
 	"INVALID",
 	"Aaa",
 	"Aad",
@@ -3125,3 +3129,55 @@ impl Default for Mnemonic {
 	}
 }
 // GENERATOR-END: Mnemonic
+
+impl Mnemonic {
+	/// Gets the name of this value. It's the same as the variant name, eg. `"Vpgatherdd"` for `Mnemonic::Vpgatherdd`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!("Vpgatherdd", Mnemonic::Vpgatherdd.as_str());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		GEN_DEBUG_MNEMONIC[self as usize]
+	}
+
+	/// Converts a name to a `Mnemonic`, ignoring ASCII case. Use [`from_str()`] if the name must match exactly.
+	///
+	/// [`from_str()`]: #method.from_str
+	///
+	/// # Errors
+	///
+	/// Fails if `value` isn't a valid `Mnemonic` name
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Ok(Mnemonic::Vpgatherdd), Mnemonic::from_str_ignore_case("vpgatherdd"));
+	/// assert!(Mnemonic::from_str_ignore_case("xyz").is_err());
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn from_str_ignore_case(value: &str) -> Result<Self, String> {
+		match name_lookup::find_ignore_case(&GEN_DEBUG_MNEMONIC, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u16) }),
+			None => Err(name_lookup::invalid_name("Mnemonic", value)),
+		}
+	}
+}
+
+impl FromStr for Mnemonic {
+	type Err = String;
+
+	/// Converts a name to a `Mnemonic`. The name must match the variant name exactly, eg. `"Vpgatherdd"`.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match name_lookup::find(&GEN_DEBUG_MNEMONIC, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u16) }),
+			None => Err(name_lookup::invalid_name("Mnemonic", value)),
+		}
+	}
+}
@@ -0,0 +1,61 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// Returns the index of `value` in `names` or `None` if it's not a valid name
+#[cfg_attr(has_must_use, must_use)]
+pub(crate) fn find(names: &[&str], value: &str) -> Option<usize> {
+	names.iter().position(|&name| name == value)
+}
+
+/// Returns the index of `value` in `names`, ignoring ASCII case, or `None` if it's not a valid name
+#[cfg_attr(has_must_use, must_use)]
+pub(crate) fn find_ignore_case(names: &[&str], value: &str) -> Option<usize> {
+	names.iter().position(|&name| eq_ignore_ascii_case(name, value))
+}
+
+fn eq_ignore_ascii_case(a: &str, b: &str) -> bool {
+	a.len() == b.len() && a.bytes().zip(b.bytes()).all(|(a, b)| to_ascii_lower(a) == to_ascii_lower(b))
+}
+
+#[inline]
+fn to_ascii_lower(c: u8) -> u8 {
+	if b'A' <= c && c <= b'Z' {
+		c + (b'a' - b'A')
+	} else {
+		c
+	}
+}
+
+/// Creates the error message returned when `value` isn't a valid `type_name` name
+#[cfg_attr(has_must_use, must_use)]
+pub(crate) fn invalid_name(type_name: &str, value: &str) -> String {
+	let mut s = String::with_capacity(type_name.len() + value.len() + 16);
+	s.push_str("Invalid ");
+	s.push_str(type_name);
+	s.push_str(" value: ");
+	s.push_str(value);
+	s
+}
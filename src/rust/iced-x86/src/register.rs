@@ -22,9 +22,15 @@ SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
 use super::iced_constants::IcedConstants;
+use super::name_lookup;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 use core::fmt;
 use core::mem;
+#[cfg(feature = "instr_info")]
+use core::ops::Range;
 use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::str::FromStr;
 
 #[cfg(feature = "instr_info")]
 pub use self::info::*;
@@ -613,6 +619,204 @@ impl Register {
 	pub fn size(self) -> usize {
 		self.info().size()
 	}
+
+	/// Gets the bits of the [`full_register()`] that this register occupies, eg. `AH` -> `8..16`, `EAX` -> `0..32`,
+	/// `XMM3` -> `0..128`. [`Register::None`] returns an empty range.
+	///
+	/// [`full_register()`]: #method.full_register
+	/// [`Register::None`]: #variant.None
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(0..8, Register::AL.bit_range_in_full());
+	/// assert_eq!(8..16, Register::AH.bit_range_in_full());
+	/// assert_eq!(0..16, Register::R9W.bit_range_in_full());
+	/// assert_eq!(0..64, Register::RSP.bit_range_in_full());
+	/// assert_eq!(0..256, Register::YMM10.bit_range_in_full());
+	/// assert_eq!(0..80, Register::ST7.bit_range_in_full());
+	/// assert_eq!(0..0, Register::None.bit_range_in_full());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn bit_range_in_full(self) -> Range<u32> {
+		if Register::AH <= self && self <= Register::BH {
+			8..16
+		} else {
+			0..self.size() as u32 * 8
+		}
+	}
+
+	/// Checks if this register and `other` share at least one bit, eg. `AL` and `RAX` overlap but `AL` and `AH` don't.
+	/// [`Register::None`] doesn't overlap any register.
+	///
+	/// [`Register::None`]: #variant.None
+	///
+	/// # Arguments
+	///
+	/// * `other`: Other register
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert!(Register::AH.overlaps(Register::AX));
+	/// assert!(Register::R8D.overlaps(Register::R8L));
+	/// assert!(Register::XMM5.overlaps(Register::ZMM5));
+	/// assert!(Register::EIP.overlaps(Register::RIP));
+	/// assert!(!Register::AH.overlaps(Register::AL));
+	/// assert!(!Register::EAX.overlaps(Register::ECX));
+	/// assert!(!Register::XMM5.overlaps(Register::YMM6));
+	/// assert!(!Register::None.overlaps(Register::None));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn overlaps(self, other: Register) -> bool {
+		if self == Register::None || self.full_register() != other.full_register() {
+			return false;
+		}
+		let range1 = self.bit_range_in_full();
+		let range2 = other.bit_range_in_full();
+		range1.start < range2.end && range2.start < range1.end
+	}
+
+	/// Gets the register with the same [`full_register()`] and the given size, eg. (`RAX`, 1, `false`) -> `AL`,
+	/// (`RCX`, 1, `true`) -> `CH`, (`R9`, 4, `false`) -> `R9D`, (`ZMM5`, 16, `false`) -> `XMM5`.
+	/// Registers without sub-registers (eg. `ES`, `K1`, `ST0`) only support their own size.
+	///
+	/// [`full_register()`]: #method.full_register
+	///
+	/// Returns `None` if there's no such register, eg. (`RSI`, 1, `true`) or (`RAX`, 16, `false`).
+	///
+	/// # Arguments
+	///
+	/// * `size`: Size of the register in bytes, see [`size()`]
+	/// * `high_byte`: `true` to get the high 8-bit register (`AH`, `CH`, `DH`, `BH`). `size` must be 1.
+	///
+	/// [`size()`]: #method.size
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Some(Register::AL), Register::RAX.with_size(1, false));
+	/// assert_eq!(Some(Register::BH), Register::EBX.with_size(1, true));
+	/// assert_eq!(Some(Register::SIL), Register::SI.with_size(1, false));
+	/// assert_eq!(Some(Register::R12W), Register::R12L.with_size(2, false));
+	/// assert_eq!(Some(Register::RDX), Register::DH.with_size(8, false));
+	/// assert_eq!(Some(Register::EIP), Register::RIP.with_size(4, false));
+	/// assert_eq!(Some(Register::YMM7), Register::XMM7.with_size(32, false));
+	/// assert_eq!(Some(Register::K1), Register::K1.with_size(8, false));
+	/// assert_eq!(None, Register::SI.with_size(1, true));
+	/// assert_eq!(None, Register::EAX.with_size(16, false));
+	/// assert_eq!(None, Register::XMM0.with_size(8, false));
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn with_size(self, size: usize, high_byte: bool) -> Option<Register> {
+		let full_register = self.full_register();
+		if high_byte && size != 1 {
+			return None;
+		}
+		if full_register.is_gpr64() {
+			let number = full_register.number() as u32;
+			match size {
+				1 if high_byte => {
+					if number < 4 {
+						Some(Register::AH + number)
+					} else {
+						None
+					}
+				}
+				// AH, CH, DH, BH are between BL and SPL
+				1 if number < 4 => Some(Register::AL + number),
+				1 => Some(Register::AL + number + 4),
+				2 => Some(Register::AX + number),
+				4 => Some(Register::EAX + number),
+				8 => Some(full_register),
+				_ => None,
+			}
+		} else if full_register.is_zmm() {
+			let number = full_register.number() as u32;
+			match size {
+				16 => Some(Register::XMM0 + number),
+				32 => Some(Register::YMM0 + number),
+				64 => Some(full_register),
+				_ => None,
+			}
+		} else if full_register == Register::RIP {
+			match size {
+				4 => Some(Register::EIP),
+				8 => Some(Register::RIP),
+				_ => None,
+			}
+		} else if full_register != Register::None && full_register.size() == size && !high_byte {
+			Some(full_register)
+		} else {
+			None
+		}
+	}
+
+	/// Gets the `XMM` register with the same number if it's an `XMM`, `YMM` or `ZMM` register, eg. `ZMM5` -> `XMM5`
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Some(Register::XMM5), Register::ZMM5.to_xmm());
+	/// assert_eq!(Some(Register::XMM31), Register::YMM31.to_xmm());
+	/// assert_eq!(None, Register::MM5.to_xmm());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn to_xmm(self) -> Option<Register> {
+		if self.is_vector_register() {
+			self.with_size(16, false)
+		} else {
+			None
+		}
+	}
+
+	/// Gets the `YMM` register with the same number if it's an `XMM`, `YMM` or `ZMM` register, eg. `XMM5` -> `YMM5`
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Some(Register::YMM5), Register::XMM5.to_ymm());
+	/// assert_eq!(Some(Register::YMM31), Register::ZMM31.to_ymm());
+	/// assert_eq!(None, Register::MM5.to_ymm());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn to_ymm(self) -> Option<Register> {
+		if self.is_vector_register() {
+			self.with_size(32, false)
+		} else {
+			None
+		}
+	}
+
+	/// Gets the `ZMM` register with the same number if it's an `XMM`, `YMM` or `ZMM` register, eg. `XMM5` -> `ZMM5`
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Some(Register::ZMM5), Register::XMM5.to_zmm());
+	/// assert_eq!(Some(Register::ZMM31), Register::YMM31.to_zmm());
+	/// assert_eq!(None, Register::MM5.to_zmm());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn to_zmm(self) -> Option<Register> {
+		if self.is_vector_register() {
+			self.with_size(64, false)
+		} else {
+			None
+		}
+	}
 }
 
 #[cfg(any(feature = "instr_info", feature = "encoder"))]
@@ -1170,7 +1374,7 @@ pub enum Register {
 	TR7,
 }
 #[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
-static GEN_DEBUG_REGISTER: [&str; 241] = [
+pub(crate) static GEN_DEBUG_REGISTER: [&str; 241] = [
 	"None",
 	"AL",
 	"CL",
@@ -1429,6 +1633,58 @@ impl Default for Register {
 }
 // GENERATOR-END: Register
 
+impl Register {
+	/// Gets the name of this value. It's the same as the variant name, eg. `"RAX"` for `Register::RAX`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!("RAX", Register::RAX.as_str());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		GEN_DEBUG_REGISTER[self as usize]
+	}
+
+	/// Converts a name to a `Register`, ignoring ASCII case. Use [`from_str()`] if the name must match exactly.
+	///
+	/// [`from_str()`]: #method.from_str
+	///
+	/// # Errors
+	///
+	/// Fails if `value` isn't a valid `Register` name
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	/// assert_eq!(Ok(Register::RAX), Register::from_str_ignore_case("rax"));
+	/// assert!(Register::from_str_ignore_case("xyz").is_err());
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn from_str_ignore_case(value: &str) -> Result<Self, String> {
+		match name_lookup::find_ignore_case(&GEN_DEBUG_REGISTER, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u8) }),
+			None => Err(name_lookup::invalid_name("Register", value)),
+		}
+	}
+}
+
+impl FromStr for Register {
+	type Err = String;
+
+	/// Converts a name to a `Register`. The name must match the variant name exactly, eg. `"RAX"`.
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match name_lookup::find(&GEN_DEBUG_REGISTER, value) {
+			Some(index) => Ok(unsafe { mem::transmute(index as u8) }),
+			None => Err(name_lookup::invalid_name("Register", value)),
+		}
+	}
+}
+
 impl Register {
 	#[cfg_attr(has_must_use, must_use)]
 	fn add(self, rhs: u32) -> Self {
@@ -0,0 +1,608 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! `serde` support. Enums are serialized as their names and structs as tuples with a fixed field order so the
+//! serialized data doesn't depend on the enum values or on the internal layout of the structs.
+
+#[cfg(test)]
+mod tests;
+
+use super::iced_constants::IcedConstants;
+use super::name_lookup;
+use super::*;
+use core::{fmt, mem};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTupleStruct, Serializer};
+
+struct EnumVisitor {
+	name: &'static str,
+	names: &'static [&'static str],
+}
+
+impl<'de> Visitor<'de> for EnumVisitor {
+	type Value = usize;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "a {} name", self.name)
+	}
+
+	fn visit_str<E>(self, value: &str) -> Result<usize, E>
+	where
+		E: de::Error,
+	{
+		match name_lookup::find(self.names, value) {
+			Some(index) => Ok(index),
+			None => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+		}
+	}
+}
+
+fn deserialize_enum<'de, D>(deserializer: D, name: &'static str, names: &'static [&'static str]) -> Result<usize, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	deserializer.deserialize_str(EnumVisitor { name, names })
+}
+
+fn next_element<'de, A, T>(seq: &mut A, index: usize, expected: &de::Expected) -> Result<T, A::Error>
+where
+	A: SeqAccess<'de>,
+	T: Deserialize<'de>,
+{
+	match seq.next_element()? {
+		Some(value) => Ok(value),
+		None => Err(de::Error::invalid_length(index, expected)),
+	}
+}
+
+// Enums are serialized as their names so the values don't change when new enum values are added
+macro_rules! serde_enum {
+	($(#[$attr:meta])* $ty:ident, $names:expr, $repr:ty) => {
+		$(#[$attr])*
+		impl Serialize for $ty {
+			#[inline]
+			fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+			where
+				S: Serializer,
+			{
+				serializer.serialize_str($names[*self as usize])
+			}
+		}
+
+		$(#[$attr])*
+		impl<'de> Deserialize<'de> for $ty {
+			#[inline]
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+			where
+				D: Deserializer<'de>,
+			{
+				let index = deserialize_enum(deserializer, stringify!($ty), &$names)?;
+				Ok(unsafe { mem::transmute(index as $repr) })
+			}
+		}
+	};
+}
+
+serde_enum!(Code, code::GEN_DEBUG_CODE, u16);
+serde_enum!(CodeSize, enums::GEN_DEBUG_CODE_SIZE, u8);
+serde_enum!(
+	#[cfg(feature = "instr_info")]
+	ConditionCode,
+	enums::GEN_DEBUG_CONDITION_CODE,
+	u8
+);
+serde_enum!(
+	#[cfg(feature = "instr_info")]
+	CpuidFeature,
+	enums::GEN_DEBUG_CPUID_FEATURE,
+	u8
+);
+serde_enum!(
+	#[cfg(any(feature = "decoder", feature = "encoder", feature = "instr_info"))]
+	EncodingKind,
+	enums::GEN_DEBUG_ENCODING_KIND,
+	u8
+);
+serde_enum!(
+	#[cfg(feature = "instr_info")]
+	FlowControl,
+	enums::GEN_DEBUG_FLOW_CONTROL,
+	u8
+);
+serde_enum!(MemorySize, memory_size::GEN_DEBUG_MEMORY_SIZE, u8);
+serde_enum!(Mnemonic, mnemonic::GEN_DEBUG_MNEMONIC, u16);
+serde_enum!(
+	#[cfg(feature = "instr_info")]
+	OpAccess,
+	enums::GEN_DEBUG_OP_ACCESS,
+	u8
+);
+serde_enum!(OpKind, enums::GEN_DEBUG_OP_KIND, u8);
+serde_enum!(Register, register::GEN_DEBUG_REGISTER, u8);
+serde_enum!(RoundingControl, enums::GEN_DEBUG_ROUNDING_CONTROL, u8);
+
+// A deserialized variable length sequence with at most `CAPACITY` elements
+const CAPACITY: usize = 16;
+struct FixedSeq<T> {
+	values: [T; CAPACITY],
+	len: usize,
+}
+
+struct FixedSeqVisitor<T> {
+	default: T,
+}
+
+impl<'de, T> Visitor<'de> for FixedSeqVisitor<T>
+where
+	T: Copy + Deserialize<'de>,
+{
+	type Value = FixedSeq<T>;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "a sequence with at most {} elements", CAPACITY)
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<FixedSeq<T>, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut result = FixedSeq { values: [self.default; CAPACITY], len: 0 };
+		while let Some(value) = seq.next_element()? {
+			if result.len == CAPACITY {
+				return Err(de::Error::invalid_length(result.len + 1, &self));
+			}
+			result.values[result.len] = value;
+			result.len += 1;
+		}
+		Ok(result)
+	}
+}
+
+impl<'de, T> Deserialize<'de> for FixedSeq<T>
+where
+	T: Copy + Default + Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_seq(FixedSeqVisitor { default: T::default() })
+	}
+}
+
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+impl Serialize for ConstantOffsets {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_tuple_struct("ConstantOffsets", 6)?;
+		state.serialize_field(&self.displacement_offset)?;
+		state.serialize_field(&self.displacement_size)?;
+		state.serialize_field(&self.immediate_offset)?;
+		state.serialize_field(&self.immediate_size)?;
+		state.serialize_field(&self.immediate_offset2)?;
+		state.serialize_field(&self.immediate_size2)?;
+		state.end()
+	}
+}
+
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+struct ConstantOffsetsVisitor;
+
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+impl<'de> Visitor<'de> for ConstantOffsetsVisitor {
+	type Value = ConstantOffsets;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a ConstantOffsets tuple")
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<ConstantOffsets, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut offsets = ConstantOffsets::default();
+		offsets.displacement_offset = next_element(&mut seq, 0, &self)?;
+		offsets.displacement_size = next_element(&mut seq, 1, &self)?;
+		offsets.immediate_offset = next_element(&mut seq, 2, &self)?;
+		offsets.immediate_size = next_element(&mut seq, 3, &self)?;
+		offsets.immediate_offset2 = next_element(&mut seq, 4, &self)?;
+		offsets.immediate_size2 = next_element(&mut seq, 5, &self)?;
+		Ok(offsets)
+	}
+}
+
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+impl<'de> Deserialize<'de> for ConstantOffsets {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_tuple_struct("ConstantOffsets", 6, ConstantOffsetsVisitor)
+	}
+}
+
+#[cfg(feature = "instr_info")]
+impl Serialize for UsedRegister {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_tuple_struct("UsedRegister", 2)?;
+		state.serialize_field(&self.register())?;
+		state.serialize_field(&self.access())?;
+		state.end()
+	}
+}
+
+#[cfg(feature = "instr_info")]
+struct UsedRegisterVisitor;
+
+#[cfg(feature = "instr_info")]
+impl<'de> Visitor<'de> for UsedRegisterVisitor {
+	type Value = UsedRegister;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a UsedRegister tuple")
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<UsedRegister, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let register = next_element(&mut seq, 0, &self)?;
+		let access = next_element(&mut seq, 1, &self)?;
+		Ok(UsedRegister::new(register, access))
+	}
+}
+
+#[cfg(feature = "instr_info")]
+impl<'de> Deserialize<'de> for UsedRegister {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_tuple_struct("UsedRegister", 2, UsedRegisterVisitor)
+	}
+}
+
+#[cfg(feature = "instr_info")]
+impl Serialize for UsedMemory {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_tuple_struct("UsedMemory", 7)?;
+		state.serialize_field(&self.segment())?;
+		state.serialize_field(&self.base())?;
+		state.serialize_field(&self.index())?;
+		state.serialize_field(&(self.scale() as u8))?;
+		state.serialize_field(&self.displacement())?;
+		state.serialize_field(&self.memory_size())?;
+		state.serialize_field(&self.access())?;
+		state.end()
+	}
+}
+
+#[cfg(feature = "instr_info")]
+struct UsedMemoryVisitor;
+
+#[cfg(feature = "instr_info")]
+impl<'de> Visitor<'de> for UsedMemoryVisitor {
+	type Value = UsedMemory;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a UsedMemory tuple")
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<UsedMemory, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let segment = next_element(&mut seq, 0, &self)?;
+		let base = next_element(&mut seq, 1, &self)?;
+		let index = next_element(&mut seq, 2, &self)?;
+		let scale: u8 = next_element(&mut seq, 3, &self)?;
+		let displacement = next_element(&mut seq, 4, &self)?;
+		let memory_size = next_element(&mut seq, 5, &self)?;
+		let access = next_element(&mut seq, 6, &self)?;
+		Ok(UsedMemory::new(segment, base, index, scale as u32, displacement, memory_size, access))
+	}
+}
+
+#[cfg(feature = "instr_info")]
+impl<'de> Deserialize<'de> for UsedMemory {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_tuple_struct("UsedMemory", 7, UsedMemoryVisitor)
+	}
+}
+
+struct PrefixBits;
+impl PrefixBits {
+	const XACQUIRE: u8 = 0x01;
+	const XRELEASE: u8 = 0x02;
+	const REPE: u8 = 0x04;
+	const REPNE: u8 = 0x08;
+	const LOCK: u8 = 0x10;
+}
+
+const INSTRUCTION_FIELDS: usize = 21;
+
+#[inline]
+fn declare_element_size(code: Code) -> usize {
+	match code {
+		Code::DeclareByte => 1,
+		Code::DeclareWord => 2,
+		Code::DeclareDword => 4,
+		Code::DeclareQword => 8,
+		_ => 0,
+	}
+}
+
+// Fields (in order): ip, len, code_size, code, op_kinds, op_registers, prefixes, segment_prefix, memory_base, memory_index,
+// memory_index_scale, memory_displacement, memory_displ_size, is_broadcast, immediate, immediate2, op_mask, zeroing_masking,
+// rounding_control, suppress_all_exceptions, declare_data.
+//
+// `immediate` is the immediate, branch target or `Memory64` address and `immediate2` is the second 8-bit immediate or
+// the far branch selector. `declare_data` contains the little endian bytes of a `db`/`dw`/`dd`/`dq` directive.
+impl Serialize for Instruction {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let code = self.code();
+		let mut op_kinds = [OpKind::default(); IcedConstants::MAX_OP_COUNT];
+		let mut op_registers = [Register::None; IcedConstants::MAX_OP_COUNT];
+		let mut op_count = 0;
+		let mut immediate = 0;
+		let mut immediate2 = 0;
+		let mut declare_data = [0u8; CAPACITY];
+		let mut declare_len = 0;
+		// The db/dw/dd/dq bytes are stored in the operand and memory fields
+		let mut memory_base = Register::None;
+		let mut memory_index = Register::None;
+		let mut memory_displacement = 0;
+		let element_size = declare_element_size(code);
+		if element_size != 0 {
+			declare_len = self.declare_data_len() * element_size;
+			for (i, b) in declare_data.iter_mut().enumerate().take(declare_len) {
+				*b = self.get_declare_byte_value(i);
+			}
+		} else {
+			memory_base = self.memory_base();
+			memory_index = self.memory_index();
+			memory_displacement = self.memory_displacement();
+			op_count = self.op_count() as usize;
+			for i in 0..op_count {
+				let op_kind = self.op_kind(i as u32);
+				op_kinds[i] = op_kind;
+				match op_kind {
+					OpKind::Register => op_registers[i] = self.op_register(i as u32),
+					OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => immediate = self.near_branch_target(),
+					OpKind::FarBranch16 => {
+						immediate = self.far_branch16() as u64;
+						immediate2 = self.far_branch_selector() as u64;
+					}
+					OpKind::FarBranch32 => {
+						immediate = self.far_branch32() as u64;
+						immediate2 = self.far_branch_selector() as u64;
+					}
+					OpKind::Immediate8_2nd => immediate2 = self.immediate8_2nd() as u64,
+					OpKind::Immediate8
+					| OpKind::Immediate16
+					| OpKind::Immediate32
+					| OpKind::Immediate64
+					| OpKind::Immediate8to16
+					| OpKind::Immediate8to32
+					| OpKind::Immediate8to64
+					| OpKind::Immediate32to64 => immediate = self.immediate(i as u32),
+					OpKind::Memory64 => immediate = self.memory_address64(),
+					_ => {}
+				}
+			}
+		}
+
+		let mut prefixes = 0;
+		if self.has_xacquire_prefix() {
+			prefixes |= PrefixBits::XACQUIRE;
+		}
+		if self.has_xrelease_prefix() {
+			prefixes |= PrefixBits::XRELEASE;
+		}
+		if self.has_repe_prefix() {
+			prefixes |= PrefixBits::REPE;
+		}
+		if self.has_repne_prefix() {
+			prefixes |= PrefixBits::REPNE;
+		}
+		if self.has_lock_prefix() {
+			prefixes |= PrefixBits::LOCK;
+		}
+
+		let mut state = serializer.serialize_tuple_struct("Instruction", INSTRUCTION_FIELDS)?;
+		state.serialize_field(&self.ip())?;
+		state.serialize_field(&(self.len() as u8))?;
+		state.serialize_field(&self.code_size())?;
+		state.serialize_field(&code)?;
+		state.serialize_field(&op_kinds[..op_count])?;
+		state.serialize_field(&op_registers[..op_count])?;
+		state.serialize_field(&prefixes)?;
+		state.serialize_field(&self.segment_prefix())?;
+		state.serialize_field(&memory_base)?;
+		state.serialize_field(&memory_index)?;
+		state.serialize_field(&(self.memory_index_scale() as u8))?;
+		state.serialize_field(&memory_displacement)?;
+		state.serialize_field(&(self.memory_displ_size() as u8))?;
+		state.serialize_field(&self.is_broadcast())?;
+		state.serialize_field(&immediate)?;
+		state.serialize_field(&immediate2)?;
+		state.serialize_field(&self.op_mask())?;
+		state.serialize_field(&self.zeroing_masking())?;
+		state.serialize_field(&self.rounding_control())?;
+		state.serialize_field(&self.suppress_all_exceptions())?;
+		state.serialize_field(&declare_data[..declare_len])?;
+		state.end()
+	}
+}
+
+struct InstructionVisitor;
+
+impl<'de> Visitor<'de> for InstructionVisitor {
+	type Value = Instruction;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("an Instruction tuple")
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Instruction, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let ip: u64 = next_element(&mut seq, 0, &self)?;
+		let len: u8 = next_element(&mut seq, 1, &self)?;
+		let code_size: CodeSize = next_element(&mut seq, 2, &self)?;
+		let code: Code = next_element(&mut seq, 3, &self)?;
+		let op_kinds: FixedSeq<OpKind> = next_element(&mut seq, 4, &self)?;
+		let op_registers: FixedSeq<Register> = next_element(&mut seq, 5, &self)?;
+		let prefixes: u8 = next_element(&mut seq, 6, &self)?;
+		let segment_prefix: Register = next_element(&mut seq, 7, &self)?;
+		let memory_base: Register = next_element(&mut seq, 8, &self)?;
+		let memory_index: Register = next_element(&mut seq, 9, &self)?;
+		let memory_index_scale: u8 = next_element(&mut seq, 10, &self)?;
+		let memory_displacement: u32 = next_element(&mut seq, 11, &self)?;
+		let memory_displ_size: u8 = next_element(&mut seq, 12, &self)?;
+		let is_broadcast: bool = next_element(&mut seq, 13, &self)?;
+		let immediate: u64 = next_element(&mut seq, 14, &self)?;
+		let immediate2: u64 = next_element(&mut seq, 15, &self)?;
+		let op_mask: Register = next_element(&mut seq, 16, &self)?;
+		let zeroing_masking: bool = next_element(&mut seq, 17, &self)?;
+		let rounding_control: RoundingControl = next_element(&mut seq, 18, &self)?;
+		let suppress_all_exceptions: bool = next_element(&mut seq, 19, &self)?;
+		let declare_data: FixedSeq<u8> = next_element(&mut seq, 20, &self)?;
+
+		if len as usize > IcedConstants::MAX_INSTRUCTION_LENGTH {
+			return Err(de::Error::invalid_value(de::Unexpected::Unsigned(len as u64), &"a valid instruction length"));
+		}
+		match memory_index_scale {
+			1 | 2 | 4 | 8 => {}
+			_ => return Err(de::Error::invalid_value(de::Unexpected::Unsigned(memory_index_scale as u64), &"1, 2, 4 or 8")),
+		}
+		if memory_displ_size > 8 {
+			return Err(de::Error::invalid_value(de::Unexpected::Unsigned(memory_displ_size as u64), &"a valid displacement size"));
+		}
+
+		let mut instruction = Instruction::default();
+		instruction.set_code(code);
+		instruction.set_code_size(code_size);
+		instruction.set_len(len as usize);
+		instruction.set_ip(ip);
+		instruction.set_has_xacquire_prefix((prefixes & PrefixBits::XACQUIRE) != 0);
+		instruction.set_has_xrelease_prefix((prefixes & PrefixBits::XRELEASE) != 0);
+		instruction.set_has_repe_prefix((prefixes & PrefixBits::REPE) != 0);
+		instruction.set_has_repne_prefix((prefixes & PrefixBits::REPNE) != 0);
+		instruction.set_has_lock_prefix((prefixes & PrefixBits::LOCK) != 0);
+		instruction.set_segment_prefix(segment_prefix);
+		instruction.set_memory_base(memory_base);
+		instruction.set_memory_index(memory_index);
+		instruction.set_memory_index_scale(memory_index_scale as u32);
+		instruction.set_memory_displacement(memory_displacement);
+		instruction.set_memory_displ_size(memory_displ_size as u32);
+		instruction.set_is_broadcast(is_broadcast);
+		instruction.set_op_mask(op_mask);
+		instruction.set_zeroing_masking(zeroing_masking);
+		instruction.set_rounding_control(rounding_control);
+		instruction.set_suppress_all_exceptions(suppress_all_exceptions);
+
+		let element_size = declare_element_size(code);
+		if element_size != 0 {
+			if declare_data.len == 0 || declare_data.len % element_size != 0 {
+				return Err(de::Error::invalid_length(declare_data.len, &"a multiple of the db/dw/dd/dq element size"));
+			}
+			instruction.set_declare_data_len(declare_data.len / element_size);
+			for i in 0..declare_data.len {
+				instruction.set_declare_byte_value(i, declare_data.values[i]);
+			}
+			return Ok(instruction);
+		}
+
+		let op_count = instruction.op_count() as usize;
+		if op_kinds.len != op_count || op_registers.len != op_count {
+			return Err(de::Error::invalid_length(op_kinds.len, &"one element per operand"));
+		}
+		for i in 0..op_count {
+			let op_kind = op_kinds.values[i];
+			// Operand #4 can only be an imm8, `set_op4_kind()` panics if it's something else
+			if i == 4 && op_kind != OpKind::Immediate8 {
+				return Err(de::Error::invalid_value(de::Unexpected::Str(enums::GEN_DEBUG_OP_KIND[op_kind as usize]), &"Immediate8"));
+			}
+			instruction.set_op_kind(i as u32, op_kind);
+			match op_kind {
+				OpKind::Register => instruction.set_op_register(i as u32, op_registers.values[i]),
+				OpKind::NearBranch16 => instruction.set_near_branch16(immediate as u16),
+				OpKind::NearBranch32 => instruction.set_near_branch32(immediate as u32),
+				OpKind::NearBranch64 => instruction.set_near_branch64(immediate),
+				OpKind::FarBranch16 => {
+					instruction.set_far_branch16(immediate as u16);
+					instruction.set_far_branch_selector(immediate2 as u16);
+				}
+				OpKind::FarBranch32 => {
+					instruction.set_far_branch32(immediate as u32);
+					instruction.set_far_branch_selector(immediate2 as u16);
+				}
+				OpKind::Immediate8_2nd => instruction.set_immediate8_2nd(immediate2 as u8),
+				OpKind::Immediate8
+				| OpKind::Immediate16
+				| OpKind::Immediate32
+				| OpKind::Immediate64
+				| OpKind::Immediate8to16
+				| OpKind::Immediate8to32
+				| OpKind::Immediate8to64
+				| OpKind::Immediate32to64 => instruction.set_immediate_u64(i as u32, immediate),
+				OpKind::Memory64 => instruction.set_memory_address64(immediate),
+				_ => {}
+			}
+		}
+		Ok(instruction)
+	}
+}
+
+impl<'de> Deserialize<'de> for Instruction {
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_tuple_struct("Instruction", INSTRUCTION_FIELDS, InstructionVisitor)
+	}
+}
@@ -0,0 +1,193 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod value;
+
+use self::value::*;
+#[cfg(feature = "decoder")]
+use super::super::decoder::tests::test_utils::decoder_tests;
+#[cfg(feature = "encoder")]
+use super::super::encoder::tests::non_decoded_tests;
+use super::super::iced_constants::IcedConstants;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::create_decoder;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::*;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[test]
+fn enums_are_serialized_as_names() {
+	assert_eq!(Value::Str(String::from("Add_rm32_r32")), to_value(&Code::Add_rm32_r32));
+	assert_eq!(Value::Str(String::from("RAX")), to_value(&Register::RAX));
+	assert_eq!(Value::Str(String::from("Vpgatherdd")), to_value(&Mnemonic::Vpgatherdd));
+	assert_eq!(Value::Str(String::from("UInt32")), to_value(&MemorySize::UInt32));
+	assert_eq!(Value::Str(String::from("Memory")), to_value(&OpKind::Memory));
+	assert_eq!(Value::Str(String::from("Code64")), to_value(&CodeSize::Code64));
+	#[cfg(feature = "instr_info")]
+	assert_eq!(Value::Str(String::from("AVX512F")), to_value(&CpuidFeature::AVX512F));
+
+	assert_eq!(Code::Add_rm32_r32, from_value(Value::Str(String::from("Add_rm32_r32"))).unwrap());
+	assert_eq!(Register::RAX, from_value(Value::Str(String::from("RAX"))).unwrap());
+	assert_eq!(Mnemonic::Vpgatherdd, from_value(Value::Str(String::from("Vpgatherdd"))).unwrap());
+	assert_eq!(MemorySize::UInt32, from_value(Value::Str(String::from("UInt32"))).unwrap());
+	assert_eq!(OpKind::Memory, from_value(Value::Str(String::from("Memory"))).unwrap());
+	#[cfg(feature = "instr_info")]
+	assert_eq!(CpuidFeature::AVX512F, from_value(Value::Str(String::from("AVX512F"))).unwrap());
+}
+
+#[test]
+fn all_enum_values_round_trip() {
+	for i in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+		let code: Code = unsafe { core::mem::transmute(i as u16) };
+		assert_eq!(code, from_value(to_value(&code)).unwrap());
+	}
+	for i in 0..IcedConstants::NUMBER_OF_REGISTERS {
+		let register: Register = unsafe { core::mem::transmute(i as u8) };
+		assert_eq!(register, from_value(to_value(&register)).unwrap());
+	}
+	for i in 0..mnemonic::GEN_DEBUG_MNEMONIC.len() {
+		let mnemonic: Mnemonic = unsafe { core::mem::transmute(i as u16) };
+		assert_eq!(mnemonic, from_value(to_value(&mnemonic)).unwrap());
+	}
+	for i in 0..IcedConstants::NUMBER_OF_MEMORY_SIZES {
+		let memory_size: MemorySize = unsafe { core::mem::transmute(i as u8) };
+		assert_eq!(memory_size, from_value(to_value(&memory_size)).unwrap());
+	}
+}
+
+#[test]
+fn invalid_enum_names_are_rejected() {
+	assert!(from_value::<Code>(Value::Str(String::from("add_rm32_r32"))).is_err());
+	assert!(from_value::<Register>(Value::Str(String::new())).is_err());
+	assert!(from_value::<Mnemonic>(Value::U64(5)).is_err());
+}
+
+#[test]
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+fn constant_offsets_round_trip() {
+	let mut offsets = ConstantOffsets::default();
+	offsets.displacement_offset = 2;
+	offsets.displacement_size = 4;
+	offsets.immediate_offset = 6;
+	offsets.immediate_size = 1;
+	let value = to_value(&offsets);
+	assert_eq!("[2,4,6,1,0,0]", value.to_string());
+	assert_eq!(offsets, from_value(value).unwrap());
+}
+
+#[test]
+#[cfg(feature = "instr_info")]
+fn used_register_and_memory_round_trip() {
+	let reg = UsedRegister::new(Register::ECX, OpAccess::ReadWrite);
+	let value = to_value(&reg);
+	assert_eq!("[\"ECX\",\"ReadWrite\"]", value.to_string());
+	assert_eq!(reg, from_value(value).unwrap());
+
+	let mem = UsedMemory::new(Register::DS, Register::RAX, Register::RCX, 4, 0x10, MemorySize::UInt64, OpAccess::Read);
+	let value = to_value(&mem);
+	assert_eq!("[\"DS\",\"RAX\",\"RCX\",4,16,\"UInt64\",\"Read\"]", value.to_string());
+	assert_eq!(mem, from_value(value).unwrap());
+}
+
+#[test]
+#[cfg(feature = "decoder")]
+fn decoded_instructions_round_trip() {
+	for info in decoder_tests(true, false) {
+		let bytes = to_vec_u8(info.hex_bytes()).unwrap();
+		let mut decoder = create_decoder(info.bitness(), &bytes, info.decoder_options()).0;
+		let instruction = decoder.decode();
+		let value = to_value(&instruction);
+		let instruction2: Instruction = from_value(value.clone()).unwrap();
+		assert!(instruction.eq_all_bits(&instruction2), "{} {}", info.hex_bytes(), value);
+	}
+}
+
+#[test]
+#[cfg(feature = "encoder")]
+fn non_decoded_instructions_round_trip() {
+	for (_, hex_bytes, instruction) in non_decoded_tests::get_tests() {
+		let value = to_value(&instruction);
+		let instruction2: Instruction = from_value(value.clone()).unwrap();
+		assert!(instruction.eq_all_bits(&instruction2), "{} {}", hex_bytes, value);
+	}
+}
+
+#[test]
+#[cfg(feature = "encoder")]
+fn invalid_instructions_are_rejected() {
+	let instruction = Instruction::with_reg_reg(Code::Add_rm32_r32, Register::ECX, Register::EDX);
+	let fields = match to_value(&instruction) {
+		Value::Seq(fields) => fields,
+		value => panic!("{}", value),
+	};
+	assert_eq!(Value::Str(String::from("Add_rm32_r32")), fields[3]);
+
+	// Wrong number of operands
+	let mut bad_fields = fields.clone();
+	bad_fields[4] = Value::Seq(vec![Value::Str(String::from("Register"))]);
+	assert!(from_value::<Instruction>(Value::Seq(bad_fields)).is_err());
+	// Invalid scale
+	let mut bad_fields = fields.clone();
+	bad_fields[10] = Value::U64(3);
+	assert!(from_value::<Instruction>(Value::Seq(bad_fields)).is_err());
+	// Missing field
+	assert!(from_value::<Instruction>(Value::Seq(fields[..4].to_vec())).is_err());
+
+	let instruction2: Instruction = from_value(Value::Seq(fields)).unwrap();
+	assert_eq!(Register::ECX, instruction2.op0_register());
+	assert_eq!(Register::EDX, instruction2.op1_register());
+}
+
+#[test]
+#[cfg(feature = "encoder")]
+fn invalid_op4_kind_is_rejected() {
+	let instruction = Instruction::with_reg_reg_reg_reg_u32(
+		Code::VEX_Vpermil2ps_xmm_xmm_xmmm128_xmm_imm2,
+		Register::XMM1,
+		Register::XMM2,
+		Register::XMM3,
+		Register::XMM4,
+		1,
+	);
+	let mut fields = match to_value(&instruction) {
+		Value::Seq(fields) => fields,
+		value => panic!("{}", value),
+	};
+	let op_kinds = vec![
+		Value::Str(String::from("Register")),
+		Value::Str(String::from("Register")),
+		Value::Str(String::from("Register")),
+		Value::Str(String::from("Register")),
+		Value::Str(String::from("Immediate8")),
+	];
+	assert_eq!(Value::Seq(op_kinds.clone()), fields[4]);
+	let instruction2: Instruction = from_value(Value::Seq(fields.clone())).unwrap();
+	assert!(instruction.eq_all_bits(&instruction2));
+
+	let mut bad_op_kinds = op_kinds;
+	bad_op_kinds[4] = Value::Str(String::from("Register"));
+	fields[4] = Value::Seq(bad_op_kinds);
+	assert!(from_value::<Instruction>(Value::Seq(fields)).is_err());
+}
@@ -0,0 +1,303 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+// A minimal serializer and deserializer used by the tests so no other serde crates are needed
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize, SerializeSeq, SerializeTuple, SerializeTupleStruct, Serializer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+	Bool(bool),
+	U64(u64),
+	I64(i64),
+	Str(String),
+	None,
+	Some(Box<Value>),
+	Unit,
+	Seq(Vec<Value>),
+}
+
+// Formats the value as JSON
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Value::Bool(value) => write!(f, "{}", value),
+			Value::U64(value) => write!(f, "{}", value),
+			Value::I64(value) => write!(f, "{}", value),
+			Value::Str(ref value) => write!(f, "\"{}\"", value),
+			Value::None | Value::Unit => write!(f, "null"),
+			Value::Some(ref value) => write!(f, "{}", value),
+			Value::Seq(ref values) => {
+				write!(f, "[")?;
+				for (i, value) in values.iter().enumerate() {
+					if i != 0 {
+						write!(f, ",")?;
+					}
+					write!(f, "{}", value)?;
+				}
+				write!(f, "]")
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub(super) struct Error(String);
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl de::StdError for Error {}
+
+impl ser::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		Error(format!("{}", msg))
+	}
+}
+
+impl de::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		Error(format!("{}", msg))
+	}
+}
+
+pub(super) fn to_value<T: Serialize>(value: &T) -> Value {
+	value.serialize(ValueSerializer).unwrap()
+}
+
+pub(super) fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+	T::deserialize(value)
+}
+
+struct ValueSerializer;
+
+struct SeqSerializer(Vec<Value>);
+
+impl Serializer for ValueSerializer {
+	type Ok = Value;
+	type Error = Error;
+	type SerializeSeq = SeqSerializer;
+	type SerializeTuple = SeqSerializer;
+	type SerializeTupleStruct = SeqSerializer;
+	type SerializeTupleVariant = Impossible<Value, Error>;
+	type SerializeMap = Impossible<Value, Error>;
+	type SerializeStruct = Impossible<Value, Error>;
+	type SerializeStructVariant = Impossible<Value, Error>;
+
+	fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+		Ok(Value::Bool(v))
+	}
+	fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+		Ok(Value::I64(v as i64))
+	}
+	fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+		Ok(Value::I64(v as i64))
+	}
+	fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+		Ok(Value::I64(v as i64))
+	}
+	fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+		Ok(Value::I64(v))
+	}
+	fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+		Ok(Value::U64(v as u64))
+	}
+	fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+		Ok(Value::U64(v as u64))
+	}
+	fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+		Ok(Value::U64(v as u64))
+	}
+	fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+		Ok(Value::U64(v))
+	}
+	fn serialize_f32(self, _: f32) -> Result<Value, Error> {
+		Err(Error(String::from("f32")))
+	}
+	fn serialize_f64(self, _: f64) -> Result<Value, Error> {
+		Err(Error(String::from("f64")))
+	}
+	fn serialize_char(self, v: char) -> Result<Value, Error> {
+		Ok(Value::Str(format!("{}", v)))
+	}
+	fn serialize_str(self, v: &str) -> Result<Value, Error> {
+		Ok(Value::Str(String::from(v)))
+	}
+	fn serialize_bytes(self, _: &[u8]) -> Result<Value, Error> {
+		Err(Error(String::from("bytes")))
+	}
+	fn serialize_none(self) -> Result<Value, Error> {
+		Ok(Value::None)
+	}
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Error> {
+		Ok(Value::Some(Box::new(value.serialize(self)?)))
+	}
+	fn serialize_unit(self) -> Result<Value, Error> {
+		Ok(Value::Unit)
+	}
+	fn serialize_unit_struct(self, _: &'static str) -> Result<Value, Error> {
+		Ok(Value::Unit)
+	}
+	fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Value, Error> {
+		Ok(Value::Str(String::from(variant)))
+	}
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Value, Error> {
+		value.serialize(self)
+	}
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<Value, Error> {
+		Err(Error(String::from("newtype variant")))
+	}
+	fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Error> {
+		Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+	}
+	fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Error> {
+		Ok(SeqSerializer(Vec::with_capacity(len)))
+	}
+	fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SeqSerializer, Error> {
+		Ok(SeqSerializer(Vec::with_capacity(len)))
+	}
+	fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, Error> {
+		Err(Error(String::from("tuple variant")))
+	}
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+		Err(Error(String::from("map")))
+	}
+	fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+		Err(Error(String::from("struct")))
+	}
+	fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, Error> {
+		Err(Error(String::from("struct variant")))
+	}
+	fn collect_str<T: ?Sized + fmt::Display>(self, value: &T) -> Result<Value, Error> {
+		Ok(Value::Str(format!("{}", value)))
+	}
+}
+
+impl SerializeSeq for SeqSerializer {
+	type Ok = Value;
+	type Error = Error;
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		self.0.push(value.serialize(ValueSerializer)?);
+		Ok(())
+	}
+	fn end(self) -> Result<Value, Error> {
+		Ok(Value::Seq(self.0))
+	}
+}
+
+impl SerializeTuple for SeqSerializer {
+	type Ok = Value;
+	type Error = Error;
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	fn end(self) -> Result<Value, Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+impl SerializeTupleStruct for SeqSerializer {
+	type Ok = Value;
+	type Error = Error;
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	fn end(self) -> Result<Value, Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+// The elements are stored in reverse order so they can be popped
+struct SeqAccess(Vec<Value>);
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+	type Error = Error;
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+		match self.0.pop() {
+			Some(value) => seed.deserialize(value).map(Some),
+			None => Ok(None),
+		}
+	}
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.0.len())
+	}
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+	type Deserializer = Value;
+	fn into_deserializer(self) -> Value {
+		self
+	}
+}
+
+impl<'de> Deserializer<'de> for Value {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		match self {
+			Value::Bool(value) => visitor.visit_bool(value),
+			Value::U64(value) => visitor.visit_u64(value),
+			Value::I64(value) => visitor.visit_i64(value),
+			Value::Str(value) => visitor.visit_str(&value),
+			Value::None => visitor.visit_none(),
+			Value::Some(value) => visitor.visit_some(*value),
+			Value::Unit => visitor.visit_unit(),
+			Value::Seq(mut values) => {
+				values.reverse();
+				let len = values.len();
+				let mut access = SeqAccess(values);
+				let result = visitor.visit_seq(&mut access)?;
+				if access.0.is_empty() {
+					Ok(result)
+				} else {
+					Err(de::Error::invalid_length(len, &"fewer elements"))
+				}
+			}
+		}
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		match self {
+			Value::None | Value::Unit => visitor.visit_none(),
+			Value::Some(value) => visitor.visit_some(*value),
+			value => visitor.visit_some(value),
+		}
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct newtype_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+}
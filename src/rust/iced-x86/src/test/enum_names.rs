@@ -0,0 +1,89 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::iced_constants::IcedConstants;
+use super::super::test_utils::from_str_conv::*;
+use super::super::*;
+use core::mem;
+use core::str::FromStr;
+
+#[test]
+fn code_names() {
+	for i in 0..IcedConstants::NUMBER_OF_CODE_VALUES {
+		let code: Code = unsafe { mem::transmute(i as u16) };
+		assert_eq!(Ok(code), to_code(code.as_str()));
+		assert_eq!(Ok(code), Code::from_str(code.as_str()));
+	}
+	assert!(Code::from_str("add_rm32_r32").is_err());
+	assert!(Code::from_str("").is_err());
+}
+
+#[test]
+fn register_names() {
+	for i in 0..IcedConstants::NUMBER_OF_REGISTERS {
+		let register: Register = unsafe { mem::transmute(i as u8) };
+		assert_eq!(Ok(register), Register::from_str(register.as_str()));
+		let lower_name = register.as_str().to_lowercase();
+		assert_eq!(Ok(register), Register::from_str_ignore_case(&lower_name));
+		if register != Register::None {
+			assert_eq!(Ok(register), to_register(&lower_name));
+		}
+	}
+	assert_eq!(Ok(Register::XMM10), "XMM10".parse());
+	assert!(Register::from_str("xmm10").is_err());
+	assert_eq!(Err("Invalid Register value: xmm99".to_string()), Register::from_str_ignore_case("xmm99"));
+}
+
+#[test]
+fn mnemonic_names() {
+	for i in 0..mnemonic::GEN_DEBUG_MNEMONIC.len() {
+		let mnemonic: Mnemonic = unsafe { mem::transmute(i as u16) };
+		assert_eq!(Ok(mnemonic), to_mnemonic(mnemonic.as_str()));
+		assert_eq!(Ok(mnemonic), Mnemonic::from_str(mnemonic.as_str()));
+		assert_eq!(Ok(mnemonic), Mnemonic::from_str_ignore_case(&mnemonic.as_str().to_uppercase()));
+	}
+	assert!(Mnemonic::from_str("add").is_err());
+	assert_eq!(Ok(Mnemonic::Add), Mnemonic::from_str_ignore_case("add"));
+	assert!(Mnemonic::from_str_ignore_case("ad").is_err());
+}
+
+#[test]
+fn memory_size_names() {
+	for i in 0..IcedConstants::NUMBER_OF_MEMORY_SIZES {
+		let memory_size: MemorySize = unsafe { mem::transmute(i as u8) };
+		assert_eq!(Ok(memory_size), to_memory_size(memory_size.as_str()));
+		assert_eq!(Ok(memory_size), MemorySize::from_str(memory_size.as_str()));
+	}
+	assert!(MemorySize::from_str("uint32").is_err());
+}
+
+#[test]
+#[cfg(feature = "instr_info")]
+fn cpuid_feature_names() {
+	for i in 0..IcedConstants::NUMBER_OF_CPUID_FEATURES {
+		let cpuid_feature: CpuidFeature = unsafe { mem::transmute(i as u8) };
+		assert_eq!(Ok(cpuid_feature), to_cpuid_features(cpuid_feature.as_str()));
+		assert_eq!(Ok(cpuid_feature), CpuidFeature::from_str(cpuid_feature.as_str()));
+	}
+	assert!(CpuidFeature::from_str("avx512f").is_err());
+}
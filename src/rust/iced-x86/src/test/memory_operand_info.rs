@@ -0,0 +1,134 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(feature = "decoder")]
+use super::super::decoder::tests::test_utils::*;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::create_decoder;
+#[cfg(feature = "decoder")]
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::*;
+
+#[cfg(feature = "decoder")]
+fn get_register_value(register: Register, element_index: usize, element_size: usize) -> u64 {
+	let value = (register as u64).wrapping_mul(0x0123_4567_89AB_CDEF).wrapping_add(element_index as u64 * 0x1111);
+	if element_size == 4 {
+		value as u32 as u64
+	} else {
+		value
+	}
+}
+
+#[test]
+#[cfg(feature = "decoder")]
+fn memory_operand_info_matches_instruction() {
+	for info in decoder_tests(true, false).iter() {
+		let bytes = to_vec_u8(info.hex_bytes()).unwrap();
+		let instruction = create_decoder(info.bitness(), bytes.as_slice(), info.decoder_options()).0.decode();
+		let mut memory_operands = 0;
+		for operand in 0..instruction.op_count() {
+			let mem_info = match instruction.memory_operand_info(operand) {
+				Some(mem_info) => mem_info,
+				None => continue,
+			};
+			memory_operands += 1;
+			assert_eq!(instruction.memory_size(), mem_info.memory_size);
+			assert_eq!(instruction.vsib().is_some(), mem_info.vsib.is_some());
+			for element_index in 0..2 {
+				assert_eq!(
+					instruction.virtual_address(operand, element_index, get_register_value),
+					mem_info.virtual_address(element_index, get_register_value),
+					"{} {:?} operand {}",
+					info.hex_bytes(),
+					info.code(),
+					operand
+				);
+			}
+
+			let mut instruction2 = instruction;
+			instruction2.set_memory_operand_info(operand, &mem_info);
+			assert!(instruction.eq_all_bits(&instruction2), "{} {:?} operand {}", info.hex_bytes(), info.code(), operand);
+		}
+		let has_memory_operand = (0..instruction.op_count()).any(|operand| match instruction.op_kind(operand) {
+			OpKind::Memory
+			| OpKind::Memory64
+			| OpKind::MemorySegSI
+			| OpKind::MemorySegESI
+			| OpKind::MemorySegRSI
+			| OpKind::MemorySegDI
+			| OpKind::MemorySegEDI
+			| OpKind::MemorySegRDI
+			| OpKind::MemoryESDI
+			| OpKind::MemoryESEDI
+			| OpKind::MemoryESRDI => true,
+			_ => false,
+		});
+		assert_eq!(has_memory_operand, memory_operands != 0);
+	}
+}
+
+#[test]
+#[cfg(feature = "decoder")]
+fn memory_operand_info_ip_relative() {
+	// mov eax,[rip+12345678h]
+	let bytes = b"\x8B\x05\x78\x56\x34\x12";
+	let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	decoder.set_ip(0x7FFF_FFFF_FFFF_FFF0);
+	let instruction = decoder.decode();
+	let info = instruction.memory_operand_info(1).unwrap();
+	assert!(info.is_ip_relative());
+	assert_eq!(Register::RIP, info.base);
+	assert_eq!(0x7FFF_FFFF_FFFF_FFF6, info.next_ip);
+	assert_eq!(instruction.ip_rel_memory_address(), info.effective_address(0, |_, _, _| unreachable!()));
+}
+
+#[test]
+#[cfg(feature = "encoder")]
+fn memory_operand_info_memory_operand_conversions() {
+	let memory = MemoryOperand::new(Register::R8, Register::RCX, 4, -0x10, 1, false, Register::FS);
+	let info = MemoryOperandInfo::from(memory);
+	assert_eq!(Register::FS, info.segment);
+	assert_eq!(8, info.address_size);
+	assert_eq!(-0x10i64 as u64, info.displacement);
+	assert_eq!(memory, MemoryOperand::from(info));
+
+	let instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base_displ(Register::EBP, -4));
+	let info = instruction.memory_operand_info(1).unwrap();
+	assert_eq!(Register::SS, info.segment);
+	assert_eq!(4, info.address_size);
+	assert_eq!(0xFFFF_FFFC, info.displacement);
+	assert_eq!(MemorySize::UInt32, info.memory_size);
+	assert_eq!(MemoryOperand::with_base_displ(Register::EBP, -4), MemoryOperand::from(info));
+
+	let mut instruction = Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, &MemoryOperand::with_base(Register::RAX));
+	instruction.set_memory_operand_info(1, &info);
+	assert_eq!(Register::EBP, instruction.memory_base());
+	assert_eq!(0xFFFF_FFFC, instruction.memory_displacement());
+}
+
+#[test]
+#[should_panic]
+fn set_memory_operand_info_panics_if_not_memory() {
+	let mut instruction = Instruction::default();
+	instruction.set_memory_operand_info(0, &MemoryOperandInfo::default());
+}
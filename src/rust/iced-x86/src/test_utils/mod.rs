@@ -27,8 +27,17 @@ pub(crate) mod from_str_conv;
 pub(crate) mod section_file_reader;
 
 use self::decoder_constants::*;
+use self::from_str_conv::to_vec_u8;
+#[cfg(any(feature = "gas", feature = "intel", feature = "masm", feature = "nasm"))]
+use super::formatter::Formatter;
 use super::iced_constants::IcedConstants;
-use super::Decoder;
+use super::{Code, Decoder, DecoderOptions, Instruction};
+#[cfg(all(not(feature = "std"), any(feature = "gas", feature = "intel", feature = "masm", feature = "nasm")))]
+use alloc::boxed::Box;
+#[cfg(all(not(feature = "std"), any(feature = "gas", feature = "intel", feature = "masm", feature = "nasm")))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use core::cmp;
 use std::env;
 use std::path::PathBuf;
@@ -86,3 +95,36 @@ pub(crate) fn create_decoder<'a>(bitness: u32, bytes: &'a [u8], options: u32) ->
 	let len = cmp::min(IcedConstants::MAX_INSTRUCTION_LENGTH, bytes.len());
 	(decoder, len, len < bytes.len())
 }
+
+/// Decodes the first instruction in `hex_bytes`, its IP is the default IP, see [`get_default_ip()`]
+pub(crate) fn decode(bitness: u32, hex_bytes: &str) -> Instruction {
+	decode_at(bitness, hex_bytes, get_default_ip(bitness))
+}
+
+/// Decodes the first instruction in `hex_bytes` at `ip`
+pub(crate) fn decode_at(bitness: u32, hex_bytes: &str, ip: u64) -> Instruction {
+	let bytes = to_vec_u8(hex_bytes).unwrap();
+	let mut decoder = Decoder::new(bitness, &bytes, DecoderOptions::NONE);
+	decoder.set_ip(ip);
+	decoder.decode()
+}
+
+/// Decodes all instructions in `hex_bytes`, they must all be valid
+pub(crate) fn decode_all(bitness: u32, hex_bytes: &str) -> Vec<Instruction> {
+	let bytes = to_vec_u8(hex_bytes).unwrap();
+	let mut decoder = create_decoder(bitness, &bytes, DecoderOptions::NONE).0;
+	let instructions: Vec<Instruction> = decoder.iter().collect();
+	for instruction in &instructions {
+		assert_ne!(Code::INVALID, instruction.code());
+	}
+	instructions
+}
+
+/// Decodes the first instruction in `hex_bytes` and formats it
+#[cfg(any(feature = "gas", feature = "intel", feature = "masm", feature = "nasm"))]
+pub(crate) fn format<'a>(bitness: u32, hex_bytes: &str, mut formatter: Box<Formatter + 'a>) -> String {
+	let instruction = decode(bitness, hex_bytes);
+	let mut output = String::new();
+	formatter.format(&instruction, &mut output);
+	output
+}
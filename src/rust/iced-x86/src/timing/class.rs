@@ -0,0 +1,715 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::*;
+
+// The timing of all instructions in the same class is identical
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum TimingClass {
+	Alu,
+	Mov,
+	Lea,
+	Xchg,
+	Nop,
+	ShiftImm,
+	ShiftCl,
+	Imul,
+	MulWide,
+	Div8,
+	Div16,
+	Div32,
+	Div64,
+	BitCount,
+	Cmov,
+	Setcc,
+	Jcc,
+	Jmp,
+	Call,
+	Ret,
+	Push,
+	Pop,
+	VecInt,
+	VecIntMul,
+	VecShuffle,
+	VecMove,
+	FpAdd,
+	FpMul,
+	Fma,
+	FpCompare,
+	Convert,
+	FpDivSingle,
+	FpDivDouble,
+}
+pub(super) const TIMING_CLASS_COUNT: usize = 33;
+
+impl TimingClass {
+	// Vector instructions use the (slower) vector load latency
+	#[inline]
+	pub(super) fn is_vector(self) -> bool {
+		self as usize >= TimingClass::VecInt as usize
+	}
+
+	// Plain moves don't need an execution port when the source or destination is memory
+	#[inline]
+	pub(super) fn is_move(self) -> bool {
+		self == TimingClass::Mov || self == TimingClass::VecMove
+	}
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum MemoryForm {
+	None,
+	Load,
+	Store,
+	LoadStore,
+}
+
+pub(super) fn timing_class(instruction: &Instruction) -> Option<(TimingClass, MemoryForm)> {
+	let class = match instruction.mnemonic() {
+		Mnemonic::Add
+		| Mnemonic::Adc
+		| Mnemonic::Sub
+		| Mnemonic::Sbb
+		| Mnemonic::And
+		| Mnemonic::Or
+		| Mnemonic::Xor
+		| Mnemonic::Cmp
+		| Mnemonic::Test
+		| Mnemonic::Inc
+		| Mnemonic::Dec
+		| Mnemonic::Neg
+		| Mnemonic::Not
+		| Mnemonic::Andn
+		| Mnemonic::Movzx
+		| Mnemonic::Movsx
+		| Mnemonic::Movsxd
+		| Mnemonic::Cbw
+		| Mnemonic::Cwde
+		| Mnemonic::Cdqe
+		| Mnemonic::Cwd
+		| Mnemonic::Cdq
+		| Mnemonic::Cqo
+		| Mnemonic::Bswap
+		| Mnemonic::Bt
+		| Mnemonic::Bts
+		| Mnemonic::Btr
+		| Mnemonic::Btc
+		| Mnemonic::Clc
+		| Mnemonic::Stc
+		| Mnemonic::Cmc
+		| Mnemonic::Lahf
+		| Mnemonic::Sahf
+		| Mnemonic::Blsi
+		| Mnemonic::Blsmsk
+		| Mnemonic::Blsr => TimingClass::Alu,
+		Mnemonic::Mov => {
+			// Moves to and from control, debug and segment registers are microcoded or serializing
+			for i in 0..instruction.op_count() {
+				if instruction.op_kind(i) == OpKind::Register {
+					let register = instruction.op_register(i);
+					if (Register::ES <= register && register <= Register::GS)
+						|| (Register::CR0 <= register && register <= Register::DR15)
+						|| (Register::TR0 <= register && register <= Register::TR7)
+					{
+						return None;
+					}
+				}
+			}
+			TimingClass::Mov
+		}
+		Mnemonic::Lea => TimingClass::Lea,
+		Mnemonic::Xchg => TimingClass::Xchg,
+		Mnemonic::Nop => TimingClass::Nop,
+		Mnemonic::Popcnt | Mnemonic::Lzcnt | Mnemonic::Tzcnt | Mnemonic::Bsf | Mnemonic::Bsr => TimingClass::BitCount,
+		Mnemonic::Cmovo
+		| Mnemonic::Cmovno
+		| Mnemonic::Cmovb
+		| Mnemonic::Cmovae
+		| Mnemonic::Cmove
+		| Mnemonic::Cmovne
+		| Mnemonic::Cmovbe
+		| Mnemonic::Cmova
+		| Mnemonic::Cmovs
+		| Mnemonic::Cmovns
+		| Mnemonic::Cmovp
+		| Mnemonic::Cmovnp
+		| Mnemonic::Cmovl
+		| Mnemonic::Cmovge
+		| Mnemonic::Cmovle
+		| Mnemonic::Cmovg => TimingClass::Cmov,
+		Mnemonic::Seto
+		| Mnemonic::Setno
+		| Mnemonic::Setb
+		| Mnemonic::Setae
+		| Mnemonic::Sete
+		| Mnemonic::Setne
+		| Mnemonic::Setbe
+		| Mnemonic::Seta
+		| Mnemonic::Sets
+		| Mnemonic::Setns
+		| Mnemonic::Setp
+		| Mnemonic::Setnp
+		| Mnemonic::Setl
+		| Mnemonic::Setge
+		| Mnemonic::Setle
+		| Mnemonic::Setg => TimingClass::Setcc,
+		Mnemonic::Jo
+		| Mnemonic::Jno
+		| Mnemonic::Jb
+		| Mnemonic::Jae
+		| Mnemonic::Je
+		| Mnemonic::Jne
+		| Mnemonic::Jbe
+		| Mnemonic::Ja
+		| Mnemonic::Js
+		| Mnemonic::Jns
+		| Mnemonic::Jp
+		| Mnemonic::Jnp
+		| Mnemonic::Jl
+		| Mnemonic::Jge
+		| Mnemonic::Jle
+		| Mnemonic::Jg => TimingClass::Jcc,
+		Mnemonic::Jmp => TimingClass::Jmp,
+		Mnemonic::Call => TimingClass::Call,
+		Mnemonic::Ret => TimingClass::Ret,
+		Mnemonic::Push => TimingClass::Push,
+		Mnemonic::Pop => TimingClass::Pop,
+		Mnemonic::Paddb
+		| Mnemonic::Paddw
+		| Mnemonic::Paddd
+		| Mnemonic::Paddq
+		| Mnemonic::Psubb
+		| Mnemonic::Psubw
+		| Mnemonic::Psubd
+		| Mnemonic::Psubq
+		| Mnemonic::Paddsb
+		| Mnemonic::Paddsw
+		| Mnemonic::Paddusb
+		| Mnemonic::Paddusw
+		| Mnemonic::Psubsb
+		| Mnemonic::Psubsw
+		| Mnemonic::Psubusb
+		| Mnemonic::Psubusw
+		| Mnemonic::Pand
+		| Mnemonic::Pandn
+		| Mnemonic::Por
+		| Mnemonic::Pxor
+		| Mnemonic::Pcmpeqb
+		| Mnemonic::Pcmpeqw
+		| Mnemonic::Pcmpeqd
+		| Mnemonic::Pcmpeqq
+		| Mnemonic::Pcmpgtb
+		| Mnemonic::Pcmpgtw
+		| Mnemonic::Pcmpgtd
+		| Mnemonic::Pminsb
+		| Mnemonic::Pminsw
+		| Mnemonic::Pminsd
+		| Mnemonic::Pminub
+		| Mnemonic::Pminuw
+		| Mnemonic::Pminud
+		| Mnemonic::Pmaxsb
+		| Mnemonic::Pmaxsw
+		| Mnemonic::Pmaxsd
+		| Mnemonic::Pmaxub
+		| Mnemonic::Pmaxuw
+		| Mnemonic::Pmaxud
+		| Mnemonic::Pavgb
+		| Mnemonic::Pavgw
+		| Mnemonic::Pabsb
+		| Mnemonic::Pabsw
+		| Mnemonic::Pabsd
+		| Mnemonic::Psignb
+		| Mnemonic::Psignw
+		| Mnemonic::Psignd
+		| Mnemonic::Psllw
+		| Mnemonic::Pslld
+		| Mnemonic::Psllq
+		| Mnemonic::Psrlw
+		| Mnemonic::Psrld
+		| Mnemonic::Psrlq
+		| Mnemonic::Psraw
+		| Mnemonic::Psrad
+		| Mnemonic::Andps
+		| Mnemonic::Andpd
+		| Mnemonic::Andnps
+		| Mnemonic::Andnpd
+		| Mnemonic::Orps
+		| Mnemonic::Orpd
+		| Mnemonic::Xorps
+		| Mnemonic::Xorpd
+		| Mnemonic::Blendps
+		| Mnemonic::Blendpd
+		| Mnemonic::Pblendw
+		| Mnemonic::Vpaddb
+		| Mnemonic::Vpaddw
+		| Mnemonic::Vpaddd
+		| Mnemonic::Vpaddq
+		| Mnemonic::Vpsubb
+		| Mnemonic::Vpsubw
+		| Mnemonic::Vpsubd
+		| Mnemonic::Vpsubq
+		| Mnemonic::Vpaddsb
+		| Mnemonic::Vpaddsw
+		| Mnemonic::Vpaddusb
+		| Mnemonic::Vpaddusw
+		| Mnemonic::Vpsubsb
+		| Mnemonic::Vpsubsw
+		| Mnemonic::Vpsubusb
+		| Mnemonic::Vpsubusw
+		| Mnemonic::Vpand
+		| Mnemonic::Vpandn
+		| Mnemonic::Vpor
+		| Mnemonic::Vpxor
+		| Mnemonic::Vpcmpeqb
+		| Mnemonic::Vpcmpeqw
+		| Mnemonic::Vpcmpeqd
+		| Mnemonic::Vpcmpeqq
+		| Mnemonic::Vpcmpgtb
+		| Mnemonic::Vpcmpgtw
+		| Mnemonic::Vpcmpgtd
+		| Mnemonic::Vpminsb
+		| Mnemonic::Vpminsw
+		| Mnemonic::Vpminsd
+		| Mnemonic::Vpminub
+		| Mnemonic::Vpminuw
+		| Mnemonic::Vpminud
+		| Mnemonic::Vpmaxsb
+		| Mnemonic::Vpmaxsw
+		| Mnemonic::Vpmaxsd
+		| Mnemonic::Vpmaxub
+		| Mnemonic::Vpmaxuw
+		| Mnemonic::Vpmaxud
+		| Mnemonic::Vpavgb
+		| Mnemonic::Vpavgw
+		| Mnemonic::Vpabsb
+		| Mnemonic::Vpabsw
+		| Mnemonic::Vpabsd
+		| Mnemonic::Vpsignb
+		| Mnemonic::Vpsignw
+		| Mnemonic::Vpsignd
+		| Mnemonic::Vpsllw
+		| Mnemonic::Vpslld
+		| Mnemonic::Vpsllq
+		| Mnemonic::Vpsrlw
+		| Mnemonic::Vpsrld
+		| Mnemonic::Vpsrlq
+		| Mnemonic::Vpsraw
+		| Mnemonic::Vpsrad
+		| Mnemonic::Vandps
+		| Mnemonic::Vandpd
+		| Mnemonic::Vandnps
+		| Mnemonic::Vandnpd
+		| Mnemonic::Vorps
+		| Mnemonic::Vorpd
+		| Mnemonic::Vxorps
+		| Mnemonic::Vxorpd
+		| Mnemonic::Vblendps
+		| Mnemonic::Vblendpd
+		| Mnemonic::Vpblendw
+		| Mnemonic::Vpandd
+		| Mnemonic::Vpandq
+		| Mnemonic::Vpandnd
+		| Mnemonic::Vpandnq
+		| Mnemonic::Vpord
+		| Mnemonic::Vporq
+		| Mnemonic::Vpxord
+		| Mnemonic::Vpxorq
+		| Mnemonic::Vpternlogd
+		| Mnemonic::Vpternlogq
+		| Mnemonic::Vpblendd => TimingClass::VecInt,
+		Mnemonic::Pmullw
+		| Mnemonic::Pmulhw
+		| Mnemonic::Pmulhuw
+		| Mnemonic::Pmulhrsw
+		| Mnemonic::Pmuludq
+		| Mnemonic::Pmuldq
+		| Mnemonic::Pmulld
+		| Mnemonic::Pmaddwd
+		| Mnemonic::Pmaddubsw
+		| Mnemonic::Psadbw
+		| Mnemonic::Vpmullw
+		| Mnemonic::Vpmulhw
+		| Mnemonic::Vpmulhuw
+		| Mnemonic::Vpmulhrsw
+		| Mnemonic::Vpmuludq
+		| Mnemonic::Vpmuldq
+		| Mnemonic::Vpmulld
+		| Mnemonic::Vpmaddwd
+		| Mnemonic::Vpmaddubsw
+		| Mnemonic::Vpsadbw
+		| Mnemonic::Vpmullq => TimingClass::VecIntMul,
+		Mnemonic::Pshufb
+		| Mnemonic::Pshufd
+		| Mnemonic::Pshufhw
+		| Mnemonic::Pshuflw
+		| Mnemonic::Shufps
+		| Mnemonic::Shufpd
+		| Mnemonic::Unpcklps
+		| Mnemonic::Unpckhps
+		| Mnemonic::Unpcklpd
+		| Mnemonic::Unpckhpd
+		| Mnemonic::Punpcklbw
+		| Mnemonic::Punpcklwd
+		| Mnemonic::Punpckldq
+		| Mnemonic::Punpcklqdq
+		| Mnemonic::Punpckhbw
+		| Mnemonic::Punpckhwd
+		| Mnemonic::Punpckhdq
+		| Mnemonic::Punpckhqdq
+		| Mnemonic::Palignr
+		| Mnemonic::Packsswb
+		| Mnemonic::Packssdw
+		| Mnemonic::Packuswb
+		| Mnemonic::Packusdw
+		| Mnemonic::Movhlps
+		| Mnemonic::Movlhps
+		| Mnemonic::Pmovzxbw
+		| Mnemonic::Pmovzxbd
+		| Mnemonic::Pmovzxbq
+		| Mnemonic::Pmovzxwd
+		| Mnemonic::Pmovzxwq
+		| Mnemonic::Pmovzxdq
+		| Mnemonic::Pmovsxbw
+		| Mnemonic::Pmovsxbd
+		| Mnemonic::Pmovsxbq
+		| Mnemonic::Pmovsxwd
+		| Mnemonic::Pmovsxwq
+		| Mnemonic::Pmovsxdq
+		| Mnemonic::Vpshufb
+		| Mnemonic::Vpshufd
+		| Mnemonic::Vpshufhw
+		| Mnemonic::Vpshuflw
+		| Mnemonic::Vshufps
+		| Mnemonic::Vshufpd
+		| Mnemonic::Vunpcklps
+		| Mnemonic::Vunpckhps
+		| Mnemonic::Vunpcklpd
+		| Mnemonic::Vunpckhpd
+		| Mnemonic::Vpunpcklbw
+		| Mnemonic::Vpunpcklwd
+		| Mnemonic::Vpunpckldq
+		| Mnemonic::Vpunpcklqdq
+		| Mnemonic::Vpunpckhbw
+		| Mnemonic::Vpunpckhwd
+		| Mnemonic::Vpunpckhdq
+		| Mnemonic::Vpunpckhqdq
+		| Mnemonic::Vpalignr
+		| Mnemonic::Vpacksswb
+		| Mnemonic::Vpackssdw
+		| Mnemonic::Vpackuswb
+		| Mnemonic::Vpackusdw
+		| Mnemonic::Vmovhlps
+		| Mnemonic::Vmovlhps
+		| Mnemonic::Vpmovzxbw
+		| Mnemonic::Vpmovzxbd
+		| Mnemonic::Vpmovzxbq
+		| Mnemonic::Vpmovzxwd
+		| Mnemonic::Vpmovzxwq
+		| Mnemonic::Vpmovzxdq
+		| Mnemonic::Vpmovsxbw
+		| Mnemonic::Vpmovsxbd
+		| Mnemonic::Vpmovsxbq
+		| Mnemonic::Vpmovsxwd
+		| Mnemonic::Vpmovsxwq
+		| Mnemonic::Vpmovsxdq
+		| Mnemonic::Vpermilps
+		| Mnemonic::Vpermilpd
+		| Mnemonic::Vpermps
+		| Mnemonic::Vpermpd
+		| Mnemonic::Vpermd
+		| Mnemonic::Vpermq
+		| Mnemonic::Vperm2f128
+		| Mnemonic::Vperm2i128
+		| Mnemonic::Vinsertf128
+		| Mnemonic::Vinserti128
+		| Mnemonic::Vextractf128
+		| Mnemonic::Vextracti128
+		| Mnemonic::Vbroadcastss
+		| Mnemonic::Vbroadcastsd
+		| Mnemonic::Vpbroadcastb
+		| Mnemonic::Vpbroadcastw
+		| Mnemonic::Vpbroadcastd
+		| Mnemonic::Vpbroadcastq => TimingClass::VecShuffle,
+		Mnemonic::Movaps
+		| Mnemonic::Movapd
+		| Mnemonic::Movups
+		| Mnemonic::Movupd
+		| Mnemonic::Movdqa
+		| Mnemonic::Movdqu
+		| Mnemonic::Movss
+		| Mnemonic::Movsd
+		| Mnemonic::Movd
+		| Mnemonic::Movq
+		| Mnemonic::Vmovaps
+		| Mnemonic::Vmovapd
+		| Mnemonic::Vmovups
+		| Mnemonic::Vmovupd
+		| Mnemonic::Vmovdqa
+		| Mnemonic::Vmovdqu
+		| Mnemonic::Vmovss
+		| Mnemonic::Vmovsd
+		| Mnemonic::Vmovd
+		| Mnemonic::Vmovq
+		| Mnemonic::Vmovdqa32
+		| Mnemonic::Vmovdqa64
+		| Mnemonic::Vmovdqu8
+		| Mnemonic::Vmovdqu16
+		| Mnemonic::Vmovdqu32
+		| Mnemonic::Vmovdqu64 => TimingClass::VecMove,
+		Mnemonic::Addps
+		| Mnemonic::Addpd
+		| Mnemonic::Addss
+		| Mnemonic::Addsd
+		| Mnemonic::Subps
+		| Mnemonic::Subpd
+		| Mnemonic::Subss
+		| Mnemonic::Subsd
+		| Mnemonic::Addsubps
+		| Mnemonic::Addsubpd
+		| Mnemonic::Vaddps
+		| Mnemonic::Vaddpd
+		| Mnemonic::Vaddss
+		| Mnemonic::Vaddsd
+		| Mnemonic::Vsubps
+		| Mnemonic::Vsubpd
+		| Mnemonic::Vsubss
+		| Mnemonic::Vsubsd
+		| Mnemonic::Vaddsubps
+		| Mnemonic::Vaddsubpd => TimingClass::FpAdd,
+		Mnemonic::Mulps
+		| Mnemonic::Mulpd
+		| Mnemonic::Mulss
+		| Mnemonic::Mulsd
+		| Mnemonic::Rcpps
+		| Mnemonic::Rcpss
+		| Mnemonic::Rsqrtps
+		| Mnemonic::Rsqrtss
+		| Mnemonic::Vmulps
+		| Mnemonic::Vmulpd
+		| Mnemonic::Vmulss
+		| Mnemonic::Vmulsd
+		| Mnemonic::Vrcpps
+		| Mnemonic::Vrcpss
+		| Mnemonic::Vrsqrtps
+		| Mnemonic::Vrsqrtss => TimingClass::FpMul,
+		Mnemonic::Vfmadd132ps
+		| Mnemonic::Vfmadd132pd
+		| Mnemonic::Vfmadd132ss
+		| Mnemonic::Vfmadd132sd
+		| Mnemonic::Vfmadd213ps
+		| Mnemonic::Vfmadd213pd
+		| Mnemonic::Vfmadd213ss
+		| Mnemonic::Vfmadd213sd
+		| Mnemonic::Vfmadd231ps
+		| Mnemonic::Vfmadd231pd
+		| Mnemonic::Vfmadd231ss
+		| Mnemonic::Vfmadd231sd
+		| Mnemonic::Vfmsub132ps
+		| Mnemonic::Vfmsub132pd
+		| Mnemonic::Vfmsub132ss
+		| Mnemonic::Vfmsub132sd
+		| Mnemonic::Vfmsub213ps
+		| Mnemonic::Vfmsub213pd
+		| Mnemonic::Vfmsub213ss
+		| Mnemonic::Vfmsub213sd
+		| Mnemonic::Vfmsub231ps
+		| Mnemonic::Vfmsub231pd
+		| Mnemonic::Vfmsub231ss
+		| Mnemonic::Vfmsub231sd
+		| Mnemonic::Vfnmadd132ps
+		| Mnemonic::Vfnmadd132pd
+		| Mnemonic::Vfnmadd132ss
+		| Mnemonic::Vfnmadd132sd
+		| Mnemonic::Vfnmadd213ps
+		| Mnemonic::Vfnmadd213pd
+		| Mnemonic::Vfnmadd213ss
+		| Mnemonic::Vfnmadd213sd
+		| Mnemonic::Vfnmadd231ps
+		| Mnemonic::Vfnmadd231pd
+		| Mnemonic::Vfnmadd231ss
+		| Mnemonic::Vfnmadd231sd
+		| Mnemonic::Vfnmsub132ps
+		| Mnemonic::Vfnmsub132pd
+		| Mnemonic::Vfnmsub132ss
+		| Mnemonic::Vfnmsub132sd
+		| Mnemonic::Vfnmsub213ps
+		| Mnemonic::Vfnmsub213pd
+		| Mnemonic::Vfnmsub213ss
+		| Mnemonic::Vfnmsub213sd
+		| Mnemonic::Vfnmsub231ps
+		| Mnemonic::Vfnmsub231pd
+		| Mnemonic::Vfnmsub231ss
+		| Mnemonic::Vfnmsub231sd => TimingClass::Fma,
+		Mnemonic::Cmpps
+		| Mnemonic::Cmppd
+		| Mnemonic::Cmpss
+		| Mnemonic::Cmpsd
+		| Mnemonic::Minps
+		| Mnemonic::Minpd
+		| Mnemonic::Minss
+		| Mnemonic::Minsd
+		| Mnemonic::Maxps
+		| Mnemonic::Maxpd
+		| Mnemonic::Maxss
+		| Mnemonic::Maxsd
+		| Mnemonic::Comiss
+		| Mnemonic::Comisd
+		| Mnemonic::Ucomiss
+		| Mnemonic::Ucomisd
+		| Mnemonic::Vcmpps
+		| Mnemonic::Vcmppd
+		| Mnemonic::Vcmpss
+		| Mnemonic::Vcmpsd
+		| Mnemonic::Vminps
+		| Mnemonic::Vminpd
+		| Mnemonic::Vminss
+		| Mnemonic::Vminsd
+		| Mnemonic::Vmaxps
+		| Mnemonic::Vmaxpd
+		| Mnemonic::Vmaxss
+		| Mnemonic::Vmaxsd
+		| Mnemonic::Vcomiss
+		| Mnemonic::Vcomisd
+		| Mnemonic::Vucomiss
+		| Mnemonic::Vucomisd => TimingClass::FpCompare,
+		Mnemonic::Cvtdq2ps
+		| Mnemonic::Cvtps2dq
+		| Mnemonic::Cvttps2dq
+		| Mnemonic::Cvtdq2pd
+		| Mnemonic::Cvtpd2dq
+		| Mnemonic::Cvttpd2dq
+		| Mnemonic::Cvtps2pd
+		| Mnemonic::Cvtpd2ps
+		| Mnemonic::Cvtss2sd
+		| Mnemonic::Cvtsd2ss
+		| Mnemonic::Cvtsi2ss
+		| Mnemonic::Cvtsi2sd
+		| Mnemonic::Cvtss2si
+		| Mnemonic::Cvtsd2si
+		| Mnemonic::Cvttss2si
+		| Mnemonic::Cvttsd2si
+		| Mnemonic::Vcvtdq2ps
+		| Mnemonic::Vcvtps2dq
+		| Mnemonic::Vcvttps2dq
+		| Mnemonic::Vcvtdq2pd
+		| Mnemonic::Vcvtpd2dq
+		| Mnemonic::Vcvttpd2dq
+		| Mnemonic::Vcvtps2pd
+		| Mnemonic::Vcvtpd2ps
+		| Mnemonic::Vcvtss2sd
+		| Mnemonic::Vcvtsd2ss
+		| Mnemonic::Vcvtsi2ss
+		| Mnemonic::Vcvtsi2sd
+		| Mnemonic::Vcvtss2si
+		| Mnemonic::Vcvtsd2si
+		| Mnemonic::Vcvttss2si
+		| Mnemonic::Vcvttsd2si => TimingClass::Convert,
+		Mnemonic::Divps
+		| Mnemonic::Divss
+		| Mnemonic::Sqrtps
+		| Mnemonic::Sqrtss
+		| Mnemonic::Vdivps
+		| Mnemonic::Vdivss
+		| Mnemonic::Vsqrtps
+		| Mnemonic::Vsqrtss => TimingClass::FpDivSingle,
+		Mnemonic::Divpd
+		| Mnemonic::Divsd
+		| Mnemonic::Sqrtpd
+		| Mnemonic::Sqrtsd
+		| Mnemonic::Vdivpd
+		| Mnemonic::Vdivsd
+		| Mnemonic::Vsqrtpd
+		| Mnemonic::Vsqrtsd => TimingClass::FpDivDouble,
+		Mnemonic::Shl | Mnemonic::Sal | Mnemonic::Shr | Mnemonic::Sar | Mnemonic::Rol | Mnemonic::Ror => {
+			if instruction.op_kind(1) == OpKind::Register {
+				TimingClass::ShiftCl
+			} else {
+				TimingClass::ShiftImm
+			}
+		}
+		Mnemonic::Rcl | Mnemonic::Rcr | Mnemonic::Shld | Mnemonic::Shrd => TimingClass::ShiftCl,
+		Mnemonic::Sarx | Mnemonic::Shlx | Mnemonic::Shrx | Mnemonic::Rorx => TimingClass::ShiftImm,
+		Mnemonic::Imul if instruction.op_count() >= 2 => TimingClass::Imul,
+		Mnemonic::Imul | Mnemonic::Mul | Mnemonic::Mulx => TimingClass::MulWide,
+		Mnemonic::Div | Mnemonic::Idiv => match instruction.code() {
+			Code::Div_rm8 | Code::Idiv_rm8 => TimingClass::Div8,
+			Code::Div_rm16 | Code::Idiv_rm16 => TimingClass::Div16,
+			Code::Div_rm64 | Code::Idiv_rm64 => TimingClass::Div64,
+			_ => TimingClass::Div32,
+		},
+		_ => return None,
+	};
+
+	let mut memory_operand = None;
+	for i in 0..instruction.op_count() {
+		match instruction.op_kind(i) {
+			OpKind::Memory | OpKind::Memory64 => memory_operand = Some(i),
+			OpKind::MemorySegSI
+			| OpKind::MemorySegESI
+			| OpKind::MemorySegRSI
+			| OpKind::MemorySegDI
+			| OpKind::MemorySegEDI
+			| OpKind::MemorySegRDI
+			| OpKind::MemoryESDI
+			| OpKind::MemoryESEDI
+			| OpKind::MemoryESRDI => return None,
+			_ => {}
+		}
+	}
+	let form = match memory_operand {
+		None => MemoryForm::None,
+		Some(_) if class == TimingClass::Lea || class == TimingClass::Nop => MemoryForm::None,
+		Some(0) => match class {
+			TimingClass::Mov | TimingClass::VecMove | TimingClass::Setcc | TimingClass::Pop => MemoryForm::Store,
+			TimingClass::Jmp | TimingClass::Call | TimingClass::Push => MemoryForm::Load,
+			TimingClass::Div8 | TimingClass::Div16 | TimingClass::Div32 | TimingClass::Div64 | TimingClass::MulWide | TimingClass::FpCompare => {
+				MemoryForm::Load
+			}
+			_ if instruction.mnemonic() == Mnemonic::Cmp || instruction.mnemonic() == Mnemonic::Test || instruction.mnemonic() == Mnemonic::Bt => {
+				MemoryForm::Load
+			}
+			_ if instruction.op_count() == 1 || !class.is_vector() => MemoryForm::LoadStore,
+			// Vector instructions with a memory destination are stores (eg. VEXTRACTF128, VMOVDQU8)
+			_ => MemoryForm::Store,
+		},
+		Some(_) => MemoryForm::Load,
+	};
+	Some((class, form))
+}
+
+// Gets the size in bits of the widest vector register operand (128 if it has no YMM or ZMM operands)
+pub(super) fn vector_width(instruction: &Instruction) -> u32 {
+	let mut width = 128;
+	for i in 0..instruction.op_count() {
+		if instruction.op_kind(i) == OpKind::Register {
+			let register = instruction.op_register(i);
+			if Register::ZMM0 <= register && register <= Register::ZMM31 {
+				return 512;
+			}
+			if Register::YMM0 <= register && register <= Register::YMM31 {
+				width = 256;
+			}
+		}
+	}
+	width
+}
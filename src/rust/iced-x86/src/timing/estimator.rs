@@ -0,0 +1,237 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// The resource that limits the throughput of a block, see [`BlockThroughput::bottleneck()`]
+///
+/// [`BlockThroughput::bottleneck()`]: struct.BlockThroughput.html#method.bottleneck
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Bottleneck {
+	/// The block has no instructions with known timings
+	None,
+	/// The front end, ie., the number of uops that can be issued per cycle
+	FrontEnd,
+	/// An execution port (index into [`Microarchitecture::port_names()`])
+	///
+	/// [`Microarchitecture::port_names()`]: enum.Microarchitecture.html#method.port_names
+	Port(u32),
+	/// The divider which isn't fully pipelined
+	Divider,
+}
+
+/// Estimates the throughput of a basic block (eg. the body of a hot loop) that's executed many times,
+/// similar to the resource pressure view of `llvm-mca`
+///
+/// The uops are spread over the ports they can execute on so the load is as even as possible. The number of cycles per iteration is the max of the front end limit, the most used port and the
+/// divider. Dependency chains (eg. a loop carried dependency) aren't taken into account so the result is a
+/// lower bound. Instructions that aren't in the timing tables are ignored, see [`unknown_instructions()`].
+///
+/// [`unknown_instructions()`]: #method.unknown_instructions
+///
+/// # Examples
+///
+/// ```
+/// use iced_x86::*;
+///
+/// // add rax,[rsi]
+/// // add rsi,8
+/// // dec ecx
+/// // jne short ...
+/// let bytes = b"\x48\x03\x06\x48\x83\xC6\x08\xFF\xC9\x75\xF4";
+/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+/// let instructions: Vec<_> = decoder.iter().collect();
+///
+/// let block = BlockThroughput::new(Microarchitecture::Skylake, &instructions);
+/// assert_eq!(4, block.uops());
+/// assert_eq!(1.0, block.cycles());
+/// assert_eq!(Bottleneck::FrontEnd, block.bottleneck());
+/// assert!(block.unknown_instructions().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockThroughput {
+	cycles: f64,
+	uops: u32,
+	port_pressure: Vec<f64>,
+	divider_cycles: f64,
+	bottleneck: Bottleneck,
+	unknown_instructions: Vec<usize>,
+}
+
+impl BlockThroughput {
+	/// Estimates the throughput of a block
+	///
+	/// # Arguments
+	///
+	/// * `microarchitecture`: Microarchitecture
+	/// * `instructions`: The instructions in the block
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn new(microarchitecture: Microarchitecture, instructions: &[Instruction]) -> Self {
+		let mut port_pressure = Vec::new();
+		port_pressure.resize(microarchitecture.port_names().len(), 0.0);
+		let mut uops = 0;
+		let mut divider_cycles = 0.0;
+		let mut unknown_instructions = Vec::new();
+		let mut usages = Vec::new();
+		for (i, instruction) in instructions.iter().enumerate() {
+			let timing = match microarchitecture.timing(instruction) {
+				Some(timing) => timing,
+				None => {
+					unknown_instructions.push(i);
+					continue;
+				}
+			};
+			uops += timing.uops();
+			if timing.uses_divider() {
+				divider_cycles += timing.reciprocal_throughput();
+			}
+			usages.extend_from_slice(timing.ports());
+		}
+		// Uops that can only use a few ports are assigned first so the other uops can avoid those ports
+		usages.sort_by_key(|usage| usage.ports().count_ones());
+		for usage in &usages {
+			distribute(&mut port_pressure, usage.ports(), usage.uops() as f64);
+		}
+
+		let mut cycles = 0.0;
+		let mut bottleneck = Bottleneck::None;
+		if uops != 0 {
+			cycles = uops as f64 / microarchitecture.issue_width() as f64;
+			bottleneck = Bottleneck::FrontEnd;
+		}
+		for (i, &pressure) in port_pressure.iter().enumerate() {
+			if pressure > cycles + EPSILON {
+				cycles = pressure;
+				bottleneck = Bottleneck::Port(i as u32);
+			}
+		}
+		if divider_cycles > cycles + EPSILON {
+			cycles = divider_cycles;
+			bottleneck = Bottleneck::Divider;
+		}
+
+		Self { cycles, uops, port_pressure, divider_cycles, bottleneck, unknown_instructions }
+	}
+
+	/// Gets the estimated number of cycles per iteration
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn cycles(&self) -> f64 {
+		self.cycles
+	}
+
+	/// Gets the total number of fused domain uops
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn uops(&self) -> u32 {
+		self.uops
+	}
+
+	/// Gets the average number of uops issued per cycle
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn uops_per_cycle(&self) -> f64 {
+		if self.cycles == 0.0 {
+			0.0
+		} else {
+			self.uops as f64 / self.cycles
+		}
+	}
+
+	/// Gets the number of cycles each port is busy per iteration, indexed by port (see [`Microarchitecture::port_names()`])
+	///
+	/// [`Microarchitecture::port_names()`]: enum.Microarchitecture.html#method.port_names
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn port_pressure(&self) -> &[f64] {
+		&self.port_pressure
+	}
+
+	/// Gets the number of cycles the divider is busy per iteration
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn divider_cycles(&self) -> f64 {
+		self.divider_cycles
+	}
+
+	/// Gets the resource that limits the throughput
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn bottleneck(&self) -> Bottleneck {
+		self.bottleneck
+	}
+
+	/// Gets the indexes of the instructions that aren't in the timing tables
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn unknown_instructions(&self) -> &[usize] {
+		&self.unknown_instructions
+	}
+}
+
+const EPSILON: f64 = 1e-9;
+const MAX_PRESSURE: f64 = 1e300;
+
+// Adds `uops` to the least used ports in `ports` until they're all used the same amount or there are no uops left
+fn distribute(port_pressure: &mut [f64], ports: u32, uops: f64) {
+	let mut left = uops;
+	while left > EPSILON {
+		let mut min = MAX_PRESSURE;
+		let mut next = MAX_PRESSURE;
+		let mut min_count = 0;
+		for (i, &pressure) in port_pressure.iter().enumerate() {
+			if (ports & (1 << i)) == 0 {
+				continue;
+			}
+			if pressure < min - EPSILON {
+				next = min;
+				min = pressure;
+				min_count = 1;
+			} else if pressure <= min + EPSILON {
+				min_count += 1;
+			} else if pressure < next {
+				next = pressure;
+			}
+		}
+		if min_count == 0 {
+			break;
+		}
+		let mut amount = left;
+		if next != MAX_PRESSURE {
+			let capacity = (next - min) * min_count as f64;
+			if capacity < amount {
+				amount = capacity;
+			}
+		}
+		let per_port = amount / min_count as f64;
+		for (i, pressure) in port_pressure.iter_mut().enumerate() {
+			if (ports & (1 << i)) != 0 && *pressure <= min + EPSILON {
+				*pressure += per_port;
+			}
+		}
+		left -= amount;
+	}
+}
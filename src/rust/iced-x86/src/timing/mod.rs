@@ -0,0 +1,270 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod class;
+mod estimator;
+mod tables;
+#[cfg(test)]
+mod tests;
+
+use self::class::*;
+pub use self::estimator::*;
+use self::tables::*;
+use super::*;
+use core::cmp;
+
+/// A microarchitecture supported by the timing model
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Microarchitecture {
+	/// Intel Skylake (client)
+	Skylake,
+	/// Intel Ice Lake (client)
+	IceLake,
+	/// AMD Zen 2
+	Zen2,
+	/// AMD Zen 3
+	Zen3,
+	/// AMD Zen 4
+	Zen4,
+}
+
+impl Microarchitecture {
+	#[inline]
+	fn info(self) -> &'static MicroarchitectureInfo {
+		&MICROARCHITECTURE_INFOS[self as usize]
+	}
+
+	/// Gets the max number of (fused domain) uops that can be issued per cycle
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn issue_width(self) -> u32 {
+		self.info().issue_width
+	}
+
+	/// Gets the names of the execution ports. Bit `N` of [`PortUsage::ports()`] is the port at index `N`.
+	///
+	/// Intel ports are named `p0`-`p9`. The AMD ports are the integer ALUs (`ALU0`-`ALU3`), the address
+	/// generation units (`AGU0`-`AGU2`) and the floating point/vector pipes (`FP0`-`FP3`).
+	///
+	/// [`PortUsage::ports()`]: struct.PortUsage.html#method.ports
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn port_names(self) -> &'static [&'static str] {
+		self.info().port_names
+	}
+
+	/// Gets the timing of an instruction or `None` if it's not in the timing tables
+	///
+	/// There's no per-[`Code`] data. The instructions are grouped by mnemonic into about 30 classes and all instructions
+	/// in a class have the same timing (the operand size is only used by `DIV`/`IDIV` and the vector width by vector
+	/// instructions). These instructions are supported:
+	///
+	/// - Integer ALU, `MOV`, `MOVZX`/`MOVSX`, `LEA`, `XCHG`, `NOP`, shifts and rotates, `IMUL`/`MUL`/`MULX`, `DIV`/`IDIV`,
+	///   `BSF`/`BSR`/`POPCNT`/`LZCNT`/`TZCNT`, `CMOVcc`, `SETcc`, `Jcc`, `JMP`/`CALL`/`RET`, `PUSH`/`POP`
+	/// - MMX/SSE/AVX/AVX-512 integer arithmetic, logic, compares, shifts, multiplies, shuffles and moves
+	/// - SSE/AVX/AVX-512 floating point add, multiply, FMA, compares, conversions, divides and square roots
+	///
+	/// All other instructions return `None`, eg. string instructions, x87 instructions,
+	/// `LOOP`/`JrCXZ`, system instructions, `CPUID`, fences, AES/SHA/`CRC32`, gathers/scatters and AVX-512 mask register
+	/// instructions.
+	///
+	/// The values are approximations of measured values and are meant for instruction selection and quick
+	/// analysis, not for cycle accurate simulation. Register forms and memory forms have different timings,
+	/// eg. a load adds the load-to-use latency and a load port uop. The divider handles 128 bits at a time so wider divides
+	/// have a lower throughput, and 512-bit instructions return `None` if the microarchitecture doesn't support AVX-512.
+	/// Moves to and from control, debug and segment registers return `None`. Special cases such as zeroing idioms,
+	/// move elimination, micro-fusion limits and AVX-512 frequency effects aren't modeled.
+	///
+	/// # Arguments
+	///
+	/// * `instruction`: Instruction
+	///
+	/// [`Code`]: enum.Code.html
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // imul eax,[rcx]
+	/// let bytes = b"\x0F\xAF\x01";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instruction = decoder.decode();
+	///
+	/// let timing = Microarchitecture::Skylake.timing(&instruction).unwrap();
+	/// assert_eq!(3 + 5, timing.latency());
+	/// assert_eq!(1.0, timing.reciprocal_throughput());
+	/// assert_eq!(1, timing.uops());
+	/// // One uop on p1 and one uop on p2 or p3
+	/// assert_eq!(2, timing.ports().len());
+	/// assert_eq!(0x02, timing.ports()[0].ports());
+	/// assert_eq!(0x0C, timing.ports()[1].ports());
+	/// ```
+	#[cfg_attr(has_must_use, must_use)]
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn timing(self, instruction: &Instruction) -> Option<InstructionTiming> {
+		let (class, form) = match timing_class(instruction) {
+			Some(class_form) => class_form,
+			None => return None,
+		};
+		let info = self.info();
+		let width = if class.is_vector() { vector_width(instruction) } else { 128 };
+		if width == 512 && info.zmm_ports == 0 {
+			return None;
+		}
+		let class_timing = &info.classes[class as usize];
+		let mut timing = InstructionTiming {
+			latency: class_timing.latency as u32,
+			reciprocal_throughput: class_timing.reciprocal_throughput as u32,
+			uops: class_timing.uops as u32,
+			ports: [PortUsage::default(); MAX_PORT_USAGES],
+			port_count: 0,
+			divider: class_timing.divider,
+		};
+		if class_timing.divider {
+			timing.reciprocal_throughput *= width / 128;
+		}
+		// A load or store replaces the ALU uop of a plain move
+		if !(class.is_move() && form != MemoryForm::None) {
+			for &usage in class_timing.ports {
+				if width == 512 && !class_timing.divider {
+					let uops = if info.zmm_split { usage.uops * 2 } else { usage.uops };
+					let ports = usage.ports & info.zmm_ports;
+					timing.reciprocal_throughput = cmp::max(timing.reciprocal_throughput, uops * 100 / ports.count_ones());
+					timing.add_ports(ports, uops);
+				} else {
+					timing.add_ports(usage.ports, usage.uops);
+				}
+			}
+		}
+		if form == MemoryForm::Load || form == MemoryForm::LoadStore {
+			let load_latency = if class.is_vector() { info.vector_load_latency } else { info.load_latency };
+			if class.is_move() {
+				timing.latency = load_latency as u32;
+			} else {
+				timing.latency += load_latency as u32;
+			}
+			timing.reciprocal_throughput = cmp::max(timing.reciprocal_throughput, 100 / info.loads_per_cycle);
+			timing.add_ports(info.load_ports, 1);
+		}
+		if form == MemoryForm::Store || form == MemoryForm::LoadStore {
+			if form == MemoryForm::LoadStore && !class.is_move() {
+				timing.uops += 1;
+			}
+			timing.reciprocal_throughput = cmp::max(timing.reciprocal_throughput, 100 / info.stores_per_cycle);
+			timing.add_ports(info.store_address_ports, 1);
+			timing.add_ports(info.store_data_ports, 1);
+		}
+		Some(timing)
+	}
+}
+
+/// Execution ports used by some uops of an instruction, see [`InstructionTiming::ports()`]
+///
+/// [`InstructionTiming::ports()`]: struct.InstructionTiming.html#method.ports
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PortUsage {
+	ports: u32,
+	uops: u32,
+}
+
+impl PortUsage {
+	/// Gets the ports (bit `N` = port `N`, see [`Microarchitecture::port_names()`]). Each uop is executed by one of these ports.
+	///
+	/// [`Microarchitecture::port_names()`]: enum.Microarchitecture.html#method.port_names
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn ports(&self) -> u32 {
+		self.ports
+	}
+
+	/// Gets the number of (unfused domain) uops
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn uops(&self) -> u32 {
+		self.uops
+	}
+}
+
+const MAX_PORT_USAGES: usize = 6;
+
+/// Latency, throughput and port usage of an instruction, see [`Microarchitecture::timing()`]
+///
+/// [`Microarchitecture::timing()`]: enum.Microarchitecture.html#method.timing
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct InstructionTiming {
+	latency: u32,
+	// Reciprocal throughput * 100
+	reciprocal_throughput: u32,
+	uops: u32,
+	ports: [PortUsage; MAX_PORT_USAGES],
+	port_count: u8,
+	divider: bool,
+}
+
+impl InstructionTiming {
+	#[inline]
+	fn add_ports(&mut self, ports: u32, uops: u32) {
+		if ports != 0 {
+			self.ports[self.port_count as usize] = PortUsage { ports, uops };
+			self.port_count += 1;
+		}
+	}
+
+	/// Gets the latency in cycles from the inputs to the outputs
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn latency(&self) -> u32 {
+		self.latency
+	}
+
+	/// Gets the reciprocal throughput, ie., the average number of cycles per instruction when executing many independent instructions
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn reciprocal_throughput(&self) -> f64 {
+		self.reciprocal_throughput as f64 / 100.0
+	}
+
+	/// Gets the number of fused domain uops (or macro ops on AMD), which is what's limited by [`Microarchitecture::issue_width()`]
+	///
+	/// [`Microarchitecture::issue_width()`]: enum.Microarchitecture.html#method.issue_width
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn uops(&self) -> u32 {
+		self.uops
+	}
+
+	/// Gets the execution ports used by the instruction
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn ports(&self) -> &[PortUsage] {
+		&self.ports[..self.port_count as usize]
+	}
+
+	/// `true` if the instruction uses the divider, which isn't fully pipelined
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn uses_divider(&self) -> bool {
+		self.divider
+	}
+}
@@ -0,0 +1,308 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::class::TIMING_CLASS_COUNT;
+use super::PortUsage;
+
+pub(super) struct ClassTiming {
+	pub(super) latency: u8,
+	// Reciprocal throughput * 100
+	pub(super) reciprocal_throughput: u16,
+	// Fused domain uops
+	pub(super) uops: u8,
+	pub(super) ports: &'static [PortUsage],
+	// The class uses the non-pipelined divider
+	pub(super) divider: bool,
+}
+
+pub(super) struct MicroarchitectureInfo {
+	pub(super) issue_width: u32,
+	pub(super) port_names: &'static [&'static str],
+	pub(super) load_ports: u32,
+	pub(super) store_address_ports: u32,
+	// 0 if the store data doesn't use a separate port
+	pub(super) store_data_ports: u32,
+	pub(super) loads_per_cycle: u32,
+	pub(super) stores_per_cycle: u32,
+	pub(super) load_latency: u8,
+	pub(super) vector_load_latency: u8,
+	// The ports 512-bit uops can use or 0 if AVX-512 isn't supported
+	pub(super) zmm_ports: u32,
+	// 512-bit uops are split into two 256-bit uops
+	pub(super) zmm_split: bool,
+	pub(super) classes: [ClassTiming; TIMING_CLASS_COUNT],
+}
+
+static INTEL_PORT_NAMES_8: [&str; 8] = ["p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7"];
+static INTEL_PORT_NAMES_10: [&str; 10] = ["p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7", "p8", "p9"];
+static ZEN_PORT_NAMES: [&str; 11] = ["ALU0", "ALU1", "ALU2", "ALU3", "AGU0", "AGU1", "AGU2", "FP0", "FP1", "FP2", "FP3"];
+
+// Same order as the Microarchitecture enum
+#[cfg_attr(feature = "cargo-fmt", rustfmt::skip)]
+pub(super) static MICROARCHITECTURE_INFOS: [MicroarchitectureInfo; 5] = [
+	// Skylake
+	MicroarchitectureInfo {
+		issue_width: 4,
+		port_names: &INTEL_PORT_NAMES_8,
+		load_ports: 0xC,
+		store_address_ports: 0x8C,
+		store_data_ports: 0x10,
+		loads_per_cycle: 2,
+		stores_per_cycle: 1,
+		load_latency: 5,
+		vector_load_latency: 6,
+		zmm_ports: 0,
+		zmm_split: false,
+		classes: [
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x63, uops: 1 }], divider: false }, // Alu
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x63, uops: 1 }], divider: false }, // Mov
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x22, uops: 1 }], divider: false }, // Lea
+			ClassTiming { latency: 2, reciprocal_throughput: 100, uops: 3, ports: &[PortUsage { ports: 0x63, uops: 3 }], divider: false }, // Xchg
+			ClassTiming { latency: 0, reciprocal_throughput: 25, uops: 1, ports: &[], divider: false }, // Nop
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // ShiftImm
+			ClassTiming { latency: 2, reciprocal_throughput: 150, uops: 3, ports: &[PortUsage { ports: 0x41, uops: 3 }], divider: false }, // ShiftCl
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // Imul
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x2, uops: 1 }, PortUsage { ports: 0x20, uops: 1 }], divider: false }, // MulWide
+			ClassTiming { latency: 23, reciprocal_throughput: 600, uops: 10, ports: &[PortUsage { ports: 0x63, uops: 10 }], divider: true }, // Div8
+			ClassTiming { latency: 23, reciprocal_throughput: 600, uops: 10, ports: &[PortUsage { ports: 0x63, uops: 10 }], divider: true }, // Div16
+			ClassTiming { latency: 26, reciprocal_throughput: 600, uops: 10, ports: &[PortUsage { ports: 0x63, uops: 10 }], divider: true }, // Div32
+			ClassTiming { latency: 42, reciprocal_throughput: 2400, uops: 36, ports: &[PortUsage { ports: 0x63, uops: 36 }], divider: true }, // Div64
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // BitCount
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Cmov
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Setcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Jcc
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x40, uops: 1 }], divider: false }, // Jmp
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x40, uops: 1 }, PortUsage { ports: 0x8C, uops: 1 }, PortUsage { ports: 0x10, uops: 1 }], divider: false }, // Call
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x40, uops: 1 }, PortUsage { ports: 0xC, uops: 1 }], divider: false }, // Ret
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x8C, uops: 1 }, PortUsage { ports: 0x10, uops: 1 }], divider: false }, // Push
+			ClassTiming { latency: 5, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0xC, uops: 1 }], divider: false }, // Pop
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x23, uops: 1 }], divider: false }, // VecInt
+			ClassTiming { latency: 5, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // VecIntMul
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x20, uops: 1 }], divider: false }, // VecShuffle
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x23, uops: 1 }], divider: false }, // VecMove
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpAdd
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpMul
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // Fma
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpCompare
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x3, uops: 1 }, PortUsage { ports: 0x20, uops: 1 }], divider: false }, // Convert
+			ClassTiming { latency: 11, reciprocal_throughput: 300, uops: 1, ports: &[PortUsage { ports: 0x1, uops: 1 }], divider: true }, // FpDivSingle
+			ClassTiming { latency: 14, reciprocal_throughput: 400, uops: 1, ports: &[PortUsage { ports: 0x1, uops: 1 }], divider: true }, // FpDivDouble
+		],
+	},
+	// IceLake
+	MicroarchitectureInfo {
+		issue_width: 5,
+		port_names: &INTEL_PORT_NAMES_10,
+		load_ports: 0xC,
+		store_address_ports: 0x180,
+		store_data_ports: 0x210,
+		loads_per_cycle: 2,
+		stores_per_cycle: 2,
+		load_latency: 5,
+		vector_load_latency: 6,
+		zmm_ports: 0x3FD,
+		zmm_split: false,
+		classes: [
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x63, uops: 1 }], divider: false }, // Alu
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x63, uops: 1 }], divider: false }, // Mov
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x22, uops: 1 }], divider: false }, // Lea
+			ClassTiming { latency: 2, reciprocal_throughput: 100, uops: 3, ports: &[PortUsage { ports: 0x63, uops: 3 }], divider: false }, // Xchg
+			ClassTiming { latency: 0, reciprocal_throughput: 25, uops: 1, ports: &[], divider: false }, // Nop
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // ShiftImm
+			ClassTiming { latency: 2, reciprocal_throughput: 150, uops: 3, ports: &[PortUsage { ports: 0x41, uops: 3 }], divider: false }, // ShiftCl
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // Imul
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x2, uops: 1 }, PortUsage { ports: 0x20, uops: 1 }], divider: false }, // MulWide
+			ClassTiming { latency: 15, reciprocal_throughput: 600, uops: 4, ports: &[PortUsage { ports: 0x63, uops: 4 }], divider: true }, // Div8
+			ClassTiming { latency: 12, reciprocal_throughput: 600, uops: 4, ports: &[PortUsage { ports: 0x63, uops: 4 }], divider: true }, // Div16
+			ClassTiming { latency: 12, reciprocal_throughput: 600, uops: 4, ports: &[PortUsage { ports: 0x63, uops: 4 }], divider: true }, // Div32
+			ClassTiming { latency: 15, reciprocal_throughput: 1000, uops: 4, ports: &[PortUsage { ports: 0x63, uops: 4 }], divider: true }, // Div64
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // BitCount
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Cmov
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Setcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x41, uops: 1 }], divider: false }, // Jcc
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x40, uops: 1 }], divider: false }, // Jmp
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x40, uops: 1 }, PortUsage { ports: 0x180, uops: 1 }, PortUsage { ports: 0x210, uops: 1 }], divider: false }, // Call
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x40, uops: 1 }, PortUsage { ports: 0xC, uops: 1 }], divider: false }, // Ret
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }, PortUsage { ports: 0x210, uops: 1 }], divider: false }, // Push
+			ClassTiming { latency: 5, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0xC, uops: 1 }], divider: false }, // Pop
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x23, uops: 1 }], divider: false }, // VecInt
+			ClassTiming { latency: 5, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // VecIntMul
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x22, uops: 1 }], divider: false }, // VecShuffle
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x23, uops: 1 }], divider: false }, // VecMove
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpAdd
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpMul
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // Fma
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x3, uops: 1 }], divider: false }, // FpCompare
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x3, uops: 1 }, PortUsage { ports: 0x20, uops: 1 }], divider: false }, // Convert
+			ClassTiming { latency: 11, reciprocal_throughput: 300, uops: 1, ports: &[PortUsage { ports: 0x1, uops: 1 }], divider: true }, // FpDivSingle
+			ClassTiming { latency: 13, reciprocal_throughput: 400, uops: 1, ports: &[PortUsage { ports: 0x1, uops: 1 }], divider: true }, // FpDivDouble
+		],
+	},
+	// Zen2
+	MicroarchitectureInfo {
+		issue_width: 5,
+		port_names: &ZEN_PORT_NAMES,
+		load_ports: 0x70,
+		store_address_ports: 0x70,
+		store_data_ports: 0x0,
+		loads_per_cycle: 2,
+		stores_per_cycle: 1,
+		load_latency: 4,
+		vector_load_latency: 7,
+		zmm_ports: 0,
+		zmm_split: false,
+		classes: [
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Alu
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Mov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Lea
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0xF, uops: 2 }], divider: false }, // Xchg
+			ClassTiming { latency: 0, reciprocal_throughput: 20, uops: 1, ports: &[], divider: false }, // Nop
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftImm
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftCl
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // Imul
+			ClassTiming { latency: 3, reciprocal_throughput: 200, uops: 2, ports: &[PortUsage { ports: 0x2, uops: 2 }], divider: false }, // MulWide
+			ClassTiming { latency: 12, reciprocal_throughput: 1200, uops: 1, ports: &[PortUsage { ports: 0x4, uops: 1 }], divider: true }, // Div8
+			ClassTiming { latency: 21, reciprocal_throughput: 2100, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div16
+			ClassTiming { latency: 30, reciprocal_throughput: 1400, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div32
+			ClassTiming { latency: 46, reciprocal_throughput: 4500, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div64
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // BitCount
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Cmov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Setcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jmp
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Call
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Ret
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Push
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Pop
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x580, uops: 1 }], divider: false }, // VecInt
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x80, uops: 1 }], divider: false }, // VecIntMul
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x300, uops: 1 }], divider: false }, // VecShuffle
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x780, uops: 1 }], divider: false }, // VecMove
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x600, uops: 1 }], divider: false }, // FpAdd
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpMul
+			ClassTiming { latency: 5, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // Fma
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpCompare
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: false }, // Convert
+			ClassTiming { latency: 10, reciprocal_throughput: 350, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivSingle
+			ClassTiming { latency: 13, reciprocal_throughput: 450, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivDouble
+		],
+	},
+	// Zen3
+	MicroarchitectureInfo {
+		issue_width: 6,
+		port_names: &ZEN_PORT_NAMES,
+		load_ports: 0x70,
+		store_address_ports: 0x70,
+		store_data_ports: 0x0,
+		loads_per_cycle: 3,
+		stores_per_cycle: 2,
+		load_latency: 4,
+		vector_load_latency: 7,
+		zmm_ports: 0,
+		zmm_split: false,
+		classes: [
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Alu
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Mov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Lea
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0xF, uops: 2 }], divider: false }, // Xchg
+			ClassTiming { latency: 0, reciprocal_throughput: 17, uops: 1, ports: &[], divider: false }, // Nop
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftImm
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftCl
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // Imul
+			ClassTiming { latency: 3, reciprocal_throughput: 200, uops: 2, ports: &[PortUsage { ports: 0x2, uops: 2 }], divider: false }, // MulWide
+			ClassTiming { latency: 9, reciprocal_throughput: 400, uops: 1, ports: &[PortUsage { ports: 0x4, uops: 1 }], divider: true }, // Div8
+			ClassTiming { latency: 10, reciprocal_throughput: 500, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div16
+			ClassTiming { latency: 10, reciprocal_throughput: 600, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div32
+			ClassTiming { latency: 14, reciprocal_throughput: 700, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div64
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // BitCount
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Cmov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Setcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jmp
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Call
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Ret
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Push
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Pop
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x580, uops: 1 }], divider: false }, // VecInt
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x480, uops: 1 }], divider: false }, // VecIntMul
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x300, uops: 1 }], divider: false }, // VecShuffle
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x780, uops: 1 }], divider: false }, // VecMove
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x600, uops: 1 }], divider: false }, // FpAdd
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpMul
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // Fma
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpCompare
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: false }, // Convert
+			ClassTiming { latency: 10, reciprocal_throughput: 350, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivSingle
+			ClassTiming { latency: 13, reciprocal_throughput: 450, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivDouble
+		],
+	},
+	// Zen4
+	MicroarchitectureInfo {
+		issue_width: 6,
+		port_names: &ZEN_PORT_NAMES,
+		load_ports: 0x70,
+		store_address_ports: 0x70,
+		store_data_ports: 0x0,
+		loads_per_cycle: 3,
+		stores_per_cycle: 2,
+		load_latency: 4,
+		vector_load_latency: 7,
+		zmm_ports: 0x7FF,
+		zmm_split: true,
+		classes: [
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Alu
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Mov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Lea
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0xF, uops: 2 }], divider: false }, // Xchg
+			ClassTiming { latency: 0, reciprocal_throughput: 17, uops: 1, ports: &[], divider: false }, // Nop
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftImm
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x6, uops: 1 }], divider: false }, // ShiftCl
+			ClassTiming { latency: 3, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x2, uops: 1 }], divider: false }, // Imul
+			ClassTiming { latency: 3, reciprocal_throughput: 200, uops: 2, ports: &[PortUsage { ports: 0x2, uops: 2 }], divider: false }, // MulWide
+			ClassTiming { latency: 9, reciprocal_throughput: 300, uops: 1, ports: &[PortUsage { ports: 0x4, uops: 1 }], divider: true }, // Div8
+			ClassTiming { latency: 11, reciprocal_throughput: 400, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div16
+			ClassTiming { latency: 12, reciprocal_throughput: 400, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div32
+			ClassTiming { latency: 16, reciprocal_throughput: 600, uops: 2, ports: &[PortUsage { ports: 0x4, uops: 2 }], divider: true }, // Div64
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // BitCount
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Cmov
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0xF, uops: 1 }], divider: false }, // Setcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jcc
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x9, uops: 1 }], divider: false }, // Jmp
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Call
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 2, ports: &[PortUsage { ports: 0x9, uops: 1 }, PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Ret
+			ClassTiming { latency: 1, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Push
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x70, uops: 1 }], divider: false }, // Pop
+			ClassTiming { latency: 1, reciprocal_throughput: 33, uops: 1, ports: &[PortUsage { ports: 0x580, uops: 1 }], divider: false }, // VecInt
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x480, uops: 1 }], divider: false }, // VecIntMul
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x300, uops: 1 }], divider: false }, // VecShuffle
+			ClassTiming { latency: 1, reciprocal_throughput: 25, uops: 1, ports: &[PortUsage { ports: 0x780, uops: 1 }], divider: false }, // VecMove
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x600, uops: 1 }], divider: false }, // FpAdd
+			ClassTiming { latency: 3, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpMul
+			ClassTiming { latency: 4, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // Fma
+			ClassTiming { latency: 1, reciprocal_throughput: 50, uops: 1, ports: &[PortUsage { ports: 0x180, uops: 1 }], divider: false }, // FpCompare
+			ClassTiming { latency: 4, reciprocal_throughput: 100, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: false }, // Convert
+			ClassTiming { latency: 10, reciprocal_throughput: 350, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivSingle
+			ClassTiming { latency: 13, reciprocal_throughput: 450, uops: 1, ports: &[PortUsage { ports: 0x400, uops: 1 }], divider: true }, // FpDivDouble
+		],
+	},
+];
@@ -0,0 +1,273 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::decode_all;
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+static MICROARCHITECTURES: [Microarchitecture; 5] =
+	[Microarchitecture::Skylake, Microarchitecture::IceLake, Microarchitecture::Zen2, Microarchitecture::Zen3, Microarchitecture::Zen4];
+
+fn timing(microarchitecture: Microarchitecture, hex_bytes: &str) -> Option<InstructionTiming> {
+	let instructions = decode_all(64, hex_bytes);
+	assert_eq!(1, instructions.len());
+	microarchitecture.timing(&instructions[0])
+}
+
+fn port_masks(timing: &InstructionTiming) -> Vec<u32> {
+	timing.ports().iter().map(|usage| usage.ports()).collect()
+}
+
+#[test]
+fn ports_are_valid() {
+	// add rax,[rcx]
+	// add [rcx],rax
+	// div rcx
+	// call qword ptr [rax]
+	// vfmadd231ps ymm0,ymm1,[rax]
+	// vdivsd xmm0,xmm1,xmm2
+	// pshufb xmm0,[rax]
+	let instructions = decode_all(64, "48 03 01 48 01 01 48 F7 F1 FF 10 C4E275 B8 00 C5F3 5E C2 66 0F38 00 00");
+	for &microarchitecture in MICROARCHITECTURES.iter() {
+		let port_count = microarchitecture.port_names().len();
+		assert!(microarchitecture.issue_width() >= 4);
+		for instruction in &instructions {
+			let timing = microarchitecture.timing(instruction).unwrap();
+			assert!(timing.uops() >= 1);
+			assert!(timing.reciprocal_throughput() > 0.0);
+			for usage in timing.ports() {
+				assert_ne!(0, usage.ports());
+				assert_ne!(0, usage.uops());
+				assert_eq!(0, usage.ports() >> port_count);
+			}
+		}
+	}
+}
+
+#[test]
+fn register_and_memory_forms() {
+	let uarch = Microarchitecture::Skylake;
+
+	// add rax,rcx
+	let reg = timing(uarch, "48 01 C8").unwrap();
+	assert_eq!(1, reg.latency());
+	assert_eq!(0.25, reg.reciprocal_throughput());
+	assert_eq!(1, reg.uops());
+	assert_eq!(vec![0x63], port_masks(&reg));
+	assert!(!reg.uses_divider());
+
+	// add rax,[rcx]
+	let load = timing(uarch, "48 03 01").unwrap();
+	assert_eq!(6, load.latency());
+	assert_eq!(0.5, load.reciprocal_throughput());
+	assert_eq!(1, load.uops());
+	assert_eq!(vec![0x63, 0x0C], port_masks(&load));
+
+	// add [rcx],rax
+	let rmw = timing(uarch, "48 01 01").unwrap();
+	assert_eq!(1.0, rmw.reciprocal_throughput());
+	assert_eq!(2, rmw.uops());
+	assert_eq!(vec![0x63, 0x0C, 0x8C, 0x10], port_masks(&rmw));
+
+	// cmp [rcx],rax
+	let cmp = timing(uarch, "48 39 01").unwrap();
+	assert_eq!(vec![0x63, 0x0C], port_masks(&cmp));
+
+	// lea rax,[rcx+rdx]
+	let lea = timing(uarch, "48 8D 04 11").unwrap();
+	assert_eq!(vec![0x22], port_masks(&lea));
+}
+
+#[test]
+fn moves_only_use_load_and_store_ports() {
+	let uarch = Microarchitecture::Skylake;
+
+	// mov rax,[rcx]
+	let load = timing(uarch, "48 8B 01").unwrap();
+	assert_eq!(5, load.latency());
+	assert_eq!(vec![0x0C], port_masks(&load));
+
+	// mov [rcx],rax
+	let store = timing(uarch, "48 89 01").unwrap();
+	assert_eq!(1, store.uops());
+	assert_eq!(vec![0x8C, 0x10], port_masks(&store));
+
+	// movaps xmm0,[rcx]
+	let vector_load = timing(uarch, "0F 28 01").unwrap();
+	assert_eq!(6, vector_load.latency());
+
+	// Zen stores don't use a store data port
+	// mov [rcx],rax
+	let store = timing(Microarchitecture::Zen3, "48 89 01").unwrap();
+	assert_eq!(vec![0x70], port_masks(&store));
+	assert_eq!(0.5, store.reciprocal_throughput());
+}
+
+#[test]
+fn operand_dependent_classes() {
+	let uarch = Microarchitecture::Skylake;
+
+	// shl eax,1 / shl eax,cl
+	assert_eq!(1, timing(uarch, "D1 E0").unwrap().uops());
+	assert_eq!(3, timing(uarch, "D3 E0").unwrap().uops());
+
+	// imul eax,ecx / mul rcx
+	assert_eq!(vec![0x02], port_masks(&timing(uarch, "0F AF C1").unwrap()));
+	assert_eq!(vec![0x02, 0x20], port_masks(&timing(uarch, "48 F7 E1").unwrap()));
+
+	// div ecx / div rcx
+	let div32 = timing(uarch, "F7 F1").unwrap();
+	let div64 = timing(uarch, "48 F7 F1").unwrap();
+	assert!(div32.uses_divider());
+	assert!(div64.uses_divider());
+	assert!(div32.latency() < div64.latency());
+	for &microarchitecture in MICROARCHITECTURES.iter() {
+		assert!(timing(microarchitecture, "F7 F1").unwrap().latency() < timing(microarchitecture, "48 F7 F1").unwrap().latency());
+		// div cl / div cx
+		assert!(timing(microarchitecture, "F6 F1").unwrap().latency() <= timing(microarchitecture, "48 F7 F1").unwrap().latency());
+		assert!(timing(microarchitecture, "66 F7 F1").unwrap().uses_divider());
+	}
+	let zen2 = Microarchitecture::Zen2;
+	assert!(timing(zen2, "F6 F1").unwrap().latency() < timing(zen2, "66 F7 F1").unwrap().latency());
+	assert!(timing(zen2, "66 F7 F1").unwrap().latency() < timing(zen2, "F7 F1").unwrap().latency());
+}
+
+#[test]
+fn system_register_moves_are_unknown() {
+	for &microarchitecture in MICROARCHITECTURES.iter() {
+		// mov cr0,rax / mov rax,cr0 / mov dr0,rax / mov ds,eax / mov eax,ds
+		for &hex_bytes in ["0F 22 C0", "0F 20 C0", "0F 23 C0", "8E D8", "8C D8"].iter() {
+			assert_eq!(None, timing(microarchitecture, hex_bytes), "{}", hex_bytes);
+		}
+		// mov eax,ecx
+		assert!(timing(microarchitecture, "8B C1").is_some());
+	}
+}
+
+#[test]
+fn vector_width() {
+	for &microarchitecture in MICROARCHITECTURES.iter() {
+		// vdivps xmm0,xmm0,xmm1 / vdivps ymm0,ymm0,ymm1
+		let xmm = timing(microarchitecture, "C5 F8 5E C1").unwrap();
+		let ymm = timing(microarchitecture, "C5 FC 5E C1").unwrap();
+		assert_eq!(xmm.reciprocal_throughput() * 2.0, ymm.reciprocal_throughput());
+		// vaddps xmm0,xmm0,xmm1 / vaddps ymm0,ymm0,ymm1
+		assert_eq!(timing(microarchitecture, "C5 F8 58 C1"), timing(microarchitecture, "C5 FC 58 C1"));
+	}
+
+	// vaddps zmm0,zmm0,zmm1
+	let zmm = "62 F1 7C 48 58 C1";
+	assert_eq!(None, timing(Microarchitecture::Skylake, zmm));
+	assert_eq!(None, timing(Microarchitecture::Zen2, zmm));
+	assert_eq!(None, timing(Microarchitecture::Zen3, zmm));
+	// Ice Lake can't use p1 for 512-bit uops
+	let icelake = timing(Microarchitecture::IceLake, zmm).unwrap();
+	assert_eq!(vec![0x01], port_masks(&icelake));
+	assert_eq!(1.0, icelake.reciprocal_throughput());
+	// Zen 4 splits 512-bit uops into two 256-bit uops
+	let zen4_ymm = timing(Microarchitecture::Zen4, "C5 FC 58 C1").unwrap();
+	let zen4_zmm = timing(Microarchitecture::Zen4, zmm).unwrap();
+	assert_eq!(zen4_ymm.uops(), zen4_zmm.uops());
+	assert_eq!(zen4_ymm.reciprocal_throughput() * 2.0, zen4_zmm.reciprocal_throughput());
+	assert_eq!(zen4_ymm.ports()[0].uops() * 2, zen4_zmm.ports()[0].uops());
+}
+
+#[test]
+fn unknown_instructions() {
+	for &microarchitecture in MICROARCHITECTURES.iter() {
+		// cpuid
+		assert_eq!(None, timing(microarchitecture, "0F A2"));
+		// movsb
+		assert_eq!(None, timing(microarchitecture, "A4"));
+		// cmpsd
+		assert_eq!(None, timing(microarchitecture, "A7"));
+		// fadd st,st(1) / loop / jrcxz / lfence
+		for &hex_bytes in ["D8 C1", "E2 FE", "E3 FE", "0F AE E8"].iter() {
+			assert_eq!(None, timing(microarchitecture, hex_bytes), "{}", hex_bytes);
+		}
+		// aesenc xmm0,xmm1 / crc32 eax,ecx / vpgatherdd ymm0,[rax+ymm1*4],ymm2 / kmovw k1,k2
+		for &hex_bytes in ["66 0F 38 DC C1", "F2 0F 38 F1 C1", "C4 E2 6D 90 04 88", "C5 F8 90 CA"].iter() {
+			assert_eq!(None, timing(microarchitecture, hex_bytes), "{}", hex_bytes);
+		}
+		// paddb mm0,mm1 / jmp far [rax]
+		assert!(timing(microarchitecture, "0F FC C1").is_some());
+		assert!(timing(microarchitecture, "FF 28").is_some());
+	}
+}
+
+#[test]
+fn block_empty() {
+	let block = BlockThroughput::new(Microarchitecture::Zen2, &[]);
+	assert_eq!(0.0, block.cycles());
+	assert_eq!(0.0, block.uops_per_cycle());
+	assert_eq!(0, block.uops());
+	assert_eq!(Bottleneck::None, block.bottleneck());
+	assert_eq!(11, block.port_pressure().len());
+}
+
+#[test]
+fn block_port_pressure_is_balanced() {
+	// imul eax,ecx
+	// add edx,ebx
+	// add esi,edi
+	// add ebp,r8d
+	let instructions = decode_all(64, "0F AF C1 01 DA 01 FE 44 01 C5");
+	let block = BlockThroughput::new(Microarchitecture::Skylake, &instructions);
+	assert_eq!(4, block.uops());
+	assert_eq!(1.0, block.cycles());
+	assert_eq!(4.0, block.uops_per_cycle());
+	assert_eq!(Bottleneck::FrontEnd, block.bottleneck());
+	assert_eq!(&[1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0], block.port_pressure());
+}
+
+#[test]
+fn block_port_bottleneck() {
+	// pshufb xmm0,xmm1
+	// pshufb xmm2,xmm3
+	// pshufb xmm4,xmm5
+	// paddd xmm6,xmm7
+	let instructions = decode_all(64, "66 0F38 00 C1 66 0F38 00 D3 66 0F38 00 E5 66 0F FE F7");
+	let block = BlockThroughput::new(Microarchitecture::Skylake, &instructions);
+	assert_eq!(3.0, block.cycles());
+	assert_eq!(Bottleneck::Port(5), block.bottleneck());
+	assert_eq!(3.0, block.port_pressure()[5]);
+
+	// Ice Lake can also execute PSHUFB on p1
+	let block = BlockThroughput::new(Microarchitecture::IceLake, &instructions);
+	assert_eq!(1.5, block.cycles());
+	assert_eq!(Bottleneck::Port(1), block.bottleneck());
+}
+
+#[test]
+fn block_divider_bottleneck() {
+	// div rcx
+	// cpuid
+	// div rcx
+	let instructions = decode_all(64, "48 F7 F1 0F A2 48 F7 F1");
+	let block = BlockThroughput::new(Microarchitecture::Zen3, &instructions);
+	assert_eq!(Bottleneck::Divider, block.bottleneck());
+	assert_eq!(14.0, block.cycles());
+	assert_eq!(14.0, block.divider_cycles());
+	assert_eq!(&[1], block.unknown_instructions());
+}
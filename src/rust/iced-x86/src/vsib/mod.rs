@@ -0,0 +1,236 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#[cfg(test)]
+mod tests;
+
+use super::*;
+use core::cmp;
+
+/// One element access of a VSIB memory operand (gather, scatter and their prefetch variants), see [`Instruction::vsib_elements()`]
+///
+/// [`Instruction::vsib_elements()`]: struct.Instruction.html#method.vsib_elements
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VsibElement {
+	operand: u32,
+	element_index: u32,
+	index_size: u32,
+	memory_size: MemorySize,
+	access: OpAccess,
+	mask_register: Register,
+}
+
+#[cfg_attr(feature = "cargo-clippy", allow(clippy::trivially_copy_pass_by_ref))]
+impl VsibElement {
+	/// Gets the operand number of the VSIB memory operand
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn operand(&self) -> u32 {
+		self.operand
+	}
+
+	/// Gets the element index. It's the lane of the vector index register and the element in the data register.
+	/// Pass it to [`Instruction::virtual_address()`] to get the address of this element.
+	///
+	/// [`Instruction::virtual_address()`]: struct.Instruction.html#method.virtual_address
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn element_index(&self) -> u32 {
+		self.element_index
+	}
+
+	/// Gets the size in bytes of each index in the vector index register (4 or 8)
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn index_size(&self) -> u32 {
+		self.index_size
+	}
+
+	/// Gets the size of the accessed element
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn memory_size(&self) -> MemorySize {
+		self.memory_size
+	}
+
+	/// Gets the memory access: [`OpAccess::CondRead`] (gathers), [`OpAccess::CondWrite`] (scatters) or
+	/// [`OpAccess::NoMemAccess`] (gather/scatter prefetches)
+	///
+	/// [`OpAccess::CondRead`]: enum.OpAccess.html#variant.CondRead
+	/// [`OpAccess::CondWrite`]: enum.OpAccess.html#variant.CondWrite
+	/// [`OpAccess::NoMemAccess`]: enum.OpAccess.html#variant.NoMemAccess
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn access(&self) -> OpAccess {
+		self.access
+	}
+
+	/// Gets the mask register that decides if the element is accessed. If it's an opmask register (EVEX), the element is accessed
+	/// if bit [`element_index()`] is set. If it's a vector register (VEX), the element is accessed if the sign bit of element
+	/// [`element_index()`] is set. Elements are accessed in order and the mask is updated as each element completes.
+	///
+	/// [`element_index()`]: #method.element_index
+	#[cfg_attr(has_must_use, must_use)]
+	#[inline]
+	pub fn mask_register(&self) -> Register {
+		self.mask_register
+	}
+}
+
+/// Iterates over all element accesses of a VSIB memory operand, see [`Instruction::vsib_elements()`]
+///
+/// [`Instruction::vsib_elements()`]: struct.Instruction.html#method.vsib_elements
+#[derive(Debug, Clone)]
+pub struct VsibElements {
+	element: VsibElement,
+	count: u32,
+}
+
+impl Iterator for VsibElements {
+	type Item = VsibElement;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.element.element_index < self.count {
+			let element = self.element;
+			self.element.element_index += 1;
+			Some(element)
+		} else {
+			None
+		}
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = (self.count - self.element.element_index) as usize;
+		(len, Some(len))
+	}
+}
+
+impl ExactSizeIterator for VsibElements {}
+
+impl Instruction {
+	/// Gets all element accesses of a VSIB memory operand (`VGATHER*`, `VPGATHER*`, `VSCATTER*`, `VPSCATTER*`, `VGATHERPF*`,
+	/// `VSCATTERPF*`). It's empty if it's not a VSIB instruction.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use iced_x86::*;
+	///
+	/// // vpgatherdq ymm1,[rax+xmm3*8],ymm2
+	/// let bytes = b"\xC4\xE2\xED\x90\x0C\xD8";
+	/// let mut decoder = Decoder::new(64, bytes, DecoderOptions::NONE);
+	/// let instr = decoder.decode();
+	///
+	/// let elements: Vec<VsibElement> = instr.vsib_elements().collect();
+	/// assert_eq!(4, elements.len());
+	/// for element in &elements {
+	///     assert_eq!(1, element.operand());
+	///     assert_eq!(4, element.index_size());
+	///     assert_eq!(MemorySize::Int64, element.memory_size());
+	///     assert_eq!(OpAccess::CondRead, element.access());
+	///     assert_eq!(Register::YMM2, element.mask_register());
+	/// }
+	///
+	/// let element = elements[3];
+	/// let va = instr.virtual_address(element.operand(), element.element_index() as usize, |register, element_index, element_size| {
+	///     match register {
+	///         Register::DS => 0,
+	///         Register::RAX => 0x1000,
+	///         Register::XMM3 => {
+	///             assert_eq!(3, element_index);
+	///             assert_eq!(4, element_size);
+	///             0x10
+	///         }
+	///         _ => unimplemented!(),
+	///     }
+	/// });
+	/// assert_eq!(0x1080, va);
+	/// ```
+	#[cfg_attr(feature = "cargo-clippy", allow(clippy::missing_inline_in_public_items))]
+	pub fn vsib_elements(&self) -> VsibElements {
+		let mut elements = VsibElements { element: VsibElement::default(), count: 0 };
+		let is_vsib64 = match self.vsib() {
+			Some(is_vsib64) => is_vsib64,
+			None => return elements,
+		};
+		let index_size = if is_vsib64 { 8 } else { 4 };
+
+		let mut operand = 0;
+		let mut data_register = Register::None;
+		let mut mask_register = self.op_mask();
+		for i in 0..self.op_count() {
+			match self.op_kind(i) {
+				OpKind::Memory => operand = i,
+				OpKind::Register => {
+					// VEX gathers: dest, mem, mask. EVEX: dest{k}, mem or mem{k}, src
+					if data_register == Register::None {
+						data_register = self.op_register(i);
+					} else {
+						mask_register = self.op_register(i);
+					}
+				}
+				_ => {}
+			}
+		}
+
+		let memory_size = self.memory_size();
+		let element_size = memory_size.element_size();
+		let mut count = self.memory_index().size() / index_size;
+		if data_register != Register::None && element_size != 0 {
+			count = cmp::min(count, data_register.size() / element_size);
+		}
+
+		let access = match self.mnemonic() {
+			Mnemonic::Vgatherpf0dps
+			| Mnemonic::Vgatherpf0dpd
+			| Mnemonic::Vgatherpf0qps
+			| Mnemonic::Vgatherpf0qpd
+			| Mnemonic::Vgatherpf1dps
+			| Mnemonic::Vgatherpf1dpd
+			| Mnemonic::Vgatherpf1qps
+			| Mnemonic::Vgatherpf1qpd
+			| Mnemonic::Vscatterpf0dps
+			| Mnemonic::Vscatterpf0dpd
+			| Mnemonic::Vscatterpf0qps
+			| Mnemonic::Vscatterpf0qpd
+			| Mnemonic::Vscatterpf1dps
+			| Mnemonic::Vscatterpf1dpd
+			| Mnemonic::Vscatterpf1qps
+			| Mnemonic::Vscatterpf1qpd => OpAccess::NoMemAccess,
+			_ => {
+				if operand == 0 {
+					OpAccess::CondWrite
+				} else {
+					OpAccess::CondRead
+				}
+			}
+		};
+
+		elements.element =
+			VsibElement { operand, element_index: 0, index_size: index_size as u32, memory_size: memory_size.element_type(), access, mask_register };
+		elements.count = count as u32;
+		elements
+	}
+}
@@ -0,0 +1,93 @@
+/*
+Copyright (C) 2018-2019 de4dot@gmail.com
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE
+SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use super::super::test_utils::from_str_conv::to_vec_u8;
+use super::super::*;
+
+#[test]
+fn vsib_elements() {
+	#[rustfmt::skip]
+	let tests: &[(&str, u32, u32, u32, MemorySize, OpAccess, Register)] = &[
+		// vgatherqps xmm2,[rcx+ymm4*4+1],xmm6
+		("C4E24D 93 54 A1 01", 1, 4, 8, MemorySize::Float32, OpAccess::CondRead, Register::XMM6),
+		// vgatherqps ymm2{k3},[rcx+zmm4*4+4]
+		("62 F27D4B 93 54 A1 01", 1, 8, 8, MemorySize::Float32, OpAccess::CondRead, Register::K3),
+		// vgatherdpd zmm2{k3},[rcx+ymm4*4+8]
+		("62 F2FD4B 92 54 A1 01", 1, 8, 4, MemorySize::Float64, OpAccess::CondRead, Register::K3),
+		// vpscatterqd [rcx+zmm4*4+4]{k3},ymm2
+		("62 F27D4B A1 54 A1 01", 0, 8, 8, MemorySize::Int32, OpAccess::CondWrite, Register::K3),
+		// vgatherpf0dpd [rcx+ymm4*4+8]{k3}
+		("62 F2FD4B C6 4C A1 01", 0, 8, 4, MemorySize::Float64, OpAccess::NoMemAccess, Register::K3),
+	];
+	for &(hex_bytes, operand, count, index_size, memory_size, access, mask_register) in tests {
+		let bytes = to_vec_u8(hex_bytes).unwrap();
+		let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+		let instruction = decoder.decode();
+		assert!(instruction.is_vsib(), "{}", hex_bytes);
+		let elements = instruction.vsib_elements();
+		assert_eq!(count as usize, elements.len(), "{}", hex_bytes);
+		for (i, element) in elements.enumerate() {
+			assert_eq!(i as u32, element.element_index());
+			assert_eq!(operand, element.operand());
+			assert_eq!(index_size, element.index_size());
+			assert_eq!(memory_size, element.memory_size());
+			assert_eq!(access, element.access());
+			assert_eq!(mask_register, element.mask_register());
+		}
+	}
+}
+
+#[test]
+fn vsib_element_addresses() {
+	// vgatherqps ymm2{k3},[rcx+zmm4*4+4]
+	let bytes = to_vec_u8("62 F27D4B 93 54 A1 01").unwrap();
+	let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+	let instruction = decoder.decode();
+	for element in instruction.vsib_elements() {
+		let va =
+			instruction.virtual_address(
+				element.operand(),
+				element.element_index() as usize,
+				|register, element_index, element_size| match register {
+					Register::DS => 0,
+					Register::RCX => 0x1000,
+					Register::ZMM4 => {
+						assert_eq!(8, element_size);
+						element_index as u64 * 0x10
+					}
+					_ => unreachable!(),
+				},
+			);
+		assert_eq!(0x1004 + element.element_index() as u64 * 0x40, va);
+	}
+}
+
+#[test]
+fn not_vsib() {
+	// add [rax],ecx
+	let bytes = [0x01, 0x08];
+	let mut decoder = Decoder::new(64, &bytes, DecoderOptions::NONE);
+	let instruction = decoder.decode();
+	assert_eq!(0, instruction.vsib_elements().len());
+	assert_eq!(None, instruction.vsib_elements().next());
+}